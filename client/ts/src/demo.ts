@@ -7,7 +7,11 @@ import * as path from "path";
 import {
   getProgramFromEnv,
   playerPda,
+  profilePda,
   battlePda,
+  pairPda,
+  lootBagPda,
+  consumablesPda,
   configPda,
   toLeBytes8,
   commitmentHash,
@@ -85,25 +89,62 @@ async function main() {
   // 1) Init config (once)
   const [cfg] = configPda();
   if (!(await connection.getAccountInfo(cfg))) {
-    // Pass numbers for i32/u16/bool; BN for u64 fields
+    // BalanceConfigParams: numbers for i32/u16/bool, BN for u64 fields
     await logTransactionCost(connection, me, "Init Config", 
       program.methods
-        .initConfig(
-          10,           // base (i32)
-          2,            // linear_a (i32)
-          20,           // dim_k (i32)
-          10,           // dim_t (i32)
-          100,          // max_level (u16)
-          true,         // tie_break_rand (bool)
-          false,        // use_dim_bonus (bool)
-          new BN(10),   // xp_base (u64)
-          7500,         // xp_tie_bps (u16)
-          2000,         // xp_loser_bps (u16)
-          new BN(10),   // upgrade_c0 (u64)
-          14000,        // upgrade_p_bps (u16)
-          20,           // rps_win_base (i32)
-          10,           // rps_tie_base (i32)
-        )
+        .initConfig({
+          base: 10,
+          linearA: 2,
+          dimK: 20,
+          dimT: 10,
+          maxLevel: 100,
+          tieBreakRand: true,
+          useDimBonus: false,
+          xpBase: new BN(10),
+          xpTieBps: 7500,
+          xpLoserBps: 2000,
+          upgradeC0: new BN(10),
+          upgradePBps: 14000,
+          rpsWinBase: 20,
+          rpsTieBase: 10,
+          basicDamageBps: 250,
+          accuracyBps: 50,
+          critBps: 500,
+          defenseBps: 100,
+          resolutionMode: { damage: {} },
+          pairCooldownSlots: new BN(150),
+          pairWindowSlots: new BN(216000),
+          pairDecayBps: 2500,
+          energyMax: 10,
+          energyRegenSlots: new BN(9000),
+          battleEnergyCost: 1,
+          energyXpCost: new BN(5),
+          lootDropBps: 3000,
+          lootRarityWeights: [6000, 2500, 1000, 400, 100],
+          lootPityThreshold: 10,
+          materialWin: 3,
+          materialLoss: 1,
+          salvageRefundBps: 5000,
+          shieldBps: 2000,
+          alphaLeakBps: 1500,
+          boostDropBps: 2000,
+          maxCharacters: 3,
+          respecRefundBps: 5000,
+          prestigePowerBps: 100,
+          prestigeOutfit: 0,
+          maxCommitSlots: new BN(1500),
+          maxRevealSlots: new BN(1500),
+          baseDamage: 100,
+          classWinBps: 12500,
+          classLossBps: 8000,
+          xpTierSize: new BN(1000),
+          xpTierBonusBps: 200,
+          specialMoves: [
+            { missPct: 30, hitEndPct: 80, hitMultBps: 15000, critMultBps: 22000 },
+            { missPct: 25, hitEndPct: 80, hitMultBps: 16000, critMultBps: 23000 },
+            { missPct: 35, hitEndPct: 80, hitMultBps: 14000, critMultBps: 20000 },
+          ],
+        } as any)
         .accounts({
           config: cfg,
           admin: me,
@@ -128,8 +169,10 @@ async function main() {
       program.methods
         .createPlayer({ shitposter: {} }) // class enum variant
         .accounts({
+          profile: profilePda(me)[0],
           player: pdaA,
           authority: me,
+          config: cfg,
           systemProgram: SystemProgram.programId,
         } as any)
         .rpc()
@@ -142,8 +185,10 @@ async function main() {
       program.methods
         .createPlayer({ builder: {} })
         .accounts({
+          profile: profilePda(keypairB.publicKey)[0],
           player: pdaB,
           authority: keypairB.publicKey,
+          config: cfg,
           systemProgram: SystemProgram.programId,
         } as any)
         .signers([keypairB])
@@ -164,13 +209,21 @@ async function main() {
 
   // 3) Create battle
   const nonce = new BN(Date.now()); // simple unique nonce for PDA seeds
-  const [battle] = battlePda(me, keypairB.publicKey, nonce);
+  const [battle] = battlePda(pdaA, pdaB, nonce);
   await logTransactionCost(connection, me, "Initiate Battle",
     program.methods
-      .initiateBattle(keypairB.publicKey, nonce, new BN(50), new BN(50)) // deadlines (slots/seconds) as your program expects
+      .initiateBattle(pdaB, nonce, new BN(50), new BN(50)) // deadlines (slots/seconds) as your program expects
       .accounts({
         battle,
+        pairRecord: pairPda(me, keypairB.publicKey)[0],
+        challengerLoot: lootBagPda(me)[0],
+        opponentLoot: lootBagPda(keypairB.publicKey)[0],
+        challengerConsumables: consumablesPda(me)[0],
+        opponentConsumables: consumablesPda(keypairB.publicKey)[0],
         challenger: me,
+        challengerAccount: pdaA,
+        opponentAccount: pdaB,
+        config: cfg,
         systemProgram: SystemProgram.programId,
         clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
       } as any)
@@ -194,14 +247,14 @@ async function main() {
   await logTransactionCost(connection, me, "Commit Move A",
     program.methods
       .commitMove([...hashA] as any)
-      .accounts({ battle, player: me, clock: anchor.web3.SYSVAR_CLOCK_PUBKEY } as any)
+      .accounts({ battle, player: me, playerAccount: pdaA, config: cfg, clock: anchor.web3.SYSVAR_CLOCK_PUBKEY } as any)
       .rpc()
   );
 
   await logTransactionCost(connection, me, "Commit Move B",
     program.methods
       .commitMove([...hashB] as any)
-      .accounts({ battle, player: keypairB.publicKey, clock: anchor.web3.SYSVAR_CLOCK_PUBKEY } as any)
+      .accounts({ battle, player: keypairB.publicKey, playerAccount: pdaB, config: cfg, clock: anchor.web3.SYSVAR_CLOCK_PUBKEY } as any)
       .signers([keypairB])
       .rpc()
   );
@@ -211,7 +264,7 @@ async function main() {
   // 5) Reveal moves
   await logTransactionCost(connection, me, "Reveal Move A",
     program.methods
-      .revealMove({ memeBomb: {} } as any, [...saltA] as any)
+      .revealMove({ memeBomb: {} } as any, [...saltA] as any, null)
       .accounts({
         battle,
         player: me,
@@ -223,7 +276,7 @@ async function main() {
 
   await logTransactionCost(connection, me, "Reveal Move B",
     program.methods
-      .revealMove({ shipIt: {} } as any, [...saltB] as any)
+      .revealMove({ shipIt: {} } as any, [...saltB] as any, null)
       .accounts({
         battle,
         player: keypairB.publicKey,
@@ -244,7 +297,11 @@ async function main() {
         battle,
         playerChallenger: pdaA,
         playerOpponent: pdaB,
-        config: cfg,
+        challengerLoot: lootBagPda(me)[0],
+        opponentLoot: lootBagPda(keypairB.publicKey)[0],
+        challengerConsumables: consumablesPda(me)[0],
+        opponentConsumables: consumablesPda(keypairB.publicKey)[0],
+        config: configPda()[0],
         clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
       } as any)
       .rpc()
@@ -265,9 +322,9 @@ async function main() {
   // Winner name mapping
   let winnerName = "<none>";
   if (winnerPk) {
-    if ((winnerPk as PublicKey).equals(me)) {
+    if ((winnerPk as PublicKey).equals(pdaA)) {
       winnerName = "A (You)";
-    } else if ((winnerPk as PublicKey).equals(keypairB.publicKey)) {
+    } else if ((winnerPk as PublicKey).equals(pdaB)) {
       winnerName = "B (Bot)";
     } else {
       winnerName = `Unknown (${(winnerPk as PublicKey).toBase58()})`;
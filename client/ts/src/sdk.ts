@@ -33,13 +33,19 @@ export function getProgram(
 }
 
 // ---------- PDA helpers ----------
-export function playerPda(authority: PublicKey): [PublicKey, number] {
+// A wallet's characters are numbered from 0 in creation order
+export function playerPda(authority: PublicKey, index = 0): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
-    [Buffer.from("player"), authority.toBuffer()],
+    [Buffer.from("player"), authority.toBuffer(), Buffer.from([index])],
     PROGRAM_ID
   );
 }
 
+export function profilePda(authority: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("profile"), authority.toBuffer()], PROGRAM_ID);
+}
+
+// Seeded by the two characters' player PDAs, not their wallets
 export function battlePda(
   challenger: PublicKey,
   opponent: PublicKey,
@@ -51,6 +57,23 @@ export function battlePda(
   );
 }
 
+export function pairPda(a: PublicKey, b: PublicKey): [PublicKey, number] {
+  // Keys in byte order, so both players derive the same record
+  const [low, high] = Buffer.compare(a.toBuffer(), b.toBuffer()) <= 0 ? [a, b] : [b, a];
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("pair"), low.toBuffer(), high.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+export function lootBagPda(owner: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("loot_bag"), owner.toBuffer()], PROGRAM_ID);
+}
+
+export function consumablesPda(owner: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("consumables"), owner.toBuffer()], PROGRAM_ID);
+}
+
 export function configPda(): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([Buffer.from("config")], PROGRAM_ID);
 }
@@ -0,0 +1,220 @@
+//! A small in-process bank that runs Anchor programs natively.
+//!
+//! Instructions are serialized into the same aligned input buffer the BPF
+//! loader builds, handed to the program's `entry` function, and the resulting
+//! account state is copied back once it passes the runtime's rules: only an
+//! account's owner writes its data, takes its lamports or reassigns it, and
+//! lamports always balance. Syscalls the program makes off-chain (rent,
+//! clock, `emit!`, CPI into the system program or another registered program)
+//! are routed through `program_stubs`, so `init`, `realloc`, events and
+//! return data behave as they do on a validator. Transactions are atomic; signatures are not
+//! checked beyond requiring every signer key to be listed.
+
+use std::collections::HashMap;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::solana_program::sysvar;
+use solana_sdk_ids::{native_loader, system_program};
+
+mod rules;
+mod serialization;
+mod stubs;
+mod system;
+
+/// Signature of an Anchor program's generated `entry` function.
+pub type Entrypoint = for<'info> fn(&Pubkey, &'info [AccountInfo<'info>], &[u8]) -> ProgramResult;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Account {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionError {
+    /// An account marked as signer was not in the signer list
+    MissingSignature(Pubkey),
+    /// The instruction targets a program that was never registered
+    UnknownProgram(Pubkey),
+    /// Instruction at `index` failed
+    InstructionError { index: usize, error: ProgramError },
+}
+
+impl TransactionError {
+    /// The program's custom error code, e.g. an Anchor `#[error_code]` variant.
+    pub fn custom_code(&self) -> Option<u32> {
+        match self {
+            TransactionError::InstructionError { error: ProgramError::Custom(code), .. } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionMeta {
+    /// Raw `sol_log_data` payloads in emission order
+    pub events: Vec<Vec<u8>>,
+    /// The last `sol_set_return_data` call and the program that made it
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+}
+
+impl TransactionMeta {
+    /// Decode every emitted event of type `T`.
+    pub fn events<T: anchor_lang::Event + AnchorDeserialize + anchor_lang::Discriminator>(&self) -> Vec<T> {
+        self.events.iter().filter_map(|raw| defighter::events::decode_event(raw)).collect()
+    }
+
+    /// Decode the value an Anchor instruction returned, e.g. from a view.
+    pub fn return_value<T: AnchorDeserialize>(&self) -> Option<T> {
+        let (_, data) = self.return_data.as_ref()?;
+        T::deserialize(&mut data.as_slice()).ok()
+    }
+}
+
+pub struct Bank {
+    accounts: HashMap<Pubkey, Account>,
+    programs: HashMap<Pubkey, Entrypoint>,
+    clock: Clock,
+}
+
+impl Default for Bank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bank {
+    pub fn new() -> Self {
+        stubs::install();
+        let mut bank = Self { accounts: HashMap::new(), programs: HashMap::new(), clock: Clock::default() };
+        bank.accounts.insert(
+            system_program::ID,
+            Account { lamports: 1, data: vec![], owner: native_loader::ID, executable: true },
+        );
+        bank.write_clock();
+        bank.write_slot_hashes();
+        bank
+    }
+
+    /// Register a program by id with its Anchor `entry` function.
+    pub fn add_program(&mut self, program_id: Pubkey, entry: Entrypoint) {
+        self.programs.insert(program_id, entry);
+        self.accounts.insert(
+            program_id,
+            Account { lamports: 1, data: vec![], owner: native_loader::ID, executable: true },
+        );
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.clock.slot
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.clock.clone()
+    }
+
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        self.clock.slot = slot;
+        self.write_clock();
+        self.write_slot_hashes();
+    }
+
+    /// The stand-in hash the SlotHashes sysvar lists for `slot`.
+    pub fn slot_hash(slot: u64) -> [u8; 32] {
+        anchor_lang::solana_program::keccak::hashv(&[b"slot", &slot.to_le_bytes()]).0
+    }
+
+    pub fn set_unix_timestamp(&mut self, unix_timestamp: i64) {
+        self.clock.unix_timestamp = unix_timestamp;
+        self.write_clock();
+    }
+
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        let account = self.accounts.entry(*key).or_default();
+        account.lamports = account.lamports.saturating_add(lamports);
+    }
+
+    pub fn get_account(&self, key: &Pubkey) -> Option<&Account> {
+        self.accounts.get(key)
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: Account) {
+        self.accounts.insert(key, account);
+    }
+
+    /// Deserialize an Anchor account, checking its discriminator.
+    pub fn get_anchor_account<T: AccountDeserialize>(&self, key: &Pubkey) -> Option<T> {
+        let account = self.accounts.get(key)?;
+        T::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    /// Execute `instructions` atomically with `signers` treated as having signed.
+    pub fn process_transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> std::result::Result<TransactionMeta, TransactionError> {
+        for ix in instructions {
+            for meta in &ix.accounts {
+                if meta.is_signer && !signers.contains(&meta.pubkey) {
+                    return Err(TransactionError::MissingSignature(meta.pubkey));
+                }
+            }
+        }
+
+        let mut working = self.accounts.clone();
+        let mut meta = TransactionMeta::default();
+        for (index, ix) in instructions.iter().enumerate() {
+            let entry = if ix.program_id == system_program::ID {
+                system::entry as Entrypoint
+            } else {
+                *self.programs.get(&ix.program_id).ok_or(TransactionError::UnknownProgram(ix.program_id))?
+            };
+            let output = stubs::with_context(ix.program_id, self.clock.clone(), self.programs.clone(), || {
+                serialization::invoke(entry, ix, &mut working)
+            })
+            .map_err(|error| TransactionError::InstructionError { index, error })?;
+            meta.events.extend(output.events);
+            if output.return_data.is_some() {
+                meta.return_data = output.return_data;
+            }
+        }
+        self.accounts = working;
+        Ok(meta)
+    }
+
+    fn write_clock(&mut self) {
+        let c = &self.clock;
+        let mut data = Vec::with_capacity(40);
+        data.extend_from_slice(&c.slot.to_le_bytes());
+        data.extend_from_slice(&c.epoch_start_timestamp.to_le_bytes());
+        data.extend_from_slice(&c.epoch.to_le_bytes());
+        data.extend_from_slice(&c.leader_schedule_epoch.to_le_bytes());
+        data.extend_from_slice(&c.unix_timestamp.to_le_bytes());
+        self.accounts.insert(
+            sysvar::clock::ID,
+            Account { lamports: 1, data, owner: sysvar::ID, executable: false },
+        );
+    }
+
+    /// Every earlier slot up to the sysvar's 512 entries, newest first, as if
+    /// none had been skipped.
+    fn write_slot_hashes(&mut self) {
+        let slots: Vec<u64> = (self.clock.slot.saturating_sub(512)..self.clock.slot).rev().collect();
+        let mut data = (slots.len() as u64).to_le_bytes().to_vec();
+        for slot in slots {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&Self::slot_hash(slot));
+        }
+        self.accounts.insert(
+            sysvar::slot_hashes::ID,
+            Account { lamports: 1, data, owner: sysvar::ID, executable: false },
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! The runtime's checks on what a program did to its accounts, applied to
+//! every top-level instruction and every CPI, each against its own program.
+
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_error::ProgramError;
+
+/// An account as the running program last saw it
+struct Snapshot {
+    key: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+    executable: bool,
+    writable: bool,
+}
+
+impl Snapshot {
+    fn of(info: &AccountInfo) -> Self {
+        Snapshot {
+            key: *info.key,
+            owner: *info.owner,
+            lamports: info.lamports(),
+            data: info.data.borrow().to_vec(),
+            executable: info.executable,
+            writable: info.is_writable,
+        }
+    }
+}
+
+/// One executing program and the accounts it was handed
+pub(crate) struct Frame {
+    program_id: Pubkey,
+    before: Vec<Snapshot>,
+    /// Lamports across `before` on entry; CPIs move them but never change the sum
+    lamports: u128,
+}
+
+impl Frame {
+    /// Record each distinct account in `infos`; duplicates share their state.
+    pub(crate) fn new(program_id: Pubkey, infos: &[AccountInfo]) -> Self {
+        let mut before: Vec<Snapshot> = Vec::with_capacity(infos.len());
+        for info in infos {
+            match before.iter_mut().find(|s| s.key == *info.key) {
+                Some(seen) => seen.writable |= info.is_writable,
+                None => before.push(Snapshot::of(info)),
+            }
+        }
+        let lamports = before.iter().map(|s| s.lamports as u128).sum();
+        Frame { program_id, before, lamports }
+    }
+
+    /// Fail if the program changed what it may not:
+    /// - read-only and executable accounts stay as they were (`Immutable`)
+    /// - only the owner writes data or takes lamports (`InvalidAccountOwner`)
+    /// - only the owner reassigns, and only with the data zeroed (`IllegalOwner`),
+    ///   which is how Anchor's `close` hands an account back to the system program
+    pub(crate) fn verify(&self, infos: &[AccountInfo]) -> ProgramResult {
+        for pre in &self.before {
+            let Some(info) = infos.iter().find(|info| *info.key == pre.key) else { continue };
+            let lamports = info.lamports();
+            let data = info.data.borrow();
+            let owner_changed = *info.owner != pre.owner;
+            let changed = owner_changed || lamports != pre.lamports || data[..] != pre.data[..];
+            if changed && (!pre.writable || pre.executable) {
+                return Err(ProgramError::Immutable);
+            }
+            let owned = pre.owner == self.program_id;
+            if !owned && (data[..] != pre.data[..] || lamports < pre.lamports) {
+                return Err(ProgramError::InvalidAccountOwner);
+            }
+            if owner_changed && !(owned && data.iter().all(|&b| b == 0)) {
+                return Err(ProgramError::IllegalOwner);
+            }
+        }
+        Ok(())
+    }
+
+    /// Take `infos` as they are now as the baseline, after a CPI that was
+    /// checked against its own program.
+    pub(crate) fn refresh(&mut self, infos: &[AccountInfo]) {
+        for pre in &mut self.before {
+            if let Some(info) = infos.iter().find(|info| *info.key == pre.key) {
+                *pre = Snapshot { writable: pre.writable, ..Snapshot::of(info) };
+            }
+        }
+    }
+
+    /// `verify`, and fail with `InvalidArgument` (the runtime's
+    /// `UnbalancedInstruction`) if lamports were made or burned.
+    pub(crate) fn finish(&self, infos: &[AccountInfo]) -> ProgramResult {
+        self.verify(infos)?;
+        let lamports: u128 = self
+            .before
+            .iter()
+            .filter_map(|pre| infos.iter().find(|info| *info.key == pre.key))
+            .map(|info| info.lamports() as u128)
+            .sum();
+        if lamports != self.lamports {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+//! The aligned input layout the BPF loader hands to `entrypoint::deserialize`.
+
+use std::collections::HashMap;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::{self, ProgramResult, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER};
+use anchor_lang::solana_program::instruction::Instruction;
+
+use crate::{stubs, Account, Entrypoint};
+
+const KEY_OFFSET: usize = 8;
+const OWNER_OFFSET: usize = 40;
+const LAMPORTS_OFFSET: usize = 72;
+const DATA_LEN_OFFSET: usize = 80;
+const DATA_OFFSET: usize = 88;
+
+struct Slot {
+    key: Pubkey,
+    offset: usize,
+    writable: bool,
+}
+
+/// Serialize `ix`'s accounts, run `entry` over them, and copy writable state back.
+pub(crate) fn invoke(entry: Entrypoint, ix: &Instruction, accounts: &mut HashMap<Pubkey, Account>) -> ProgramResult {
+    let mut bytes = Vec::new();
+    let mut slots: Vec<Slot> = Vec::new();
+
+    bytes.extend_from_slice(&(ix.accounts.len() as u64).to_le_bytes());
+    for (i, meta) in ix.accounts.iter().enumerate() {
+        if let Some(first) = ix.accounts[..i].iter().position(|m| m.pubkey == meta.pubkey) {
+            // Duplicates point back at the first occurrence
+            let writable = &mut slots.iter_mut().find(|s| s.key == meta.pubkey).unwrap().writable;
+            *writable |= meta.is_writable;
+            bytes.push(first as u8);
+            bytes.extend_from_slice(&[0u8; 7]);
+            continue;
+        }
+        let account = accounts.entry(meta.pubkey).or_default();
+        let offset = bytes.len();
+        bytes.push(NON_DUP_MARKER);
+        bytes.push(meta.is_signer as u8);
+        bytes.push(meta.is_writable as u8);
+        bytes.push(account.executable as u8);
+        bytes.extend_from_slice(&[0u8; 4]);
+        bytes.extend_from_slice(meta.pubkey.as_ref());
+        bytes.extend_from_slice(account.owner.as_ref());
+        bytes.extend_from_slice(&account.lamports.to_le_bytes());
+        bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&account.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        bytes.resize(bytes.len().next_multiple_of(8), 0);
+        bytes.extend_from_slice(&0u64.to_le_bytes()); // rent_epoch
+        slots.push(Slot { key: meta.pubkey, offset, writable: meta.is_writable });
+    }
+    bytes.extend_from_slice(&(ix.data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&ix.data);
+    bytes.extend_from_slice(ix.program_id.as_ref());
+
+    // u64 backing storage keeps the buffer 8-byte aligned like the loader's
+    let mut storage = vec![0u64; bytes.len().div_ceil(8)];
+    let buffer = storage.as_mut_ptr() as *mut u8;
+    // SAFETY: `storage` holds at least `bytes.len()` bytes
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, bytes.len()) };
+
+    let result = {
+        // SAFETY: the buffer follows the loader's layout and outlives the infos
+        let (program_id, infos, data) = unsafe { entrypoint::deserialize(buffer) };
+        stubs::enter(&infos);
+        let result = entry(program_id, &infos, data);
+        let checked = stubs::exit(&infos);
+        result.and(checked)
+    };
+    result?;
+
+    // SAFETY: same buffer, re-read after the program may have resized data
+    let read = |offset: usize, len: usize| unsafe { std::slice::from_raw_parts(buffer.add(offset), len) };
+    for slot in slots.iter().filter(|s| s.writable) {
+        debug_assert_eq!(read(slot.offset + KEY_OFFSET, 32), slot.key.as_ref());
+        let owner = Pubkey::try_from(read(slot.offset + OWNER_OFFSET, 32)).unwrap();
+        let lamports = u64::from_le_bytes(read(slot.offset + LAMPORTS_OFFSET, 8).try_into().unwrap());
+        let data_len = u64::from_le_bytes(read(slot.offset + DATA_LEN_OFFSET, 8).try_into().unwrap()) as usize;
+        let data = read(slot.offset + DATA_OFFSET, data_len).to_vec();
+
+        let account = accounts.get_mut(&slot.key).unwrap();
+        account.owner = owner;
+        account.lamports = lamports;
+        account.data = data;
+    }
+    // Like the runtime, accounts drained of lamports are gone after the instruction
+    accounts.retain(|_, account| account.lamports > 0 || account.executable);
+    Ok(())
+}
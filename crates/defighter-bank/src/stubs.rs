@@ -0,0 +1,182 @@
+//! Off-chain syscall handlers. State lives in a thread-local context so
+//! parallel tests each see only their own bank.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Once;
+
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use anchor_lang::solana_program::rent::Rent;
+use solana_sdk_ids::system_program;
+
+use crate::rules::Frame;
+use crate::{system, Entrypoint};
+
+const SUCCESS: u64 = 0;
+
+struct Context {
+    /// Programs currently executing, innermost last
+    program_stack: Vec<Pubkey>,
+    /// Account changes allowed so far, one per entry in `program_stack` once
+    /// its accounts are known
+    frames: Vec<Frame>,
+    programs: HashMap<Pubkey, Entrypoint>,
+    clock: Clock,
+    events: Vec<Vec<u8>>,
+    return_data: Option<(Pubkey, Vec<u8>)>,
+}
+
+/// What an instruction left behind besides account changes.
+pub(crate) struct Output {
+    pub events: Vec<Vec<u8>>,
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<Context>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn install() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        set_syscall_stubs(Box::new(BankStubs));
+    });
+}
+
+/// Run `f` as `program_id` and return the events and return data it produced.
+pub(crate) fn with_context(
+    program_id: Pubkey,
+    clock: Clock,
+    programs: HashMap<Pubkey, Entrypoint>,
+    f: impl FnOnce() -> ProgramResult,
+) -> Result<Output, ProgramError> {
+    CONTEXT.with(|c| {
+        *c.borrow_mut() = Some(Context {
+            program_stack: vec![program_id],
+            frames: vec![],
+            programs,
+            clock,
+            events: vec![],
+            return_data: None,
+        });
+    });
+    let result = f();
+    let context = CONTEXT.with(|c| c.borrow_mut().take()).expect("bank context");
+    result.map(|()| Output { events: context.events, return_data: context.return_data })
+}
+
+/// Start checking the top-level program's changes to `infos`.
+pub(crate) fn enter(infos: &[AccountInfo]) {
+    with(|c| {
+        let frame = Frame::new(*c.program_stack.last().unwrap(), infos);
+        c.frames.push(frame);
+    });
+}
+
+/// Check everything the top-level program did to `infos`.
+pub(crate) fn exit(infos: &[AccountInfo]) -> ProgramResult {
+    with(|c| c.frames.pop().expect("entered frame")).finish(infos)
+}
+
+fn with<R>(f: impl FnOnce(&mut Context) -> R) -> R {
+    CONTEXT.with(|c| f(c.borrow_mut().as_mut().expect("syscall outside of a bank transaction")))
+}
+
+struct BankStubs;
+
+impl SyscallStubs for BankStubs {
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let (caller, entry) = with(|c| {
+            let caller = *c.program_stack.last().unwrap();
+            let entry = if instruction.program_id == system_program::ID {
+                Some(system::entry as Entrypoint)
+            } else {
+                c.programs.get(&instruction.program_id).copied()
+            };
+            (caller, entry)
+        });
+        let entry = entry.ok_or(ProgramError::IncorrectProgramId)?;
+
+        let pda_signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+
+        let mut infos = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            let mut info = account_infos
+                .iter()
+                .find(|info| *info.key == meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?
+                .clone();
+            let signed = info.is_signer || pda_signers.contains(info.key);
+            if meta.is_signer && !signed {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if meta.is_writable && !info.is_writable {
+                return Err(ProgramError::InvalidArgument);
+            }
+            info.is_signer = meta.is_signer;
+            info.is_writable = meta.is_writable;
+            infos.push(info);
+        }
+
+        // The caller answers for what it changed before the call; the callee
+        // for what it changes during it
+        with(|c| c.frames.last().unwrap().verify(&infos))?;
+        with(|c| {
+            c.program_stack.push(instruction.program_id);
+            c.frames.push(Frame::new(instruction.program_id, &infos));
+        });
+        // SAFETY: `entry` only borrows the infos for the duration of the call;
+        // the invariant `'info` just can't be shortened to this stack frame.
+        let infos: &[AccountInfo] = unsafe { std::mem::transmute(infos.as_slice()) };
+        let result = entry(&instruction.program_id, infos, &instruction.data);
+        let frame = with(|c| {
+            c.program_stack.pop();
+            c.frames.pop().unwrap()
+        });
+        let result = result.and_then(|()| frame.finish(infos));
+        with(|c| c.frames.last_mut().unwrap().refresh(infos));
+        result
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = with(|c| c.clock.clone());
+        // SAFETY: the sysvar getter passes a pointer to a `Clock`
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        // SAFETY: the sysvar getter passes a pointer to a `Rent`
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        with(|c| c.return_data.clone())
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        with(|c| {
+            let program = *c.program_stack.last().unwrap();
+            c.return_data = (!data.is_empty()).then(|| (program, data.to_vec()));
+        });
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        with(|c| c.events.push(fields.concat()));
+    }
+}
@@ -0,0 +1,109 @@
+//! The subset of the system program Anchor programs lean on: account
+//! creation (`init`), transfers, allocation and assignment.
+
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_error::ProgramError;
+use solana_sdk_ids::system_program;
+
+const CREATE_ACCOUNT: u32 = 0;
+const ASSIGN: u32 = 1;
+const TRANSFER: u32 = 2;
+const ALLOCATE: u32 = 8;
+
+/// `SystemError::AccountAlreadyInUse`
+const ACCOUNT_ALREADY_IN_USE: u32 = 0;
+/// `SystemError::ResultWithNegativeLamports`
+const NEGATIVE_LAMPORTS: u32 = 1;
+
+pub(crate) fn entry<'info>(_program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> ProgramResult {
+    let mut reader = Reader(data);
+    match reader.u32()? {
+        CREATE_ACCOUNT => {
+            let lamports = reader.u64()?;
+            let space = reader.u64()?;
+            let owner = reader.pubkey()?;
+            let [from, to] = two(accounts)?;
+            if to.lamports() > 0 || !to.data_is_empty() || *to.owner != system_program::ID {
+                return Err(ProgramError::Custom(ACCOUNT_ALREADY_IN_USE));
+            }
+            transfer(from, to, lamports)?;
+            allocate(to, space)?;
+            signed(to)?;
+            to.assign(&owner);
+            Ok(())
+        }
+        ASSIGN => {
+            let owner = reader.pubkey()?;
+            let account = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            signed(account)?;
+            account.assign(&owner);
+            Ok(())
+        }
+        TRANSFER => {
+            let lamports = reader.u64()?;
+            let [from, to] = two(accounts)?;
+            transfer(from, to, lamports)
+        }
+        ALLOCATE => {
+            let space = reader.u64()?;
+            allocate(accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?, space)
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn two<'a, 'info>(accounts: &'a [AccountInfo<'info>]) -> Result<[&'a AccountInfo<'info>; 2], ProgramError> {
+    match accounts {
+        [a, b, ..] => Ok([a, b]),
+        _ => Err(ProgramError::NotEnoughAccountKeys),
+    }
+}
+
+fn signed(account: &AccountInfo) -> ProgramResult {
+    if account.is_signer { Ok(()) } else { Err(ProgramError::MissingRequiredSignature) }
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    signed(from)?;
+    if *from.owner != system_program::ID || !from.data_is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let remaining = from.lamports().checked_sub(lamports).ok_or(ProgramError::Custom(NEGATIVE_LAMPORTS))?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fn allocate(account: &AccountInfo, space: u64) -> ProgramResult {
+    signed(account)?;
+    if !account.data_is_empty() || *account.owner != system_program::ID {
+        return Err(ProgramError::Custom(ACCOUNT_ALREADY_IN_USE));
+    }
+    account.resize(space as usize)
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], ProgramError> {
+        if self.0.len() < N {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (head, rest) = self.0.split_at(N);
+        self.0 = rest;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, ProgramError> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, ProgramError> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey, ProgramError> {
+        self.take().map(Pubkey::new_from_array)
+    }
+}
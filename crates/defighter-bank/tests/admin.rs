@@ -0,0 +1,367 @@
+//! Two-step admin transfer and M-of-N admin sets.
+
+use anchor_lang::prelude::*;
+use anchor_lang::error::ErrorCode;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::events::{
+    AdminAccepted, AdminProposed, AdminSetCreated, ConfigChangeApproved, ConfigChangeCancelled, ConfigChangeExecuted,
+    ConfigChangeQueued, ConfigUpdated,
+};
+use defighter::state::admin::{AdminSet, ConfigProposal, ProposalAction};
+use defighter::state::config::{BalanceConfig, ConfigUpdate, PendingConfig};
+use defighter::state::item::{GearSlot, GearStats, Item, ItemParams, Rarity};
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{apply_config_change, config_pda, ix, item_pda, pending_config_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+fn config(bank: &Bank) -> BalanceConfig {
+    bank.get_anchor_account(&config_pda()).unwrap()
+}
+
+fn admin_set_pda(id: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"admin_set", &id.to_le_bytes()], &defighter::ID).0
+}
+
+fn proposal_pda(admin_set: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"proposal", admin_set.as_ref(), &id.to_le_bytes()], &defighter::ID).0
+}
+
+fn propose_admin(bank: &mut Bank, admin: Pubkey, new_admin: Pubkey) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ProposeAdmin { config: config_pda(), admin },
+            defighter::instruction::ProposeAdmin { new_admin },
+        )],
+        &[admin],
+    )
+}
+
+fn accept_admin(bank: &mut Bank, new_admin: Pubkey) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::AcceptAdmin { config: config_pda(), new_admin },
+            defighter::instruction::AcceptAdmin {},
+        )],
+        &[new_admin],
+    )
+}
+
+fn update(bank: &mut Bank, admin: Pubkey, update: ConfigUpdate) -> TxResult {
+    bank.airdrop(&admin, 1_000_000_000);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::UpdateConfig {
+                config: config_pda(),
+                pending_config: pending_config_pda(),
+                admin,
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::UpdateConfig { update },
+        )],
+        &[admin],
+    )
+}
+
+/// Hand the config to a fresh 2-of-3 set and return its address and members.
+fn two_of_three(bank: &mut Bank, admin: Pubkey) -> (Pubkey, [Pubkey; 3]) {
+    let members = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    for m in &members {
+        bank.airdrop(m, 1_000_000_000);
+    }
+    let admin_set = admin_set_pda(0);
+    let meta = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::CreateAdminSet {
+                    config: config_pda(),
+                    admin_set,
+                    admin,
+                    current_set: None,
+                    proposal: None,
+                    system_program: system_program::ID,
+                },
+                defighter::instruction::CreateAdminSet { id: 0, members: members.to_vec(), threshold: 2 },
+            )],
+            &[admin],
+        )
+        .unwrap();
+    let created = meta.events::<AdminSetCreated>();
+    assert_eq!((created[0].admin_set, created[0].threshold), (admin_set, 2));
+    (admin_set, members)
+}
+
+fn propose(bank: &mut Bank, admin_set: Pubkey, proposer: Pubkey, action: ProposalAction) -> std::result::Result<Pubkey, TransactionError> {
+    let set: AdminSet = bank.get_anchor_account(&admin_set).unwrap();
+    let proposal = proposal_pda(&admin_set, set.proposal_count);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ProposeConfigChange {
+                admin_set,
+                proposal,
+                proposer,
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::ProposeConfigChange { action },
+        )],
+        &[proposer],
+    )?;
+    Ok(proposal)
+}
+
+fn approve(bank: &mut Bank, admin_set: Pubkey, proposal: Pubkey, member: Pubkey) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ApproveConfigChange { admin_set, proposal, member },
+            defighter::instruction::ApproveConfigChange {},
+        )],
+        &[member],
+    )
+}
+
+fn execute(bank: &mut Bank, admin_set: Pubkey, proposal: Pubkey) -> TxResult {
+    let payer = Pubkey::new_unique();
+    bank.airdrop(&payer, 1_000_000_000);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ExecuteConfigChange {
+                config: config_pda(),
+                admin_set,
+                proposal,
+                pending_config: pending_config_pda(),
+                payer,
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::ExecuteConfigChange {},
+        )],
+        &[payer],
+    )
+}
+
+#[test]
+fn admin_transfer_takes_two_steps() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let next = Pubkey::new_unique();
+    let stranger = Pubkey::new_unique();
+
+    let meta = propose_admin(&mut bank, admin, next).unwrap();
+    assert_eq!(meta.events::<AdminProposed>()[0].pending_admin, next);
+    // Nothing changes hands until the candidate signs
+    assert_eq!(config(&bank).admin, admin);
+    let err = accept_admin(&mut bank, stranger).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotPendingAdmin.into()));
+    let err = propose_admin(&mut bank, stranger, stranger).unwrap_err();
+    assert_eq!(err.custom_code(), Some(ErrorCode::ConstraintHasOne.into()));
+
+    let meta = accept_admin(&mut bank, next).unwrap();
+    let accepted = meta.events::<AdminAccepted>();
+    assert_eq!((accepted[0].old_admin, accepted[0].new_admin), (admin, next));
+    let cfg = config(&bank);
+    assert_eq!((cfg.admin, cfg.pending_admin), (next, None));
+    // The offer is spent
+    let err = accept_admin(&mut bank, next).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotPendingAdmin.into()));
+    update(&mut bank, next, ConfigUpdate::XpBase(20)).unwrap();
+    assert!(update(&mut bank, admin, ConfigUpdate::XpBase(30)).is_err());
+}
+
+#[test]
+fn admin_set_rejects_bad_membership() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let a = Pubkey::new_unique();
+    for (members, threshold) in [(vec![a], 2), (vec![a, a], 1), (vec![], 0)] {
+        let err = bank
+            .process_transaction(
+                &[ix(
+                    defighter::accounts::CreateAdminSet {
+                        config: config_pda(),
+                        admin_set: admin_set_pda(0),
+                        admin,
+                        current_set: None,
+                        proposal: None,
+                        system_program: system_program::ID,
+                    },
+                    defighter::instruction::CreateAdminSet { id: 0, members, threshold },
+                )],
+                &[admin],
+            )
+            .unwrap_err();
+        assert_eq!(err.custom_code(), Some(CustomError::InvalidAdminSet.into()));
+    }
+    assert_eq!(config(&bank).admin, admin);
+}
+
+#[test]
+fn multisig_config_change() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let (admin_set, [m0, m1, m2]) = two_of_three(&mut bank, admin);
+    assert_eq!(config(&bank).admin, admin_set);
+
+    // The old key no longer has a say
+    let err = update(&mut bank, admin, ConfigUpdate::XpBase(50)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(ErrorCode::ConstraintHasOne.into()));
+    let err = propose(&mut bank, admin_set, admin, ProposalAction::Update(ConfigUpdate::XpBase(50))).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotAdminSetMember.into()));
+
+    let proposal = propose(&mut bank, admin_set, m0, ProposalAction::Update(ConfigUpdate::XpBase(50))).unwrap();
+    let state: ConfigProposal = bank.get_anchor_account(&proposal).unwrap();
+    assert_eq!((state.id, state.proposer, state.approval_count()), (0, m0, 1));
+
+    // The proposer's approval already counts, but one is not enough
+    let err = approve(&mut bank, admin_set, proposal, m0).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::AlreadyApproved.into()));
+    let err = approve(&mut bank, admin_set, proposal, admin).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotAdminSetMember.into()));
+    let err = execute(&mut bank, admin_set, proposal).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotEnoughApprovals.into()));
+
+    let meta = approve(&mut bank, admin_set, proposal, m2).unwrap();
+    let approved = meta.events::<ConfigChangeApproved>();
+    assert_eq!((approved[0].member, approved[0].approvals), (m2, 2));
+
+    let before = config(&bank).params();
+    let meta = execute(&mut bank, admin_set, proposal).unwrap();
+    let executed = meta.events::<ConfigChangeExecuted>();
+    assert_eq!(executed[0].action, ProposalAction::Update(ConfigUpdate::XpBase(50)));
+    // Queued behind the timelock like any other change
+    assert_eq!(meta.events::<ConfigChangeQueued>()[0].admin, admin_set);
+    assert_eq!(config(&bank).xp_base, 10);
+    let meta = apply_config_change(&mut bank);
+    assert_eq!(config(&bank).xp_base, 50);
+    let updated = meta.events::<ConfigUpdated>();
+    assert_eq!((updated[0].admin, updated[0].old, updated[0].new.xp_base), (admin_set, before, 50));
+
+    let err = execute(&mut bank, admin_set, proposal).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::ProposalExecuted.into()));
+    let err = approve(&mut bank, admin_set, proposal, m1).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::ProposalExecuted.into()));
+
+    // Values are validated when the change lands, not when it's proposed
+    let bad = propose(&mut bank, admin_set, m1, ProposalAction::Update(ConfigUpdate::MaxLevel(0))).unwrap();
+    approve(&mut bank, admin_set, bad, m2).unwrap();
+    let err = execute(&mut bank, admin_set, bad).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidConfig.into()));
+
+    // Cancelling a queued change also takes a proposal
+    let queue = propose(&mut bank, admin_set, m1, ProposalAction::Update(ConfigUpdate::XpBase(5))).unwrap();
+    approve(&mut bank, admin_set, queue, m0).unwrap();
+    execute(&mut bank, admin_set, queue).unwrap();
+    let cancel = propose(&mut bank, admin_set, m2, ProposalAction::CancelChange).unwrap();
+    approve(&mut bank, admin_set, cancel, m0).unwrap();
+    let meta = execute(&mut bank, admin_set, cancel).unwrap();
+    assert_eq!(meta.events::<ConfigChangeCancelled>()[0].admin, admin_set);
+    let pending: PendingConfig = bank.get_anchor_account(&pending_config_pda()).unwrap();
+    assert!(!pending.active);
+}
+
+#[test]
+fn admin_set_hands_control_back() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let (admin_set, [m0, m1, _]) = two_of_three(&mut bank, admin);
+    let next = Pubkey::new_unique();
+
+    let proposal = propose(&mut bank, admin_set, m1, ProposalAction::ProposeAdmin(next)).unwrap();
+    approve(&mut bank, admin_set, proposal, m0).unwrap();
+    let meta = execute(&mut bank, admin_set, proposal).unwrap();
+    assert_eq!(meta.events::<AdminProposed>()[0].admin, admin_set);
+    assert_eq!(config(&bank).pending_admin, Some(next));
+
+    accept_admin(&mut bank, next).unwrap();
+    assert_eq!(config(&bank).admin, next);
+    update(&mut bank, next, ConfigUpdate::XpBase(40)).unwrap();
+    apply_config_change(&mut bank);
+
+    // With the config gone, leftover proposals can no longer execute
+    let stale = propose(&mut bank, admin_set, m0, ProposalAction::Update(ConfigUpdate::XpBase(60))).unwrap();
+    approve(&mut bank, admin_set, stale, m1).unwrap();
+    let err = execute(&mut bank, admin_set, stale).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::AdminSetNotInControl.into()));
+}
+
+fn create_item(bank: &mut Bank, payer: Pubkey, admin_set: Pubkey, proposal: Pubkey, id: u32, params: ItemParams) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CreateItem {
+                config: config_pda(),
+                item: item_pda(id),
+                admin: payer,
+                admin_set: Some(admin_set),
+                proposal: Some(proposal),
+                system_program: system_program::ID,
+            },
+            defighter::instruction::CreateItem { id, params },
+        )],
+        &[payer],
+    )
+}
+
+#[test]
+fn admin_set_runs_admin_instructions() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let (admin_set, [m0, m1, m2]) = two_of_three(&mut bank, admin);
+    let params = ItemParams {
+        slot: GearSlot::Weapon,
+        rarity: Rarity::Rare,
+        class: None,
+        stats: GearStats { attack_bps: 100, defense_bps: 0 },
+    };
+
+    let err = common::create_item(&mut bank, admin, 1, params).unwrap_err();
+    assert_eq!(err.custom_code(), Some(ErrorCode::ConstraintHasOne.into()));
+
+    // Runs through its own instruction once approved, for exactly what was approved
+    let action = ProposalAction::CreateItem { id: 1, params };
+    let proposal = propose(&mut bank, admin_set, m0, action).unwrap();
+    let err = create_item(&mut bank, m2, admin_set, proposal, 1, params).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotEnoughApprovals.into()));
+    approve(&mut bank, admin_set, proposal, m1).unwrap();
+    let err = execute(&mut bank, admin_set, proposal).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::WrongProposalAction.into()));
+    let err = create_item(&mut bank, m2, admin_set, proposal, 2, params).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::WrongProposalAction.into()));
+
+    let meta = create_item(&mut bank, m2, admin_set, proposal, 1, params).unwrap();
+    assert_eq!(meta.events::<ConfigChangeExecuted>()[0].action, action);
+    assert_eq!(bank.get_anchor_account::<Item>(&item_pda(1)).unwrap().rarity, Rarity::Rare);
+    assert!(bank.get_anchor_account::<ConfigProposal>(&proposal).unwrap().executed);
+
+    // The set can hand the config on to another set
+    let next = [Pubkey::new_unique()];
+    let action = ProposalAction::CreateAdminSet { id: 1, members_hash: AdminSet::members_hash(&next), threshold: 1 };
+    let handover = propose(&mut bank, admin_set, m1, action).unwrap();
+    approve(&mut bank, admin_set, handover, m2).unwrap();
+    let create_set = |bank: &mut Bank, members: Vec<Pubkey>| {
+        bank.process_transaction(
+            &[ix(
+                defighter::accounts::CreateAdminSet {
+                    config: config_pda(),
+                    admin_set: admin_set_pda(1),
+                    admin: m0,
+                    current_set: Some(admin_set),
+                    proposal: Some(handover),
+                    system_program: system_program::ID,
+                },
+                defighter::instruction::CreateAdminSet { id: 1, members, threshold: 1 },
+            )],
+            &[m0],
+        )
+    };
+    let err = create_set(&mut bank, vec![m0]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::WrongProposalAction.into()));
+    let meta = create_set(&mut bank, next.to_vec()).unwrap();
+    let accepted = meta.events::<AdminAccepted>();
+    assert_eq!((accepted[0].old_admin, accepted[0].new_admin), (admin_set, admin_set_pda(1)));
+    assert_eq!(config(&bank).admin, admin_set_pda(1));
+}
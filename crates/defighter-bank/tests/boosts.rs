@@ -0,0 +1,144 @@
+//! Boosts: admin grants, committing one in the hash, burning on reveal and battle drops.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::events::{BattleResolved, BoostGranted, BoostUsed};
+use defighter::logic::boosted_commitment_hash;
+use defighter::state::config::BalanceConfigParams;
+use defighter::state::consumable::{Boost, Consumables};
+use defighter::state::player::{FighterClass, MoveChoice};
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, consumables_pda, create_player, ix, player_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+const SALT: [u8; 32] = [9; 32];
+
+fn setup(params: BalanceConfigParams) -> (Bank, Pubkey, Pubkey, Pubkey) {
+    let mut bank = common::bank();
+    let admin = common::init_config_with_params(&mut bank, params);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+    (bank, admin, alice, bob)
+}
+
+fn grant(bank: &mut Bank, admin: Pubkey, recipient: Pubkey, boost: Boost, amount: u16) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::GrantBoost {
+                config: config_pda(),
+                consumables: consumables_pda(&recipient),
+                admin,
+                admin_set: None,
+                proposal: None,
+                system_program: system_program::ID,
+            },
+            defighter::instruction::GrantBoost { recipient, boost, amount },
+        )],
+        &[admin],
+    )
+}
+
+fn commit(bank: &mut Bank, battle: Pubkey, who: Pubkey, move_choice: MoveChoice, boost: Option<Boost>) {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CommitMove {
+                battle,
+                player: who,
+                player_account: player_pda(&who),
+                config: config_pda(),
+                clock: sysvar::clock::ID,
+                session: None,
+                character_token: None,
+            },
+            defighter::instruction::CommitMove {
+                commitment: boosted_commitment_hash(move_choice, boost, &SALT, &who, &battle),
+            },
+        )],
+        &[who],
+    )
+    .unwrap();
+}
+
+fn reveal(bank: &mut Bank, battle: Pubkey, who: Pubkey, move_choice: MoveChoice, boost: Option<Boost>) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::RevealMove {
+                battle,
+                player: who,
+                player_account: player_pda(&who),
+                clock: sysvar::clock::ID,
+                consumables: Some(consumables_pda(&who)),
+                session: None,
+                character_token: None,
+            },
+            defighter::instruction::RevealMove { move_choice, salt: SALT, boost },
+        )],
+        &[who],
+    )
+}
+
+fn balance(bank: &Bank, owner: Pubkey, boost: Boost) -> u16 {
+    bank.get_anchor_account::<Consumables>(&consumables_pda(&owner)).unwrap().balances[boost as usize]
+}
+
+#[test]
+fn a_revealed_shield_is_burned_and_blocks_damage() {
+    let (mut bank, admin, alice, bob) = setup(BalanceConfigParams::default());
+    let err = grant(&mut bank, alice, bob, Boost::LiquidityShield, 1).unwrap_err();
+    assert_eq!(err.custom_code(), Some(anchor_lang::error::ErrorCode::ConstraintHasOne.into()));
+    let meta = grant(&mut bank, admin, bob, Boost::LiquidityShield, 1).unwrap();
+    assert_eq!(meta.events::<BoostGranted>()[0].balance, 1);
+
+    let battle = common::initiate_battle(&mut bank, alice, bob, 1, 10);
+    commit(&mut bank, battle, alice, MoveChoice::MemeBomb, None);
+    commit(&mut bank, battle, bob, MoveChoice::ShipIt, Some(Boost::LiquidityShield));
+    // The commitment pins the boost: revealing without it doesn't match
+    let err = reveal(&mut bank, battle, bob, MoveChoice::ShipIt, None).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidReveal.into()));
+
+    reveal(&mut bank, battle, alice, MoveChoice::MemeBomb, None).unwrap();
+    let meta = reveal(&mut bank, battle, bob, MoveChoice::ShipIt, Some(Boost::LiquidityShield)).unwrap();
+    assert_eq!(meta.events::<BoostUsed>()[0].remaining, 0);
+    assert_eq!(balance(&bank, bob, Boost::LiquidityShield), 0);
+
+    let meta = common::resolve_battle(&mut bank, battle, alice, bob);
+    let resolved = &meta.events::<BattleResolved>()[0];
+    // 80 * 0.8 against Bob; Alice still takes the full 125
+    assert_eq!((resolved.challenger_damage, resolved.opponent_damage), (64, 125));
+}
+
+#[test]
+fn boosts_are_only_spent_when_revealed() {
+    let (mut bank, admin, alice, bob) = setup(BalanceConfigParams::default());
+    grant(&mut bank, admin, bob, Boost::AlphaLeak, 1).unwrap();
+
+    // Alice has none to reveal
+    let battle = common::initiate_battle(&mut bank, alice, bob, 1, 10);
+    commit(&mut bank, battle, alice, MoveChoice::MemeBomb, Some(Boost::AlphaLeak));
+    commit(&mut bank, battle, bob, MoveChoice::ShipIt, Some(Boost::AlphaLeak));
+    let err = reveal(&mut bank, battle, alice, MoveChoice::MemeBomb, Some(Boost::AlphaLeak)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NoBoost.into()));
+
+    // Bob never reveals, forfeits, and keeps his boost
+    bank.warp_to_slot(100);
+    common::resolve_battle(&mut bank, battle, alice, bob);
+    assert_eq!(balance(&bank, bob, Boost::AlphaLeak), 1);
+}
+
+#[test]
+fn wins_can_drop_boosts() {
+    let params = BalanceConfigParams { boost_drop_bps: 10_000, ..Default::default() };
+    let (mut bank, _, alice, bob) = setup(params);
+    let meta = common::play_battle(&mut bank, alice, bob, 1, [MoveChoice::MemeBomb, MoveChoice::ShipIt]);
+    let granted = &meta.events::<BoostGranted>()[0];
+    assert_eq!((granted.owner, granted.amount, granted.battle), (bob, 1, Some(common::battle_pda(&alice, &bob, 1))));
+    assert_eq!(balance(&bank, bob, granted.boost), 1);
+    assert_eq!(bank.get_anchor_account::<Consumables>(&consumables_pda(&alice)).unwrap().balances, [0; Boost::COUNT]);
+}
@@ -0,0 +1,354 @@
+//! PvE boss fights, including shared-HP raids.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::events::{BossBattleResolved, XpAwarded};
+use defighter::logic::commitment_hash;
+use defighter::state::battle::BattleState;
+use defighter::state::boss::{Boss, BossBattle, BossParams};
+use defighter::state::config::{BalanceConfig, BalanceConfigParams, ConfigUpdate};
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, create_player, ix, pending_config_pda, player_pda};
+
+fn boss_pda(id: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"boss", &id.to_le_bytes()], &defighter::ID).0
+}
+
+fn battle_pda(boss: &Pubkey, player: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"boss_battle", boss.as_ref(), player.as_ref(), &nonce.to_le_bytes()],
+        &defighter::ID,
+    )
+    .0
+}
+
+/// A VC boss that always plays its basic move.
+fn params(max_hp: u32, shared_hp: bool) -> BossParams {
+    BossParams { class: FighterClass::VC, level: 0, max_hp, special_bps: 0, shared_hp, xp_win: 50, xp_loss: 5 }
+}
+
+fn create_boss(bank: &mut Bank, admin: Pubkey, id: u32, params: BossParams) -> std::result::Result<Pubkey, TransactionError> {
+    let boss = boss_pda(id);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CreateBoss {
+                config: config_pda(),
+                boss,
+                admin,
+                admin_set: None,
+                proposal: None,
+                system_program: system_program::ID,
+            },
+            defighter::instruction::CreateBoss { id, params },
+        )],
+        &[admin],
+    )?;
+    Ok(boss)
+}
+
+fn start(bank: &mut Bank, boss: Pubkey, player: Pubkey, nonce: u64) -> std::result::Result<Pubkey, TransactionError> {
+    start_with_deadlines(bank, boss, player, nonce, 10)
+}
+
+fn start_with_deadlines(
+    bank: &mut Bank,
+    boss: Pubkey,
+    player: Pubkey,
+    nonce: u64,
+    deadline_slots: u64,
+) -> std::result::Result<Pubkey, TransactionError> {
+    let battle = battle_pda(&boss, &player, nonce);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::StartBossBattle {
+                battle,
+                boss,
+                player,
+                player_account: player_pda(&player),
+                config: config_pda(),
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+                character_token: None,
+            },
+            defighter::instruction::StartBossBattle {
+                nonce,
+                commit_deadline_slots: deadline_slots,
+                reveal_deadline_slots: deadline_slots,
+            },
+        )],
+        &[player],
+    )?;
+    Ok(battle)
+}
+
+fn commit(bank: &mut Bank, battle: Pubkey, player: Pubkey, move_choice: MoveChoice, salt: [u8; 32]) {
+    try_commit(bank, battle, player, move_choice, salt).unwrap();
+}
+
+fn try_commit(
+    bank: &mut Bank,
+    battle: Pubkey,
+    player: Pubkey,
+    move_choice: MoveChoice,
+    salt: [u8; 32],
+) -> std::result::Result<TransactionMeta, TransactionError> {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CommitBossMove {
+                battle,
+                player,
+                player_account: player_pda(&player),
+                config: config_pda(),
+                clock: sysvar::clock::ID,
+                session: None,
+                character_token: None,
+            },
+            defighter::instruction::CommitBossMove { commitment: commitment_hash(move_choice, &salt, &player, &battle) },
+        )],
+        &[player],
+    )
+}
+
+fn reveal(
+    bank: &mut Bank,
+    battle: Pubkey,
+    boss: Pubkey,
+    player: Pubkey,
+    move_choice: MoveChoice,
+    salt: [u8; 32],
+) -> std::result::Result<TransactionMeta, TransactionError> {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::RevealBossMove {
+                battle,
+                boss,
+                player,
+                player_account: player_pda(&player),
+                slot_hashes: sysvar::slot_hashes::ID,
+                clock: sysvar::clock::ID,
+                session: None,
+                character_token: None,
+            },
+            defighter::instruction::RevealBossMove { move_choice, salt },
+        )],
+        &[player],
+    )
+}
+
+fn resolve(bank: &mut Bank, battle: Pubkey, boss: Pubkey, player: Pubkey) -> std::result::Result<TransactionMeta, TransactionError> {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ResolveBossBattle {
+                battle,
+                boss,
+                player_account: player_pda(&player),
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::ResolveBossBattle {},
+        )],
+        &[],
+    )
+}
+
+/// Start, commit, reveal a slot later and resolve a MemeBomb against `boss`.
+fn fight(bank: &mut Bank, boss: Pubkey, player: Pubkey, nonce: u64) -> BossBattleResolved {
+    let battle = start(bank, boss, player, nonce).unwrap();
+    commit(bank, battle, player, MoveChoice::MemeBomb, [nonce as u8; 32]);
+    bank.warp_to_slot(bank.slot() + 2);
+    reveal(bank, battle, boss, player, MoveChoice::MemeBomb, [nonce as u8; 32]).unwrap();
+    resolve(bank, battle, boss, player).unwrap().events::<BossBattleResolved>().remove(0)
+}
+
+#[test]
+fn only_admin_creates_valid_bosses() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let stranger = Pubkey::new_unique();
+    bank.airdrop(&stranger, 1_000_000_000);
+
+    let err = create_boss(&mut bank, stranger, 1, params(150, false)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(ErrorCode::ConstraintHasOne.into()));
+    let err = create_boss(&mut bank, admin, 1, params(0, false)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidBossParams.into()));
+    let err = create_boss(&mut bank, admin, 1, params(100_000, false)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidBossParams.into()));
+
+    let boss = create_boss(&mut bank, admin, 1, params(100_000, true)).unwrap();
+    let state: Boss = bank.get_anchor_account(&boss).unwrap();
+    assert_eq!(state.hp, 100_000);
+}
+
+#[test]
+fn regular_boss_fight_awards_boss_xp() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let boss = create_boss(&mut bank, admin, 1, params(150, false)).unwrap();
+    let player = Pubkey::new_unique();
+    create_player(&mut bank, player, FighterClass::Shitposter);
+
+    // MemeBomb deals 125 (150 -> 25); the boss's SeriesACannon deals 80 (200 -> 120)
+    let resolved = fight(&mut bank, boss, player, 1);
+    assert!(resolved.player_won);
+    assert_eq!(resolved.boss_move, Some(MoveChoice::SeriesACannon));
+    assert_eq!((resolved.player_damage, resolved.boss_damage, resolved.boss_hp), (125, 80, 25));
+
+    let p: Player = bank.get_anchor_account(&player_pda(&player)).unwrap();
+    assert_eq!(p.xp, 50);
+    // Regular bosses heal between fights
+    let state: Boss = bank.get_anchor_account(&boss).unwrap();
+    assert_eq!((state.hp, state.fights), (150, 1));
+}
+
+#[test]
+fn raid_pool_is_shared_until_the_killing_blow() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let boss = create_boss(&mut bank, admin, 7, params(200, true)).unwrap();
+    let [first, second, third] = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    for player in [first, second, third] {
+        create_player(&mut bank, player, FighterClass::Shitposter);
+    }
+
+    let resolved = fight(&mut bank, boss, first, 1);
+    assert!(!resolved.player_won);
+    assert_eq!(resolved.boss_hp, 75);
+    let resolved = fight(&mut bank, boss, second, 2);
+    assert!(resolved.player_won);
+    assert_eq!(resolved.boss_hp, 0);
+
+    let state: Boss = bank.get_anchor_account(&boss).unwrap();
+    assert_eq!(state.defeated_by, Some(second));
+    let xp = |bank: &Bank, player: Pubkey| bank.get_anchor_account::<Player>(&player_pda(&player)).unwrap().xp;
+    assert_eq!((xp(&bank, first), xp(&bank, second)), (5, 50));
+
+    let err = start(&mut bank, boss, third, 3).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::BossDefeated.into()));
+}
+
+#[test]
+fn boss_fight_keeps_the_rules_it_started_with() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let boss = create_boss(&mut bank, admin, 1, params(150, false)).unwrap();
+    let player = Pubkey::new_unique();
+    create_player(&mut bank, player, FighterClass::Shitposter);
+
+    // Queue ten times the base damage to land mid-fight
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::UpdateConfig {
+                config: config_pda(),
+                pending_config: pending_config_pda(),
+                admin,
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::UpdateConfig { update: ConfigUpdate::BaseDamage(1_000) },
+        )],
+        &[admin],
+    )
+    .unwrap();
+    let effective = bank.slot() + BalanceConfig::CONFIG_DELAY_SLOTS;
+    bank.warp_to_slot(effective - 2);
+    let battle = start(&mut bank, boss, player, 1).unwrap();
+    commit(&mut bank, battle, player, MoveChoice::MemeBomb, [1; 32]);
+    bank.warp_to_slot(effective);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ApplyConfigChange {
+                config: config_pda(),
+                pending_config: pending_config_pda(),
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::ApplyConfigChange {},
+        )],
+        &[],
+    )
+    .unwrap();
+    assert_eq!(bank.get_anchor_account::<BalanceConfig>(&config_pda()).unwrap().base_damage, 1_000);
+
+    reveal(&mut bank, battle, boss, player, MoveChoice::MemeBomb, [1; 32]).unwrap();
+    let resolved = resolve(&mut bank, battle, boss, player).unwrap().events::<BossBattleResolved>().remove(0);
+    // Same exchange as `regular_boss_fight_awards_boss_xp`
+    assert_eq!((resolved.player_damage, resolved.boss_damage), (125, 80));
+    let state: BossBattle = bank.get_anchor_account(&battle).unwrap();
+    assert_eq!(state.rules, BalanceConfigParams::default());
+}
+
+#[test]
+fn boss_move_waits_for_the_next_slot_hash() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let boss = create_boss(&mut bank, admin, 1, params(150, false)).unwrap();
+    let player = Pubkey::new_unique();
+    create_player(&mut bank, player, FighterClass::Shitposter);
+
+    let err = start_with_deadlines(&mut bank, boss, player, 1, 251).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidDeadlines.into()));
+
+    let battle = start(&mut bank, boss, player, 1).unwrap();
+    bank.warp_to_slot(5);
+    commit(&mut bank, battle, player, MoveChoice::MemeBomb, [1; 32]);
+    // Nothing has landed after the commit slot yet
+    bank.warp_to_slot(6);
+    let err = reveal(&mut bank, battle, boss, player, MoveChoice::MemeBomb, [1; 32]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::SlotHashUnavailable.into()));
+
+    bank.warp_to_slot(9);
+    reveal(&mut bank, battle, boss, player, MoveChoice::MemeBomb, [1; 32]).unwrap();
+    let state: BossBattle = bank.get_anchor_account(&battle).unwrap();
+    assert_eq!(state.slot_hash, Bank::slot_hash(6));
+}
+
+#[test]
+fn a_sold_character_commits_only_for_the_holder() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let boss = create_boss(&mut bank, admin, 1, params(150, false)).unwrap();
+    let player = Pubkey::new_unique();
+    create_player(&mut bank, player, FighterClass::Shitposter);
+    let battle = start(&mut bank, boss, player, 1).unwrap();
+
+    // Tokenized mid-fight and the token sold on: the old wallet can't commit
+    let key = player_pda(&player);
+    let mut state: Player = bank.get_anchor_account(&key).unwrap();
+    state.character_mint = Some(Pubkey::new_unique());
+    let mut account = bank.get_account(&key).unwrap().clone();
+    account.data.clear();
+    state.try_serialize(&mut account.data).unwrap();
+    bank.set_account(key, account);
+    let err = try_commit(&mut bank, battle, player, MoveChoice::MemeBomb, [1; 32]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NftNotHeld.into()));
+}
+
+#[test]
+fn unrevealed_fight_is_a_costly_loss() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let boss = create_boss(&mut bank, admin, 1, params(150, false)).unwrap();
+    let player = Pubkey::new_unique();
+    create_player(&mut bank, player, FighterClass::Shitposter);
+    fight(&mut bank, boss, player, 1);
+    assert_eq!(bank.get_anchor_account::<Player>(&player_pda(&player)).unwrap().xp, 50);
+
+    let battle = start(&mut bank, boss, player, 2).unwrap();
+    commit(&mut bank, battle, player, MoveChoice::ShipIt, [1; 32]);
+    let err = resolve(&mut bank, battle, boss, player).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotReadyToResolve.into()));
+
+    bank.warp_to_slot(bank.slot() + 21);
+    let meta = resolve(&mut bank, battle, boss, player).unwrap();
+    let resolved = &meta.events::<BossBattleResolved>()[0];
+    assert!(!resolved.player_won);
+    assert_eq!(resolved.boss_move, None);
+    // Walking away costs the XP a revealed loss would have paid
+    assert_eq!(meta.events::<XpAwarded>()[0].delta, -5);
+    let state: BossBattle = bank.get_anchor_account(&battle).unwrap();
+    assert!(state.state == BattleState::Resolved);
+    assert_eq!(bank.get_anchor_account::<Player>(&player_pda(&player)).unwrap().xp, 45);
+}
@@ -0,0 +1,210 @@
+//! Tokenized characters: binding a Player to a 1/1 and claiming it from another wallet.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::events::PlayerTransferred;
+use defighter::logic::nft::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use defighter::state::customization::{CharacterCustomizationDataV1, CharacterCustomizationV1, CHARACTER_CUSTOM_SEED};
+use defighter::state::nft::CharacterMint;
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter_bank::{Account, Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, create_player, inventory_pda, ix, next_character_pda, player_pda, profile_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+fn customization_pda(owner: &Pubkey) -> Pubkey {
+    look_pda(&player_pda(owner))
+}
+
+fn look_pda(player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CHARACTER_CUSTOM_SEED, player.as_ref()], &defighter::ID).0
+}
+
+fn character_mint_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"character_mint", mint.as_ref()], &defighter::ID).0
+}
+
+/// An initialized 0-decimal mint with `supply`, its authority revoked unless `mintable`
+fn set_mint(bank: &mut Bank, key: Pubkey, supply: u64, mintable: bool) {
+    let mut data = vec![0; 82];
+    if mintable {
+        data[0] = 1;
+        data[4..36].copy_from_slice(Pubkey::new_unique().as_ref());
+    }
+    data[36..44].copy_from_slice(&supply.to_le_bytes());
+    data[45] = 1;
+    bank.set_account(key, Account { lamports: 1_461_600, data, owner: TOKEN_PROGRAM_ID, executable: false });
+}
+
+fn set_token_account(bank: &mut Bank, key: Pubkey, mint: Pubkey, owner: Pubkey) {
+    let mut data = vec![0; 165];
+    data[0..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&1u64.to_le_bytes());
+    data[108] = 1;
+    bank.set_account(key, Account { lamports: 2_039_280, data, owner: TOKEN_PROGRAM_ID, executable: false });
+}
+
+fn tokenize(bank: &mut Bank, owner: Pubkey, mint: Pubkey, token: Pubkey) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::TokenizePlayer {
+                player: player_pda(&owner),
+                character_mint: character_mint_pda(&mint),
+                mint,
+                token_account: token,
+                authority: owner,
+                system_program: system_program::ID,
+            },
+            defighter::instruction::TokenizePlayer {},
+        )],
+        &[owner],
+    )
+}
+
+fn claim(bank: &mut Bank, seller: Pubkey, buyer: Pubkey, mint: Pubkey, token: Pubkey, with_customization: bool) -> TxResult {
+    bank.airdrop(&buyer, 1_000_000_000);
+    let new_player = next_character_pda(bank, &buyer);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ClaimPlayer {
+                character_mint: character_mint_pda(&mint),
+                old_player: player_pda(&seller),
+                old_profile: profile_pda(&seller),
+                new_profile: profile_pda(&buyer),
+                new_player,
+                old_customization: customization_pda(&seller),
+                new_customization: with_customization.then(|| look_pda(&new_player)),
+                old_inventory: inventory_pda(&seller),
+                token_account: token,
+                old_authority: seller,
+                new_authority: buyer,
+                config: config_pda(),
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::ClaimPlayer {},
+        )],
+        &[buyer],
+    )
+}
+
+fn player(bank: &Bank, owner: Pubkey) -> Player {
+    bank.get_anchor_account(&player_pda(&owner)).unwrap()
+}
+
+/// Alice, with a custom look and one battle won against Bob, and her 1/1 mint and token account.
+fn setup() -> (Bank, Pubkey, Pubkey, Pubkey, Pubkey) {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    create_player(&mut bank, alice, FighterClass::Builder);
+    create_player(&mut bank, bob, FighterClass::Shitposter);
+    common::play_battle(&mut bank, alice, bob, 1, [MoveChoice::ShipIt, MoveChoice::MemeBomb]);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CreateCharacterCustomization {
+                player: player_pda(&alice),
+                character_customization: customization_pda(&alice),
+                authority: alice,
+                config: config_pda(),
+                system_program: system_program::ID,
+                character_token: None,
+            },
+            defighter::instruction::CreateCharacterCustomization {
+                data: CharacterCustomizationDataV1 { hair_style_index: 7, accessory_slots: [255; 2], ..Default::default() },
+            },
+        )],
+        &[alice],
+    )
+    .unwrap();
+
+    let (mint, token) = (Pubkey::new_unique(), Pubkey::new_unique());
+    set_mint(&mut bank, mint, 1, false);
+    set_token_account(&mut bank, token, mint, alice);
+    (bank, alice, bob, mint, token)
+}
+
+#[test]
+fn the_token_holder_claims_the_character_after_a_sale() {
+    let (mut bank, alice, bob, mint, token) = setup();
+    let bad = Pubkey::new_unique();
+    set_mint(&mut bank, bad, 1, true);
+    let err = tokenize(&mut bank, alice, bad, token).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidCharacterMint.into()));
+    // Token-2022 extensions could move the token behind the holder's back
+    let extended = Pubkey::new_unique();
+    set_mint(&mut bank, extended, 1, false);
+    let mut account = bank.get_account(&extended).unwrap().clone();
+    account.owner = TOKEN_2022_PROGRAM_ID;
+    bank.set_account(extended, account);
+    let err = tokenize(&mut bank, alice, extended, token).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidCharacterMint.into()));
+    tokenize(&mut bank, alice, mint, token).unwrap();
+    let other = Pubkey::new_unique();
+    set_mint(&mut bank, other, 1, false);
+    let err = tokenize(&mut bank, alice, other, token).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::AlreadyTokenized.into()));
+    // Once tokenized, the character only fights for a wallet holding the token
+    let err = common::try_initiate_battle(&mut bank, alice, bob, 2, 10).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NftNotHeld.into()));
+
+    // Alice still holds the token, so Carol can't take the character
+    let carol = Pubkey::new_unique();
+    let err = claim(&mut bank, alice, carol, mint, token, true).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NftNotHeld.into()));
+
+    let before = player(&bank, alice);
+    set_token_account(&mut bank, token, mint, carol);
+    // Sold but not yet claimed: Alice can't play it any more
+    let err = common::try_initiate_battle_with_token(&mut bank, alice, bob, 2, 10, Some(token)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NftNotHeld.into()));
+    let err = claim(&mut bank, alice, carol, mint, token, false).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::CustomizationNotMoved.into()));
+    let meta = claim(&mut bank, alice, carol, mint, token, true).unwrap();
+    assert_eq!(meta.events::<PlayerTransferred>()[0].mint, Some(mint));
+
+    let after = player(&bank, carol);
+    assert_eq!((after.authority, after.xp, after.elo, after.character_mint), (carol, before.xp, before.elo, Some(mint)));
+    assert!(bank.get_account(&player_pda(&alice)).is_none());
+    assert!(bank.get_account(&customization_pda(&alice)).is_none());
+    let look: CharacterCustomizationV1 = bank.get_anchor_account(&customization_pda(&carol)).unwrap();
+    assert_eq!((look.player, look.hair_style_index), (player_pda(&carol), 7));
+    let registry: CharacterMint = bank.get_anchor_account(&character_mint_pda(&mint)).unwrap();
+    assert_eq!(registry.player, player_pda(&carol));
+
+    // The bought rating sits out the cooldown, on either side of a challenge
+    let err = common::try_initiate_battle_with_token(&mut bank, carol, bob, 1, 10, Some(token)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::CharacterCooldown.into()));
+    let err = common::try_initiate_battle(&mut bank, bob, carol, 2, 10).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::CharacterCooldown.into()));
+    bank.warp_to_slot(Player::TRANSFER_COOLDOWN_SLOTS);
+    common::try_initiate_battle_with_token(&mut bank, carol, bob, 1, 10, Some(token)).unwrap();
+}
+
+#[test]
+fn characters_in_open_battles_stay_put() {
+    let (mut bank, alice, _, mint, token) = setup();
+    tokenize(&mut bank, alice, mint, token).unwrap();
+    let dave = Pubkey::new_unique();
+    create_player(&mut bank, dave, FighterClass::VC);
+    let battle = common::initiate_battle(&mut bank, dave, alice, 1, 10);
+    // The challenge only holds Alice's character once she accepts it
+    assert_eq!((player(&bank, alice).open_battles, player(&bank, dave).open_battles), (0, 1));
+    common::commit_move(&mut bank, battle, alice, MoveChoice::ShipIt, [2; 32], Some(token));
+    assert_eq!(player(&bank, alice).open_battles, 1);
+
+    let carol = Pubkey::new_unique();
+    set_token_account(&mut bank, token, mint, carol);
+    let err = claim(&mut bank, alice, carol, mint, token, true).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::OpenBattles.into()));
+
+    bank.warp_to_slot(bank.slot() + 21);
+    common::resolve_battle(&mut bank, battle, dave, alice);
+    assert_eq!(player(&bank, alice).open_battles, 0);
+    claim(&mut bank, alice, carol, mint, token, true).unwrap();
+}
@@ -0,0 +1,261 @@
+//! Several characters per wallet: the roster cap, battles between specific
+//! characters and the wallet's shared gear.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::events::BattleResolved;
+use defighter::state::battle::Battle;
+use defighter::state::item::{GearSlot, GearStats, Inventory, ItemParams, Rarity};
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter::state::profile::Profile;
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{character_pda, config_pda, create_player, inventory_pda, ix, item_pda, profile_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+/// `challenger` fights with its character `challenger_account` against `opponent_account`
+fn initiate(bank: &mut Bank, challenger: Pubkey, challenger_account: Pubkey, opponent_account: Pubkey, nonce: u64) -> TxResult {
+    let opponent: Player = bank.get_anchor_account(&opponent_account).unwrap();
+    let battle = Pubkey::find_program_address(
+        &[b"battle", challenger_account.as_ref(), opponent_account.as_ref(), &nonce.to_le_bytes()],
+        &defighter::ID,
+    )
+    .0;
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::InitiateBattle {
+                battle,
+                pair_record: common::pair_pda(&challenger, &opponent.authority),
+                challenger_loot: common::loot_bag_pda(&challenger),
+                opponent_loot: common::loot_bag_pda(&opponent.authority),
+                challenger_consumables: common::consumables_pda(&challenger),
+                opponent_consumables: common::consumables_pda(&opponent.authority),
+                challenger,
+                challenger_account,
+                opponent_account,
+                config: config_pda(),
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+                character_token: None,
+            },
+            defighter::instruction::InitiateBattle {
+                opponent: opponent_account,
+                nonce,
+                commit_deadline_slots: 10,
+                reveal_deadline_slots: 10,
+            },
+        )],
+        &[challenger],
+    )
+}
+
+fn player_move(bank: &mut Bank, battle: Pubkey, who: Pubkey, character: Pubkey, move_choice: MoveChoice, salt: [u8; 32]) {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CommitMove {
+                battle,
+                player: who,
+                player_account: character,
+                config: config_pda(),
+                clock: sysvar::clock::ID,
+                session: None,
+                character_token: None,
+            },
+            defighter::instruction::CommitMove {
+                commitment: defighter::logic::commitment_hash(move_choice, &salt, &who, &battle),
+            },
+        )],
+        &[who],
+    )
+    .unwrap();
+}
+
+fn reveal(bank: &mut Bank, battle: Pubkey, who: Pubkey, character: Pubkey, move_choice: MoveChoice, salt: [u8; 32]) {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::RevealMove {
+                battle,
+                player: who,
+                player_account: character,
+                clock: sysvar::clock::ID,
+                consumables: None,
+                session: None,
+                character_token: None,
+            },
+            defighter::instruction::RevealMove { move_choice, salt, boost: None },
+        )],
+        &[who],
+    )
+    .unwrap();
+}
+
+fn equip(bank: &mut Bank, owner: Pubkey, character: Pubkey, id: u32) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::EquipItem {
+                player: character,
+                inventory: inventory_pda(&owner),
+                item: item_pda(id),
+                authority: owner,
+                character_token: None,
+            },
+            defighter::instruction::EquipItem {},
+        )],
+        &[owner],
+    )
+}
+
+fn unequip(bank: &mut Bank, owner: Pubkey, character: Pubkey, slot: GearSlot) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::UnequipItem { player: character, inventory: inventory_pda(&owner), authority: owner, character_token: None },
+            defighter::instruction::UnequipItem { slot },
+        )],
+        &[owner],
+    )
+}
+
+#[test]
+fn one_of_each_class_up_to_the_cap() {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let characters = FighterClass::ALL.map(|class| create_player(&mut bank, alice, class));
+    assert_eq!(characters, [0, 1, 2].map(|i| character_pda(&alice, i)));
+    let err = common::try_create_player(&mut bank, alice, FighterClass::VC).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::TooManyCharacters.into()));
+    let profile: Profile = bank.get_anchor_account(&profile_pda(&alice)).unwrap();
+    assert_eq!((profile.authority, profile.characters, profile.next_index), (alice, 3, 3));
+    let vc: Player = bank.get_anchor_account(&characters[2]).unwrap();
+    assert_eq!((vc.authority, vc.class, vc.index), (alice, FighterClass::VC, 2));
+
+    // A wallet's characters can't farm each other
+    let err = initiate(&mut bank, alice, characters[0], characters[1], 1).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::SameOwner.into()));
+    // Nor can anyone fight with a character they don't own
+    let bob_builder = create_player(&mut bank, bob, FighterClass::Builder);
+    assert!(initiate(&mut bank, bob, characters[0], characters[1], 1).is_err());
+
+    // Alice's Builder takes on Bob's; only those two characters are touched
+    let battle = Pubkey::find_program_address(
+        &[b"battle", characters[1].as_ref(), bob_builder.as_ref(), &1u64.to_le_bytes()],
+        &defighter::ID,
+    )
+    .0;
+    initiate(&mut bank, alice, characters[1], bob_builder, 1).unwrap();
+    let state: Battle = bank.get_anchor_account(&battle).unwrap();
+    assert_eq!((state.challenger, state.opponent), (characters[1], bob_builder));
+    player_move(&mut bank, battle, alice, characters[1], MoveChoice::TestnetDeploy, [1; 32]);
+    player_move(&mut bank, battle, bob, bob_builder, MoveChoice::ShipIt, [2; 32]);
+    reveal(&mut bank, battle, alice, characters[1], MoveChoice::TestnetDeploy, [1; 32]);
+    reveal(&mut bank, battle, bob, bob_builder, MoveChoice::ShipIt, [2; 32]);
+    let meta = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::ResolveBattle {
+                    battle,
+                    player_challenger: characters[1],
+                    player_opponent: bob_builder,
+                    challenger_loot: common::loot_bag_pda(&alice),
+                    opponent_loot: common::loot_bag_pda(&bob),
+                    challenger_consumables: common::consumables_pda(&alice),
+                    opponent_consumables: common::consumables_pda(&bob),
+                    config: config_pda(),
+                    clock: sysvar::clock::ID,
+                },
+                defighter::instruction::ResolveBattle {},
+            )],
+            &[],
+        )
+        .unwrap();
+    let winner = meta.events::<BattleResolved>()[0].winner;
+    assert!(winner == characters[1] || winner == bob_builder);
+    let xp = characters.map(|c| bank.get_anchor_account::<Player>(&c).unwrap().xp);
+    assert_eq!((xp[0], xp[2]), (0, 0));
+    assert!(xp[1] > 0);
+}
+
+#[test]
+fn gear_goes_on_one_character_at_a_time() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    let first = create_player(&mut bank, alice, FighterClass::Builder);
+    let second = create_player(&mut bank, alice, FighterClass::VC);
+    let helmet = ItemParams {
+        slot: GearSlot::Head,
+        rarity: Rarity::Epic,
+        class: None,
+        stats: GearStats { attack_bps: 0, defense_bps: 250 },
+    };
+    common::create_item(&mut bank, admin, 1, helmet).unwrap();
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::GrantItem {
+                config: config_pda(),
+                item: item_pda(1),
+                inventory: inventory_pda(&alice),
+                admin,
+                admin_set: None,
+                proposal: None,
+                system_program: system_program::ID,
+            },
+            defighter::instruction::GrantItem { recipient: alice },
+        )],
+        &[admin],
+    )
+    .unwrap();
+
+    equip(&mut bank, alice, first, 1).unwrap();
+    let err = equip(&mut bank, alice, second, 1).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::GearInUse.into()));
+    let err = unequip(&mut bank, alice, second, GearSlot::Head).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::GearInUse.into()));
+
+    // Once the first character is bare, the second may dress
+    unequip(&mut bank, alice, first, GearSlot::Head).unwrap();
+    let inventory: Inventory = bank.get_anchor_account(&inventory_pda(&alice)).unwrap();
+    assert_eq!(inventory.wearer, None);
+    equip(&mut bank, alice, second, 1).unwrap();
+    let inventory: Inventory = bank.get_anchor_account(&inventory_pda(&alice)).unwrap();
+    assert_eq!(inventory.wearer, Some(second));
+    assert_eq!(bank.get_anchor_account::<Player>(&second).unwrap().gear.defense_bps, 250);
+    assert_eq!(bank.get_anchor_account::<Player>(&first).unwrap().gear, GearStats::default());
+
+    // A character leaving the wallet leaves its gear behind, unworn
+    let fresh = Pubkey::new_unique();
+    bank.airdrop(&fresh, 1_000_000_000);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::TransferPlayer {
+                old_player: second,
+                old_profile: profile_pda(&alice),
+                new_profile: profile_pda(&fresh),
+                new_player: character_pda(&fresh, 0),
+                old_customization: Pubkey::find_program_address(
+                    &[defighter::state::customization::CHARACTER_CUSTOM_SEED, second.as_ref()],
+                    &defighter::ID,
+                )
+                .0,
+                new_customization: None,
+                old_inventory: inventory_pda(&alice),
+                old_authority: alice,
+                new_authority: fresh,
+                config: config_pda(),
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::TransferPlayer {},
+        )],
+        &[alice, fresh],
+    )
+    .unwrap();
+    let inventory: Inventory = bank.get_anchor_account(&inventory_pda(&alice)).unwrap();
+    assert_eq!((inventory.wearer, inventory.equipped, inventory.items.len()), (None, [None; 3], 1));
+    assert_eq!(bank.get_anchor_account::<Profile>(&profile_pda(&alice)).unwrap().characters, 1);
+    equip(&mut bank, alice, first, 1).unwrap();
+}
@@ -0,0 +1,140 @@
+//! Closing a character: rent back, its look and gear let go, and a
+//! tombstone for a bad rating.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::events::{BattleCancelled, EnergyRefilled, PlayerClosed};
+use defighter::state::battle::{Battle, BattleState};
+use defighter::state::customization::{CharacterCustomizationDataV1, CHARACTER_CUSTOM_SEED};
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter::state::profile::Profile;
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{character_pda, config_pda, create_player, inventory_pda, ix, player_pda, profile_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+fn look_pda(player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CHARACTER_CUSTOM_SEED, player.as_ref()], &defighter::ID).0
+}
+
+fn close(bank: &mut Bank, owner: Pubkey, player: Pubkey) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ClosePlayer {
+                player,
+                profile: profile_pda(&owner),
+                character_customization: look_pda(&player),
+                inventory: inventory_pda(&owner),
+                authority: owner,
+            },
+            defighter::instruction::ClosePlayer {},
+        )],
+        &[owner],
+    )
+}
+
+fn set_elo(bank: &mut Bank, key: Pubkey, elo: i32) {
+    let mut state: Player = bank.get_anchor_account(&key).unwrap();
+    state.elo = elo;
+    let mut account = bank.get_account(&key).unwrap().clone();
+    account.data.clear();
+    state.try_serialize(&mut account.data).unwrap();
+    bank.set_account(key, account);
+}
+
+#[test]
+fn closing_refunds_rent_and_keeps_a_bad_rating() {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let first = create_player(&mut bank, alice, FighterClass::Builder);
+    create_player(&mut bank, bob, FighterClass::VC);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CreateCharacterCustomization {
+                player: first,
+                character_customization: look_pda(&first),
+                authority: alice,
+                config: config_pda(),
+                system_program: system_program::ID,
+                character_token: None,
+            },
+            defighter::instruction::CreateCharacterCustomization {
+                data: CharacterCustomizationDataV1 { accessory_slots: [255; 2], ..Default::default() },
+            },
+        )],
+        &[alice],
+    )
+    .unwrap();
+
+    let battle = common::initiate_battle(&mut bank, bob, alice, 1, 10);
+    common::commit_move(&mut bank, battle, alice, MoveChoice::ShipIt, [2; 32], None);
+    let err = close(&mut bank, alice, first).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::OpenBattles.into()));
+    bank.warp_to_slot(bank.slot() + 21);
+    common::resolve_battle(&mut bank, battle, bob, alice);
+    // Bob can't close Alice's character
+    assert!(close(&mut bank, bob, first).is_err());
+
+    set_elo(&mut bank, first, 880);
+    let rent = bank.get_account(&first).unwrap().lamports + bank.get_account(&look_pda(&first)).unwrap().lamports;
+    let before = bank.get_account(&alice).unwrap().lamports;
+    let meta = close(&mut bank, alice, first).unwrap();
+    let event = &meta.events::<PlayerClosed>()[0];
+    assert_eq!((event.player, event.elo, event.tombstone_elo), (first, 880, Some(880)));
+    assert_eq!(bank.get_account(&alice).unwrap().lamports, before + rent);
+    assert!(bank.get_account(&first).is_none());
+    assert!(bank.get_account(&look_pda(&first)).is_none());
+    let profile: Profile = bank.get_anchor_account(&profile_pda(&alice)).unwrap();
+    assert_eq!((profile.characters, profile.tombstone_elo), (0, Some(880)));
+
+    // A fresh character takes a new index and starts no higher than the old rating
+    let second = create_player(&mut bank, alice, FighterClass::Builder);
+    assert_eq!(second, character_pda(&alice, 1));
+    assert_ne!(second, player_pda(&alice));
+    assert_eq!(bank.get_anchor_account::<Player>(&second).unwrap().elo, 880);
+}
+
+#[test]
+fn unaccepted_challenges_leave_the_opponent_free() {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let first = create_player(&mut bank, alice, FighterClass::Builder);
+    create_player(&mut bank, bob, FighterClass::VC);
+
+    // Nobody can be held past the configured windows
+    let err = common::try_initiate_battle(&mut bank, bob, alice, 1, 1_501).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidDeadlines.into()));
+
+    let battle = common::initiate_battle(&mut bank, bob, alice, 1, 10);
+    assert_eq!(bank.get_anchor_account::<Player>(&player_pda(&bob)).unwrap().energy_used, 1);
+    close(&mut bank, alice, first).unwrap();
+
+    // Bob's battle can't be resolved without Alice's character, so he cancels it
+    let cancel = |bank: &mut Bank| {
+        bank.process_transaction(
+            &[ix(
+                defighter::accounts::CancelBattle { battle, player_challenger: player_pda(&bob), clock: sysvar::clock::ID },
+                defighter::instruction::CancelBattle {},
+            )],
+            &[],
+        )
+    };
+    let err = cancel(&mut bank).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotReadyToResolve.into()));
+    bank.warp_to_slot(bank.slot() + 11);
+    let meta = cancel(&mut bank).unwrap();
+    assert_eq!(meta.events::<BattleCancelled>()[0].challenger, player_pda(&bob));
+    // The challenge cost Bob energy; it comes back with the cancel
+    assert_eq!(meta.events::<EnergyRefilled>()[0].amount, 1);
+    let state: Battle = bank.get_anchor_account(&battle).unwrap();
+    assert!(state.state == BattleState::Resolved && state.winner.is_none());
+    let bob_player = bank.get_anchor_account::<Player>(&player_pda(&bob)).unwrap();
+    assert_eq!((bob_player.open_battles, bob_player.energy_used), (0, 0));
+    assert!(cancel(&mut bank).is_err());
+}
@@ -0,0 +1,308 @@
+//! Helpers shared by the program tests.
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use defighter::state::config::{BalanceConfig, BalanceConfigParams, ResolutionMode};
+use defighter::state::item::ItemParams;
+use defighter::state::player::{FighterClass, MoveChoice};
+use defighter::state::profile::Profile;
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+pub fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction { program_id: defighter::ID, accounts: accounts.to_account_metas(None), data: data.data() }
+}
+
+/// The wallet's first character
+pub fn player_pda(authority: &Pubkey) -> Pubkey {
+    character_pda(authority, 0)
+}
+
+pub fn character_pda(authority: &Pubkey, index: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"player", authority.as_ref(), &[index]], &defighter::ID).0
+}
+
+pub fn profile_pda(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"profile", authority.as_ref()], &defighter::ID).0
+}
+
+/// Where the wallet's next character, created or received, will live
+pub fn next_character_pda(bank: &Bank, authority: &Pubkey) -> Pubkey {
+    let next = bank.get_anchor_account::<Profile>(&profile_pda(authority)).map_or(0, |p| p.next_index);
+    character_pda(authority, next)
+}
+
+pub fn config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &defighter::ID).0
+}
+
+pub fn pair_pda(a: &Pubkey, b: &Pubkey) -> Pubkey {
+    let (low, high) = defighter::state::pair::PairRecord::sorted(*a, *b);
+    Pubkey::find_program_address(&[b"pair", low.as_ref(), high.as_ref()], &defighter::ID).0
+}
+
+pub fn loot_bag_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"loot_bag", owner.as_ref()], &defighter::ID).0
+}
+
+pub fn consumables_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"consumables", owner.as_ref()], &defighter::ID).0
+}
+
+pub fn item_pda(id: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"item", &id.to_le_bytes()], &defighter::ID).0
+}
+
+pub fn inventory_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"inventory", owner.as_ref()], &defighter::ID).0
+}
+
+pub fn pending_config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"pending_config"], &defighter::ID).0
+}
+
+/// A bank with the program loaded.
+pub fn bank() -> Bank {
+    let mut bank = Bank::new();
+    bank.add_program(defighter::ID, defighter::entry);
+    bank
+}
+
+/// Initialize the config with the demo values and return the admin.
+pub fn init_config(bank: &mut Bank) -> Pubkey {
+    init_config_with_mode(bank, ResolutionMode::Damage)
+}
+
+pub fn init_config_with_mode(bank: &mut Bank, resolution_mode: ResolutionMode) -> Pubkey {
+    init_config_with_params(bank, BalanceConfigParams { resolution_mode, ..Default::default() })
+}
+
+pub fn init_config_with_params(bank: &mut Bank, params: BalanceConfigParams) -> Pubkey {
+    let admin = Pubkey::new_unique();
+    bank.airdrop(&admin, 1_000_000_000);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::InitConfig { config: config_pda(), admin, system_program: system_program::ID },
+            defighter::instruction::InitConfig { params },
+        )],
+        &[admin],
+    )
+    .unwrap();
+    admin
+}
+
+/// Warp past the timelock and apply the queued config change.
+pub fn apply_config_change(bank: &mut Bank) -> TransactionMeta {
+    bank.warp_to_slot(bank.slot() + BalanceConfig::CONFIG_DELAY_SLOTS);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ApplyConfigChange {
+                config: config_pda(),
+                pending_config: pending_config_pda(),
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::ApplyConfigChange {},
+        )],
+        &[],
+    )
+    .unwrap()
+}
+
+/// Create the wallet's next character and return its `Player`
+pub fn create_player(bank: &mut Bank, authority: Pubkey, class: FighterClass) -> Pubkey {
+    try_create_player(bank, authority, class).unwrap();
+    let profile: Profile = bank.get_anchor_account(&profile_pda(&authority)).unwrap();
+    character_pda(&authority, profile.next_index - 1)
+}
+
+pub fn try_create_player(
+    bank: &mut Bank,
+    authority: Pubkey,
+    class: FighterClass,
+) -> std::result::Result<TransactionMeta, TransactionError> {
+    bank.airdrop(&authority, 1_000_000_000);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CreatePlayer {
+                payer: authority,
+                profile: profile_pda(&authority),
+                player: next_character_pda(bank, &authority),
+                authority,
+                config: config_pda(),
+                system_program: system_program::ID,
+            },
+            defighter::instruction::CreatePlayer { class },
+        )],
+        &[authority],
+    )
+}
+
+/// The battle between two wallets' first characters
+pub fn battle_pda(challenger: &Pubkey, opponent: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"battle", player_pda(challenger).as_ref(), player_pda(opponent).as_ref(), &nonce.to_le_bytes()],
+        &defighter::ID,
+    )
+    .0
+}
+
+/// Initiate, commit, reveal and resolve one battle; returns the resolve's meta.
+pub fn play_battle(
+    bank: &mut Bank,
+    challenger: Pubkey,
+    opponent: Pubkey,
+    nonce: u64,
+    moves: [MoveChoice; 2],
+) -> TransactionMeta {
+    let battle = initiate_battle(bank, challenger, opponent, nonce, 10);
+    commit_moves(bank, battle, [challenger, opponent], moves);
+    reveal_moves(bank, battle, [challenger, opponent], moves);
+    resolve_battle(bank, battle, challenger, opponent)
+}
+
+/// Both commit and reveal windows are `deadline_slots` long.
+pub fn initiate_battle(bank: &mut Bank, challenger: Pubkey, opponent: Pubkey, nonce: u64, deadline_slots: u64) -> Pubkey {
+    try_initiate_battle(bank, challenger, opponent, nonce, deadline_slots).unwrap();
+    battle_pda(&challenger, &opponent, nonce)
+}
+
+pub fn try_initiate_battle(
+    bank: &mut Bank,
+    challenger: Pubkey,
+    opponent: Pubkey,
+    nonce: u64,
+    deadline_slots: u64,
+) -> std::result::Result<TransactionMeta, TransactionError> {
+    try_initiate_battle_with_token(bank, challenger, opponent, nonce, deadline_slots, None)
+}
+
+/// For a tokenized challenger, `character_token` is its token account
+pub fn try_initiate_battle_with_token(
+    bank: &mut Bank,
+    challenger: Pubkey,
+    opponent: Pubkey,
+    nonce: u64,
+    deadline_slots: u64,
+    character_token: Option<Pubkey>,
+) -> std::result::Result<TransactionMeta, TransactionError> {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::InitiateBattle {
+                battle: battle_pda(&challenger, &opponent, nonce),
+                pair_record: pair_pda(&challenger, &opponent),
+                challenger_loot: loot_bag_pda(&challenger),
+                opponent_loot: loot_bag_pda(&opponent),
+                challenger_consumables: consumables_pda(&challenger),
+                opponent_consumables: consumables_pda(&opponent),
+                challenger,
+                challenger_account: player_pda(&challenger),
+                opponent_account: player_pda(&opponent),
+                config: config_pda(),
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+                character_token,
+            },
+            defighter::instruction::InitiateBattle {
+                opponent: player_pda(&opponent),
+                nonce,
+                commit_deadline_slots: deadline_slots,
+                reveal_deadline_slots: deadline_slots,
+            },
+        )],
+        &[challenger],
+    )
+}
+
+/// Salts are `[1; 32]` for the first player and `[2; 32]` for the second.
+pub fn commit_moves(bank: &mut Bank, battle: Pubkey, players: [Pubkey; 2], moves: [MoveChoice; 2]) {
+    for (i, (who, move_choice)) in players.into_iter().zip(moves).enumerate() {
+        commit_move(bank, battle, who, move_choice, [i as u8 + 1; 32], None);
+    }
+}
+
+pub fn commit_move(
+    bank: &mut Bank,
+    battle: Pubkey,
+    who: Pubkey,
+    move_choice: MoveChoice,
+    salt: [u8; 32],
+    character_token: Option<Pubkey>,
+) {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CommitMove { battle, player: who, player_account: player_pda(&who), config: config_pda(), clock: sysvar::clock::ID, session: None, character_token },
+            defighter::instruction::CommitMove {
+                commitment: defighter::logic::commitment_hash(move_choice, &salt, &who, &battle),
+            },
+        )],
+        &[who],
+    )
+    .unwrap();
+}
+
+pub fn reveal_moves(bank: &mut Bank, battle: Pubkey, players: [Pubkey; 2], moves: [MoveChoice; 2]) {
+    for (i, (who, move_choice)) in players.into_iter().zip(moves).enumerate() {
+        bank.process_transaction(
+            &[ix(
+                defighter::accounts::RevealMove {
+                    battle,
+                    player: who,
+                    player_account: player_pda(&who),
+                    clock: sysvar::clock::ID,
+                    consumables: None,
+                    session: None,
+                    character_token: None,
+                },
+                defighter::instruction::RevealMove { move_choice, salt: [i as u8 + 1; 32], boost: None },
+            )],
+            &[who],
+        )
+        .unwrap();
+    }
+}
+
+pub fn resolve_battle(bank: &mut Bank, battle: Pubkey, challenger: Pubkey, opponent: Pubkey) -> TransactionMeta {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ResolveBattle {
+                battle,
+                player_challenger: player_pda(&challenger),
+                player_opponent: player_pda(&opponent),
+                challenger_loot: loot_bag_pda(&challenger),
+                opponent_loot: loot_bag_pda(&opponent),
+                challenger_consumables: consumables_pda(&challenger),
+                opponent_consumables: consumables_pda(&opponent),
+                config: config_pda(),
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::ResolveBattle {},
+        )],
+        &[],
+    )
+    .unwrap()
+}
+
+pub fn create_item(
+    bank: &mut Bank,
+    admin: Pubkey,
+    id: u32,
+    params: ItemParams,
+) -> std::result::Result<TransactionMeta, TransactionError> {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CreateItem {
+                config: config_pda(),
+                item: item_pda(id),
+                admin,
+                admin_set: None,
+                proposal: None,
+                system_program: system_program::ID,
+            },
+            defighter::instruction::CreateItem { id, params },
+        )],
+        &[admin],
+    )
+}
@@ -0,0 +1,228 @@
+//! Admin config updates: validation, single-field setters, the timelock and
+//! battles keeping the rules they started under.
+
+use anchor_lang::prelude::*;
+use anchor_lang::error::ErrorCode;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::events::{BattleResolved, ConfigChangeCancelled, ConfigChangeQueued, ConfigUpdated};
+use defighter::state::battle::Battle;
+use defighter::state::config::{BalanceConfig, BalanceConfigParams, ConfigUpdate, PendingConfig, ResolutionMode};
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{apply_config_change, config_pda, create_player, ix, pending_config_pda, player_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+fn update(bank: &mut Bank, admin: Pubkey, update: ConfigUpdate) -> TxResult {
+    bank.airdrop(&admin, 1_000_000_000);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::UpdateConfig {
+                config: config_pda(),
+                pending_config: pending_config_pda(),
+                admin,
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::UpdateConfig { update },
+        )],
+        &[admin],
+    )
+}
+
+fn try_apply(bank: &mut Bank) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ApplyConfigChange {
+                config: config_pda(),
+                pending_config: pending_config_pda(),
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::ApplyConfigChange {},
+        )],
+        &[],
+    )
+}
+
+fn cancel(bank: &mut Bank, admin: Pubkey) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CancelConfigChange { config: config_pda(), pending_config: pending_config_pda(), admin },
+            defighter::instruction::CancelConfigChange {},
+        )],
+        &[admin],
+    )
+}
+
+fn pending(bank: &Bank) -> PendingConfig {
+    bank.get_anchor_account(&pending_config_pda()).unwrap()
+}
+
+fn config(bank: &Bank) -> BalanceConfig {
+    bank.get_anchor_account(&config_pda()).unwrap()
+}
+
+#[test]
+fn setter_changes_one_field_and_reports_it() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let before = config(&bank).params();
+    assert_eq!(before, BalanceConfigParams::default());
+
+    update(&mut bank, admin, ConfigUpdate::ResolutionMode(ResolutionMode::Score)).unwrap();
+    let meta = apply_config_change(&mut bank);
+    let after = config(&bank);
+    assert_eq!(after.admin, admin);
+    assert_eq!(after.params(), BalanceConfigParams { resolution_mode: ResolutionMode::Score, ..before });
+
+    let events = meta.events::<ConfigUpdated>();
+    assert_eq!(events.len(), 1);
+    assert_eq!((events[0].admin, events[0].version), (admin, 2));
+    assert_eq!(events[0].old, before);
+    assert_eq!(events[0].new, after.params());
+}
+
+#[test]
+fn invalid_values_are_rejected_and_leave_config_untouched() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let before = config(&bank).params();
+
+    for bad in [ConfigUpdate::MaxLevel(0), ConfigUpdate::XpLoserBps(9_000), ConfigUpdate::DefenseBps(20_000)] {
+        let err = update(&mut bank, admin, bad).unwrap_err();
+        assert_eq!(err.custom_code(), Some(CustomError::InvalidConfig.into()));
+    }
+    let err = update(&mut bank, admin, ConfigUpdate::UpgradePBps(40_000)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::UnsupportedUpgradeExponent.into()));
+    // Cross-field invariant: the tie base can't exceed the win base
+    let err = update(&mut bank, admin, ConfigUpdate::RpsTieBase(25)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidConfig.into()));
+    assert_eq!(config(&bank).params(), before);
+
+    let full = bank.process_transaction(
+        &[ix(
+            defighter::accounts::AdminUpdateConfig {
+                config: config_pda(),
+                pending_config: pending_config_pda(),
+                admin,
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::AdminUpdateConfig { params: BalanceConfigParams { base: -5, ..before } },
+        )],
+        &[admin],
+    );
+    assert_eq!(full.unwrap_err().custom_code(), Some(CustomError::InvalidConfig.into()));
+}
+
+#[test]
+fn only_admin_updates() {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let mallory = Pubkey::new_unique();
+    let err = update(&mut bank, mallory, ConfigUpdate::XpBase(1_000_000)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(ErrorCode::ConstraintHasOne.into()));
+    assert_eq!(config(&bank).xp_base, 10);
+}
+
+#[test]
+fn changes_wait_out_the_timelock() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    assert_eq!(config(&bank).version, 1);
+    bank.warp_to_slot(50);
+
+    let meta = update(&mut bank, admin, ConfigUpdate::XpBase(25)).unwrap();
+    let queued = meta.events::<ConfigChangeQueued>();
+    let effective_slot = 50 + BalanceConfig::CONFIG_DELAY_SLOTS;
+    assert_eq!((queued[0].version, queued[0].effective_slot, queued[0].params.xp_base), (2, effective_slot, 25));
+    let state = pending(&bank);
+    assert!(state.active);
+    assert_eq!((state.queued_by, state.queued_slot, state.effective_slot), (admin, 50, effective_slot));
+    assert_eq!(config(&bank).xp_base, 10);
+
+    // One change at a time, and not a slot early
+    let err = update(&mut bank, admin, ConfigUpdate::XpBase(30)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::ConfigChangePending.into()));
+    bank.warp_to_slot(effective_slot - 1);
+    let err = try_apply(&mut bank).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::TimelockNotElapsed.into()));
+
+    bank.warp_to_slot(effective_slot);
+    try_apply(&mut bank).unwrap();
+    let cfg = config(&bank);
+    assert_eq!((cfg.xp_base, cfg.version), (25, 2));
+    assert!(!pending(&bank).active);
+    let err = try_apply(&mut bank).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NoPendingConfig.into()));
+}
+
+#[test]
+fn admin_cancels_a_queued_change() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    update(&mut bank, admin, ConfigUpdate::MaxLevel(5)).unwrap();
+
+    let err = cancel(&mut bank, Pubkey::new_unique()).unwrap_err();
+    assert_eq!(err.custom_code(), Some(ErrorCode::ConstraintHasOne.into()));
+    let meta = cancel(&mut bank, admin).unwrap();
+    assert_eq!(meta.events::<ConfigChangeCancelled>()[0].version, 2);
+    let err = cancel(&mut bank, admin).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NoPendingConfig.into()));
+
+    bank.warp_to_slot(bank.slot() + BalanceConfig::CONFIG_DELAY_SLOTS);
+    let err = try_apply(&mut bank).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NoPendingConfig.into()));
+    assert_eq!(config(&bank).params(), BalanceConfigParams::default());
+
+    // The slot is free again
+    update(&mut bank, admin, ConfigUpdate::MaxLevel(50)).unwrap();
+    apply_config_change(&mut bank);
+    assert_eq!(config(&bank).max_level, 50);
+}
+
+#[test]
+fn battle_resolves_under_the_rules_it_started_with() {
+    let mut bank = common::bank();
+    // Long enough windows to queue two changes mid-battle
+    let window = 2 * BalanceConfig::CONFIG_DELAY_SLOTS;
+    let rules = BalanceConfigParams { max_commit_slots: window, max_reveal_slots: window, ..Default::default() };
+    let admin = common::init_config_with_params(&mut bank, rules);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+
+    let players = [alice, bob];
+    let moves = [MoveChoice::MemeBomb, MoveChoice::ShipIt];
+    let battle = common::initiate_battle(&mut bank, alice, bob, 1, window);
+    let state: Battle = bank.get_anchor_account(&battle).unwrap();
+    assert_eq!((state.config_version, state.rules), (1, rules));
+    common::commit_moves(&mut bank, battle, players, moves);
+
+    // Switch the mode and XP mid-battle
+    update(&mut bank, admin, ConfigUpdate::ResolutionMode(ResolutionMode::Score)).unwrap();
+    apply_config_change(&mut bank);
+    update(&mut bank, admin, ConfigUpdate::XpBase(100)).unwrap();
+    apply_config_change(&mut bank);
+    assert_eq!(config(&bank).version, 3);
+
+    common::reveal_moves(&mut bank, battle, players, moves);
+    let meta = common::resolve_battle(&mut bank, battle, alice, bob);
+    // Same outcome as `full_battle` on the demo rules
+    let resolved = meta.events::<BattleResolved>();
+    assert_eq!((resolved[0].challenger_hp, resolved[0].opponent_hp), (75, 120));
+    let bob_player: Player = bank.get_anchor_account(&player_pda(&bob)).unwrap();
+    assert_eq!(bob_player.xp, 10);
+
+    // A fresh battle picks up version 3
+    let meta = common::play_battle(&mut bank, alice, bob, 2, moves);
+    assert_eq!((meta.events::<BattleResolved>()[0].challenger_hp, meta.events::<BattleResolved>()[0].opponent_hp), (180, 200));
+    let bob_player: Player = bank.get_anchor_account(&player_pda(&bob)).unwrap();
+    // A rematch within the day: 75% of the new 100 XP base
+    assert_eq!(bob_player.xp, 10 + 75);
+}
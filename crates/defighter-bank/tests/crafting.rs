@@ -0,0 +1,220 @@
+//! Crafting: battle materials, recipes, salvage and unlock-gated customization.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::events::{CosmeticSalvaged, MaterialsEarned};
+use defighter::state::config::BalanceConfigParams;
+use defighter::state::crafting::{CosmeticKind, Material, RecipeParams};
+use defighter::state::customization::{CharacterCustomizationDataV1, CharacterCustomizationV1, CHARACTER_CUSTOM_SEED};
+use defighter::state::loot::LootBag;
+use defighter::state::player::{FighterClass, MoveChoice};
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, create_player, ix, loot_bag_pda, player_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+const OUTFIT: u8 = 2;
+
+fn customization_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CHARACTER_CUSTOM_SEED, player_pda(owner).as_ref()], &defighter::ID).0
+}
+
+fn recipe_pda(kind: CosmeticKind, index: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"recipe", &[kind as u8], &[index]], &defighter::ID).0
+}
+
+fn receipt_pda(owner: &Pubkey, kind: CosmeticKind, index: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"crafted", player_pda(owner).as_ref(), &[kind as u8], &[index]], &defighter::ID).0
+}
+
+fn look(outfit_style_index: u8) -> CharacterCustomizationDataV1 {
+    CharacterCustomizationDataV1 {
+        outfit_style_index,
+        accessory_slots: [CharacterCustomizationV1::NO_ACCESSORY; 2],
+        ..Default::default()
+    }
+}
+
+fn set_recipe(bank: &mut Bank, admin: Pubkey, kind: CosmeticKind, index: u8, params: RecipeParams) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::SetRecipe {
+                config: config_pda(),
+                recipe: recipe_pda(kind, index),
+                admin,
+                admin_set: None,
+                proposal: None,
+                system_program: system_program::ID,
+            },
+            defighter::instruction::SetRecipe { kind, index, params },
+        )],
+        &[admin],
+    )
+}
+
+fn create_look(bank: &mut Bank, owner: Pubkey, data: CharacterCustomizationDataV1) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CreateCharacterCustomization {
+                player: player_pda(&owner),
+                character_customization: customization_pda(&owner),
+                authority: owner,
+                config: config_pda(),
+                system_program: system_program::ID,
+                character_token: None,
+            },
+            defighter::instruction::CreateCharacterCustomization { data },
+        )],
+        &[owner],
+    )
+}
+
+fn update_look(bank: &mut Bank, owner: Pubkey, data: CharacterCustomizationDataV1) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::UpdateCharacterCustomization {
+                player: player_pda(&owner),
+                character_customization: customization_pda(&owner),
+                authority: owner,
+                config: config_pda(),
+                character_token: None,
+            },
+            defighter::instruction::UpdateCharacterCustomization { data },
+        )],
+        &[owner],
+    )
+}
+
+fn craft(bank: &mut Bank, owner: Pubkey, kind: CosmeticKind, index: u8) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CraftCosmetic {
+                player: player_pda(&owner),
+                character_customization: customization_pda(&owner),
+                loot_bag: loot_bag_pda(&owner),
+                recipe: recipe_pda(kind, index),
+                craft_receipt: receipt_pda(&owner, kind, index),
+                config: config_pda(),
+                authority: owner,
+                system_program: system_program::ID,
+                character_token: None,
+            },
+            defighter::instruction::CraftCosmetic {},
+        )],
+        &[owner],
+    )
+}
+
+fn salvage(bank: &mut Bank, owner: Pubkey, kind: CosmeticKind, index: u8) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::SalvageCosmetic {
+                player: player_pda(&owner),
+                character_customization: customization_pda(&owner),
+                loot_bag: loot_bag_pda(&owner),
+                recipe: recipe_pda(kind, index),
+                craft_receipt: receipt_pda(&owner, kind, index),
+                config: config_pda(),
+                authority: owner,
+                character_token: None,
+            },
+            defighter::instruction::SalvageCosmetic {},
+        )],
+        &[owner],
+    )
+}
+
+fn materials(bank: &Bank, owner: Pubkey) -> [u32; Material::COUNT] {
+    bank.get_anchor_account::<LootBag>(&loot_bag_pda(&owner)).unwrap().materials
+}
+
+/// Alice and Bob after one battle Bob wins, each with a plain look. Returns
+/// the material it paid out.
+fn setup() -> (Bank, Pubkey, Pubkey, Pubkey, Material) {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+    let meta = common::play_battle(&mut bank, alice, bob, 1, [MoveChoice::MemeBomb, MoveChoice::ShipIt]);
+    let material = meta.events::<MaterialsEarned>()[0].material;
+    create_look(&mut bank, alice, look(0)).unwrap();
+    create_look(&mut bank, bob, look(0)).unwrap();
+    (bank, admin, alice, bob, material)
+}
+
+#[test]
+fn battles_pay_materials_to_both_sides() {
+    let (bank, _, alice, bob, material) = setup();
+    let rules = BalanceConfigParams::default();
+    let mut expected = [0; Material::COUNT];
+    expected[material as usize] = rules.material_win as u32;
+    assert_eq!(materials(&bank, bob), expected);
+    expected[material as usize] = rules.material_loss as u32;
+    assert_eq!(materials(&bank, alice), expected);
+}
+
+#[test]
+fn crafting_unlocks_a_look_until_salvaged() {
+    let (mut bank, admin, _, bob, material) = setup();
+    let err = update_look(&mut bank, bob, look(OUTFIT)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::CosmeticLocked.into()));
+
+    let mut cost = [0; Material::COUNT];
+    cost[material as usize] = 2;
+    let recipe = RecipeParams { materials: cost, xp_cost: 5, active: true };
+    set_recipe(&mut bank, admin, CosmeticKind::Outfit, OUTFIT, recipe).unwrap();
+    craft(&mut bank, bob, CosmeticKind::Outfit, OUTFIT).unwrap();
+    assert_eq!(materials(&bank, bob)[material as usize], 1);
+    let err = craft(&mut bank, bob, CosmeticKind::Outfit, OUTFIT).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::AlreadyUnlocked.into()));
+    update_look(&mut bank, bob, look(OUTFIT)).unwrap();
+
+    let err = salvage(&mut bank, bob, CosmeticKind::Outfit, OUTFIT).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::CosmeticWorn.into()));
+    update_look(&mut bank, bob, look(0)).unwrap();
+    // Repricing after the craft doesn't change what salvage returns
+    cost[material as usize] = 20;
+    set_recipe(&mut bank, admin, CosmeticKind::Outfit, OUTFIT, RecipeParams { materials: cost, ..recipe }).unwrap();
+    let meta = salvage(&mut bank, bob, CosmeticKind::Outfit, OUTFIT).unwrap();
+    assert_eq!(meta.events::<CosmeticSalvaged>()[0].refund[material as usize], 1);
+    assert_eq!(materials(&bank, bob)[material as usize], 2);
+    assert!(bank.get_account(&receipt_pda(&bob, CosmeticKind::Outfit, OUTFIT)).is_none());
+
+    let err = update_look(&mut bank, bob, look(OUTFIT)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::CosmeticLocked.into()));
+    // Nothing left to salvage: the receipt went with the cosmetic
+    let err = salvage(&mut bank, bob, CosmeticKind::Outfit, OUTFIT).unwrap_err();
+    assert_eq!(err.custom_code(), Some(anchor_lang::error::ErrorCode::AccountNotInitialized.into()));
+}
+
+#[test]
+fn recipes_check_range_activity_and_costs() {
+    let (mut bank, admin, alice, bob, material) = setup();
+    let recipe = RecipeParams { materials: [0; Material::COUNT], xp_cost: 0, active: true };
+    let err = set_recipe(&mut bank, admin, CosmeticKind::Accessory, 128, recipe).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidRecipe.into()));
+    let err = set_recipe(&mut bank, alice, CosmeticKind::Accessory, 7, recipe).unwrap_err();
+    assert_eq!(err.custom_code(), Some(anchor_lang::error::ErrorCode::ConstraintHasOne.into()));
+
+    set_recipe(&mut bank, admin, CosmeticKind::Accessory, 7, RecipeParams { active: false, ..recipe }).unwrap();
+    let err = craft(&mut bank, bob, CosmeticKind::Accessory, 7).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::RecipeInactive.into()));
+
+    let mut cost = [0; Material::COUNT];
+    cost[material as usize] = 2;
+    set_recipe(&mut bank, admin, CosmeticKind::Accessory, 7, RecipeParams { materials: cost, ..recipe }).unwrap();
+    let err = craft(&mut bank, alice, CosmeticKind::Accessory, 7).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotEnoughMaterials.into()));
+    set_recipe(&mut bank, admin, CosmeticKind::Accessory, 7, RecipeParams { materials: cost, xp_cost: 1_000, ..recipe }).unwrap();
+    let err = craft(&mut bank, bob, CosmeticKind::Accessory, 7).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotEnoughXP.into()));
+
+    set_recipe(&mut bank, admin, CosmeticKind::Accessory, 7, RecipeParams { materials: cost, ..recipe }).unwrap();
+    craft(&mut bank, bob, CosmeticKind::Accessory, 7).unwrap();
+    update_look(&mut bank, bob, CharacterCustomizationDataV1 { accessory_slots: [7, 255], ..look(0) }).unwrap();
+}
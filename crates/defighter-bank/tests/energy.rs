@@ -0,0 +1,152 @@
+//! Energy: battle costs on both sides, lazy regen and buying it back with XP.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use defighter::errors::CustomError;
+use defighter::events::{EnergyRefilled, EnergySpent};
+use defighter::state::config::{BalanceConfig, BalanceConfigParams};
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, create_player, ix, player_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+fn setup() -> (Bank, Pubkey, Pubkey) {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+    (bank, alice, bob)
+}
+
+fn edit_player(bank: &mut Bank, player: Pubkey, f: impl FnOnce(&mut Player)) {
+    let key = player_pda(&player);
+    let mut state: Player = bank.get_anchor_account(&key).unwrap();
+    f(&mut state);
+    let mut account = bank.get_account(&key).unwrap().clone();
+    account.data.clear();
+    state.try_serialize(&mut account.data).unwrap();
+    bank.set_account(key, account);
+}
+
+fn energy(bank: &Bank, player: Pubkey) -> u16 {
+    let state: Player = bank.get_anchor_account(&player_pda(&player)).unwrap();
+    let cfg: BalanceConfig = bank.get_anchor_account(&config_pda()).unwrap();
+    state.energy(bank.slot(), &cfg)
+}
+
+fn refill(bank: &mut Bank, player: Pubkey, amount: u16) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::RefillEnergy {
+                player: player_pda(&player),
+                authority: player,
+                config: config_pda(),
+                clock: sysvar::clock::ID,
+                character_token: None,
+            },
+            defighter::instruction::RefillEnergy { amount },
+        )],
+        &[player],
+    )
+}
+
+#[test]
+fn challenger_pays_up_front_and_opponent_on_accepting() {
+    let (mut bank, alice, bob) = setup();
+    let max = BalanceConfigParams::default().energy_max;
+    let meta = common::try_initiate_battle(&mut bank, alice, bob, 1, 10).unwrap();
+    let spent = meta.events::<EnergySpent>();
+    assert_eq!((spent[0].player, spent[0].amount, spent[0].energy), (player_pda(&alice), 1, max - 1));
+    assert_eq!(energy(&bank, bob), max);
+
+    let battle = common::battle_pda(&alice, &bob, 1);
+    common::commit_moves(&mut bank, battle, [alice, bob], [MoveChoice::MemeBomb, MoveChoice::ShipIt]);
+    assert_eq!((energy(&bank, alice), energy(&bank, bob)), (max - 1, max - 1));
+}
+
+#[test]
+fn an_empty_bar_waits_for_regen() {
+    let (mut bank, alice, bob) = setup();
+    let rules = BalanceConfigParams::default();
+    bank.warp_to_slot(100);
+    edit_player(&mut bank, alice, |p| {
+        p.energy_used = rules.energy_max;
+        p.energy_updated_slot = 100;
+    });
+
+    let err = common::try_initiate_battle(&mut bank, alice, bob, 1, 10).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotEnoughEnergy.into()));
+    bank.warp_to_slot(100 + rules.energy_regen_slots - 1);
+    assert_eq!(energy(&bank, alice), 0);
+    bank.warp_to_slot(100 + rules.energy_regen_slots);
+    common::try_initiate_battle(&mut bank, alice, bob, 1, 10).unwrap();
+    assert_eq!(energy(&bank, alice), 0);
+
+    // Out of energy, Bob can't take up Alice's challenge
+    let slot = bank.slot();
+    edit_player(&mut bank, bob, |p| {
+        p.energy_used = rules.energy_max;
+        p.energy_updated_slot = slot;
+    });
+    let battle = common::battle_pda(&alice, &bob, 1);
+    let err = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::CommitMove {
+                    battle,
+                    player: bob,
+                    player_account: player_pda(&bob),
+                    config: config_pda(),
+                    clock: sysvar::clock::ID,
+                    session: None,
+                    character_token: None,
+                },
+                defighter::instruction::CommitMove { commitment: [0; 32] },
+            )],
+            &[bob],
+        )
+        .unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotEnoughEnergy.into()));
+}
+
+#[test]
+fn exhausted_opponents_cant_be_challenged() {
+    let (mut bank, alice, bob) = setup();
+    let slot = bank.slot();
+    edit_player(&mut bank, bob, |p| {
+        p.energy_used = BalanceConfigParams::default().energy_max;
+        p.energy_updated_slot = slot;
+    });
+    let err = common::try_initiate_battle(&mut bank, alice, bob, 1, 10).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotEnoughEnergy.into()));
+    assert_eq!(energy(&bank, alice), BalanceConfigParams::default().energy_max);
+}
+
+#[test]
+fn refill_buys_only_what_fits() {
+    let (mut bank, alice, _) = setup();
+    let rules = BalanceConfigParams::default();
+    let slot = bank.slot();
+    edit_player(&mut bank, alice, |p| {
+        p.energy_used = 3;
+        p.energy_updated_slot = slot;
+    });
+
+    let err = refill(&mut bank, alice, 1).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotEnoughXP.into()));
+
+    edit_player(&mut bank, alice, |p| p.xp = 100);
+    let meta = refill(&mut bank, alice, 5).unwrap();
+    let refilled = &meta.events::<EnergyRefilled>()[0];
+    assert_eq!((refilled.amount, refilled.xp_cost, refilled.energy), (3, 3 * rules.energy_xp_cost, rules.energy_max));
+    let state: Player = bank.get_anchor_account(&player_pda(&alice)).unwrap();
+    assert_eq!(state.xp, 100 - 3 * rules.energy_xp_cost);
+
+    let err = refill(&mut bank, alice, 1).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::EnergyFull.into()));
+}
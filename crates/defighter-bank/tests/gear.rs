@@ -0,0 +1,154 @@
+//! Gear: the item registry, inventories, equipping and its effect on damage.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::events::{BattleResolved, ItemEquipped};
+use defighter::state::item::{GearSlot, GearStats, Inventory, ItemParams, Rarity};
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, create_item, create_player, inventory_pda, ix, item_pda, player_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+const WEAPON: u32 = 1;
+const HELMET: u32 = 2;
+
+fn grant(bank: &mut Bank, admin: Pubkey, id: u32, recipient: Pubkey) {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::GrantItem {
+                config: config_pda(),
+                item: item_pda(id),
+                inventory: inventory_pda(&recipient),
+                admin,
+                admin_set: None,
+                proposal: None,
+                system_program: system_program::ID,
+            },
+            defighter::instruction::GrantItem { recipient },
+        )],
+        &[admin],
+    )
+    .unwrap();
+}
+
+fn equip(bank: &mut Bank, player: Pubkey, id: u32) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::EquipItem {
+                player: player_pda(&player),
+                inventory: inventory_pda(&player),
+                item: item_pda(id),
+                authority: player,
+                character_token: None,
+            },
+            defighter::instruction::EquipItem {},
+        )],
+        &[player],
+    )
+}
+
+fn unequip(bank: &mut Bank, player: Pubkey, slot: GearSlot) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::UnequipItem { player: player_pda(&player), inventory: inventory_pda(&player), authority: player, character_token: None },
+            defighter::instruction::UnequipItem { slot },
+        )],
+        &[player],
+    )
+}
+
+fn gear(bank: &Bank, player: Pubkey) -> GearStats {
+    bank.get_anchor_account::<Player>(&player_pda(&player)).unwrap().gear
+}
+
+/// A Builder-only legendary weapon and an epic helmet anyone can wear.
+fn setup() -> (Bank, Pubkey, Pubkey, Pubkey) {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+
+    let weapon = ItemParams {
+        slot: GearSlot::Weapon,
+        rarity: Rarity::Legendary,
+        class: Some(FighterClass::Builder),
+        stats: GearStats { attack_bps: 300, defense_bps: 0 },
+    };
+    let helmet = ItemParams {
+        slot: GearSlot::Head,
+        rarity: Rarity::Epic,
+        class: None,
+        stats: GearStats { attack_bps: 0, defense_bps: 250 },
+    };
+    create_item(&mut bank, admin, WEAPON, weapon).unwrap();
+    create_item(&mut bank, admin, HELMET, helmet).unwrap();
+    (bank, admin, alice, bob)
+}
+
+#[test]
+fn items_are_admin_made_and_capped_by_rarity() {
+    let (mut bank, admin, alice, _) = setup();
+    let too_strong = ItemParams {
+        slot: GearSlot::Body,
+        rarity: Rarity::Common,
+        class: None,
+        stats: GearStats { attack_bps: 101, defense_bps: 0 },
+    };
+    let err = create_item(&mut bank, admin, 3, too_strong).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidItemParams.into()));
+    let fine = ItemParams { rarity: Rarity::Uncommon, ..too_strong };
+    let err = create_item(&mut bank, alice, 3, fine).unwrap_err();
+    assert_eq!(err.custom_code(), Some(anchor_lang::error::ErrorCode::ConstraintHasOne.into()));
+    create_item(&mut bank, admin, 3, fine).unwrap();
+}
+
+#[test]
+fn equipping_checks_ownership_and_class() {
+    let (mut bank, admin, alice, bob) = setup();
+    grant(&mut bank, admin, WEAPON, alice);
+    grant(&mut bank, admin, HELMET, bob);
+    let inventory: Inventory = bank.get_anchor_account(&inventory_pda(&alice)).unwrap();
+    assert_eq!((inventory.owner, inventory.items.clone()), (alice, vec![WEAPON]));
+
+    let err = equip(&mut bank, alice, WEAPON).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::ItemClassMismatch.into()));
+    let err = equip(&mut bank, bob, WEAPON).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::ItemNotOwned.into()));
+
+    grant(&mut bank, admin, WEAPON, bob);
+    equip(&mut bank, bob, HELMET).unwrap();
+    let meta = equip(&mut bank, bob, WEAPON).unwrap();
+    let equipped = &meta.events::<ItemEquipped>()[0];
+    assert_eq!((equipped.slot, equipped.replaced), (GearSlot::Weapon, None));
+    assert_eq!(gear(&bank, bob), GearStats { attack_bps: 300, defense_bps: 250 });
+
+    unequip(&mut bank, bob, GearSlot::Head).unwrap();
+    assert_eq!(gear(&bank, bob), GearStats { attack_bps: 300, defense_bps: 0 });
+    let err = unequip(&mut bank, bob, GearSlot::Head).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::SlotEmpty.into()));
+}
+
+#[test]
+fn equipped_gear_changes_battle_damage() {
+    let (mut bank, admin, alice, bob) = setup();
+    let moves = [MoveChoice::MemeBomb, MoveChoice::ShipIt];
+    let meta = common::play_battle(&mut bank, alice, bob, 1, moves);
+    let plain = &meta.events::<BattleResolved>()[0];
+    assert_eq!((plain.challenger_damage, plain.opponent_damage), (80, 125));
+
+    grant(&mut bank, admin, WEAPON, bob);
+    grant(&mut bank, admin, HELMET, bob);
+    equip(&mut bank, bob, WEAPON).unwrap();
+    equip(&mut bank, bob, HELMET).unwrap();
+    bank.warp_to_slot(bank.slot() + 1_000);
+    let meta = common::play_battle(&mut bank, alice, bob, 2, moves);
+    let geared = &meta.events::<BattleResolved>()[0];
+    // 80 * 0.975 and 125 * 1.03, rounded down
+    assert_eq!((geared.challenger_damage, geared.opponent_damage), (78, 128));
+}
@@ -0,0 +1,156 @@
+//! Loot: drops on revealed wins, pity, the loot table and claiming into the inventory.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::system_program;
+use defighter::events::LootDropped;
+use defighter::logic::{loot_entropy, tie_break_entropy};
+use defighter::state::config::BalanceConfigParams;
+use defighter::state::item::{GearSlot, GearStats, Inventory, ItemParams, Rarity};
+use defighter::state::loot::LootBag;
+use defighter::state::player::{FighterClass, MoveChoice};
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, create_item, create_player, inventory_pda, ix, item_pda, loot_bag_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+/// Bob (Builder) beats Alice (Shitposter) with these
+const MOVES: [MoveChoice; 2] = [MoveChoice::MemeBomb, MoveChoice::ShipIt];
+
+fn loot_table_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"loot_table"], &defighter::ID).0
+}
+
+fn setup(params: BalanceConfigParams) -> (Bank, Pubkey, Pubkey, Pubkey) {
+    let mut bank = common::bank();
+    let admin = common::init_config_with_params(&mut bank, params);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+    (bank, admin, alice, bob)
+}
+
+fn set_loot_table(bank: &mut Bank, admin: Pubkey, items: &[u32]) -> TxResult {
+    let mut instruction = ix(
+        defighter::accounts::SetLootTable {
+            config: config_pda(),
+            loot_table: loot_table_pda(),
+            admin,
+            admin_set: None,
+            proposal: None,
+            system_program: system_program::ID,
+        },
+        defighter::instruction::SetLootTable {},
+    );
+    instruction.accounts.extend(items.iter().map(|id| AccountMeta::new_readonly(item_pda(*id), false)));
+    bank.process_transaction(&[instruction], &[admin])
+}
+
+fn claim(bank: &mut Bank, owner: Pubkey) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ClaimLoot {
+                loot_bag: loot_bag_pda(&owner),
+                loot_table: loot_table_pda(),
+                inventory: inventory_pda(&owner),
+                owner,
+                system_program: system_program::ID,
+            },
+            defighter::instruction::ClaimLoot {},
+        )],
+        &[owner],
+    )
+}
+
+fn bag(bank: &Bank, owner: Pubkey) -> LootBag {
+    bank.get_anchor_account(&loot_bag_pda(&owner)).unwrap()
+}
+
+fn item(rarity: Rarity) -> ItemParams {
+    ItemParams { slot: GearSlot::Body, rarity, class: None, stats: GearStats::default() }
+}
+
+#[test]
+fn winners_get_drops_they_can_claim_as_items() {
+    let params = BalanceConfigParams { loot_drop_bps: 10_000, loot_rarity_weights: [1, 0, 1, 0, 0], ..Default::default() };
+    let (mut bank, admin, alice, bob) = setup(params);
+    create_item(&mut bank, admin, 1, item(Rarity::Common)).unwrap();
+    create_item(&mut bank, admin, 2, item(Rarity::Rare)).unwrap();
+    let err = set_loot_table(&mut bank, alice, &[1, 2]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(anchor_lang::error::ErrorCode::ConstraintHasOne.into()));
+    set_loot_table(&mut bank, admin, &[1, 2]).unwrap();
+
+    let meta = common::play_battle(&mut bank, alice, bob, 1, MOVES);
+    let dropped = &meta.events::<LootDropped>()[0];
+    assert_eq!((dropped.player, dropped.stored), (bob, true));
+    assert!(matches!(dropped.rarity, Rarity::Common | Rarity::Rare));
+    // The roll mixes both revealed salts, `[1; 32]` and `[2; 32]` in `play_battle`
+    let battle = common::battle_pda(&alice, &bob, 1);
+    let (c, o) = (MOVES[0] as u8, MOVES[1] as u8);
+    let c_vrf = tie_break_entropy(c, o, &[1; 32], &[2; 32], &battle);
+    let o_vrf = tie_break_entropy(o, c, &[2; 32], &[1; 32], &battle);
+    assert_eq!(dropped.roll, loot_entropy(c_vrf, o_vrf, &battle));
+    let bob_bag = bag(&bank, bob);
+    assert_eq!((bob_bag.drops.len(), bob_bag.total_drops), (1, 1));
+    assert_eq!(bag(&bank, alice).pity, 1);
+
+    claim(&mut bank, bob).unwrap();
+    let inventory: Inventory = bank.get_anchor_account(&inventory_pda(&bob)).unwrap();
+    let expected = if dropped.rarity == Rarity::Common { 1 } else { 2 };
+    assert_eq!(inventory.items, vec![expected]);
+    assert!(bag(&bank, bob).drops.is_empty());
+}
+
+#[test]
+fn drops_without_a_table_entry_wait_in_the_bag() {
+    let params = BalanceConfigParams { loot_drop_bps: 10_000, loot_rarity_weights: [0, 0, 0, 0, 1], ..Default::default() };
+    let (mut bank, admin, alice, bob) = setup(params);
+    create_item(&mut bank, admin, 1, item(Rarity::Common)).unwrap();
+    set_loot_table(&mut bank, admin, &[1]).unwrap();
+
+    common::play_battle(&mut bank, alice, bob, 1, MOVES);
+    claim(&mut bank, bob).unwrap();
+    assert_eq!(bag(&bank, bob).drops[0].rarity, Rarity::Legendary);
+
+    create_item(&mut bank, admin, 2, item(Rarity::Legendary)).unwrap();
+    set_loot_table(&mut bank, admin, &[1, 2]).unwrap();
+    claim(&mut bank, bob).unwrap();
+    let inventory: Inventory = bank.get_anchor_account(&inventory_pda(&bob)).unwrap();
+    assert_eq!(inventory.items, vec![2]);
+}
+
+#[test]
+fn pity_guarantees_a_rare_after_dry_battles() {
+    let params = BalanceConfigParams { loot_drop_bps: 0, loot_pity_threshold: 2, ..Default::default() };
+    let (mut bank, _, alice, bob) = setup(params);
+    let mut slot = 0;
+    for nonce in 1..=2 {
+        slot += params.pair_cooldown_slots;
+        bank.warp_to_slot(slot);
+        let meta = common::play_battle(&mut bank, alice, bob, nonce, MOVES);
+        assert!(meta.events::<LootDropped>().is_empty());
+    }
+    assert_eq!((bag(&bank, alice).pity, bag(&bank, bob).pity), (2, 2));
+
+    bank.warp_to_slot(slot + params.pair_cooldown_slots);
+    let meta = common::play_battle(&mut bank, alice, bob, 3, MOVES);
+    let dropped = &meta.events::<LootDropped>()[0];
+    assert_eq!(dropped.player, bob);
+    assert!(dropped.rarity >= Rarity::Rare);
+    assert_eq!((bag(&bank, alice).pity, bag(&bank, bob).pity), (3, 0));
+}
+
+#[test]
+fn forfeits_drop_nothing() {
+    let params = BalanceConfigParams { loot_drop_bps: 10_000, ..Default::default() };
+    let (mut bank, _, alice, bob) = setup(params);
+    let battle = common::initiate_battle(&mut bank, alice, bob, 1, 10);
+    common::commit_moves(&mut bank, battle, [alice, bob], MOVES);
+    bank.warp_to_slot(100);
+    let meta = common::resolve_battle(&mut bank, battle, alice, bob);
+    assert!(meta.events::<LootDropped>().is_empty());
+    assert!(bag(&bank, alice).drops.is_empty());
+}
@@ -0,0 +1,337 @@
+//! Growing accounts written under older, shorter layouts.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::state::battle::{Battle, BattleState};
+use defighter::state::config::{BalanceConfig, BalanceConfigParams};
+use defighter::state::customization::CHARACTER_CUSTOM_SEED;
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter::state::profile::Profile;
+use defighter_bank::Account;
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, consumables_pda, create_player, inventory_pda, ix, loot_bag_pda, pending_config_pda, player_pda, profile_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+/// Cut `key` down to `len` bytes, with only the rent that length needs.
+fn truncate(bank: &mut Bank, key: Pubkey, len: usize) {
+    let mut account = bank.get_account(&key).unwrap().clone();
+    account.data.truncate(len);
+    account.lamports = Rent::default().minimum_balance(len);
+    bank.set_account(key, account);
+}
+
+/// `Player` as the baseline laid it out, at `[b"player", authority]`
+#[derive(AnchorSerialize)]
+struct BaselinePlayer {
+    authority: Pubkey,
+    class: FighterClass,
+    xp: u64,
+    abilities: [u16; 3],
+    elo: i32,
+    version: u8,
+}
+
+/// `Battle` as the baseline laid it out, naming wallets
+#[derive(AnchorSerialize)]
+struct BaselineBattle {
+    challenger: Pubkey,
+    opponent: Pubkey,
+    nonce: u64,
+    state: BattleState,
+    created_slot: u64,
+    commit_deadline_slot: u64,
+    reveal_deadline_slot: u64,
+    commit_challenger: Option<[u8; 32]>,
+    commit_opponent: Option<[u8; 32]>,
+    reveal_challenger: Option<MoveChoice>,
+    reveal_opponent: Option<MoveChoice>,
+    winner: Option<Pubkey>,
+    challenger_hp: u16,
+    opponent_hp: u16,
+}
+
+/// Write `value` as a program account with discriminator `disc`
+fn put(bank: &mut Bank, key: Pubkey, disc: &[u8], value: impl AnchorSerialize) {
+    let mut data = disc.to_vec();
+    value.serialize(&mut data).unwrap();
+    let lamports = Rent::default().minimum_balance(data.len());
+    bank.set_account(key, Account { lamports, data, owner: defighter::ID, executable: false });
+}
+
+fn legacy_player_pda(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"player", authority.as_ref()], &defighter::ID).0
+}
+
+fn migrate_legacy_player(bank: &mut Bank, authority: Pubkey) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::MigrateLegacyPlayer {
+                legacy_player: legacy_player_pda(&authority),
+                profile: profile_pda(&authority),
+                player: player_pda(&authority),
+                loot_bag: loot_bag_pda(&authority),
+                consumables: consumables_pda(&authority),
+                authority,
+                config: config_pda(),
+                system_program: system_program::ID,
+            },
+            defighter::instruction::MigrateLegacyPlayer {},
+        )],
+        &[authority],
+    )
+}
+
+fn migrate_player(bank: &mut Bank, authority: Pubkey, player: Pubkey) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::MigratePlayer { player, authority, system_program: system_program::ID },
+            defighter::instruction::MigratePlayer { index: 0 },
+        )],
+        &[authority],
+    )
+}
+
+fn migrate_config(bank: &mut Bank, admin: Pubkey, params: BalanceConfigParams) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::MigrateConfig {
+                config: config_pda(),
+                pending_config: pending_config_pda(),
+                admin,
+                system_program: system_program::ID,
+            },
+            defighter::instruction::MigrateConfig { params },
+        )],
+        &[admin],
+    )
+}
+
+fn migrate_battle(bank: &mut Bank, payer: Pubkey, battle: Pubkey, players: [Pubkey; 2]) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::MigrateBattle {
+                battle,
+                player_challenger: players[0],
+                player_opponent: players[1],
+                config: config_pda(),
+                payer,
+                system_program: system_program::ID,
+            },
+            defighter::instruction::MigrateBattle {},
+        )],
+        &[payer],
+    )
+}
+
+#[test]
+fn player_grows_with_zeroed_new_fields() {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    let player = create_player(&mut bank, alice, FighterClass::Builder);
+    let before: Player = bank.get_anchor_account(&player).unwrap();
+    // The layout before `xp_spent`
+    truncate(&mut bank, player, Player::SPACE - 8);
+
+    migrate_player(&mut bank, alice, player).unwrap();
+    let account = bank.get_account(&player).unwrap();
+    assert_eq!(account.data.len(), Player::SPACE);
+    assert_eq!(account.lamports, Rent::default().minimum_balance(Player::SPACE));
+    let after: Player = bank.get_anchor_account(&player).unwrap();
+    assert_eq!((after.xp, after.elo, after.xp_spent), (before.xp, before.elo, 0));
+    assert_eq!(after.version, Player::VERSION);
+
+    let err = migrate_player(&mut bank, alice, player).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::AlreadyMigrated.into()));
+}
+
+#[test]
+fn config_is_rebuilt_by_its_admin_only() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    // Queue a change under the old layout; migrating drops it
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::AdminUpdateConfig {
+                config: config_pda(),
+                pending_config: pending_config_pda(),
+                admin,
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::AdminUpdateConfig { params: BalanceConfigParams::default() },
+        )],
+        &[admin],
+    )
+    .unwrap();
+    // The layout before the damage tables moved into the config
+    truncate(&mut bank, config_pda(), BalanceConfig::SPACE - 40);
+
+    let params = BalanceConfigParams { base_damage: 120, ..Default::default() };
+    let mallory = Pubkey::new_unique();
+    bank.airdrop(&mallory, 1_000_000_000);
+    let err = migrate_config(&mut bank, mallory, params).unwrap_err();
+    assert_eq!(err.custom_code(), Some(anchor_lang::error::ErrorCode::ConstraintHasOne.into()));
+    let err = migrate_config(&mut bank, admin, BalanceConfigParams { base_damage: 0, ..params }).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidConfig.into()));
+
+    migrate_config(&mut bank, admin, params).unwrap();
+    let cfg: BalanceConfig = bank.get_anchor_account(&config_pda()).unwrap();
+    assert_eq!(cfg.admin, admin);
+    assert_eq!(cfg.params(), params);
+    assert_eq!(bank.get_account(&pending_config_pda()).map_or(0, |a| a.lamports), 0);
+
+    let err = migrate_config(&mut bank, admin, params).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::AlreadyMigrated.into()));
+}
+
+#[test]
+fn open_battle_grows_and_plays_out() {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let first = create_player(&mut bank, alice, FighterClass::Builder);
+    let second = create_player(&mut bank, bob, FighterClass::VC);
+    let battle = common::initiate_battle(&mut bank, alice, bob, 1, 10);
+    // The layout before per-battle rules, boosts and salts
+    let tail = 4 + BalanceConfigParams::SIZE + 2 + 2 * 2 + 2 * 32;
+    truncate(&mut bank, battle, Battle::SPACE - tail);
+
+    migrate_battle(&mut bank, bob, battle, [first, second]).unwrap();
+    let state: Battle = bank.get_anchor_account(&battle).unwrap();
+    assert_eq!((state.challenger, state.opponent), (first, second));
+    assert_eq!(state.rules, BalanceConfigParams::default());
+    assert_eq!(state.xp_scale_bps, 10_000);
+
+    let moves = [MoveChoice::ShipIt, MoveChoice::SeriesACannon];
+    common::commit_moves(&mut bank, battle, [alice, bob], moves);
+    common::reveal_moves(&mut bank, battle, [alice, bob], moves);
+    common::resolve_battle(&mut bank, battle, alice, bob);
+    let state: Battle = bank.get_anchor_account(&battle).unwrap();
+    assert!(state.state == BattleState::Resolved);
+
+    let err = migrate_battle(&mut bank, bob, battle, [first, second]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::AlreadyMigrated.into()));
+}
+
+#[test]
+fn baseline_players_and_battles_move_to_characters() {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    for (who, class, xp) in [(alice, FighterClass::Builder, 70), (bob, FighterClass::VC, 0)] {
+        bank.airdrop(&who, 1_000_000_000);
+        let legacy = BaselinePlayer { authority: who, class, xp, abilities: [2, 0, 1], elo: 1_040, version: 1 };
+        put(&mut bank, legacy_player_pda(&who), Player::DISCRIMINATOR, legacy);
+    }
+    let battle = Pubkey::find_program_address(&[b"battle", alice.as_ref(), bob.as_ref(), &1u64.to_le_bytes()], &defighter::ID).0;
+    let now = bank.slot();
+    let head = BaselineBattle {
+        challenger: alice,
+        opponent: bob,
+        nonce: 1,
+        state: BattleState::WaitingForCommits,
+        created_slot: now,
+        commit_deadline_slot: now + 10,
+        reveal_deadline_slot: now + 20,
+        commit_challenger: None,
+        commit_opponent: None,
+        reveal_challenger: None,
+        reveal_opponent: None,
+        winner: None,
+        challenger_hp: 200,
+        opponent_hp: 200,
+    };
+    put(&mut bank, battle, Battle::DISCRIMINATOR, head);
+
+    // The battle waits for both players to move over
+    assert!(migrate_battle(&mut bank, bob, battle, [player_pda(&alice), player_pda(&bob)]).is_err());
+    // Only the wallet that owns it can move a player
+    let err = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::MigrateLegacyPlayer {
+                    legacy_player: legacy_player_pda(&alice),
+                    profile: profile_pda(&bob),
+                    player: player_pda(&bob),
+                    loot_bag: loot_bag_pda(&bob),
+                    consumables: consumables_pda(&bob),
+                    authority: bob,
+                    config: config_pda(),
+                    system_program: system_program::ID,
+                },
+                defighter::instruction::MigrateLegacyPlayer {},
+            )],
+            &[bob],
+        )
+        .unwrap_err();
+    assert_eq!(err.custom_code(), Some(anchor_lang::error::ErrorCode::ConstraintSeeds.into()));
+
+    let balance = bank.get_account(&alice).unwrap().lamports;
+    let legacy_rent = bank.get_account(&legacy_player_pda(&alice)).unwrap().lamports;
+    migrate_legacy_player(&mut bank, alice).unwrap();
+    migrate_legacy_player(&mut bank, bob).unwrap();
+    assert!(bank.get_account(&legacy_player_pda(&alice)).is_none());
+    let player: Player = bank.get_anchor_account(&player_pda(&alice)).unwrap();
+    assert_eq!((player.authority, player.class, player.xp), (alice, FighterClass::Builder, 70));
+    assert_eq!((player.abilities, player.elo, player.index), ([2, 0, 1], 1_040, 0));
+    assert_eq!(player.version, Player::VERSION);
+    let profile: Profile = bank.get_anchor_account(&profile_pda(&alice)).unwrap();
+    assert_eq!((profile.authority, profile.characters, profile.next_index), (alice, 1, 1));
+    // The old rent comes back, less what the new accounts cost
+    let spent = [player_pda(&alice), profile_pda(&alice), loot_bag_pda(&alice), consumables_pda(&alice)]
+        .iter()
+        .map(|key| bank.get_account(key).unwrap().lamports)
+        .sum::<u64>();
+    assert_eq!(bank.get_account(&alice).unwrap().lamports, balance + legacy_rent - spent);
+    assert!(migrate_legacy_player(&mut bank, alice).is_err());
+
+    // The battle now names the characters and plays out between them
+    migrate_battle(&mut bank, bob, battle, [player_pda(&alice), player_pda(&bob)]).unwrap();
+    let state: Battle = bank.get_anchor_account(&battle).unwrap();
+    assert_eq!((state.challenger, state.opponent), (player_pda(&alice), player_pda(&bob)));
+    let open = |bank: &Bank, who: &Pubkey| bank.get_anchor_account::<Player>(&player_pda(who)).unwrap().open_battles;
+    assert_eq!(open(&bank, &alice), 1);
+
+    let moves = [MoveChoice::ShipIt, MoveChoice::SeriesACannon];
+    common::commit_moves(&mut bank, battle, [alice, bob], moves);
+    common::reveal_moves(&mut bank, battle, [alice, bob], moves);
+    common::resolve_battle(&mut bank, battle, alice, bob);
+    let state: Battle = bank.get_anchor_account(&battle).unwrap();
+    assert!(state.state == BattleState::Resolved);
+    assert_eq!((open(&bank, &alice), open(&bank, &bob)), (0, 0));
+}
+
+#[test]
+fn legacy_player_needs_the_first_slot() {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let carol = Pubkey::new_unique();
+    bank.airdrop(&carol, 1_000_000_000);
+    let legacy = BaselinePlayer { authority: carol, class: FighterClass::VC, xp: 0, abilities: [0; 3], elo: 1_000, version: 1 };
+    put(&mut bank, legacy_player_pda(&carol), Player::DISCRIMINATOR, legacy);
+    // A character made first takes index 0; closing it doesn't free the slot
+    let first = create_player(&mut bank, carol, FighterClass::Builder);
+    assert!(migrate_legacy_player(&mut bank, carol).is_err());
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::ClosePlayer {
+                player: first,
+                profile: profile_pda(&carol),
+                character_customization: Pubkey::find_program_address(&[CHARACTER_CUSTOM_SEED, first.as_ref()], &defighter::ID).0,
+                inventory: inventory_pda(&carol),
+                authority: carol,
+            },
+            defighter::instruction::ClosePlayer {},
+        )],
+        &[carol],
+    )
+    .unwrap();
+    let err = migrate_legacy_player(&mut bank, carol).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::CharacterSlotTaken.into()));
+}
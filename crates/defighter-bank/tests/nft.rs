@@ -0,0 +1,276 @@
+//! NFT-verified cosmetics against locally built token and Metaplex metadata accounts.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::events::{NftPerkRevoked, NftVerified};
+use defighter::logic::nft::{metadata_pda, METADATA_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use defighter::state::crafting::CosmeticKind;
+use defighter::state::customization::{CharacterCustomizationDataV1, CharacterCustomizationV1, CHARACTER_CUSTOM_SEED};
+use defighter::state::player::FighterClass;
+use defighter_bank::{Account, Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, create_player, ix, player_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+const OUTFIT: u8 = 5;
+
+struct Fixture {
+    bank: Bank,
+    admin: Pubkey,
+    alice: Pubkey,
+    bob: Pubkey,
+    collection: Pubkey,
+    mint: Pubkey,
+    token: Pubkey,
+}
+
+fn customization_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CHARACTER_CUSTOM_SEED, player_pda(owner).as_ref()], &defighter::ID).0
+}
+
+fn nft_collection_pda(collection: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"nft_collection", collection.as_ref()], &defighter::ID).0
+}
+
+fn nft_perk_pda(owner: &Pubkey, collection: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"nft_perk", customization_pda(owner).as_ref(), collection.as_ref()], &defighter::ID).0
+}
+
+/// An initialized SPL token account holding `amount` of `mint`
+fn set_token_account(bank: &mut Bank, key: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+    let mut data = vec![0; 165];
+    data[0..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data[108] = 1;
+    bank.set_account(key, Account { lamports: 2_039_280, data, owner: TOKEN_PROGRAM_ID, executable: false });
+}
+
+/// A `MetadataV1` account for `mint` in `collection`, zero-padded like on-chain
+fn set_metadata(bank: &mut Bank, mint: Pubkey, collection: Pubkey, verified: bool, owner: Pubkey) {
+    let mut data = vec![4];
+    data.extend(Pubkey::new_unique().to_bytes());
+    data.extend(mint.to_bytes());
+    for field in ["Mad Lad #7", "MAD", "https://example.com/7.json"] {
+        data.extend((field.len() as u32).to_le_bytes());
+        data.extend(field.as_bytes());
+    }
+    data.extend(500u16.to_le_bytes());
+    data.push(0); // no creators
+    data.extend([1, 1, 0, 0]); // primary sale, mutable, no edition nonce or token standard
+    data.extend([1, verified as u8]);
+    data.extend(collection.to_bytes());
+    data.resize(679, 0);
+    bank.set_account(metadata_pda(&mint), Account { lamports: 5_616_720, data, owner, executable: false });
+}
+
+fn set_nft_collection(bank: &mut Bank, admin: Pubkey, collection: Pubkey, index: u8, active: bool) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::SetNftCollection {
+                config: config_pda(),
+                nft_collection: nft_collection_pda(&collection),
+                admin,
+                admin_set: None,
+                proposal: None,
+                system_program: system_program::ID,
+            },
+            defighter::instruction::SetNftCollection { collection, kind: CosmeticKind::Outfit, index, active },
+        )],
+        &[admin],
+    )
+}
+
+fn verify(bank: &mut Bank, owner: Pubkey, collection: Pubkey, token: Pubkey, mint: Pubkey) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::VerifyNft {
+                player: player_pda(&owner),
+                character_customization: customization_pda(&owner),
+                nft_collection: nft_collection_pda(&collection),
+                nft_perk: nft_perk_pda(&owner, &collection),
+                token_account: token,
+                metadata: metadata_pda(&mint),
+                config: config_pda(),
+                authority: owner,
+                system_program: system_program::ID,
+                character_token: None,
+            },
+            defighter::instruction::VerifyNft {},
+        )],
+        &[owner],
+    )
+}
+
+fn revoke(bank: &mut Bank, signer: Pubkey, owner: Pubkey, collection: Pubkey, token: Pubkey) -> TxResult {
+    bank.airdrop(&signer, 1_000_000_000);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::RevokeNftPerk {
+                player: player_pda(&owner),
+                character_customization: customization_pda(&owner),
+                nft_perk: nft_perk_pda(&owner, &collection),
+                nft_collection: nft_collection_pda(&collection),
+                token_account: token,
+                authority: owner,
+            },
+            defighter::instruction::RevokeNftPerk {},
+        )],
+        &[signer],
+    )
+}
+
+fn look(outfit_style_index: u8) -> CharacterCustomizationDataV1 {
+    CharacterCustomizationDataV1 {
+        outfit_style_index,
+        accessory_slots: [CharacterCustomizationV1::NO_ACCESSORY; 2],
+        ..Default::default()
+    }
+}
+
+/// Update `owner`'s look, presenting `perks` as (collection, token account) pairs
+fn update_look(bank: &mut Bank, owner: Pubkey, data: CharacterCustomizationDataV1, perks: &[(Pubkey, Pubkey)]) -> TxResult {
+    let mut instruction = ix(
+        defighter::accounts::UpdateCharacterCustomization {
+            player: player_pda(&owner),
+            character_customization: customization_pda(&owner),
+            authority: owner,
+            config: config_pda(),
+            character_token: None,
+        },
+        defighter::instruction::UpdateCharacterCustomization { data },
+    );
+    for (collection, token) in perks {
+        instruction.accounts.push(AccountMeta::new_readonly(nft_perk_pda(&owner, collection), false));
+        instruction.accounts.push(AccountMeta::new_readonly(*token, false));
+    }
+    bank.process_transaction(&[instruction], &[owner])
+}
+
+fn customization(bank: &Bank, owner: Pubkey) -> CharacterCustomizationV1 {
+    bank.get_anchor_account(&customization_pda(&owner)).unwrap()
+}
+
+/// Alice holds a verified NFT from an allowlisted collection that unlocks `OUTFIT`.
+fn setup() -> Fixture {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    for (who, class) in [(alice, FighterClass::Shitposter), (bob, FighterClass::Builder)] {
+        create_player(&mut bank, who, class);
+        bank.process_transaction(
+            &[ix(
+                defighter::accounts::CreateCharacterCustomization {
+                    player: player_pda(&who),
+                    character_customization: customization_pda(&who),
+                    authority: who,
+                    config: config_pda(),
+                    system_program: system_program::ID,
+                    character_token: None,
+                },
+                defighter::instruction::CreateCharacterCustomization { data: look(0) },
+            )],
+            &[who],
+        )
+        .unwrap();
+    }
+
+    let (collection, mint, token) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    set_token_account(&mut bank, token, mint, alice, 1);
+    set_metadata(&mut bank, mint, collection, true, METADATA_PROGRAM_ID);
+    set_nft_collection(&mut bank, admin, collection, OUTFIT, true).unwrap();
+    Fixture { bank, admin, alice, bob, collection, mint, token }
+}
+
+#[test]
+fn holding_the_nft_unlocks_its_cosmetic_until_sold() {
+    let Fixture { mut bank, alice, bob, collection, mint, token, .. } = setup();
+    let err = update_look(&mut bank, alice, look(OUTFIT), &[]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::CosmeticLocked.into()));
+
+    let meta = verify(&mut bank, alice, collection, token, mint).unwrap();
+    let verified = &meta.events::<NftVerified>()[0];
+    assert_eq!((verified.mint, verified.kind, verified.index), (mint, CosmeticKind::Outfit, OUTFIT));
+    update_look(&mut bank, alice, look(OUTFIT), &[(collection, token)]).unwrap();
+    let err = revoke(&mut bank, bob, alice, collection, token).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NftStillHeld.into()));
+
+    // Alice sells the NFT to Bob: her perk no longer re-verifies
+    set_token_account(&mut bank, token, mint, bob, 1);
+    let err = update_look(&mut bank, alice, look(OUTFIT), &[(collection, token)]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NftNotHeld.into()));
+    let err = verify(&mut bank, alice, collection, token, mint).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NftNotHeld.into()));
+
+    // Anyone can revoke the stale perk, taking the outfit off
+    let meta = revoke(&mut bank, bob, alice, collection, token).unwrap();
+    assert!(meta.events::<NftPerkRevoked>()[0].stripped);
+    assert_eq!(customization(&bank, alice).outfit_style_index, CharacterCustomizationV1::FREE_OUTFIT);
+    assert!(bank.get_account(&nft_perk_pda(&alice, &collection)).is_none());
+
+    // Bob now verifies it for himself
+    verify(&mut bank, bob, collection, token, mint).unwrap();
+    update_look(&mut bank, bob, look(OUTFIT), &[(collection, token)]).unwrap();
+}
+
+#[test]
+fn perks_only_count_for_their_own_customization() {
+    let Fixture { mut bank, alice, bob, collection, mint, token, .. } = setup();
+    verify(&mut bank, alice, collection, token, mint).unwrap();
+
+    // Bob can't borrow Alice's perk
+    let mut instruction = ix(
+        defighter::accounts::UpdateCharacterCustomization {
+            player: player_pda(&bob),
+            character_customization: customization_pda(&bob),
+            authority: bob,
+            config: config_pda(),
+            character_token: None,
+        },
+        defighter::instruction::UpdateCharacterCustomization { data: look(OUTFIT) },
+    );
+    instruction.accounts.push(AccountMeta::new_readonly(nft_perk_pda(&alice, &collection), false));
+    instruction.accounts.push(AccountMeta::new_readonly(token, false));
+    let err = bank.process_transaction(&[instruction], &[bob]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidNftPerk.into()));
+
+    // A token account other than the verified one doesn't count
+    let other = Pubkey::new_unique();
+    set_token_account(&mut bank, other, mint, alice, 1);
+    let err = update_look(&mut bank, alice, look(OUTFIT), &[(collection, other)]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NftNotHeld.into()));
+}
+
+#[test]
+fn verification_checks_the_allowlist_and_metadata() {
+    let Fixture { mut bank, admin, alice, collection, mint, token, .. } = setup();
+    let err = set_nft_collection(&mut bank, alice, collection, OUTFIT, true).unwrap_err();
+    assert_eq!(err.custom_code(), Some(anchor_lang::error::ErrorCode::ConstraintHasOne.into()));
+    let err = set_nft_collection(&mut bank, admin, collection, 32, true).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidNftCollection.into()));
+
+    // Unverified membership, a spoofed metadata owner and an empty token account
+    set_metadata(&mut bank, mint, collection, false, METADATA_PROGRAM_ID);
+    let err = verify(&mut bank, alice, collection, token, mint).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NftNotInCollection.into()));
+    set_metadata(&mut bank, mint, collection, true, defighter::ID);
+    let err = verify(&mut bank, alice, collection, token, mint).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidNftMetadata.into()));
+    set_metadata(&mut bank, mint, collection, true, METADATA_PROGRAM_ID);
+    set_token_account(&mut bank, token, mint, alice, 0);
+    let err = verify(&mut bank, alice, collection, token, mint).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NftNotHeld.into()));
+
+    // Delisting blocks new verifications and lets held perks be revoked
+    set_token_account(&mut bank, token, mint, alice, 1);
+    verify(&mut bank, alice, collection, token, mint).unwrap();
+    set_nft_collection(&mut bank, admin, collection, OUTFIT, false).unwrap();
+    let err = verify(&mut bank, alice, collection, token, mint).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NftNotInCollection.into()));
+    let meta = revoke(&mut bank, admin, alice, collection, token).unwrap();
+    assert!(!meta.events::<NftPerkRevoked>()[0].stripped);
+}
@@ -0,0 +1,93 @@
+//! Anti-farming: rematch cooldowns, XP decay and empty forfeits.
+
+use anchor_lang::prelude::*;
+use defighter::errors::CustomError;
+use defighter::events::{BattleInitiated, XpAwarded};
+use defighter::state::config::BalanceConfigParams;
+use defighter::state::pair::PairRecord;
+use defighter::state::player::{FighterClass, MoveChoice};
+use defighter_bank::Bank;
+
+mod common;
+use common::{create_player, pair_pda, player_pda};
+
+const MOVES: [MoveChoice; 2] = [MoveChoice::MemeBomb, MoveChoice::ShipIt];
+
+fn setup() -> (Bank, Pubkey, Pubkey) {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+    (bank, alice, bob)
+}
+
+/// XP each side got from one resolve, in `[challenger, opponent]` order.
+fn xp_gained(meta: &defighter_bank::TransactionMeta, challenger: Pubkey) -> [i64; 2] {
+    let challenger = player_pda(&challenger);
+    let awards = meta.events::<XpAwarded>();
+    let of = |who: bool| awards.iter().filter(|a| (a.player == challenger) == who).map(|a| a.delta).sum();
+    [of(true), of(false)]
+}
+
+#[test]
+fn rematches_wait_out_the_cooldown_either_way_round() {
+    let (mut bank, alice, bob) = setup();
+    let cooldown = BalanceConfigParams::default().pair_cooldown_slots;
+    bank.warp_to_slot(10);
+    common::play_battle(&mut bank, alice, bob, 1, MOVES);
+
+    let err = common::try_initiate_battle(&mut bank, alice, bob, 2, 10).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::PairOnCooldown.into()));
+    let err = common::try_initiate_battle(&mut bank, bob, alice, 2, 10).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::PairOnCooldown.into()));
+    // Other opponents are unaffected
+    let carol = Pubkey::new_unique();
+    create_player(&mut bank, carol, FighterClass::VC);
+    common::try_initiate_battle(&mut bank, alice, carol, 2, 10).unwrap();
+
+    bank.warp_to_slot(10 + cooldown);
+    common::try_initiate_battle(&mut bank, bob, alice, 2, 10).unwrap();
+    let pair: PairRecord = bank.get_anchor_account(&pair_pda(&alice, &bob)).unwrap();
+    assert_eq!((pair.low, pair.high), PairRecord::sorted(alice, bob));
+    assert_eq!((pair.battles_in_window, pair.total_battles, pair.last_battle_slot), (2, 2, 10 + cooldown));
+}
+
+#[test]
+fn repeat_xp_decays_until_the_window_resets() {
+    let (mut bank, alice, bob) = setup();
+    let rules = BalanceConfigParams::default();
+    let mut slot = 0;
+    // Bob wins each; the loser's 20% share decays with it
+    for (nonce, expected) in [(1, [2, 10]), (2, [1, 7]), (3, [1, 5]), (4, [0, 2]), (5, [0, 0])] {
+        slot += rules.pair_cooldown_slots;
+        bank.warp_to_slot(slot);
+        let meta = common::play_battle(&mut bank, alice, bob, nonce, MOVES);
+        assert_eq!(xp_gained(&meta, alice), expected, "battle {nonce}");
+    }
+
+    bank.warp_to_slot(rules.pair_cooldown_slots + rules.pair_window_slots);
+    let battle = common::initiate_battle(&mut bank, alice, bob, 6, 10);
+    let state: defighter::state::battle::Battle = bank.get_anchor_account(&battle).unwrap();
+    assert_eq!(state.xp_scale_bps, 10_000);
+}
+
+#[test]
+fn forfeit_without_commits_earns_nothing() {
+    let (mut bank, alice, bob) = setup();
+    let battle = common::initiate_battle(&mut bank, alice, bob, 1, 10);
+    bank.warp_to_slot(100);
+    let meta = common::resolve_battle(&mut bank, battle, alice, bob);
+    assert_eq!(xp_gained(&meta, alice), [0, 0]);
+
+    // Committing counts as playing, even if nobody reveals
+    bank.warp_to_slot(1_000);
+    let meta = common::try_initiate_battle(&mut bank, alice, bob, 2, 10).unwrap();
+    assert_eq!(meta.events::<BattleInitiated>()[0].xp_scale_bps, 7_500);
+    let battle = common::battle_pda(&alice, &bob, 2);
+    common::commit_moves(&mut bank, battle, [alice, bob], MOVES);
+    bank.warp_to_slot(1_100);
+    let meta = common::resolve_battle(&mut bank, battle, alice, bob);
+    assert_eq!(xp_gained(&meta, alice), [7, 1]);
+}
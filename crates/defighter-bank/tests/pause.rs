@@ -0,0 +1,177 @@
+//! Emergency pause flags, the guardian, and in-flight battles finishing anyway.
+
+use anchor_lang::prelude::*;
+use anchor_lang::error::ErrorCode;
+use anchor_lang::solana_program::sysvar;
+use defighter::errors::CustomError;
+use defighter::events::{BattleCancelled, BattleResolved, GuardianChanged, PauseChanged};
+use defighter::state::config::BalanceConfig;
+use defighter::state::battle::Battle;
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, create_player, ix, player_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+fn set_paused(bank: &mut Bank, authority: Pubkey, flags: u8) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::SetPaused { config: config_pda(), authority },
+            defighter::instruction::SetPaused { flags },
+        )],
+        &[authority],
+    )
+}
+
+fn set_guardian(bank: &mut Bank, admin: Pubkey, guardian: Option<Pubkey>) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::SetGuardian { config: config_pda(), admin },
+            defighter::instruction::SetGuardian { guardian },
+        )],
+        &[admin],
+    )
+}
+
+fn paused(bank: &Bank) -> u8 {
+    bank.get_anchor_account::<BalanceConfig>(&config_pda()).unwrap().paused
+}
+
+#[test]
+fn guardian_pauses_admin_unpauses() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let guardian = Pubkey::new_unique();
+    let stranger = Pubkey::new_unique();
+
+    let err = set_paused(&mut bank, stranger, BalanceConfig::PAUSE_ALL).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::PauseUnauthorized.into()));
+    let err = set_guardian(&mut bank, stranger, Some(stranger)).unwrap_err();
+    assert_eq!(err.custom_code(), Some(ErrorCode::ConstraintHasOne.into()));
+    let meta = set_guardian(&mut bank, admin, Some(guardian)).unwrap();
+    assert_eq!(meta.events::<GuardianChanged>()[0].guardian, Some(guardian));
+
+    let meta = set_paused(&mut bank, guardian, BalanceConfig::PAUSE_BATTLES).unwrap();
+    let changed = meta.events::<PauseChanged>();
+    assert_eq!((changed[0].authority, changed[0].old, changed[0].new), (guardian, 0, BalanceConfig::PAUSE_BATTLES));
+    set_paused(&mut bank, guardian, BalanceConfig::PAUSE_BATTLES | BalanceConfig::PAUSE_UPGRADES).unwrap();
+
+    // The guardian can only widen the pause
+    let err = set_paused(&mut bank, guardian, BalanceConfig::PAUSE_UPGRADES).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::PauseUnauthorized.into()));
+    let err = set_paused(&mut bank, admin, 1 << 7).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidPauseFlags.into()));
+
+    set_paused(&mut bank, admin, 0).unwrap();
+    assert_eq!(paused(&bank), 0);
+}
+
+#[test]
+fn each_flag_gates_its_feature() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+
+    set_paused(&mut bank, admin, BalanceConfig::PAUSE_BATTLES).unwrap();
+    let err = common::try_initiate_battle(&mut bank, alice, bob, 1, 10).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::ProgramPaused.into()));
+
+    // Commits are their own switch
+    set_paused(&mut bank, admin, BalanceConfig::PAUSE_COMMITS).unwrap();
+    common::try_initiate_battle(&mut bank, alice, bob, 1, 10).unwrap();
+    let battle = common::battle_pda(&alice, &bob, 1);
+    let err = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::CommitMove {
+                    battle,
+                    player: alice,
+                    player_account: player_pda(&alice),
+                    config: config_pda(),
+                    clock: sysvar::clock::ID,
+                    session: None,
+                    character_token: None,
+                },
+                defighter::instruction::CommitMove { commitment: [0; 32] },
+            )],
+            &[alice],
+        )
+        .unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::ProgramPaused.into()));
+
+    set_paused(&mut bank, admin, BalanceConfig::PAUSE_UPGRADES).unwrap();
+    let err = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::UpgradeAbility { player: player_pda(&alice), authority: alice, config: config_pda(), character_token: None },
+                defighter::instruction::UpgradeAbility { ability_index: 0 },
+            )],
+            &[alice],
+        )
+        .unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::ProgramPaused.into()));
+}
+
+#[test]
+fn in_flight_battles_still_resolve() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+
+    let players = [alice, bob];
+    let moves = [MoveChoice::MemeBomb, MoveChoice::ShipIt];
+    let battle = common::initiate_battle(&mut bank, alice, bob, 1, 10);
+    common::commit_moves(&mut bank, battle, players, moves);
+
+    // Everything off: reveals and resolves are never gated
+    set_paused(&mut bank, admin, BalanceConfig::PAUSE_ALL).unwrap();
+    common::reveal_moves(&mut bank, battle, players, moves);
+    let meta = common::resolve_battle(&mut bank, battle, alice, bob);
+    assert_eq!(meta.events::<BattleResolved>()[0].winner, player_pda(&bob));
+}
+
+#[test]
+fn commits_blocked_by_a_pause_are_refunded() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+    let energy_used = |bank: &Bank, who: &Pubkey| bank.get_anchor_account::<Player>(&player_pda(who)).unwrap().energy_used;
+
+    // Alice commits, then commits stop before Bob gets to his
+    let battle = common::initiate_battle(&mut bank, alice, bob, 1, 10);
+    common::commit_move(&mut bank, battle, alice, MoveChoice::MemeBomb, [1; 32], None);
+    set_paused(&mut bank, admin, BalanceConfig::PAUSE_COMMITS).unwrap();
+    bank.warp_to_slot(bank.slot() + 25);
+    set_paused(&mut bank, admin, 0).unwrap();
+    assert_eq!(energy_used(&bank, &alice), 1);
+
+    // Bob doesn't forfeit, and Alice's energy comes back
+    let meta = common::resolve_battle(&mut bank, battle, alice, bob);
+    assert!(meta.events::<BattleResolved>().is_empty());
+    assert_eq!(meta.events::<BattleCancelled>()[0].challenger, player_pda(&alice));
+    let state: Battle = bank.get_anchor_account(&battle).unwrap();
+    assert_eq!(state.winner, None);
+    assert_eq!((energy_used(&bank, &alice), energy_used(&bank, &bob)), (0, 0));
+    let xp = |who: &Pubkey| bank.get_anchor_account::<Player>(&player_pda(who)).unwrap().xp;
+    assert_eq!((xp(&alice), xp(&bob)), (0, 0));
+
+    // A pause that ended before the battle began doesn't excuse anyone
+    bank.warp_to_slot(bank.slot() + 150);
+    let battle = common::initiate_battle(&mut bank, alice, bob, 2, 10);
+    common::commit_move(&mut bank, battle, alice, MoveChoice::MemeBomb, [1; 32], None);
+    bank.warp_to_slot(bank.slot() + 25);
+    let meta = common::resolve_battle(&mut bank, battle, alice, bob);
+    assert_eq!(meta.events::<BattleResolved>()[0].winner, player_pda(&alice));
+    assert_eq!(energy_used(&bank, &alice), 1);
+}
@@ -0,0 +1,61 @@
+//! The browser previews from `defighter-wasm` against what `resolve_battle`
+//! actually does.
+
+use anchor_lang::prelude::*;
+use defighter::events::BattleResolved;
+use defighter::state::battle::Battle;
+use defighter::state::config::BalanceConfigParams;
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter_bank::Bank;
+use defighter_wasm::{move_entropy, predict_damage, CombatConfig, Fighter};
+
+mod common;
+use common::{config_pda, create_player, player_pda};
+
+fn fighter(bank: &Bank, authority: &Pubkey) -> Fighter {
+    let p: Player = bank.get_anchor_account(&player_pda(authority)).unwrap();
+    Fighter::new(p.class as u8, p.xp, &p.abilities, p.prestige, p.gear.attack_bps, p.gear.defense_bps).unwrap()
+}
+
+#[test]
+fn preview_matches_resolve_battle() {
+    let mut bank = common::bank();
+    // Off the demo balance, so a preview on the default config would be wrong
+    common::init_config_with_params(
+        &mut bank,
+        BalanceConfigParams { base_damage: 120, class_win_bps: 13000, ..Default::default() },
+    );
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::VC);
+    let config = CombatConfig::from_account(&bank.get_account(&config_pda()).unwrap().data).unwrap();
+
+    let moves = [MoveChoice::RugPullRumor, MoveChoice::ExitLiquidity];
+    let battle = common::initiate_battle(&mut bank, alice, bob, 1, 10);
+    common::commit_moves(&mut bank, battle, [alice, bob], moves);
+    common::reveal_moves(&mut bank, battle, [alice, bob], moves);
+    let state: Battle = bank.get_anchor_account(&battle).unwrap();
+    let (a, b) = (fighter(&bank, &alice), fighter(&bank, &bob));
+
+    // Salts as `commit_moves` picked them; the revealed ones on the account agree
+    let (salt_a, salt_b) = ([1u8; 32], [2u8; 32]);
+    assert_eq!((state.salt_challenger, state.salt_opponent), (salt_a, salt_b));
+    let (c, o) = (moves[0].to_byte(), moves[1].to_byte());
+    let c_roll = move_entropy(c, o, &salt_a, &salt_b, battle.as_ref()).unwrap();
+    let o_roll = move_entropy(o, c, &salt_b, &salt_a, battle.as_ref()).unwrap();
+    let c_hit = predict_damage(c, &a, &b, state.opponent_hp, c_roll, &config).unwrap();
+    let o_hit = predict_damage(o, &b, &a, state.challenger_hp, o_roll, &config).unwrap();
+
+    let meta = common::resolve_battle(&mut bank, battle, alice, bob);
+    let resolved = &meta.events::<BattleResolved>()[0];
+    assert_eq!((resolved.challenger_damage, resolved.opponent_damage), (c_hit.damage_dealt, o_hit.damage_dealt));
+    assert_eq!((resolved.challenger_hp, resolved.opponent_hp), (o_hit.remaining_hp, c_hit.remaining_hp));
+    // The rolls landed on a hit; a miss would make the damage check trivial
+    assert!(c_hit.damage_dealt > 0 && o_hit.damage_dealt > 0);
+
+    // The rules snapshot on the battle previews the same
+    let rules = CombatConfig::from_params(&state.rules.try_to_vec().unwrap()).unwrap();
+    assert_eq!(predict_damage(c, &a, &b, state.opponent_hp, c_roll, &rules).unwrap(), c_hit);
+    assert_ne!(predict_damage(c, &a, &b, state.opponent_hp, c_roll, &CombatConfig::new()).unwrap(), c_hit);
+}
@@ -0,0 +1,205 @@
+//! Drives the real DeFighter program through a full battle.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::state::battle::{Battle, BattleState};
+use defighter::state::config::{BalanceConfigParams, ResolutionMode};
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter_bank::{Bank, TransactionError};
+
+mod common;
+use common::{config_pda, create_player, ix, player_pda};
+
+#[test]
+fn full_battle() {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+    let player: Player = bank.get_anchor_account(&player_pda(&alice)).unwrap();
+    assert_eq!(player.authority, alice);
+    assert_eq!(player.class, FighterClass::Shitposter);
+
+    let nonce = 7u64;
+    let battle = Pubkey::find_program_address(
+        &[b"battle", player_pda(&alice).as_ref(), player_pda(&bob).as_ref(), &nonce.to_le_bytes()],
+        &defighter::ID,
+    )
+    .0;
+    bank.warp_to_slot(100);
+    let meta = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::InitiateBattle {
+                    battle,
+                    pair_record: common::pair_pda(&alice, &bob),
+                    challenger_loot: common::loot_bag_pda(&alice),
+                    opponent_loot: common::loot_bag_pda(&bob),
+                    challenger_consumables: common::consumables_pda(&alice),
+                    opponent_consumables: common::consumables_pda(&bob),
+                    challenger: alice,
+                    challenger_account: player_pda(&alice),
+                    opponent_account: player_pda(&bob),
+                    config: config_pda(),
+                    system_program: system_program::ID,
+                    clock: sysvar::clock::ID,
+                    character_token: None,
+                },
+                defighter::instruction::InitiateBattle {
+                    opponent: player_pda(&bob),
+                    nonce,
+                    commit_deadline_slots: 10,
+                    reveal_deadline_slots: 10,
+                },
+            )],
+            &[alice],
+        )
+        .unwrap();
+    let initiated = meta.events::<defighter::events::BattleInitiated>();
+    assert_eq!(initiated.len(), 1);
+    assert_eq!(initiated[0].opponent, player_pda(&bob));
+    assert_eq!(initiated[0].commit_deadline_slot, 110);
+
+    let commit = |bank: &mut Bank, who: Pubkey, mv: MoveChoice, salt: [u8; 32]| {
+        bank.process_transaction(
+            &[ix(
+                defighter::accounts::CommitMove { battle, player: who, player_account: player_pda(&who), config: config_pda(), clock: sysvar::clock::ID, session: None, character_token: None },
+                defighter::instruction::CommitMove {
+                    commitment: defighter::logic::commitment_hash(mv, &salt, &who, &battle),
+                },
+            )],
+            &[who],
+        )
+    };
+    commit(&mut bank, alice, MoveChoice::MemeBomb, [1; 32]).unwrap();
+    let err = commit(&mut bank, alice, MoveChoice::MemeBomb, [1; 32]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(defighter::errors::CustomError::AlreadyCommitted.into()));
+    commit(&mut bank, bob, MoveChoice::ShipIt, [2; 32]).unwrap();
+    let state: Battle = bank.get_anchor_account(&battle).unwrap();
+    assert!(state.state == BattleState::WaitingForReveals);
+
+    for (who, mv, salt) in [(alice, MoveChoice::MemeBomb, [1; 32]), (bob, MoveChoice::ShipIt, [2; 32])] {
+        bank.process_transaction(
+            &[ix(
+                defighter::accounts::RevealMove {
+                    battle,
+                    player: who,
+                    player_account: player_pda(&who),
+                    clock: sysvar::clock::ID,
+                    consumables: None,
+                    session: None,
+                    character_token: None,
+                },
+                defighter::instruction::RevealMove { move_choice: mv, salt, boost: None },
+            )],
+            &[who],
+        )
+        .unwrap();
+    }
+
+    let meta = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::ResolveBattle {
+                    battle,
+                    player_challenger: player_pda(&alice),
+                    player_opponent: player_pda(&bob),
+                    challenger_loot: common::loot_bag_pda(&alice),
+                    opponent_loot: common::loot_bag_pda(&bob),
+                    challenger_consumables: common::consumables_pda(&alice),
+                    opponent_consumables: common::consumables_pda(&bob),
+                    config: config_pda(),
+                    clock: sysvar::clock::ID,
+                },
+                defighter::instruction::ResolveBattle {},
+            )],
+            &[],
+        )
+        .unwrap();
+    let resolved = meta.events::<defighter::events::BattleResolved>();
+    assert_eq!(resolved[0].winner, player_pda(&bob));
+    assert_eq!(resolved[0].challenger_hp, 75);
+    assert_eq!(resolved[0].opponent_hp, 120);
+
+    let bob_player: Player = bank.get_anchor_account(&player_pda(&bob)).unwrap();
+    assert_eq!(bob_player.xp, 10);
+    let alice_player: Player = bank.get_anchor_account(&player_pda(&alice)).unwrap();
+    assert_eq!(alice_player.xp, 2);
+}
+
+#[test]
+fn score_mode_battle() {
+    let mut bank = common::bank();
+    common::init_config_with_mode(&mut bank, ResolutionMode::Score);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+
+    // Builder has the RPS edge: 20 + 10 against 0 + 10, dealt as a 20 HP margin
+    let meta = common::play_battle(&mut bank, alice, bob, 1, [MoveChoice::MemeBomb, MoveChoice::ShipIt]);
+    let resolved = meta.events::<defighter::events::BattleResolved>();
+    assert_eq!(resolved[0].winner, player_pda(&bob));
+    assert_eq!((resolved[0].challenger_damage, resolved[0].opponent_damage), (0, 20));
+    assert_eq!((resolved[0].challenger_hp, resolved[0].opponent_hp), (180, 200));
+}
+
+#[test]
+fn failed_transactions_roll_back() {
+    let mut bank = common::bank();
+    common::init_config_with_params(&mut bank, BalanceConfigParams { max_characters: 1, ..Default::default() });
+    let alice = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::VC);
+    let lamports = bank.get_account(&alice).unwrap().lamports;
+
+    // The second character's account is paid for before the cap check fails
+    let err = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::CreatePlayer {
+                    payer: alice,
+                    profile: common::profile_pda(&alice),
+                    player: common::character_pda(&alice, 1),
+                    authority: alice,
+                    config: config_pda(),
+                    system_program: system_program::ID,
+                },
+                defighter::instruction::CreatePlayer { class: FighterClass::Builder },
+            )],
+            &[alice],
+        )
+        .unwrap_err();
+    assert!(matches!(err, TransactionError::InstructionError { index: 0, .. }));
+    assert_eq!(bank.get_account(&alice).unwrap().lamports, lamports);
+    assert!(bank.get_account(&common::character_pda(&alice, 1)).is_none());
+    let player: Player = bank.get_anchor_account(&player_pda(&alice)).unwrap();
+    assert_eq!(player.class, FighterClass::VC);
+}
+
+#[test]
+fn unsigned_signer_is_rejected() {
+    let mut bank = common::bank();
+    let alice = Pubkey::new_unique();
+    bank.airdrop(&alice, 1_000_000_000);
+    let err = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::CreatePlayer {
+                    payer: alice,
+                    profile: common::profile_pda(&alice),
+                    player: player_pda(&alice),
+                    authority: alice,
+                    config: config_pda(),
+                    system_program: system_program::ID,
+                },
+                defighter::instruction::CreatePlayer { class: FighterClass::Builder },
+            )],
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err, TransactionError::MissingSignature(alice));
+}
@@ -0,0 +1,108 @@
+//! The bank holds programs to the runtime's rules on account changes.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::system_program;
+use defighter_bank::{Account, Bank, TransactionError, TransactionMeta};
+
+const PROGRAM: Pubkey = Pubkey::new_from_array([7; 32]);
+
+const WRITE: u8 = 0;
+const TAKE: u8 = 1;
+const MINT: u8 = 2;
+const CLOSE: u8 = 3;
+const ASSIGN_DIRTY: u8 = 4;
+
+/// Does one thing to `accounts[0]`, crediting `accounts[1]` where lamports move.
+fn entry<'info>(_program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> ProgramResult {
+    let target = &accounts[0];
+    match data[0] {
+        WRITE => target.try_borrow_mut_data()?[0] += 1,
+        TAKE => {
+            **target.try_borrow_mut_lamports()? -= 10;
+            **accounts[1].try_borrow_mut_lamports()? += 10;
+        }
+        MINT => **target.try_borrow_mut_lamports()? += 10,
+        CLOSE => {
+            **accounts[1].try_borrow_mut_lamports()? += target.lamports();
+            **target.try_borrow_mut_lamports()? = 0;
+            target.assign(&system_program::ID);
+            target.resize(0)?;
+        }
+        ASSIGN_DIRTY => target.assign(&system_program::ID),
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+    Ok(())
+}
+
+fn setup() -> (Bank, Pubkey, Pubkey, Pubkey) {
+    let mut bank = Bank::new();
+    bank.add_program(PROGRAM, entry);
+    let (owned, foreign, sink) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    bank.set_account(owned, Account { lamports: 100, data: vec![0; 4], owner: PROGRAM, executable: false });
+    bank.set_account(foreign, Account { lamports: 100, data: vec![0; 4], owner: system_program::ID, executable: false });
+    bank.airdrop(&sink, 100);
+    (bank, owned, foreign, sink)
+}
+
+fn run(bank: &mut Bank, op: u8, target: Pubkey, sink: Pubkey) -> std::result::Result<TransactionMeta, TransactionError> {
+    bank.process_transaction(
+        &[Instruction {
+            program_id: PROGRAM,
+            accounts: vec![AccountMeta::new(target, false), AccountMeta::new(sink, false)],
+            data: vec![op],
+        }],
+        &[],
+    )
+}
+
+fn error(result: std::result::Result<TransactionMeta, TransactionError>) -> ProgramError {
+    match result.unwrap_err() {
+        TransactionError::InstructionError { error, .. } => error,
+        other => panic!("{other:?}"),
+    }
+}
+
+#[test]
+fn only_the_owner_writes_or_debits() {
+    let (mut bank, owned, foreign, sink) = setup();
+    run(&mut bank, WRITE, owned, sink).unwrap();
+    run(&mut bank, TAKE, owned, sink).unwrap();
+    assert_eq!(bank.get_account(&owned).unwrap().data[0], 1);
+    assert_eq!(bank.get_account(&owned).unwrap().lamports, 90);
+
+    assert_eq!(error(run(&mut bank, WRITE, foreign, sink)), ProgramError::InvalidAccountOwner);
+    assert_eq!(error(run(&mut bank, TAKE, foreign, sink)), ProgramError::InvalidAccountOwner);
+    // Crediting someone else's account is fine
+    run(&mut bank, TAKE, owned, foreign).unwrap();
+    assert_eq!(bank.get_account(&foreign).unwrap().lamports, 110);
+
+    // Read-only accounts don't change, even for their owner
+    let read_only = Instruction {
+        program_id: PROGRAM,
+        accounts: vec![AccountMeta::new_readonly(owned, false), AccountMeta::new(sink, false)],
+        data: vec![WRITE],
+    };
+    assert_eq!(error(bank.process_transaction(&[read_only], &[])), ProgramError::Immutable);
+}
+
+#[test]
+fn lamports_must_balance() {
+    let (mut bank, owned, _, sink) = setup();
+    assert_eq!(error(run(&mut bank, MINT, owned, sink)), ProgramError::InvalidArgument);
+    assert_eq!(bank.get_account(&owned).unwrap().lamports, 100);
+}
+
+#[test]
+fn reassigning_needs_zeroed_data() {
+    let (mut bank, owned, _, sink) = setup();
+    run(&mut bank, WRITE, owned, sink).unwrap();
+    assert_eq!(error(run(&mut bank, ASSIGN_DIRTY, owned, sink)), ProgramError::IllegalOwner);
+
+    // Closing the way Anchor does: drain, hand back, shrink to nothing
+    run(&mut bank, CLOSE, owned, sink).unwrap();
+    assert!(bank.get_account(&owned).is_none());
+    assert_eq!(bank.get_account(&sink).unwrap().lamports, 200);
+}
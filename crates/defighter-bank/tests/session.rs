@@ -0,0 +1,231 @@
+//! Session keys acting for a player's wallet in commit/reveal.
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::logic::commitment_hash;
+use defighter::state::battle::Battle;
+use defighter::state::player::{FighterClass, MoveChoice};
+use defighter::state::session::SessionToken;
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, create_player, ix, player_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+struct Fixture {
+    bank: Bank,
+    alice: Pubkey,
+    bob: Pubkey,
+    battle: Pubkey,
+}
+
+/// Alice challenges Bob with a 10 + 10 slot window.
+fn setup() -> Fixture {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::Builder);
+
+    let nonce = 1u64;
+    let battle = Pubkey::find_program_address(
+        &[b"battle", player_pda(&alice).as_ref(), player_pda(&bob).as_ref(), &nonce.to_le_bytes()],
+        &defighter::ID,
+    )
+    .0;
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::InitiateBattle {
+                battle,
+                pair_record: common::pair_pda(&alice, &bob),
+                challenger_loot: common::loot_bag_pda(&alice),
+                opponent_loot: common::loot_bag_pda(&bob),
+                challenger_consumables: common::consumables_pda(&alice),
+                opponent_consumables: common::consumables_pda(&bob),
+                challenger: alice,
+                challenger_account: player_pda(&alice),
+                opponent_account: player_pda(&bob),
+                config: config_pda(),
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+                character_token: None,
+            },
+            defighter::instruction::InitiateBattle {
+                opponent: player_pda(&bob),
+                nonce,
+                commit_deadline_slots: 10,
+                reveal_deadline_slots: 10,
+            },
+        )],
+        &[alice],
+    )
+    .unwrap();
+    Fixture { bank, alice, bob, battle }
+}
+
+fn session_pda(authority: &Pubkey, session_key: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"session", authority.as_ref(), session_key.as_ref()], &defighter::ID).0
+}
+
+fn create_session(bank: &mut Bank, authority: Pubkey, session_key: Pubkey, duration_slots: u64, scope: u8) -> TxResult {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CreateSession {
+                session: session_pda(&authority, &session_key),
+                authority,
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::CreateSession { session_key, duration_slots, scope },
+        )],
+        &[authority],
+    )
+}
+
+/// Commit `move_choice` for `player`, signed by `signer` with an optional session.
+fn commit(f: &mut Fixture, player: Pubkey, signer: Pubkey, session: Option<Pubkey>, move_choice: MoveChoice) -> TxResult {
+    let commitment = commitment_hash(move_choice, &[5; 32], &player, &f.battle);
+    f.bank.process_transaction(
+        &[ix(
+            defighter::accounts::CommitMove {
+                battle: f.battle,
+                player: signer,
+                player_account: player_pda(&player),
+                config: config_pda(),
+                clock: sysvar::clock::ID,
+                session,
+                character_token: None,
+            },
+            defighter::instruction::CommitMove { commitment },
+        )],
+        &[signer],
+    )
+}
+
+fn reveal(f: &mut Fixture, player: Pubkey, signer: Pubkey, session: Option<Pubkey>, move_choice: MoveChoice) -> TxResult {
+    f.bank.process_transaction(
+        &[ix(
+            defighter::accounts::RevealMove {
+                battle: f.battle,
+                player: signer,
+                player_account: player_pda(&player),
+                clock: sysvar::clock::ID,
+                consumables: None,
+                session,
+                character_token: None,
+            },
+            defighter::instruction::RevealMove { move_choice, salt: [5; 32], boost: None },
+        )],
+        &[signer],
+    )
+}
+
+#[test]
+fn session_key_commits_and_reveals_for_its_authority() {
+    let mut f = setup();
+    let key = Pubkey::new_unique();
+    create_session(&mut f.bank, f.alice, key, 100, SessionToken::ALL).unwrap();
+    let session = Some(session_pda(&f.alice, &key));
+
+    let (alice, bob) = (f.alice, f.bob);
+    commit(&mut f, alice, key, session, MoveChoice::MemeBomb).unwrap();
+    commit(&mut f, bob, bob, None, MoveChoice::ShipIt).unwrap();
+    reveal(&mut f, alice, key, session, MoveChoice::MemeBomb).unwrap();
+    reveal(&mut f, bob, bob, None, MoveChoice::ShipIt).unwrap();
+
+    let state: Battle = f.bank.get_anchor_account(&f.battle).unwrap();
+    assert_eq!(state.reveal_challenger, Some(MoveChoice::MemeBomb));
+    assert_eq!(state.reveal_opponent, Some(MoveChoice::ShipIt));
+}
+
+#[test]
+fn session_key_without_a_session_is_just_a_stranger() {
+    let mut f = setup();
+    let key = Pubkey::new_unique();
+    let alice = f.alice;
+    let err = commit(&mut f, alice, key, None, MoveChoice::MemeBomb).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::NotInBattle.into()));
+
+    // Someone else's session key can't borrow Alice's session
+    create_session(&mut f.bank, alice, key, 100, SessionToken::ALL).unwrap();
+    let thief = Pubkey::new_unique();
+    let err = commit(&mut f, alice, thief, Some(session_pda(&alice, &key)), MoveChoice::MemeBomb).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidSession.into()));
+}
+
+#[test]
+fn scope_limits_what_the_key_can_do() {
+    let mut f = setup();
+    let key = Pubkey::new_unique();
+    let (alice, bob) = (f.alice, f.bob);
+    create_session(&mut f.bank, alice, key, 100, SessionToken::COMMIT).unwrap();
+    let session = Some(session_pda(&alice, &key));
+
+    commit(&mut f, alice, key, session, MoveChoice::MemeBomb).unwrap();
+    commit(&mut f, bob, bob, None, MoveChoice::ShipIt).unwrap();
+    let err = reveal(&mut f, alice, key, session, MoveChoice::MemeBomb).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::SessionScope.into()));
+    reveal(&mut f, alice, alice, None, MoveChoice::MemeBomb).unwrap();
+}
+
+#[test]
+fn expired_session_is_rejected() {
+    let mut f = setup();
+    let key = Pubkey::new_unique();
+    let alice = f.alice;
+    create_session(&mut f.bank, alice, key, 5, SessionToken::ALL).unwrap();
+    let session = Some(session_pda(&alice, &key));
+
+    f.bank.warp_to_slot(6);
+    let err = commit(&mut f, alice, key, session, MoveChoice::MemeBomb).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::SessionExpired.into()));
+    // The wallet itself is unaffected
+    commit(&mut f, alice, alice, None, MoveChoice::MemeBomb).unwrap();
+}
+
+#[test]
+fn revoked_session_is_closed_and_unusable() {
+    let mut f = setup();
+    let key = Pubkey::new_unique();
+    let alice = f.alice;
+    create_session(&mut f.bank, alice, key, 100, SessionToken::ALL).unwrap();
+    let session = session_pda(&alice, &key);
+    let revoke = |bank: &mut Bank, authority: Pubkey| {
+        bank.airdrop(&authority, 1_000_000_000);
+        bank.process_transaction(
+            &[ix(
+                defighter::accounts::RevokeSession { session, authority },
+                defighter::instruction::RevokeSession {},
+            )],
+            &[authority],
+        )
+    };
+
+    let err = revoke(&mut f.bank, Pubkey::new_unique()).unwrap_err();
+    assert_eq!(err.custom_code(), Some(ErrorCode::ConstraintHasOne.into()));
+    revoke(&mut f.bank, alice).unwrap();
+    assert!(f.bank.get_account(&session).is_none());
+
+    let err = commit(&mut f, alice, key, Some(session), MoveChoice::MemeBomb).unwrap_err();
+    assert_eq!(err.custom_code(), Some(ErrorCode::AccountNotInitialized.into()));
+}
+
+#[test]
+fn session_parameters_are_bounded() {
+    let mut f = setup();
+    let alice = f.alice;
+    for (duration, scope) in [
+        (0, SessionToken::ALL),
+        (SessionToken::MAX_DURATION_SLOTS + 1, SessionToken::ALL),
+        (100, 0),
+        (100, SessionToken::ALL + 1),
+    ] {
+        let err = create_session(&mut f.bank, alice, Pubkey::new_unique(), duration, scope).unwrap_err();
+        assert_eq!(err.custom_code(), Some(CustomError::InvalidSessionParams.into()));
+    }
+}
@@ -0,0 +1,123 @@
+//! Moving a character to a new wallet with both wallets' signatures.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::state::customization::{CharacterCustomizationDataV1, CharacterCustomizationV1, CHARACTER_CUSTOM_SEED};
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter_bank::{Bank, TransactionError, TransactionMeta};
+
+mod common;
+use common::{config_pda, create_player, inventory_pda, ix, next_character_pda, player_pda, profile_pda};
+
+type TxResult = std::result::Result<TransactionMeta, TransactionError>;
+
+fn customization_pda(owner: &Pubkey) -> Pubkey {
+    look_pda(&player_pda(owner))
+}
+
+fn look_pda(player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CHARACTER_CUSTOM_SEED, player.as_ref()], &defighter::ID).0
+}
+
+fn transfer(bank: &mut Bank, old: Pubkey, new: Pubkey, with_customization: bool, signers: &[Pubkey]) -> TxResult {
+    bank.airdrop(&new, 1_000_000_000);
+    let new_player = next_character_pda(bank, &new);
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::TransferPlayer {
+                old_player: player_pda(&old),
+                old_profile: profile_pda(&old),
+                new_profile: profile_pda(&new),
+                new_player,
+                old_customization: customization_pda(&old),
+                new_customization: with_customization.then(|| look_pda(&new_player)),
+                old_inventory: inventory_pda(&old),
+                old_authority: old,
+                new_authority: new,
+                config: config_pda(),
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::TransferPlayer {},
+        )],
+        signers,
+    )
+}
+
+fn create_look(bank: &mut Bank, owner: Pubkey) {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CreateCharacterCustomization {
+                player: player_pda(&owner),
+                character_customization: customization_pda(&owner),
+                authority: owner,
+                config: config_pda(),
+                system_program: system_program::ID,
+                character_token: None,
+            },
+            defighter::instruction::CreateCharacterCustomization {
+                data: CharacterCustomizationDataV1 { skin_tone_index: 4, accessory_slots: [255; 2], ..Default::default() },
+            },
+        )],
+        &[owner],
+    )
+    .unwrap();
+}
+
+fn setup() -> (Bank, Pubkey, Pubkey) {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    create_player(&mut bank, alice, FighterClass::VC);
+    create_player(&mut bank, bob, FighterClass::Builder);
+    (bank, alice, bob)
+}
+
+#[test]
+fn both_wallets_move_the_character_and_its_look() {
+    let (mut bank, alice, bob) = setup();
+    common::play_battle(&mut bank, alice, bob, 1, [MoveChoice::SeriesACannon, MoveChoice::ShipIt]);
+    create_look(&mut bank, alice);
+    let before: Player = bank.get_anchor_account(&player_pda(&alice)).unwrap();
+
+    let fresh = Pubkey::new_unique();
+    let err = transfer(&mut bank, alice, fresh, true, &[alice]).unwrap_err();
+    assert_eq!(err, TransactionError::MissingSignature(fresh));
+    let err = transfer(&mut bank, alice, fresh, true, &[fresh]).unwrap_err();
+    assert_eq!(err, TransactionError::MissingSignature(alice));
+    let err = transfer(&mut bank, alice, alice, true, &[alice]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::SameOwner.into()));
+
+    transfer(&mut bank, alice, fresh, true, &[alice, fresh]).unwrap();
+    let after: Player = bank.get_anchor_account(&player_pda(&fresh)).unwrap();
+    assert_eq!((after.authority, after.class, after.xp, after.elo), (fresh, before.class, before.xp, before.elo));
+    assert_eq!(after.cooldown_until_slot, bank.slot() + Player::TRANSFER_COOLDOWN_SLOTS);
+    assert!(bank.get_account(&player_pda(&alice)).is_none());
+    let look: CharacterCustomizationV1 = bank.get_anchor_account(&customization_pda(&fresh)).unwrap();
+    assert_eq!((look.player, look.skin_tone_index), (player_pda(&fresh), 4));
+    assert!(bank.get_account(&customization_pda(&alice)).is_none());
+
+    // The old wallet can start over
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+}
+
+#[test]
+fn transfers_wait_for_open_battles_and_need_the_look() {
+    let (mut bank, alice, bob) = setup();
+    let fresh = Pubkey::new_unique();
+    let battle = common::initiate_battle(&mut bank, alice, bob, 1, 10);
+    let err = transfer(&mut bank, alice, fresh, false, &[alice, fresh]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::OpenBattles.into()));
+    bank.warp_to_slot(bank.slot() + 21);
+    common::resolve_battle(&mut bank, battle, alice, bob);
+
+    // A character without a look can't conjure one, and one with a look can't drop it
+    let err = transfer(&mut bank, alice, fresh, true, &[alice, fresh]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::CustomizationNotMoved.into()));
+    create_look(&mut bank, alice);
+    let err = transfer(&mut bank, alice, fresh, false, &[alice, fresh]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::CustomizationNotMoved.into()));
+    transfer(&mut bank, alice, fresh, true, &[alice, fresh]).unwrap();
+}
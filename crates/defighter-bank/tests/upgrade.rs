@@ -0,0 +1,214 @@
+//! Upgrade-cost curve, its config validation, the preview view, respecs and
+//! prestige.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use defighter::errors::CustomError;
+use defighter::events::{AbilitiesRespecced, Prestiged};
+use defighter::state::customization::{CharacterCustomizationDataV1, CHARACTER_CUSTOM_SEED};
+use defighter::state::config::{BalanceConfig, BalanceConfigParams};
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter_bank::{Bank, TransactionError};
+
+mod common;
+use common::{config_pda, create_player, ix, pending_config_pda, player_pda};
+
+fn preview(bank: &mut Bank, player: Pubkey, ability_index: u8, count: u8) -> Vec<u64> {
+    let meta = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::PreviewUpgradeCosts { player: player_pda(&player), config: config_pda() },
+                defighter::instruction::PreviewUpgradeCosts { ability_index, count },
+            )],
+            &[],
+        )
+        .unwrap();
+    meta.return_value().unwrap()
+}
+
+/// Queue `params` and, if that passed validation, let the timelock run out.
+fn update_config(bank: &mut Bank, admin: Pubkey, params: BalanceConfigParams) -> std::result::Result<(), TransactionError> {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::AdminUpdateConfig {
+                config: config_pda(),
+                pending_config: pending_config_pda(),
+                admin,
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+            },
+            defighter::instruction::AdminUpdateConfig { params },
+        )],
+        &[admin],
+    )?;
+    common::apply_config_change(bank);
+    Ok(())
+}
+
+fn give_xp(bank: &mut Bank, player: Pubkey, xp: u64) {
+    let key = player_pda(&player);
+    let mut state: Player = bank.get_anchor_account(&key).unwrap();
+    state.xp = xp;
+    let mut account = bank.get_account(&key).unwrap().clone();
+    account.data.clear();
+    state.try_serialize(&mut account.data).unwrap();
+    bank.set_account(key, account);
+}
+
+fn upgrade(bank: &mut Bank, player: Pubkey, ability_index: u8) {
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::UpgradeAbility { player: player_pda(&player), authority: player, config: config_pda(), character_token: None },
+            defighter::instruction::UpgradeAbility { ability_index },
+        )],
+        &[player],
+    )
+    .unwrap();
+}
+
+#[test]
+fn preview_tracks_the_fractional_curve() {
+    let mut bank = common::bank();
+    common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Builder);
+
+    // 10 * l^1.4: 10, 26.39, 46.56
+    assert_eq!(preview(&mut bank, alice, 0, 3), vec![10, 26, 47]);
+
+    give_xp(&mut bank, alice, 100);
+    upgrade(&mut bank, alice, 0);
+    let player: Player = bank.get_anchor_account(&player_pda(&alice)).unwrap();
+    assert_eq!(player.xp, 90);
+    assert_eq!(preview(&mut bank, alice, 0, 2), vec![26, 47]);
+    assert_eq!(preview(&mut bank, alice, 1, 1), vec![10]);
+
+    // Capped at MAX_COST_PREVIEW entries and at max_level
+    assert_eq!(preview(&mut bank, alice, 2, u8::MAX).len(), defighter::instructions::MAX_COST_PREVIEW as usize);
+
+    let err = bank
+        .process_transaction(
+            &[ix(
+                defighter::accounts::PreviewUpgradeCosts { player: player_pda(&alice), config: config_pda() },
+                defighter::instruction::PreviewUpgradeCosts { ability_index: 3, count: 1 },
+            )],
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::InvalidAbility.into()));
+}
+
+#[test]
+fn config_rejects_unsupported_exponents() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let alice = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::VC);
+    let config = bank.get_anchor_account::<BalanceConfig>(&config_pda()).unwrap().params();
+
+    for upgrade_p_bps in [9_999, 30_001, 0] {
+        let err = update_config(&mut bank, admin, BalanceConfigParams { upgrade_p_bps, ..config }).unwrap_err();
+        assert_eq!(err.custom_code(), Some(CustomError::UnsupportedUpgradeExponent.into()));
+    }
+
+    update_config(&mut bank, admin, BalanceConfigParams { upgrade_p_bps: 25_000, max_level: 2, ..config }).unwrap();
+    // 10 * 2^2.5 = 56.57, and nothing past max_level
+    assert_eq!(preview(&mut bank, alice, 1, 5), vec![10, 57]);
+}
+
+#[test]
+fn respec_refunds_part_of_the_upgrade_xp() {
+    let mut bank = common::bank();
+    let admin = common::init_config(&mut bank);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    create_player(&mut bank, alice, FighterClass::Shitposter);
+    create_player(&mut bank, bob, FighterClass::VC);
+    give_xp(&mut bank, alice, 1_000);
+    // 10 + 26 on the first ability, 10 on the second
+    upgrade(&mut bank, alice, 0);
+    upgrade(&mut bank, alice, 0);
+    upgrade(&mut bank, alice, 1);
+
+    let respec = |bank: &mut Bank| {
+        bank.process_transaction(
+            &[ix(
+                defighter::accounts::RespecAbilities { player: player_pda(&alice), authority: alice, config: config_pda(), character_token: None },
+                defighter::instruction::RespecAbilities {},
+            )],
+            &[alice],
+        )
+    };
+    let battle = common::initiate_battle(&mut bank, bob, alice, 1, 10);
+    common::commit_move(&mut bank, battle, alice, MoveChoice::ShipIt, [2; 32], None);
+    let err = respec(&mut bank).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::OpenBattles.into()));
+    bank.warp_to_slot(bank.slot() + 21);
+    common::resolve_battle(&mut bank, battle, bob, alice);
+    // Raising the curve after the upgrades leaves the refund alone
+    let config = bank.get_anchor_account::<BalanceConfig>(&config_pda()).unwrap().params();
+    update_config(&mut bank, admin, BalanceConfigParams { upgrade_c0: 1_000, ..config }).unwrap();
+
+    let xp = bank.get_anchor_account::<Player>(&player_pda(&alice)).unwrap().xp;
+    let meta = respec(&mut bank).unwrap();
+    let event = &meta.events::<AbilitiesRespecced>()[0];
+    // Half of the 46 spent comes back at the default respec_refund_bps
+    assert_eq!((event.levels, event.refund), ([2, 1, 0], 23));
+    let player: Player = bank.get_anchor_account(&player_pda(&alice)).unwrap();
+    assert_eq!((player.abilities, player.xp, player.xp_spent), ([0; 3], xp + 23, 0));
+}
+
+#[test]
+fn prestige_needs_every_ability_maxed() {
+    let mut bank = common::bank();
+    common::init_config_with_params(
+        &mut bank,
+        BalanceConfigParams { max_level: 2, prestige_outfit: 3, ..Default::default() },
+    );
+    let alice = Pubkey::new_unique();
+    create_player(&mut bank, alice, FighterClass::Builder);
+    give_xp(&mut bank, alice, 1_000);
+    let prestige = |bank: &mut Bank| {
+        bank.process_transaction(
+            &[ix(
+                defighter::accounts::Prestige { player: player_pda(&alice), authority: alice, config: config_pda(), character_token: None },
+                defighter::instruction::Prestige {},
+            )],
+            &[alice],
+        )
+    };
+
+    for ability_index in [0, 0, 1, 1, 2] {
+        upgrade(&mut bank, alice, ability_index);
+    }
+    let err = prestige(&mut bank).unwrap_err();
+    assert_eq!(err.custom_code(), Some(CustomError::AbilitiesNotMaxed.into()));
+    upgrade(&mut bank, alice, 2);
+
+    let xp = bank.get_anchor_account::<Player>(&player_pda(&alice)).unwrap().xp;
+    let meta = prestige(&mut bank).unwrap();
+    let event = &meta.events::<Prestiged>()[0];
+    assert_eq!((event.rank, event.power_bps), (1, 100));
+    let player: Player = bank.get_anchor_account(&player_pda(&alice)).unwrap();
+    assert_eq!((player.prestige, player.abilities, player.xp, player.xp_spent), (1, [0; 3], xp, 0));
+
+    // The prestige outfit is worn without crafting
+    let look = Pubkey::find_program_address(&[CHARACTER_CUSTOM_SEED, player_pda(&alice).as_ref()], &defighter::ID).0;
+    bank.process_transaction(
+        &[ix(
+            defighter::accounts::CreateCharacterCustomization {
+                player: player_pda(&alice),
+                character_customization: look,
+                authority: alice,
+                config: config_pda(),
+                system_program: system_program::ID,
+                character_token: None,
+            },
+            defighter::instruction::CreateCharacterCustomization {
+                data: CharacterCustomizationDataV1 { outfit_style_index: 3, accessory_slots: [255; 2], ..Default::default() },
+            },
+        )],
+        &[alice],
+    )
+    .unwrap();
+}
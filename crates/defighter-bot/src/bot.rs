@@ -0,0 +1,154 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::Pubkey;
+use defighter::logic::{commitment_hash, BattleSide};
+use defighter::state::battle::{Battle, BattleState};
+use defighter::state::config::BalanceConfig;
+use defighter::state::player::{MoveChoice, Player};
+use rand::rngs::{OsRng, StdRng};
+use rand::Rng;
+
+use crate::chain::Chain;
+use crate::instructions::{commit_move, player_pda, resolve_battle, reveal_move};
+use crate::store::{Commitment, SaltStore};
+use crate::strategy::Policy;
+
+/// Something the bot did during a tick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Started tracking a battle that names the bot's character as opponent
+    Joined(Pubkey),
+    Committed { battle: Pubkey, move_choice: MoveChoice },
+    Revealed { battle: Pubkey, move_choice: MoveChoice },
+    /// Sent `resolve_battle`
+    Resolved(Pubkey),
+    /// Stopped tracking a battle that is resolved or gone
+    Finished { battle: Pubkey, winner: Option<Pubkey> },
+    /// A step failed; it is retried on the next tick
+    Failed { battle: Pubkey, error: String },
+}
+
+pub struct Bot<C> {
+    chain: C,
+    policy: Policy,
+    store: SaltStore,
+    /// Drives mixed strategies; salts always come from the OS
+    rng: StdRng,
+    battles: BTreeSet<Pubkey>,
+}
+
+impl<C: Chain> Bot<C> {
+    /// Battles with a stored commitment are picked up again, so a restarted
+    /// bot still reveals what it committed.
+    pub fn new(chain: C, policy: Policy, store: SaltStore, rng: StdRng) -> Self {
+        let battles = store.battles().into_iter().collect();
+        Self { chain, policy, store, rng, battles }
+    }
+
+    pub fn chain_mut(&mut self) -> &mut C {
+        &mut self.chain
+    }
+
+    pub fn battles(&self) -> impl Iterator<Item = &Pubkey> {
+        self.battles.iter()
+    }
+
+    /// Pick up new challenges, then advance every tracked battle by one step.
+    pub fn tick(&mut self) -> Result<Vec<Action>, String> {
+        let me = player_pda(&self.chain.authority());
+        let mut actions = Vec::new();
+        for event in self.chain.battle_events()? {
+            if event.opponent == me && self.battles.insert(event.battle) {
+                actions.push(Action::Joined(event.battle));
+            }
+        }
+
+        let slot = self.chain.slot()?;
+        for battle in self.battles.clone() {
+            match self.step(battle, slot) {
+                Ok(Some(action)) => actions.push(action),
+                Ok(None) => {}
+                Err(error) => actions.push(Action::Failed { battle, error }),
+            }
+        }
+        Ok(actions)
+    }
+
+    fn step(&mut self, battle: Pubkey, slot: u64) -> Result<Option<Action>, String> {
+        let me = self.chain.authority();
+        let Some(state) = self.chain.account::<Battle>(&battle)? else {
+            return self.finish(battle, None).map(Some);
+        };
+        let character = player_pda(&me);
+        let side = if character == state.challenger {
+            BattleSide::Challenger
+        } else if character == state.opponent {
+            BattleSide::Opponent
+        } else {
+            return self.finish(battle, None).map(Some);
+        };
+        let (my_commit, my_reveal) = match side {
+            BattleSide::Challenger => (state.commit_challenger, state.reveal_challenger),
+            BattleSide::Opponent => (state.commit_opponent, state.reveal_opponent),
+        };
+        let past_reveal = slot > state.reveal_deadline_slot;
+
+        match state.state {
+            BattleState::Resolved => self.finish(battle, state.winner).map(Some),
+            BattleState::WaitingForCommits if my_commit.is_none() && slot <= state.commit_deadline_slot => {
+                self.commit(battle, &state, side).map(Some)
+            }
+            BattleState::WaitingForReveals if my_reveal.is_none() && !past_reveal => {
+                let commitment = self.store.get(&battle).ok_or("no stored salt for committed battle")?;
+                let move_choice = commitment.move_choice().ok_or("stored move is not a valid MoveChoice")?;
+                self.chain.send(reveal_move(battle, me, move_choice, commitment.salt))?;
+                Ok(Some(Action::Revealed { battle, move_choice }))
+            }
+            _ => {
+                let both_revealed = state.reveal_challenger.is_some() && state.reveal_opponent.is_some();
+                if both_revealed || past_reveal {
+                    let challenger = self.player(&state.challenger)?;
+                    let opponent = self.player(&state.opponent)?;
+                    self.chain.send(resolve_battle(battle, &state, &challenger, &opponent))?;
+                    Ok(Some(Action::Resolved(battle)))
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    fn commit(&mut self, battle: Pubkey, state: &Battle, side: BattleSide) -> Result<Action, String> {
+        let me = self.chain.authority();
+        // A stored entry means an earlier commit may not have landed; resend
+        // the same commitment rather than picking again
+        let commitment = match self.store.get(&battle) {
+            Some(commitment) => commitment,
+            None => {
+                let rival = if side == BattleSide::Challenger { state.opponent } else { state.challenger };
+                let me_player = self.player(&player_pda(&me))?;
+                let rival_player = self.player(&rival)?;
+                // Plan against the rules the battle will resolve under
+                let cfg = BalanceConfig::with_params(&state.rules);
+                let move_choice = self.policy.pick(&me_player, &rival_player, side, &cfg, &mut self.rng);
+                let commitment = Commitment::new(move_choice, OsRng.gen());
+                self.store.insert(battle, commitment)?;
+                commitment
+            }
+        };
+        let move_choice = commitment.move_choice().ok_or("stored move is not a valid MoveChoice")?;
+        let hash = commitment_hash(move_choice, &commitment.salt, &me, &battle);
+        self.chain.send(commit_move(battle, me, hash))?;
+        Ok(Action::Committed { battle, move_choice })
+    }
+
+    fn player(&mut self, character: &Pubkey) -> Result<Player, String> {
+        self.chain.account::<Player>(character)?.ok_or_else(|| format!("no player account at {character}"))
+    }
+
+    fn finish(&mut self, battle: Pubkey, winner: Option<Pubkey>) -> Result<Action, String> {
+        self.store.remove(&battle)?;
+        self.battles.remove(&battle);
+        Ok(Action::Finished { battle, winner })
+    }
+}
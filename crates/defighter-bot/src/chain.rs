@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use defighter::events::BattleInitiated;
+
+/// What the bot needs from a cluster.
+pub trait Chain {
+    /// The bot's own wallet; every instruction is signed by it.
+    fn authority(&self) -> Pubkey;
+
+    fn slot(&mut self) -> std::result::Result<u64, String>;
+
+    /// `BattleInitiated` events emitted since the previous call.
+    fn battle_events(&mut self) -> std::result::Result<Vec<BattleInitiated>, String>;
+
+    /// Raw account data, or `None` if the account does not exist.
+    fn account_data(&mut self, key: &Pubkey) -> std::result::Result<Option<Vec<u8>>, String>;
+
+    /// Sign `instruction` with the authority and wait for it to land.
+    fn send(&mut self, instruction: Instruction) -> std::result::Result<(), String>;
+
+    fn account<T: AccountDeserialize>(&mut self, key: &Pubkey) -> std::result::Result<Option<T>, String> {
+        match self.account_data(key)? {
+            Some(data) => T::try_deserialize(&mut data.as_slice()).map(Some).map_err(|e| e.to_string()),
+            None => Ok(None),
+        }
+    }
+}
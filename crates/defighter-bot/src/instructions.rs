@@ -0,0 +1,91 @@
+//! Instruction builders for the handful of calls the bot makes.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use defighter::state::battle::Battle;
+use defighter::state::player::{MoveChoice, Player};
+
+/// The wallet's first character, the one the bot fights with
+pub fn player_pda(authority: &Pubkey) -> Pubkey {
+    character_pda(authority, 0)
+}
+
+pub fn character_pda(authority: &Pubkey, index: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"player", authority.as_ref(), &[index]], &defighter::ID).0
+}
+
+pub fn profile_pda(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"profile", authority.as_ref()], &defighter::ID).0
+}
+
+pub fn config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &defighter::ID).0
+}
+
+pub fn pair_pda(a: &Pubkey, b: &Pubkey) -> Pubkey {
+    let (low, high) = defighter::state::pair::PairRecord::sorted(*a, *b);
+    Pubkey::find_program_address(&[b"pair", low.as_ref(), high.as_ref()], &defighter::ID).0
+}
+
+pub fn loot_bag_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"loot_bag", owner.as_ref()], &defighter::ID).0
+}
+
+pub fn consumables_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"consumables", owner.as_ref()], &defighter::ID).0
+}
+
+fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction { program_id: defighter::ID, accounts: accounts.to_account_metas(None), data: data.data() }
+}
+
+pub fn commit_move(battle: Pubkey, player: Pubkey, commitment: [u8; 32]) -> Instruction {
+    instruction(
+        defighter::accounts::CommitMove {
+            battle,
+            player,
+            player_account: player_pda(&player),
+            config: config_pda(),
+            clock: sysvar::clock::ID,
+            session: None,
+            character_token: None,
+        },
+        defighter::instruction::CommitMove { commitment },
+    )
+}
+
+pub fn reveal_move(battle: Pubkey, player: Pubkey, move_choice: MoveChoice, salt: [u8; 32]) -> Instruction {
+    instruction(
+        defighter::accounts::RevealMove {
+            battle,
+            player,
+            player_account: player_pda(&player),
+            clock: sysvar::clock::ID,
+            consumables: None,
+            session: None,
+            character_token: None,
+        },
+        defighter::instruction::RevealMove { move_choice, salt, boost: None },
+    )
+}
+
+/// `challenger` and `opponent` are the battle's characters, whose wallets
+/// receive any loot
+pub fn resolve_battle(battle: Pubkey, state: &Battle, challenger: &Player, opponent: &Player) -> Instruction {
+    instruction(
+        defighter::accounts::ResolveBattle {
+            battle,
+            player_challenger: state.challenger,
+            player_opponent: state.opponent,
+            challenger_loot: loot_bag_pda(&challenger.authority),
+            opponent_loot: loot_bag_pda(&opponent.authority),
+            challenger_consumables: consumables_pda(&challenger.authority),
+            opponent_consumables: consumables_pda(&opponent.authority),
+            config: config_pda(),
+            clock: sysvar::clock::ID,
+        },
+        defighter::instruction::ResolveBattle {},
+    )
+}
@@ -0,0 +1,17 @@
+//! House bot for DeFighter.
+//!
+//! The bot watches for `BattleInitiated` events naming it as the opponent,
+//! commits a move chosen by a pluggable strategy, reveals once both sides
+//! have committed, and resolves the battle when it is ready or its deadlines
+//! have passed. Salts are persisted before every commit so a restarted bot
+//! can still reveal.
+//!
+//! Everything that touches the network goes through [`chain::Chain`], so the
+//! same loop runs against an RPC node or an in-process bank in tests.
+
+pub mod bot;
+pub mod chain;
+pub mod instructions;
+pub mod rpc;
+pub mod store;
+pub mod strategy;
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use clap::Parser;
+use defighter_bot::bot::{Action, Bot};
+use defighter_bot::rpc::RpcChain;
+use defighter_bot::store::SaltStore;
+use defighter_bot::strategy::Policy;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// House bot: accepts every DeFighter challenge sent to its wallet and plays
+/// it through commit, reveal and resolve.
+#[derive(Parser)]
+#[command(name = "defighter-bot", version)]
+struct Args {
+    /// JSON-RPC endpoint
+    #[arg(long, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Keypair file of the bot's wallet (must already have a player account)
+    #[arg(short, long)]
+    keypair: PathBuf,
+    #[arg(long, default_value_t = defighter::ID)]
+    program_id: Pubkey,
+    #[arg(short, long, value_enum, default_value_t = Policy::Equilibrium)]
+    strategy: Policy,
+    /// Where committed moves and salts are kept between restarts
+    #[arg(long, default_value = "defighter-bot-salts.json")]
+    salts: PathBuf,
+    #[arg(long, default_value_t = 2000)]
+    poll_ms: u64,
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("defighter-bot: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let keypair =
+        solana_keypair::read_keypair_file(&args.keypair).map_err(|e| format!("{}: {e}", args.keypair.display()))?;
+    let store = SaltStore::open(&args.salts)?;
+    let chain = RpcChain::new(args.url, keypair, args.program_id);
+    let mut bot = Bot::new(chain, args.strategy, store, StdRng::from_entropy());
+
+    loop {
+        match bot.tick() {
+            Ok(actions) => actions.iter().for_each(report),
+            Err(e) => eprintln!("defighter-bot: {e}"),
+        }
+        thread::sleep(Duration::from_millis(args.poll_ms));
+    }
+}
+
+fn report(action: &Action) {
+    match action {
+        Action::Joined(battle) => println!("{battle}: accepted challenge"),
+        Action::Committed { battle, move_choice } => println!("{battle}: committed {move_choice:?}"),
+        Action::Revealed { battle, move_choice } => println!("{battle}: revealed {move_choice:?}"),
+        Action::Resolved(battle) => println!("{battle}: resolved"),
+        Action::Finished { battle, winner: Some(winner) } => println!("{battle}: done, winner {winner}"),
+        Action::Finished { battle, winner: None } => println!("{battle}: done"),
+        Action::Failed { battle, error } => eprintln!("{battle}: {error}"),
+    }
+}
@@ -0,0 +1,173 @@
+//! [`Chain`] over Solana JSON-RPC.
+
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use defighter::events::{decode_event, BattleInitiated};
+use serde_json::{json, Value};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::chain::Chain;
+
+/// How far back the first event poll looks, so challenges sent while the bot
+/// was down are not missed.
+const BACKFILL_SIGNATURES: u64 = 100;
+/// Most signatures `getSignaturesForAddress` returns per call
+const SIGNATURE_PAGE: u64 = 1_000;
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+const CONFIRM_POLL: Duration = Duration::from_millis(500);
+
+pub struct RpcChain {
+    url: String,
+    agent: ureq::Agent,
+    keypair: Keypair,
+    program_id: Pubkey,
+    /// Newest program signature already scanned for events
+    cursor: Option<String>,
+}
+
+impl RpcChain {
+    pub fn new(url: impl Into<String>, keypair: Keypair, program_id: Pubkey) -> Self {
+        Self { url: url.into(), agent: ureq::agent(), keypair, program_id, cursor: None }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(request)
+            .map_err(|e| format!("{method}: {e}"))?
+            .into_json()
+            .map_err(|e| format!("{method}: {e}"))?;
+        if let Some(error) = response.get("error") {
+            return Err(format!("{method}: {error}"));
+        }
+        Ok(response["result"].clone())
+    }
+
+    fn transaction_logs(&self, signature: &str) -> Result<Vec<String>, String> {
+        let result = self.call(
+            "getTransaction",
+            json!([signature, { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 }]),
+        )?;
+        Ok(result["meta"]["logMessages"]
+            .as_array()
+            .map(|logs| logs.iter().filter_map(|l| l.as_str().map(String::from)).collect())
+            .unwrap_or_default())
+    }
+
+    /// Program signatures newer than `cursor`, newest first, paging back with
+    /// `before` so a burst between polls isn't cut off at one page. Without a
+    /// cursor, the newest `BACKFILL_SIGNATURES`.
+    fn new_signatures(&self) -> Result<Vec<Value>, String> {
+        let mut signatures: Vec<Value> = Vec::new();
+        loop {
+            let mut options = json!({ "commitment": "confirmed" });
+            match &self.cursor {
+                Some(cursor) => {
+                    options["until"] = json!(cursor);
+                    options["limit"] = json!(SIGNATURE_PAGE);
+                }
+                None => options["limit"] = json!(BACKFILL_SIGNATURES),
+            }
+            if let Some(oldest) = signatures.last().and_then(|s| s["signature"].as_str()) {
+                options["before"] = json!(oldest);
+            }
+            let result = self.call("getSignaturesForAddress", json!([self.program_id.to_string(), options]))?;
+            let page = result.as_array().cloned().unwrap_or_default();
+            let full = page.len() as u64 == SIGNATURE_PAGE;
+            signatures.extend(page);
+            if self.cursor.is_none() || !full {
+                return Ok(signatures);
+            }
+        }
+    }
+
+    fn confirm(&self, signature: &str) -> Result<(), String> {
+        let started = Instant::now();
+        while started.elapsed() < CONFIRM_TIMEOUT {
+            let result = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(format!("transaction {signature} failed: {}", status["err"]));
+                }
+                if matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized")) {
+                    return Ok(());
+                }
+            }
+            thread::sleep(CONFIRM_POLL);
+        }
+        Err(format!("transaction {signature} not confirmed after {CONFIRM_TIMEOUT:?}"))
+    }
+}
+
+impl Chain for RpcChain {
+    fn authority(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
+    fn slot(&mut self) -> Result<u64, String> {
+        self.call("getSlot", json!([{ "commitment": "confirmed" }]))?
+            .as_u64()
+            .ok_or_else(|| "getSlot: expected a number".into())
+    }
+
+    fn battle_events(&mut self) -> Result<Vec<BattleInitiated>, String> {
+        let signatures = self.new_signatures()?;
+        if let Some(newest) = signatures.first().and_then(|s| s["signature"].as_str()) {
+            self.cursor = Some(newest.to_string());
+        }
+
+        let mut events = Vec::new();
+        // Newest first from the node; replay oldest first
+        for entry in signatures.iter().rev().filter(|s| s["err"].is_null()) {
+            let Some(signature) = entry["signature"].as_str() else { continue };
+            for log in self.transaction_logs(signature)? {
+                let Some(data) = log.strip_prefix("Program data: ") else { continue };
+                if let Some(event) = BASE64.decode(data).ok().and_then(|raw| decode_event(&raw)) {
+                    events.push(event);
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    fn account_data(&mut self, key: &Pubkey) -> Result<Option<Vec<u8>>, String> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([key.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        let value = &result["value"];
+        if value.is_null() {
+            return Ok(None);
+        }
+        let data = value["data"][0].as_str().ok_or("getAccountInfo: missing data")?;
+        BASE64.decode(data).map(Some).map_err(|e| format!("getAccountInfo: {e}"))
+    }
+
+    fn send(&mut self, instruction: Instruction) -> Result<(), String> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"].as_str().ok_or("getLatestBlockhash: missing blockhash")?;
+        let blockhash = Hash::from_str(blockhash).map_err(|e| format!("getLatestBlockhash: {e}"))?;
+
+        let payer = self.keypair.pubkey();
+        let tx = Transaction::new_signed_with_payer(&[instruction], Some(&payer), &[&self.keypair], blockhash);
+        let wire = bincode::serialize(&tx).map_err(|e| e.to_string())?;
+        let signature = self.call(
+            "sendTransaction",
+            json!([BASE64.encode(wire), { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let signature = signature.as_str().ok_or("sendTransaction: expected a signature")?;
+        self.confirm(signature)
+    }
+}
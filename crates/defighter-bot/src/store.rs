@@ -0,0 +1,105 @@
+//! On-disk record of committed moves and their salts.
+//!
+//! A commitment is useless without its salt, so the entry is written (and
+//! renamed into place) before the commit transaction is sent. If the bot dies
+//! between the two, the stale entry is harmless and gets dropped with the
+//! battle.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anchor_lang::prelude::Pubkey;
+use defighter::state::player::MoveChoice;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Commitment {
+    /// `MoveChoice::to_byte`
+    pub move_choice: u8,
+    pub salt: [u8; 32],
+}
+
+impl Commitment {
+    pub fn new(move_choice: MoveChoice, salt: [u8; 32]) -> Self {
+        Self { move_choice: move_choice.to_byte(), salt }
+    }
+
+    pub fn move_choice(&self) -> Option<MoveChoice> {
+        MoveChoice::from_byte(self.move_choice)
+    }
+}
+
+/// Commitments keyed by battle address.
+#[derive(Debug)]
+pub struct SaltStore {
+    path: PathBuf,
+    entries: BTreeMap<String, Commitment>,
+}
+
+impl SaltStore {
+    /// Open the store at `path`, starting empty if the file does not exist yet.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let entries = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(format!("{}: {e}", path.display())),
+        };
+        Ok(Self { path, entries })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, battle: &Pubkey) -> Option<Commitment> {
+        self.entries.get(&battle.to_string()).copied()
+    }
+
+    pub fn battles(&self) -> Vec<Pubkey> {
+        self.entries.keys().filter_map(|key| key.parse().ok()).collect()
+    }
+
+    pub fn insert(&mut self, battle: Pubkey, commitment: Commitment) -> Result<(), String> {
+        self.entries.insert(battle.to_string(), commitment);
+        self.flush()
+    }
+
+    pub fn remove(&mut self, battle: &Pubkey) -> Result<(), String> {
+        if self.entries.remove(&battle.to_string()).is_some() {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(&self.entries).map_err(|e| e.to_string())?;
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, text).map_err(|e| format!("{}: {e}", tmp.display()))?;
+        fs::rename(&tmp, &self.path).map_err(|e| format!("{}: {e}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn survives_reopen() {
+        let path = std::env::temp_dir().join(format!("defighter-bot-store-{}.json", std::process::id()));
+        let battle = Pubkey::new_unique();
+        let commitment = Commitment::new(MoveChoice::ShipIt, [9; 32]);
+
+        let mut store = SaltStore::open(&path).unwrap();
+        store.insert(battle, commitment).unwrap();
+        let reopened = SaltStore::open(&path).unwrap();
+        assert_eq!(reopened.get(&battle), Some(commitment));
+        assert_eq!(reopened.battles(), vec![battle]);
+        assert_eq!(commitment.move_choice(), Some(MoveChoice::ShipIt));
+
+        store.remove(&battle).unwrap();
+        assert!(SaltStore::open(&path).unwrap().get(&battle).is_none());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,45 @@
+//! Move selection, reusing the simulator's strategies so the bot plays the
+//! same way the balance reports assume.
+
+use defighter::logic::BattleSide;
+use defighter::state::config::BalanceConfig;
+use defighter::state::player::{MoveChoice, Player};
+use defighter_sim::strategy::StrategySpec;
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Policy {
+    /// Basic or special with equal probability
+    Random,
+    /// Whichever move has the higher expected damage
+    Greedy,
+    /// The Nash-equilibrium mix for the matchup
+    Equilibrium,
+    /// Always the class's basic move
+    Basic,
+    /// Always the class's special move
+    Special,
+}
+
+impl Policy {
+    pub fn pick<R: Rng>(
+        self,
+        me: &Player,
+        rival: &Player,
+        side: BattleSide,
+        cfg: &BalanceConfig,
+        rng: &mut R,
+    ) -> MoveChoice {
+        self.spec().build(me, rival, side, cfg).pick(rng)
+    }
+
+    fn spec(self) -> StrategySpec {
+        match self {
+            Policy::Random => StrategySpec::Mixed { special_bps: 5_000 },
+            Policy::Greedy => StrategySpec::Greedy,
+            Policy::Equilibrium => StrategySpec::Equilibrium,
+            Policy::Basic => StrategySpec::Basic,
+            Policy::Special => StrategySpec::Special,
+        }
+    }
+}
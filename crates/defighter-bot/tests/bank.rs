@@ -0,0 +1,230 @@
+//! The bot loop against the real program running in `defighter-bank`.
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use defighter::events::BattleInitiated;
+use defighter::logic::commitment_hash;
+use defighter::state::battle::{Battle, BattleState};
+use defighter::state::config::BalanceConfigParams;
+use defighter::state::player::{FighterClass, MoveChoice};
+use defighter_bank::Bank;
+use defighter_bot::bot::{Action, Bot};
+use defighter_bot::chain::Chain;
+use defighter_bot::instructions::{config_pda, consumables_pda, loot_bag_pda, pair_pda, player_pda, profile_pda, reveal_move};
+use defighter_bot::store::SaltStore;
+use defighter_bot::strategy::Policy;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+struct BankChain {
+    bank: Bank,
+    authority: Pubkey,
+    events: Vec<BattleInitiated>,
+}
+
+impl BankChain {
+    /// Send as another wallet, capturing events the bot would see on-chain.
+    fn send_as(&mut self, signer: Pubkey, instruction: Instruction) {
+        let meta = self.bank.process_transaction(&[instruction], &[signer]).unwrap();
+        self.events.extend(meta.events::<BattleInitiated>());
+    }
+}
+
+impl Chain for BankChain {
+    fn authority(&self) -> Pubkey {
+        self.authority
+    }
+
+    fn slot(&mut self) -> std::result::Result<u64, String> {
+        Ok(self.bank.slot())
+    }
+
+    fn battle_events(&mut self) -> std::result::Result<Vec<BattleInitiated>, String> {
+        Ok(std::mem::take(&mut self.events))
+    }
+
+    fn account_data(&mut self, key: &Pubkey) -> std::result::Result<Option<Vec<u8>>, String> {
+        Ok(self.bank.get_account(key).map(|a| a.data.clone()))
+    }
+
+    fn send(&mut self, instruction: Instruction) -> std::result::Result<(), String> {
+        self.bank.process_transaction(&[instruction], &[self.authority]).map(|_| ()).map_err(|e| format!("{e:?}"))
+    }
+}
+
+fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction { program_id: defighter::ID, accounts: accounts.to_account_metas(None), data: data.data() }
+}
+
+fn create_player(chain: &mut BankChain, authority: Pubkey, class: FighterClass) {
+    chain.bank.airdrop(&authority, 1_000_000_000);
+    chain.send_as(
+        authority,
+        ix(
+            defighter::accounts::CreatePlayer {
+                payer: authority,
+                profile: profile_pda(&authority),
+                player: player_pda(&authority),
+                authority,
+                config: config_pda(),
+                system_program: system_program::ID,
+            },
+            defighter::instruction::CreatePlayer { class },
+        ),
+    );
+}
+
+/// A bank with config, a house bot and a human challenger.
+fn setup() -> (BankChain, Pubkey) {
+    let mut bank = Bank::new();
+    bank.add_program(defighter::ID, defighter::entry);
+    let mut chain = BankChain { bank, authority: Pubkey::new_unique(), events: vec![] };
+
+    let admin = Pubkey::new_unique();
+    chain.bank.airdrop(&admin, 1_000_000_000);
+    chain.send_as(
+        admin,
+        ix(
+            defighter::accounts::InitConfig { config: config_pda(), admin, system_program: system_program::ID },
+            defighter::instruction::InitConfig { params: BalanceConfigParams::default() },
+        ),
+    );
+
+    let human = Pubkey::new_unique();
+    let house = chain.authority;
+    create_player(&mut chain, house, FighterClass::Builder);
+    create_player(&mut chain, human, FighterClass::VC);
+    (chain, human)
+}
+
+fn challenge(chain: &mut BankChain, human: Pubkey, nonce: u64) -> Pubkey {
+    let house = chain.authority;
+    let battle = Pubkey::find_program_address(
+        &[b"battle", player_pda(&human).as_ref(), player_pda(&house).as_ref(), &nonce.to_le_bytes()],
+        &defighter::ID,
+    )
+    .0;
+    chain.send_as(
+        human,
+        ix(
+            defighter::accounts::InitiateBattle {
+                battle,
+                pair_record: pair_pda(&human, &house),
+                challenger_loot: loot_bag_pda(&human),
+                opponent_loot: loot_bag_pda(&house),
+                challenger_consumables: consumables_pda(&human),
+                opponent_consumables: consumables_pda(&house),
+                challenger: human,
+                challenger_account: player_pda(&human),
+                opponent_account: player_pda(&house),
+                config: config_pda(),
+                system_program: system_program::ID,
+                clock: sysvar::clock::ID,
+                character_token: None,
+            },
+            defighter::instruction::InitiateBattle {
+                opponent: player_pda(&house),
+                nonce,
+                commit_deadline_slots: 10,
+                reveal_deadline_slots: 10,
+            },
+        ),
+    );
+    battle
+}
+
+fn salts(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("defighter-bot-{name}-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn bot(chain: BankChain, path: &PathBuf) -> Bot<BankChain> {
+    Bot::new(chain, Policy::Equilibrium, SaltStore::open(path).unwrap(), StdRng::seed_from_u64(7))
+}
+
+#[test]
+fn plays_a_challenge_to_the_end() {
+    let (mut chain, human) = setup();
+    let battle = challenge(&mut chain, human, 1);
+    let path = salts("full");
+    let mut bot = bot(chain, &path);
+
+    let actions = bot.tick().unwrap();
+    assert_eq!(actions[0], Action::Joined(battle));
+    let Action::Committed { move_choice: bot_move, .. } = actions[1] else { panic!("{actions:?}") };
+    assert_eq!(bot_move.class(), FighterClass::Builder);
+
+    // Nothing to do until the human commits
+    assert!(bot.tick().unwrap().is_empty());
+
+    let salt = [3; 32];
+    let human_move = MoveChoice::SeriesACannon;
+    bot.chain_mut().send_as(
+        human,
+        ix(
+            defighter::accounts::CommitMove { battle, player: human, player_account: player_pda(&human), config: config_pda(), clock: sysvar::clock::ID, session: None, character_token: None },
+            defighter::instruction::CommitMove { commitment: commitment_hash(human_move, &salt, &human, &battle) },
+        ),
+    );
+    assert_eq!(bot.tick().unwrap(), vec![Action::Revealed { battle, move_choice: bot_move }]);
+
+    bot.chain_mut().send_as(human, reveal_move(battle, human, human_move, salt));
+    assert_eq!(bot.tick().unwrap(), vec![Action::Resolved(battle)]);
+
+    let state: Battle = bot.chain_mut().account(&battle).unwrap().unwrap();
+    assert!(state.state == BattleState::Resolved);
+    assert_eq!(state.reveal_opponent, Some(bot_move));
+    assert_eq!(bot.tick().unwrap(), vec![Action::Finished { battle, winner: state.winner }]);
+    assert_eq!(bot.battles().count(), 0);
+    assert!(SaltStore::open(&path).unwrap().get(&battle).is_none());
+}
+
+#[test]
+fn resolves_a_forfeit_after_the_deadline() {
+    let (mut chain, human) = setup();
+    let battle = challenge(&mut chain, human, 2);
+    let house = chain.authority;
+    let mut bot = bot(chain, &salts("forfeit"));
+    bot.tick().unwrap();
+
+    // The human never commits; nothing happens until the reveal deadline
+    bot.chain_mut().bank.warp_to_slot(20);
+    assert!(bot.tick().unwrap().is_empty());
+    bot.chain_mut().bank.warp_to_slot(21);
+    assert_eq!(bot.tick().unwrap(), vec![Action::Resolved(battle)]);
+    assert_eq!(bot.tick().unwrap(), vec![Action::Finished { battle, winner: Some(player_pda(&house)) }]);
+}
+
+#[test]
+fn reveals_after_a_restart() {
+    let (mut chain, human) = setup();
+    let battle = challenge(&mut chain, human, 3);
+    let path = salts("restart");
+    let mut first = bot(chain, &path);
+    let actions = first.tick().unwrap();
+    let Action::Committed { move_choice, .. } = actions[1] else { panic!("{actions:?}") };
+
+    let salt = [4; 32];
+    let human_move = MoveChoice::TestnetDeploy;
+    first.chain_mut().send_as(
+        human,
+        ix(
+            defighter::accounts::CommitMove { battle, player: human, player_account: player_pda(&human), config: config_pda(), clock: sysvar::clock::ID, session: None, character_token: None },
+            defighter::instruction::CommitMove { commitment: commitment_hash(human_move, &salt, &human, &battle) },
+        ),
+    );
+
+    // A fresh bot only has the salt file and the chain; no events replayed
+    let chain = std::mem::replace(
+        first.chain_mut(),
+        BankChain { bank: Bank::new(), authority: Pubkey::default(), events: vec![] },
+    );
+    let mut second = bot(chain, &path);
+    assert_eq!(second.tick().unwrap(), vec![Action::Revealed { battle, move_choice }]);
+}
@@ -0,0 +1,64 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use defighter_sim::config::SimConfig;
+use defighter_sim::equilibrium::{solve_all, Payoff};
+
+/// Nash-equilibrium solver for the Basic/Special move game of every class
+/// pairing and power tier.
+#[derive(Parser)]
+#[command(name = "defighter-solve", version)]
+struct Args {
+    /// Simulation config (.toml or .json) supplying balance and tiers
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+    #[arg(long, value_enum, default_value_t = Payoff::WinProbability)]
+    payoff: Payoff,
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+    /// Write the report here instead of stdout
+    #[arg(short, long)]
+    out: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Csv,
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("defighter-solve: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let cfg = match &args.config {
+        Some(path) => SimConfig::load(path)?,
+        None => SimConfig::default(),
+    };
+    let report = solve_all(&cfg, args.payoff);
+
+    let out: Box<dyn Write> = match &args.out {
+        Some(path) => Box::new(File::create(path).map_err(|e| format!("creating {}: {e}", path.display()))?),
+        None => Box::new(io::stdout().lock()),
+    };
+    match args.format {
+        Format::Json => serde_json::to_writer_pretty(out, &report).map_err(|e| e.to_string()),
+        Format::Csv => {
+            let mut w = csv::Writer::from_writer(out);
+            for row in &report.matchups {
+                w.serialize(row).map_err(|e| e.to_string())?;
+            }
+            w.flush().map_err(|e| e.to_string())
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use std::path::Path;
+
+use defighter::state::config::{BalanceConfig, BalanceConfigParams, ResolutionMode, SpecialMoveParams};
+use serde::{Deserialize, Serialize};
+
+use crate::strategy::StrategySpec;
+
+/// Everything a simulation run needs, loaded from a JSON or TOML file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimConfig {
+    pub seed: u64,
+    pub battles_per_matchup: u64,
+    /// Rounds fought before HP is compared; 1 matches the on-chain single exchange.
+    pub max_rounds: u16,
+    pub starting_hp: u16,
+    pub balance: BalanceFile,
+    pub tiers: Vec<PowerTier>,
+    pub strategies: Vec<StrategySpec>,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            battles_per_matchup: 10_000,
+            max_rounds: 1,
+            starting_hp: 200,
+            balance: BalanceFile::default(),
+            tiers: vec![
+                PowerTier { name: "fresh".into(), xp: 0, abilities: [0, 0, 0] },
+                PowerTier { name: "mid".into(), xp: 2_500, abilities: [3, 3, 3] },
+                PowerTier { name: "veteran".into(), xp: 10_000, abilities: [8, 8, 8] },
+            ],
+            strategies: vec![
+                StrategySpec::Basic,
+                StrategySpec::Special,
+                StrategySpec::Mixed { special_bps: 5_000 },
+                StrategySpec::Greedy,
+            ],
+        }
+    }
+}
+
+impl SimConfig {
+    /// Load a config, picking the format from the file extension (`.toml`, otherwise JSON).
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("reading {}: {e}", path.display()))?;
+        let is_toml = path.extension().is_some_and(|ext| ext == "toml");
+        let cfg: SimConfig = if is_toml {
+            toml::from_str(&text).map_err(|e| format!("parsing {}: {e}", path.display()))?
+        } else {
+            serde_json::from_str(&text).map_err(|e| format!("parsing {}: {e}", path.display()))?
+        };
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tiers.is_empty() {
+            return Err("at least one power tier is required".into());
+        }
+        if self.strategies.is_empty() {
+            return Err("at least one strategy is required".into());
+        }
+        if self.max_rounds == 0 {
+            return Err("max_rounds must be at least 1".into());
+        }
+        for strategy in &self.strategies {
+            strategy.validate()?;
+        }
+        // Only balances the program would accept are worth reporting on
+        BalanceConfigParams::from(&self.balance).validate().map_err(|e| format!("balance: {e}"))?;
+        Ok(())
+    }
+}
+
+/// A named progression level both sides can be placed at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerTier {
+    pub name: String,
+    pub xp: u64,
+    pub abilities: [u16; 3],
+}
+
+/// Off-chain mirror of `BalanceConfig` (minus `admin`), defaulting to the demo values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BalanceFile {
+    pub base: i32,
+    pub linear_a: i32,
+    pub dim_k: i32,
+    pub dim_t: i32,
+    pub max_level: u16,
+    pub tie_break_rand: bool,
+    pub use_dim_bonus: bool,
+    pub xp_base: u64,
+    pub xp_tie_bps: u16,
+    pub xp_loser_bps: u16,
+    pub upgrade_c0: u64,
+    pub upgrade_p_bps: u16,
+    pub rps_win_base: i32,
+    pub rps_tie_base: i32,
+    pub basic_damage_bps: u16,
+    pub accuracy_bps: u16,
+    pub crit_bps: u16,
+    pub defense_bps: u16,
+    pub resolution_mode: Mode,
+    pub base_damage: u16,
+    pub class_win_bps: u16,
+    pub class_loss_bps: u16,
+    pub xp_tier_size: u64,
+    pub xp_tier_bonus_bps: u16,
+    /// Indexed by class: Shitposter, Builder, VC
+    pub special_moves: [SpecialMove; 3],
+}
+
+/// Serde-friendly `SpecialMoveParams`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpecialMove {
+    pub miss_pct: u8,
+    pub hit_end_pct: u8,
+    pub hit_mult_bps: u16,
+    pub crit_mult_bps: u16,
+}
+
+impl From<SpecialMoveParams> for SpecialMove {
+    fn from(p: SpecialMoveParams) -> Self {
+        Self { miss_pct: p.miss_pct, hit_end_pct: p.hit_end_pct, hit_mult_bps: p.hit_mult_bps, crit_mult_bps: p.crit_mult_bps }
+    }
+}
+
+impl From<SpecialMove> for SpecialMoveParams {
+    fn from(m: SpecialMove) -> Self {
+        Self { miss_pct: m.miss_pct, hit_end_pct: m.hit_end_pct, hit_mult_bps: m.hit_mult_bps, crit_mult_bps: m.crit_mult_bps }
+    }
+}
+
+/// Serde-friendly `ResolutionMode`: `"damage"` or `"score"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    #[default]
+    Damage,
+    Score,
+}
+
+impl From<Mode> for ResolutionMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Damage => ResolutionMode::Damage,
+            Mode::Score => ResolutionMode::Score,
+        }
+    }
+}
+
+impl Default for BalanceFile {
+    fn default() -> Self {
+        let demo = BalanceConfigParams::default();
+        Self {
+            base: 10,
+            linear_a: 2,
+            dim_k: 20,
+            dim_t: 10,
+            max_level: 100,
+            tie_break_rand: true,
+            use_dim_bonus: false,
+            xp_base: 10,
+            xp_tie_bps: 7500,
+            xp_loser_bps: 2000,
+            upgrade_c0: 10,
+            upgrade_p_bps: 14000,
+            rps_win_base: 20,
+            rps_tie_base: 10,
+            basic_damage_bps: BalanceConfig::DEFAULT_BASIC_DAMAGE_BPS,
+            accuracy_bps: BalanceConfig::DEFAULT_ACCURACY_BPS,
+            crit_bps: BalanceConfig::DEFAULT_CRIT_BPS,
+            defense_bps: BalanceConfig::DEFAULT_DEFENSE_BPS,
+            resolution_mode: Mode::Damage,
+            base_damage: demo.base_damage,
+            class_win_bps: demo.class_win_bps,
+            class_loss_bps: demo.class_loss_bps,
+            xp_tier_size: demo.xp_tier_size,
+            xp_tier_bonus_bps: demo.xp_tier_bonus_bps,
+            special_moves: demo.special_moves.map(SpecialMove::from),
+        }
+    }
+}
+
+impl From<&BalanceFile> for BalanceConfigParams {
+    fn from(f: &BalanceFile) -> Self {
+        BalanceConfigParams {
+            base: f.base,
+            linear_a: f.linear_a,
+            dim_k: f.dim_k,
+            dim_t: f.dim_t,
+            max_level: f.max_level,
+            tie_break_rand: f.tie_break_rand,
+            use_dim_bonus: f.use_dim_bonus,
+            xp_base: f.xp_base,
+            xp_tie_bps: f.xp_tie_bps,
+            xp_loser_bps: f.xp_loser_bps,
+            upgrade_c0: f.upgrade_c0,
+            upgrade_p_bps: f.upgrade_p_bps,
+            rps_win_base: f.rps_win_base,
+            rps_tie_base: f.rps_tie_base,
+            basic_damage_bps: f.basic_damage_bps,
+            accuracy_bps: f.accuracy_bps,
+            crit_bps: f.crit_bps,
+            defense_bps: f.defense_bps,
+            resolution_mode: f.resolution_mode.into(),
+            base_damage: f.base_damage,
+            class_win_bps: f.class_win_bps,
+            class_loss_bps: f.class_loss_bps,
+            xp_tier_size: f.xp_tier_size,
+            xp_tier_bonus_bps: f.xp_tier_bonus_bps,
+            special_moves: f.special_moves.map(SpecialMoveParams::from),
+            // Rematch decay, energy, loot, boosts, prestige and deadlines aren't
+            // simulated; they keep the demo values so the whole set validates
+            ..BalanceConfigParams::default()
+        }
+    }
+}
+
+impl From<&BalanceFile> for BalanceConfig {
+    fn from(f: &BalanceFile) -> Self {
+        BalanceConfig::with_params(&f.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_and_json_agree() {
+        let toml_cfg: SimConfig = toml::from_str(
+            r#"
+            seed = 7
+            battles_per_matchup = 100
+
+            [balance]
+            tie_break_rand = false
+            resolution_mode = "score"
+
+            [[tiers]]
+            name = "fresh"
+            xp = 0
+            abilities = [0, 0, 0]
+
+            [[strategies]]
+            kind = "mixed"
+            special_bps = 2500
+            "#,
+        )
+        .unwrap();
+        let json_cfg: SimConfig = serde_json::from_str(
+            r#"{
+                "seed": 7,
+                "battles_per_matchup": 100,
+                "balance": { "tie_break_rand": false, "resolution_mode": "score" },
+                "tiers": [{ "name": "fresh", "xp": 0, "abilities": [0, 0, 0] }],
+                "strategies": [{ "kind": "mixed", "special_bps": 2500 }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&toml_cfg).unwrap(),
+            serde_json::to_value(&json_cfg).unwrap()
+        );
+        assert!(!toml_cfg.balance.tie_break_rand);
+        assert_eq!(toml_cfg.balance.xp_base, 10);
+        assert_eq!(BalanceConfig::from(&toml_cfg.balance).resolution_mode, ResolutionMode::Score);
+        assert_eq!(toml_cfg.max_rounds, 1);
+        assert_eq!(
+            BalanceConfig::from(&toml_cfg.balance).special_moves,
+            BalanceConfigParams::default().special_moves
+        );
+    }
+
+    #[test]
+    fn rejects_empty_tiers() {
+        let cfg = SimConfig { tiers: vec![], ..Default::default() };
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_balances_the_program_would() {
+        assert!(SimConfig::default().validate().is_ok());
+        let steep = BalanceFile { upgrade_p_bps: 40_000, ..Default::default() };
+        assert!(SimConfig { balance: steep, ..Default::default() }.validate().is_err());
+        let wall = BalanceFile { defense_bps: 10_001, ..Default::default() };
+        assert!(SimConfig { balance: wall, ..Default::default() }.validate().is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use anchor_lang::prelude::Pubkey;
+use defighter::logic::{resolve_round, BattleSide, RoundInput};
+use defighter::state::config::BalanceConfig;
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::config::{PowerTier, SimConfig};
+use crate::strategy::Strategy;
+
+/// One cell of the simulation grid: classes, tiers and strategies for both sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Matchup {
+    pub challenger_class: FighterClass,
+    pub opponent_class: FighterClass,
+    pub challenger_tier: usize,
+    pub opponent_tier: usize,
+    pub challenger_strategy: usize,
+    pub opponent_strategy: usize,
+}
+
+impl Matchup {
+    /// Same class, tier and strategy on both sides, so any skew is first-mover bias.
+    pub fn is_mirror(&self) -> bool {
+        self.challenger_class == self.opponent_class
+            && self.challenger_tier == self.opponent_tier
+            && self.challenger_strategy == self.opponent_strategy
+    }
+}
+
+/// Per-move tallies from the point of view of the side that picked it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MoveTally {
+    pub picks: u64,
+    pub damage: u64,
+    /// Picks made by the side that went on to win the battle
+    pub winning_picks: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchupStats {
+    pub matchup: Matchup,
+    pub battles: u64,
+    pub challenger_wins: u64,
+    pub total_rounds: u64,
+    /// Indexed by `MoveChoice as usize`
+    pub moves: [MoveTally; 6],
+}
+
+/// Every class pairing x tier pairing x strategy pairing, in a stable order.
+pub fn matchups(cfg: &SimConfig) -> Vec<Matchup> {
+    let mut out = Vec::new();
+    for challenger_class in FighterClass::ALL {
+        for opponent_class in FighterClass::ALL {
+            for challenger_tier in 0..cfg.tiers.len() {
+                for opponent_tier in 0..cfg.tiers.len() {
+                    for challenger_strategy in 0..cfg.strategies.len() {
+                        for opponent_strategy in 0..cfg.strategies.len() {
+                            out.push(Matchup {
+                                challenger_class,
+                                opponent_class,
+                                challenger_tier,
+                                opponent_tier,
+                                challenger_strategy,
+                                opponent_strategy,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Run `cfg.battles_per_matchup` battles for every matchup.
+pub fn run(cfg: &SimConfig) -> Vec<MatchupStats> {
+    let balance = BalanceConfig::from(&cfg.balance);
+    matchups(cfg)
+        .into_iter()
+        .enumerate()
+        .map(|(i, matchup)| simulate_matchup(cfg, &balance, matchup, i as u64))
+        .collect()
+}
+
+/// Each matchup draws from its own ChaCha stream, so results depend only on
+/// the seed and the matchup's position in the grid.
+pub fn simulate_matchup(cfg: &SimConfig, balance: &BalanceConfig, matchup: Matchup, stream: u64) -> MatchupStats {
+    let mut rng = ChaCha8Rng::seed_from_u64(cfg.seed);
+    rng.set_stream(stream);
+
+    let challenger = fighter(matchup.challenger_class, &cfg.tiers[matchup.challenger_tier]);
+    let opponent = fighter(matchup.opponent_class, &cfg.tiers[matchup.opponent_tier]);
+    let challenger_strategy = cfg.strategies[matchup.challenger_strategy].build(&challenger, &opponent, BattleSide::Challenger, balance);
+    let opponent_strategy = cfg.strategies[matchup.opponent_strategy].build(&opponent, &challenger, BattleSide::Opponent, balance);
+
+    let mut stats = MatchupStats {
+        matchup,
+        battles: cfg.battles_per_matchup,
+        challenger_wins: 0,
+        total_rounds: 0,
+        moves: [MoveTally::default(); 6],
+    };
+
+    for _ in 0..cfg.battles_per_matchup {
+        let battle = simulate_battle(
+            cfg,
+            balance,
+            (&challenger, &challenger_strategy),
+            (&opponent, &opponent_strategy),
+            &mut rng,
+        );
+        stats.total_rounds += battle.rounds as u64;
+        let challenger_won = battle.winner == BattleSide::Challenger;
+        if challenger_won {
+            stats.challenger_wins += 1;
+        }
+        for pick in &battle.picks {
+            let tally = &mut stats.moves[pick.move_choice as usize];
+            tally.picks += 1;
+            tally.damage += pick.damage as u64;
+            if (pick.side == BattleSide::Challenger) == challenger_won {
+                tally.winning_picks += 1;
+            }
+        }
+    }
+    stats
+}
+
+struct Pick {
+    side: BattleSide,
+    move_choice: MoveChoice,
+    damage: u16,
+}
+
+struct BattleResult {
+    winner: BattleSide,
+    rounds: u16,
+    picks: Vec<Pick>,
+}
+
+fn simulate_battle<R: Rng>(
+    cfg: &SimConfig,
+    balance: &BalanceConfig,
+    (challenger, challenger_strategy): (&Player, &Strategy),
+    (opponent, opponent_strategy): (&Player, &Strategy),
+    rng: &mut R,
+) -> BattleResult {
+    let mut challenger_hp = cfg.starting_hp;
+    let mut opponent_hp = cfg.starting_hp;
+    let mut picks = Vec::with_capacity(2 * cfg.max_rounds as usize);
+
+    let mut round = 0;
+    loop {
+        round += 1;
+        let c_move = challenger_strategy.pick(rng);
+        let o_move = opponent_strategy.pick(rng);
+        let resolution = resolve_round(
+            RoundInput { player: challenger, move_choice: c_move, hp: challenger_hp, vrf_result: rng.gen(), boost: None },
+            RoundInput { player: opponent, move_choice: o_move, hp: opponent_hp, vrf_result: rng.gen(), boost: None },
+            balance,
+        );
+        challenger_hp = resolution.opponent.remaining_hp;
+        opponent_hp = resolution.challenger.remaining_hp;
+        picks.push(Pick { side: BattleSide::Challenger, move_choice: c_move, damage: resolution.challenger.damage_dealt });
+        picks.push(Pick { side: BattleSide::Opponent, move_choice: o_move, damage: resolution.opponent.damage_dealt });
+
+        if challenger_hp == 0 || opponent_hp == 0 || round >= cfg.max_rounds {
+            return BattleResult { winner: resolution.winner, rounds: round, picks };
+        }
+    }
+}
+
+/// A `Player` at the given tier, as the on-chain account would look.
+pub fn fighter(class: FighterClass, tier: &PowerTier) -> Player {
+    Player { xp: tier.xp, ..Player::new(Pubkey::default(), class, tier.abilities) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PowerTier;
+    use crate::strategy::StrategySpec;
+
+    fn small_config() -> SimConfig {
+        SimConfig {
+            seed: 42,
+            battles_per_matchup: 200,
+            tiers: vec![PowerTier { name: "fresh".into(), xp: 0, abilities: [0, 0, 0] }],
+            strategies: vec![StrategySpec::Basic, StrategySpec::Mixed { special_bps: 5_000 }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn grid_covers_every_pairing() {
+        let cfg = small_config();
+        // 3x3 classes, 1x1 tiers, 2x2 strategies
+        assert_eq!(matchups(&cfg).len(), 9 * 4);
+    }
+
+    #[test]
+    fn same_seed_same_results() {
+        let cfg = small_config();
+        assert_eq!(run(&cfg), run(&cfg));
+
+        let other = SimConfig { seed: 43, ..small_config() };
+        assert_ne!(run(&cfg), run(&other));
+    }
+
+    #[test]
+    fn basic_moves_follow_class_advantage() {
+        let cfg = small_config();
+        let stats = run(&cfg);
+        // Builder's ShipIt (125) always out-damages the Shitposter's MemeBomb (80)
+        let cell = stats
+            .iter()
+            .find(|s| {
+                s.matchup.challenger_class == FighterClass::Shitposter
+                    && s.matchup.opponent_class == FighterClass::Builder
+                    && s.matchup.challenger_strategy == 0
+                    && s.matchup.opponent_strategy == 0
+            })
+            .unwrap();
+        assert_eq!(cell.challenger_wins, 0);
+        assert_eq!(cell.total_rounds, cfg.battles_per_matchup);
+        assert_eq!(cell.moves[MoveChoice::MemeBomb as usize].damage, 80 * cfg.battles_per_matchup);
+    }
+
+    #[test]
+    fn extra_rounds_run_until_knockout() {
+        let cfg = SimConfig { max_rounds: 10, ..small_config() };
+        let stats = run(&cfg);
+        let cell = stats
+            .iter()
+            .find(|s| s.matchup.is_mirror() && s.matchup.challenger_strategy == 0)
+            .unwrap();
+        // 100 damage per basic hit in a mirror: both drop to 0 on round 2
+        assert_eq!(cell.total_rounds, 2 * cfg.battles_per_matchup);
+    }
+}
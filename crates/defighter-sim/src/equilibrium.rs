@@ -0,0 +1,319 @@
+//! Zero-sum solver for the per-matchup Basic/Special move game.
+//!
+//! Both players pick one of their class's two moves simultaneously, so every
+//! matchup is a 2x2 game with the challenger as the row player. The solver
+//! finds the mixed strategy each side should play and flags moves that are
+//! never better than the alternative.
+
+use defighter::logic::{calculate_expected_damage, resolve_round, BattleSide, RoundInput};
+use defighter::state::config::BalanceConfig;
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use serde::Serialize;
+
+use crate::config::SimConfig;
+use crate::engine::fighter;
+
+/// Move rolls are `vrf % 100`, so enumerating 0..100 covers every outcome.
+const ROLLS: u64 = 100;
+
+/// What the row player is trying to maximise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Payoff {
+    /// Probability the challenger wins a fresh exchange, enumerating every roll
+    /// through `resolve_round`
+    WinProbability,
+    /// `calculate_expected_damage` dealt minus taken. Each side's term only
+    /// depends on its own move, so this game always has a pure solution.
+    DamageMargin,
+}
+
+/// Rows are the challenger's `[basic, special]`, columns the opponent's.
+pub type Matrix = [[f64; 2]; 2];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Solution {
+    /// Probability the challenger should play its special move
+    pub challenger_special: f64,
+    /// Probability the opponent should play its special move
+    pub opponent_special: f64,
+    /// Expected payoff to the challenger under equilibrium play
+    pub value: f64,
+    /// Challenger move index (0 basic, 1 special) never better than the other
+    pub challenger_dominated: Option<usize>,
+    pub opponent_dominated: Option<usize>,
+}
+
+pub fn payoff_matrix(challenger: &Player, opponent: &Player, cfg: &BalanceConfig, payoff: Payoff) -> Matrix {
+    let c_moves = challenger.class.moves();
+    let o_moves = opponent.class.moves();
+    let mut m = [[0.0; 2]; 2];
+    for (i, &c_move) in c_moves.iter().enumerate() {
+        for (j, &o_move) in o_moves.iter().enumerate() {
+            m[i][j] = match payoff {
+                Payoff::WinProbability => win_probability(challenger, c_move, opponent, o_move, cfg),
+                Payoff::DamageMargin => {
+                    calculate_expected_damage(c_move, challenger, opponent, cfg)
+                        - calculate_expected_damage(o_move, opponent, challenger, cfg)
+                }
+            };
+        }
+    }
+    m
+}
+
+fn win_probability(
+    challenger: &Player,
+    c_move: MoveChoice,
+    opponent: &Player,
+    o_move: MoveChoice,
+    cfg: &BalanceConfig,
+) -> f64 {
+    // Basic moves ignore the roll, except for its parity in tie-breaks
+    let c_rolls = if c_move.is_special_move() || cfg.tie_break_rand { ROLLS } else { 1 };
+    let o_rolls = if o_move.is_special_move() { ROLLS } else { 1 };
+    let mut wins = 0u64;
+    for c_roll in 0..c_rolls {
+        for o_roll in 0..o_rolls {
+            let round = resolve_round(
+                RoundInput { player: challenger, move_choice: c_move, hp: 200, vrf_result: c_roll, boost: None },
+                RoundInput { player: opponent, move_choice: o_move, hp: 200, vrf_result: o_roll, boost: None },
+                cfg,
+            );
+            if round.winner == BattleSide::Challenger {
+                wins += 1;
+            }
+        }
+    }
+    wins as f64 / (c_rolls * o_rolls) as f64
+}
+
+/// Solve a 2x2 zero-sum game where the row player maximises.
+pub fn solve(m: Matrix) -> Solution {
+    let challenger_dominated = dominated(m[0], m[1], |a, b| a < b);
+    let opponent_dominated = dominated([m[0][0], m[1][0]], [m[0][1], m[1][1]], |a, b| a > b);
+
+    let row_floor = [m[0][0].min(m[0][1]), m[1][0].min(m[1][1])];
+    let col_ceiling = [m[0][0].max(m[1][0]), m[0][1].max(m[1][1])];
+    let maximin = row_floor[0].max(row_floor[1]);
+    let minimax = col_ceiling[0].min(col_ceiling[1]);
+
+    if (maximin - minimax).abs() < f64::EPSILON {
+        // Saddle point: both sides have a pure best move
+        let row = if row_floor[1] > row_floor[0] { 1 } else { 0 };
+        let col = if col_ceiling[1] < col_ceiling[0] { 1 } else { 0 };
+        return Solution {
+            challenger_special: row as f64,
+            opponent_special: col as f64,
+            value: maximin,
+            challenger_dominated,
+            opponent_dominated,
+        };
+    }
+
+    let denom = m[0][0] - m[0][1] - m[1][0] + m[1][1];
+    let challenger_basic = (m[1][1] - m[1][0]) / denom;
+    let opponent_basic = (m[1][1] - m[0][1]) / denom;
+    Solution {
+        challenger_special: 1.0 - challenger_basic,
+        opponent_special: 1.0 - opponent_basic,
+        value: (m[0][0] * m[1][1] - m[0][1] * m[1][0]) / denom,
+        challenger_dominated,
+        opponent_dominated,
+    }
+}
+
+/// Index of the option that is never better and sometimes worse than the other.
+fn dominated(a: [f64; 2], b: [f64; 2], worse: impl Fn(f64, f64) -> bool) -> Option<usize> {
+    let no_better = |x: [f64; 2], y: [f64; 2]| (0..2).all(|k| x[k] == y[k] || worse(x[k], y[k]));
+    let sometimes_worse = |x: [f64; 2], y: [f64; 2]| (0..2).any(|k| worse(x[k], y[k]));
+    if no_better(a, b) && sometimes_worse(a, b) {
+        Some(0)
+    } else if no_better(b, a) && sometimes_worse(b, a) {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SolverReport {
+    pub payoff: Payoff,
+    pub matchups: Vec<SolvedMatchup>,
+    /// Moves dominated in every matchup they appear in
+    pub never_worth_picking: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SolvedMatchup {
+    pub challenger_class: String,
+    pub opponent_class: String,
+    pub challenger_tier: String,
+    pub opponent_tier: String,
+    pub challenger_special: f64,
+    pub opponent_special: f64,
+    pub value: f64,
+    /// Dominated moves as `side:Move`, `;`-separated
+    pub dominated: String,
+}
+
+/// Solve every class pairing at every tier pairing in `cfg`.
+pub fn solve_all(cfg: &SimConfig, payoff: Payoff) -> SolverReport {
+    let balance = BalanceConfig::from(&cfg.balance);
+    let mut matchups = Vec::new();
+    // Per move: (appearances, times dominated)
+    let mut dominance = [(0u32, 0u32); 6];
+
+    for challenger_class in FighterClass::ALL {
+        for opponent_class in FighterClass::ALL {
+            for challenger_tier in &cfg.tiers {
+                for opponent_tier in &cfg.tiers {
+                    let challenger = fighter(challenger_class, challenger_tier);
+                    let opponent = fighter(opponent_class, opponent_tier);
+                    let s = solve(payoff_matrix(&challenger, &opponent, &balance, payoff));
+
+                    let mut dominated = Vec::new();
+                    for (side, moves, flagged) in [
+                        ("challenger", challenger_class.moves(), s.challenger_dominated),
+                        ("opponent", opponent_class.moves(), s.opponent_dominated),
+                    ] {
+                        for (i, mv) in moves.iter().enumerate() {
+                            dominance[*mv as usize].0 += 1;
+                            if flagged == Some(i) {
+                                dominance[*mv as usize].1 += 1;
+                                dominated.push(format!("{side}:{mv:?}"));
+                            }
+                        }
+                    }
+
+                    matchups.push(SolvedMatchup {
+                        challenger_class: format!("{challenger_class:?}"),
+                        opponent_class: format!("{opponent_class:?}"),
+                        challenger_tier: challenger_tier.name.clone(),
+                        opponent_tier: opponent_tier.name.clone(),
+                        challenger_special: s.challenger_special,
+                        opponent_special: s.opponent_special,
+                        value: s.value,
+                        dominated: dominated.join(";"),
+                    });
+                }
+            }
+        }
+    }
+
+    let never_worth_picking = FighterClass::ALL
+        .into_iter()
+        .flat_map(|class| class.moves())
+        .filter(|mv| {
+            let (seen, dominated) = dominance[*mv as usize];
+            seen > 0 && seen == dominated
+        })
+        .map(|mv| format!("{mv:?}"))
+        .collect();
+
+    SolverReport { payoff, matchups, never_worth_picking }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use defighter::state::config::BalanceConfigParams;
+    use defighter::state::player::FighterClass;
+    use crate::strategy::{Strategy, StrategySpec};
+
+    fn demo_config() -> BalanceConfig {
+        BalanceConfig::with_params(&BalanceConfigParams::default())
+    }
+
+    fn player(class: FighterClass) -> Player {
+        Player::new(Pubkey::default(), class, [0; 3])
+    }
+
+    #[test]
+    fn matching_pennies_is_fully_mixed() {
+        let s = solve([[1.0, 0.0], [0.0, 1.0]]);
+        assert!((s.challenger_special - 0.5).abs() < 1e-12);
+        assert!((s.opponent_special - 0.5).abs() < 1e-12);
+        assert!((s.value - 0.5).abs() < 1e-12);
+        assert_eq!(s.challenger_dominated, None);
+        assert_eq!(s.opponent_dominated, None);
+    }
+
+    #[test]
+    fn saddle_point_is_pure() {
+        // Row special dominates; column basic is the better reply to it
+        let s = solve([[0.2, 0.4], [0.6, 0.7]]);
+        assert_eq!(s.challenger_special, 1.0);
+        assert_eq!(s.opponent_special, 0.0);
+        assert_eq!(s.value, 0.6);
+        assert_eq!(s.challenger_dominated, Some(0));
+        assert_eq!(s.opponent_dominated, Some(1));
+    }
+
+    #[test]
+    fn mixed_solution_is_indifferent() {
+        let m = [[0.3, 0.8], [0.7, 0.4]];
+        let s = solve(m);
+        let p = s.challenger_special;
+        let against_basic = (1.0 - p) * m[0][0] + p * m[1][0];
+        let against_special = (1.0 - p) * m[0][1] + p * m[1][1];
+        assert!((against_basic - against_special).abs() < 1e-12);
+        assert!((against_basic - s.value).abs() < 1e-12);
+    }
+
+    #[test]
+    fn mirror_basics_split_evenly_with_random_tie_break() {
+        let cfg = BalanceConfig { tie_break_rand: true, ..demo_config() };
+        let vc = player(FighterClass::VC);
+        let m = payoff_matrix(&vc, &vc, &cfg, Payoff::WinProbability);
+        assert_eq!(m[0][0], 0.5);
+
+        let cfg = BalanceConfig { tie_break_rand: false, ..demo_config() };
+        let m = payoff_matrix(&vc, &vc, &cfg, Payoff::WinProbability);
+        assert_eq!(m[0][0], 1.0);
+    }
+
+    #[test]
+    fn solves_every_pairing() {
+        let cfg = SimConfig::default();
+        let report = solve_all(&cfg, Payoff::WinProbability);
+        assert_eq!(report.matchups.len(), 9 * cfg.tiers.len() * cfg.tiers.len());
+        for m in &report.matchups {
+            assert!((0.0..=1.0).contains(&m.value));
+            assert!((0.0..=1.0).contains(&m.challenger_special));
+            assert!((0.0..=1.0).contains(&m.opponent_special));
+        }
+    }
+
+    #[test]
+    fn damage_margin_is_pure() {
+        let cfg = demo_config();
+        for c in FighterClass::ALL {
+            for o in FighterClass::ALL {
+                let s = solve(payoff_matrix(&player(c), &player(o), &cfg, Payoff::DamageMargin));
+                assert!(s.challenger_special == 0.0 || s.challenger_special == 1.0);
+                assert!(s.opponent_special == 0.0 || s.opponent_special == 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn equilibrium_strategy_plays_its_own_side() {
+        let cfg = demo_config();
+        let bps = |rate: f64| (rate * 10_000.0).round() as u16;
+        for c in FighterClass::ALL {
+            for o in FighterClass::ALL {
+                let (challenger, opponent) = (player(c), player(o));
+                let s = solve(payoff_matrix(&challenger, &opponent, &cfg, Payoff::WinProbability));
+                let spec = StrategySpec::Equilibrium;
+                let mix = |strategy| match strategy {
+                    Strategy::Mixed { special_bps, .. } => special_bps,
+                    Strategy::Fixed(_) => unreachable!(),
+                };
+                assert_eq!(mix(spec.build(&challenger, &opponent, BattleSide::Challenger, &cfg)), bps(s.challenger_special));
+                assert_eq!(mix(spec.build(&opponent, &challenger, BattleSide::Opponent, &cfg)), bps(s.opponent_special));
+            }
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Offline balance tooling for DeFighter.
+//!
+//! The simulator drives the program's own `logic` module, so numbers here are
+//! the numbers `resolve_battle` would produce for the same moves and rolls.
+
+pub mod config;
+pub mod engine;
+pub mod equilibrium;
+pub mod report;
+pub mod strategy;
@@ -0,0 +1,74 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use defighter_sim::config::SimConfig;
+use defighter_sim::{engine, report};
+
+/// Monte Carlo balance simulator: every class pairing, power tier and strategy
+/// pairing, resolved with the on-chain damage code.
+#[derive(Parser)]
+#[command(name = "defighter-sim", version)]
+struct Args {
+    /// Simulation config (.toml or .json); built-in defaults when omitted
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+    /// Override the config's seed
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Override the config's battles per matchup
+    #[arg(long)]
+    battles: Option<u64>,
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+    /// Table to print with `--format csv`
+    #[arg(long, value_enum, default_value_t = report::Table::Matchups)]
+    table: report::Table,
+    /// Write the report here instead of stdout
+    #[arg(short, long)]
+    out: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Csv,
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("defighter-sim: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let mut cfg = match &args.config {
+        Some(path) => SimConfig::load(path)?,
+        None => SimConfig::default(),
+    };
+    if let Some(seed) = args.seed {
+        cfg.seed = seed;
+    }
+    if let Some(battles) = args.battles {
+        cfg.battles_per_matchup = battles;
+    }
+    cfg.validate()?;
+
+    let stats = engine::run(&cfg);
+    let report = report::build(&cfg, &stats);
+
+    let out: Box<dyn Write> = match &args.out {
+        Some(path) => Box::new(File::create(path).map_err(|e| format!("creating {}: {e}", path.display()))?),
+        None => Box::new(io::stdout().lock()),
+    };
+    match args.format {
+        Format::Json => report::write_json(&report, out),
+        Format::Csv => report::write_csv(&report, args.table, out),
+    }
+}
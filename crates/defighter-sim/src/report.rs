@@ -0,0 +1,144 @@
+use std::io::Write;
+
+use defighter::state::player::{FighterClass, MoveChoice};
+use serde::Serialize;
+
+use crate::config::SimConfig;
+use crate::engine::{MatchupStats, MoveTally};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub seed: u64,
+    pub summary: Summary,
+    pub matchups: Vec<MatchupRow>,
+    pub moves: Vec<MoveRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub battles: u64,
+    pub challenger_win_rate: f64,
+    pub avg_rounds: f64,
+    /// Challenger win rate in mirror matchups; 0.5 means no first-mover bias
+    pub mirror_challenger_win_rate: f64,
+    pub first_mover_bias: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchupRow {
+    pub challenger_class: String,
+    pub opponent_class: String,
+    pub challenger_tier: String,
+    pub opponent_tier: String,
+    pub challenger_strategy: String,
+    pub opponent_strategy: String,
+    pub battles: u64,
+    pub challenger_win_rate: f64,
+    pub avg_rounds: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MoveRow {
+    #[serde(rename = "move")]
+    pub move_choice: String,
+    pub class: String,
+    pub picks: u64,
+    pub avg_damage: f64,
+    /// Share of picks made by the eventual battle winner
+    pub win_rate: f64,
+}
+
+/// Which table `--format csv` prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Table {
+    Matchups,
+    Moves,
+    Summary,
+}
+
+pub fn build(cfg: &SimConfig, stats: &[MatchupStats]) -> Report {
+    let mut battles = 0;
+    let mut challenger_wins = 0;
+    let mut rounds = 0;
+    let mut mirror_battles = 0;
+    let mut mirror_wins = 0;
+    let mut moves = [MoveTally::default(); 6];
+
+    let matchups = stats
+        .iter()
+        .map(|s| {
+            battles += s.battles;
+            challenger_wins += s.challenger_wins;
+            rounds += s.total_rounds;
+            if s.matchup.is_mirror() {
+                mirror_battles += s.battles;
+                mirror_wins += s.challenger_wins;
+            }
+            for (total, tally) in moves.iter_mut().zip(s.moves.iter()) {
+                total.picks += tally.picks;
+                total.damage += tally.damage;
+                total.winning_picks += tally.winning_picks;
+            }
+            let m = &s.matchup;
+            MatchupRow {
+                challenger_class: format!("{:?}", m.challenger_class),
+                opponent_class: format!("{:?}", m.opponent_class),
+                challenger_tier: cfg.tiers[m.challenger_tier].name.clone(),
+                opponent_tier: cfg.tiers[m.opponent_tier].name.clone(),
+                challenger_strategy: cfg.strategies[m.challenger_strategy].name(),
+                opponent_strategy: cfg.strategies[m.opponent_strategy].name(),
+                battles: s.battles,
+                challenger_win_rate: ratio(s.challenger_wins, s.battles),
+                avg_rounds: ratio(s.total_rounds, s.battles),
+            }
+        })
+        .collect();
+
+    let moves = FighterClass::ALL
+        .into_iter()
+        .flat_map(|class| class.moves())
+        .map(|mv: MoveChoice| {
+            let tally = moves[mv as usize];
+            MoveRow {
+                move_choice: format!("{mv:?}"),
+                class: format!("{:?}", mv.class()),
+                picks: tally.picks,
+                avg_damage: ratio(tally.damage, tally.picks),
+                win_rate: ratio(tally.winning_picks, tally.picks),
+            }
+        })
+        .collect();
+
+    let mirror_challenger_win_rate = ratio(mirror_wins, mirror_battles);
+    Report {
+        seed: cfg.seed,
+        summary: Summary {
+            battles,
+            challenger_win_rate: ratio(challenger_wins, battles),
+            avg_rounds: ratio(rounds, battles),
+            mirror_challenger_win_rate,
+            first_mover_bias: if mirror_battles == 0 { 0.0 } else { mirror_challenger_win_rate - 0.5 },
+        },
+        matchups,
+        moves,
+    }
+}
+
+pub fn write_json<W: Write>(report: &Report, out: W) -> Result<(), String> {
+    serde_json::to_writer_pretty(out, report).map_err(|e| e.to_string())
+}
+
+pub fn write_csv<W: Write>(report: &Report, table: Table, out: W) -> Result<(), String> {
+    let mut w = csv::Writer::from_writer(out);
+    let res = match table {
+        Table::Matchups => report.matchups.iter().try_for_each(|r| w.serialize(r)),
+        Table::Moves => report.moves.iter().try_for_each(|r| w.serialize(r)),
+        Table::Summary => w.serialize(&report.summary),
+    };
+    res.map_err(|e| e.to_string())?;
+    w.flush().map_err(|e| e.to_string())
+}
+
+fn ratio(num: u64, den: u64) -> f64 {
+    if den == 0 { 0.0 } else { num as f64 / den as f64 }
+}
@@ -0,0 +1,87 @@
+use defighter::logic::{calculate_expected_damage, BattleSide};
+use defighter::state::config::BalanceConfig;
+use defighter::state::player::{MoveChoice, Player};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::equilibrium::{payoff_matrix, solve, Payoff};
+
+/// How a fighter picks its move, as written in a config file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StrategySpec {
+    /// Always the class's basic move
+    Basic,
+    /// Always the class's special move
+    Special,
+    /// Special move with probability `special_bps / 10000`
+    Mixed { special_bps: u16 },
+    /// Whichever move has the higher expected damage against the defender
+    Greedy,
+    /// The Nash-equilibrium mix for this matchup (see `equilibrium`)
+    Equilibrium,
+}
+
+impl StrategySpec {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            StrategySpec::Mixed { special_bps } if *special_bps > 10_000 => {
+                Err(format!("mixed strategy special_bps {special_bps} exceeds 10000"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            StrategySpec::Basic => "basic".into(),
+            StrategySpec::Special => "special".into(),
+            StrategySpec::Mixed { special_bps } => format!("mixed_{special_bps}"),
+            StrategySpec::Greedy => "greedy".into(),
+            StrategySpec::Equilibrium => "equilibrium".into(),
+        }
+    }
+
+    /// Resolve the spec against a concrete matchup so picking a move is cheap.
+    /// `side` is the attacker's; it matters only for `Equilibrium`, where the
+    /// challenger plays the row player's mix and the opponent the column's.
+    pub fn build(&self, attacker: &Player, defender: &Player, side: BattleSide, cfg: &BalanceConfig) -> Strategy {
+        let [basic, special] = attacker.class.moves();
+        match self {
+            StrategySpec::Basic => Strategy::Fixed(basic),
+            StrategySpec::Special => Strategy::Fixed(special),
+            StrategySpec::Mixed { special_bps } => Strategy::Mixed { basic, special, special_bps: *special_bps },
+            StrategySpec::Greedy => {
+                let basic_dmg = calculate_expected_damage(basic, attacker, defender, cfg);
+                let special_dmg = calculate_expected_damage(special, attacker, defender, cfg);
+                Strategy::Fixed(if special_dmg > basic_dmg { special } else { basic })
+            }
+            StrategySpec::Equilibrium => {
+                let special_rate = match side {
+                    BattleSide::Challenger => solve(payoff_matrix(attacker, defender, cfg, Payoff::WinProbability)).challenger_special,
+                    BattleSide::Opponent => solve(payoff_matrix(defender, attacker, cfg, Payoff::WinProbability)).opponent_special,
+                };
+                let special_bps = (special_rate * 10_000.0).round() as u16;
+                Strategy::Mixed { basic, special, special_bps }
+            }
+        }
+    }
+}
+
+/// A strategy bound to one attacker/defender pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Fixed(MoveChoice),
+    Mixed { basic: MoveChoice, special: MoveChoice, special_bps: u16 },
+}
+
+impl Strategy {
+    pub fn pick<R: Rng>(&self, rng: &mut R) -> MoveChoice {
+        match *self {
+            Strategy::Fixed(move_choice) => move_choice,
+            Strategy::Mixed { basic, special, special_bps } => {
+                if rng.gen_range(0..10_000) < special_bps { special } else { basic }
+            }
+        }
+    }
+}
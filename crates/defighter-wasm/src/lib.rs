@@ -0,0 +1,221 @@
+//! Browser bindings for the DeFighter battle engine.
+//!
+//! Every function here is a thin wrapper over the on-chain `logic` module, so
+//! the numbers the web app previews are the numbers `resolve_battle` produces.
+//! Classes and moves cross the boundary as their `u8` discriminants and
+//! pubkeys and salts as 32-byte arrays.
+
+use anchor_lang::prelude::{AccountDeserialize, AnchorDeserialize, Pubkey};
+use defighter::logic;
+use defighter::state::config::{BalanceConfig, BalanceConfigParams};
+use defighter::state::item::GearStats;
+use defighter::state::player::{FighterClass, MoveChoice, Player};
+use defighter::logic::MoveResult;
+use wasm_bindgen::prelude::*;
+
+/// Result of a single attack, mirroring `logic::BattleOutcome`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DamagePreview {
+    pub damage_dealt: u16,
+    pub remaining_hp: u16,
+    /// 0 = BasicHit, 1 = SpecialMiss, 2 = SpecialHit, 3 = SpecialCritical
+    pub move_result: u8,
+}
+
+/// The balance rules a battle runs under. `new()` is the demo `init_config`
+/// balance; `fromParams`/`fromAccount` take the live config so previews track
+/// whatever the admin has set.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct CombatConfig {
+    cfg: BalanceConfig,
+}
+
+#[wasm_bindgen]
+impl CombatConfig {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> CombatConfig {
+        CombatConfig { cfg: BalanceConfig::with_params(&BalanceConfigParams::default()) }
+    }
+
+    /// From the Borsh encoding of `BalanceConfigParams`, as `init_config` takes
+    /// it or as a battle's `rules` snapshot stores it.
+    #[wasm_bindgen(js_name = fromParams)]
+    pub fn from_params(params: &[u8]) -> Result<CombatConfig, InputError> {
+        let params = BalanceConfigParams::try_from_slice(params)
+            .map_err(|_| InputError("malformed balance config params"))?;
+        Self::checked(params)
+    }
+
+    /// From the raw data of the `config` account.
+    #[wasm_bindgen(js_name = fromAccount)]
+    pub fn from_account(data: &[u8]) -> Result<CombatConfig, InputError> {
+        let config = BalanceConfig::try_deserialize(&mut &data[..])
+            .map_err(|_| InputError("not a balance config account"))?;
+        Self::checked(config.params())
+    }
+
+    fn checked(params: BalanceConfigParams) -> Result<CombatConfig, InputError> {
+        params.validate().map_err(|_| InputError("balance config fails validation"))?;
+        Ok(CombatConfig { cfg: BalanceConfig::with_params(&params) })
+    }
+}
+
+impl Default for CombatConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One side of a battle: everything on `Player` the damage math reads.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Fighter {
+    player: Player,
+}
+
+#[wasm_bindgen]
+impl Fighter {
+    /// Gear bps are the equipped totals; the engine applies the same cap as
+    /// `equip_item`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        class: u8,
+        xp: u64,
+        abilities: &[u16],
+        prestige: u8,
+        gear_attack_bps: u16,
+        gear_defense_bps: u16,
+    ) -> Result<Fighter, InputError> {
+        let abilities: [u16; 3] = abilities
+            .try_into()
+            .map_err(|_| InputError("abilities must have exactly 3 levels"))?;
+        let player = Player {
+            xp,
+            prestige,
+            gear: GearStats { attack_bps: gear_attack_bps, defense_bps: gear_defense_bps },
+            ..Player::new(Pubkey::default(), parse_class(class)?, abilities)
+        };
+        Ok(Fighter { player })
+    }
+}
+
+/// Why an argument was rejected; thrown to JS as an `Error` with this message.
+/// Kept apart from `JsError`, which can only be built on wasm32, so the
+/// checks can be tested natively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputError(pub &'static str);
+
+impl From<InputError> for JsValue {
+    fn from(error: InputError) -> Self {
+        JsError::new(error.0).into()
+    }
+}
+
+/// Damage `move_choice` deals for a given entropy roll (see `moveEntropy`),
+/// without boosts.
+#[wasm_bindgen(js_name = predictDamage)]
+pub fn predict_damage(
+    move_choice: u8,
+    attacker: &Fighter,
+    defender: &Fighter,
+    defender_hp: u16,
+    vrf_result: u64,
+    config: &CombatConfig,
+) -> Result<DamagePreview, InputError> {
+    let outcome = logic::calculate_battle_outcome(
+        parse_move(move_choice)?,
+        &attacker.player,
+        &defender.player,
+        defender_hp,
+        vrf_result,
+        logic::AttackBoosts::default(),
+        &config.cfg,
+    );
+    Ok(DamagePreview {
+        damage_dealt: outcome.damage_dealt,
+        remaining_hp: outcome.remaining_hp,
+        move_result: move_result_byte(&outcome.move_result),
+    })
+}
+
+/// Probability-weighted damage of `move_choice`, as used by the strategy helpers.
+#[wasm_bindgen(js_name = expectedDamage)]
+pub fn expected_damage(
+    move_choice: u8,
+    attacker: &Fighter,
+    defender: &Fighter,
+    config: &CombatConfig,
+) -> Result<f64, InputError> {
+    Ok(logic::calculate_expected_damage(
+        parse_move(move_choice)?,
+        &attacker.player,
+        &defender.player,
+        &config.cfg,
+    ))
+}
+
+/// Entropy `resolve_battle` feeds into the attacker's move roll, from both
+/// moves and both revealed salts. Swap the arguments for the defender's roll.
+#[wasm_bindgen(js_name = moveEntropy)]
+pub fn move_entropy(
+    attacker_move: u8,
+    defender_move: u8,
+    attacker_salt: &[u8],
+    defender_salt: &[u8],
+    battle: &[u8],
+) -> Result<u64, InputError> {
+    Ok(logic::tie_break_entropy(
+        parse_move(attacker_move)?.to_byte(),
+        parse_move(defender_move)?.to_byte(),
+        &parse_salt(attacker_salt)?,
+        &parse_salt(defender_salt)?,
+        &parse_pubkey(battle)?,
+    ))
+}
+
+/// XP cost of raising an ability to `level_next` under the given curve;
+/// `upgrade_p_bps` is clamped to the 10000..=30000 the program accepts.
+#[wasm_bindgen(js_name = upgradeCost)]
+pub fn upgrade_cost(level_next: u16, upgrade_c0: u64, upgrade_p_bps: u16) -> u64 {
+    let cfg = BalanceConfig { upgrade_c0, upgrade_p_bps, ..Default::default() };
+    logic::upgrade_cost(level_next, &cfg)
+}
+
+/// Commitment `commit_move` expects for a move/salt pair.
+#[wasm_bindgen(js_name = commitmentHash)]
+pub fn commitment_hash(move_choice: u8, salt: &[u8], player: &[u8], battle: &[u8]) -> Result<Vec<u8>, InputError> {
+    let hash = logic::commitment_hash(
+        parse_move(move_choice)?,
+        &parse_salt(salt)?,
+        &parse_pubkey(player)?,
+        &parse_pubkey(battle)?,
+    );
+    Ok(hash.to_vec())
+}
+
+fn parse_class(byte: u8) -> Result<FighterClass, InputError> {
+    FighterClass::from_byte(byte).ok_or(InputError("unknown fighter class"))
+}
+
+fn parse_move(byte: u8) -> Result<MoveChoice, InputError> {
+    MoveChoice::from_byte(byte).ok_or(InputError("unknown move"))
+}
+
+fn parse_salt(bytes: &[u8]) -> Result<[u8; 32], InputError> {
+    bytes.try_into().map_err(|_| InputError("salt must be 32 bytes"))
+}
+
+fn parse_pubkey(bytes: &[u8]) -> Result<Pubkey, InputError> {
+    Pubkey::try_from(bytes).map_err(|_| InputError("pubkey must be 32 bytes"))
+}
+
+fn move_result_byte(result: &MoveResult) -> u8 {
+    match result {
+        MoveResult::BasicHit => 0,
+        MoveResult::SpecialMiss => 1,
+        MoveResult::SpecialHit => 2,
+        MoveResult::SpecialCritical => 3,
+    }
+}
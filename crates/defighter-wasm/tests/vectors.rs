@@ -0,0 +1,201 @@
+//! Reference vectors shared by the native and wasm32 test runs. The same file
+//! runs under `cargo test` and `wasm-pack test --node`, so a drift between the
+//! browser build and the program shows up in either.
+
+use defighter_wasm::*;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test as test;
+
+const BATTLE: [u8; 32] = [7u8; 32];
+const PLAYER: [u8; 32] = [9u8; 32];
+
+struct DamageVector {
+    move_choice: u8,
+    attacker_class: u8,
+    attacker_xp: u64,
+    abilities: [u16; 3],
+    defender_class: u8,
+    defender_abilities: [u16; 3],
+    defender_hp: u16,
+    vrf: u64,
+    expected: DamagePreview,
+}
+
+const fn preview(damage_dealt: u16, remaining_hp: u16, move_result: u8) -> DamagePreview {
+    DamagePreview { damage_dealt, remaining_hp, move_result }
+}
+
+const DAMAGE_VECTORS: &[DamageVector] = &[
+    // MemeBomb into a Builder: 100 * 0.80
+    DamageVector { move_choice: 0, attacker_class: 0, attacker_xp: 0, abilities: [0, 0, 0], defender_class: 1, defender_abilities: [0, 0, 0], defender_hp: 200, vrf: 0, expected: preview(80, 120, 0) },
+    // ShipIt into a Shitposter: 100 * 1.25
+    DamageVector { move_choice: 2, attacker_class: 1, attacker_xp: 0, abilities: [0, 0, 0], defender_class: 0, defender_abilities: [0, 0, 0], defender_hp: 200, vrf: 0, expected: preview(125, 75, 0) },
+    // RugPullRumor miss/hit boundary at roll 30
+    DamageVector { move_choice: 1, attacker_class: 0, attacker_xp: 0, abilities: [0, 0, 0], defender_class: 0, defender_abilities: [0, 0, 0], defender_hp: 200, vrf: 29, expected: preview(0, 200, 1) },
+    DamageVector { move_choice: 1, attacker_class: 0, attacker_xp: 0, abilities: [0, 0, 0], defender_class: 0, defender_abilities: [0, 0, 0], defender_hp: 200, vrf: 30, expected: preview(150, 50, 2) },
+    // RugPullRumor crit into a VC overkills
+    DamageVector { move_choice: 1, attacker_class: 0, attacker_xp: 0, abilities: [0, 0, 0], defender_class: 2, defender_abilities: [0, 0, 0], defender_hp: 200, vrf: 99, expected: preview(275, 0, 3) },
+    // Progressed Builder: power 1.04, disadvantaged, TestnetDeploy hit
+    DamageVector { move_choice: 3, attacker_class: 1, attacker_xp: 2500, abilities: [4, 3, 2], defender_class: 2, defender_abilities: [0, 0, 0], defender_hp: 200, vrf: 55, expected: preview(133, 67, 2) },
+    // ExitLiquidity crit (roll 80) with a large XP tier and +0.1x from CritPower
+    DamageVector { move_choice: 5, attacker_class: 2, attacker_xp: 12000, abilities: [1, 1, 1], defender_class: 1, defender_abilities: [0, 0, 0], defender_hp: 60, vrf: 1080, expected: preview(325, 0, 3) },
+    // Basic move ignores the roll; 10 BasicDamage levels add 50%
+    DamageVector { move_choice: 4, attacker_class: 2, attacker_xp: 999, abilities: [10, 0, 0], defender_class: 0, defender_abilities: [0, 0, 0], defender_hp: 200, vrf: 7, expected: preview(120, 80, 0) },
+    // SpecialAccuracy pulls the miss boundary from 30 down to 25
+    DamageVector { move_choice: 1, attacker_class: 0, attacker_xp: 0, abilities: [0, 5, 0], defender_class: 0, defender_abilities: [0, 0, 0], defender_hp: 200, vrf: 25, expected: preview(150, 50, 2) },
+    // A Builder's Defense levels soak 10% of a ShipIt
+    DamageVector { move_choice: 2, attacker_class: 1, attacker_xp: 0, abilities: [0, 0, 0], defender_class: 1, defender_abilities: [0, 0, 5], defender_hp: 200, vrf: 0, expected: preview(90, 110, 0) },
+];
+
+#[test]
+fn damage_vectors() {
+    for (i, v) in DAMAGE_VECTORS.iter().enumerate() {
+        let attacker = Fighter::new(v.attacker_class, v.attacker_xp, &v.abilities, 0, 0, 0).unwrap();
+        let defender = Fighter::new(v.defender_class, 0, &v.defender_abilities, 0, 0, 0).unwrap();
+        let got = predict_damage(v.move_choice, &attacker, &defender, v.defender_hp, v.vrf, &CombatConfig::new()).unwrap();
+        assert_eq!(got, v.expected, "vector {i}");
+    }
+}
+
+/// (move, attacker class, xp, abilities, defender class, defender abilities, expected damage)
+type ExpectedVector = (u8, u8, u64, [u16; 3], u8, [u16; 3], f64);
+
+#[test]
+fn expected_damage_vectors() {
+    let cases: &[ExpectedVector] = &[
+        (0, 0, 0, [0, 0, 0], 1, [0, 0, 0], 80.0),
+        (1, 0, 0, [0, 0, 0], 0, [0, 0, 0], 119.0),
+        (1, 0, 0, [0, 0, 0], 2, [0, 0, 0], 148.75),
+        (3, 1, 2500, [4, 3, 2], 2, [0, 0, 0], 115.4816),
+        (5, 2, 12000, [1, 1, 1], 1, [0, 0, 0], 162.75),
+    ];
+    for &(m, ac, xp, ab, dc, dab, want) in cases {
+        let attacker = Fighter::new(ac, xp, &ab, 0, 0, 0).unwrap();
+        let defender = Fighter::new(dc, 0, &dab, 0, 0, 0).unwrap();
+        let got = expected_damage(m, &attacker, &defender, &CombatConfig::new()).unwrap();
+        assert!((got - want).abs() < 1e-9, "move {m}: {got} != {want}");
+    }
+}
+
+#[test]
+fn gear_and_prestige_vectors() {
+    let cfg = CombatConfig::new();
+    let builder = Fighter::new(1, 0, &[0, 0, 0], 0, 0, 0).unwrap();
+    // ShipIt into a Shitposter: 125, +4% from gear attack
+    let geared = Fighter::new(1, 0, &[0, 0, 0], 0, 400, 0).unwrap();
+    let shitposter = Fighter::new(0, 0, &[0, 0, 0], 0, 0, 0).unwrap();
+    assert_eq!(predict_damage(2, &geared, &shitposter, 200, 0, &cfg).unwrap(), preview(130, 70, 0));
+    // Gear past the 5% cap counts as 5%
+    let overgeared = Fighter::new(1, 0, &[0, 0, 0], 0, 2000, 0).unwrap();
+    assert_eq!(predict_damage(2, &overgeared, &shitposter, 200, 0, &cfg).unwrap(), preview(131, 69, 0));
+    // -4% from the defender's gear defense
+    let armored = Fighter::new(0, 0, &[0, 0, 0], 0, 0, 400).unwrap();
+    assert_eq!(predict_damage(2, &builder, &armored, 200, 0, &cfg).unwrap(), preview(120, 80, 0));
+    // Each prestige adds 1% to the attacker's power
+    let veteran = Fighter::new(1, 0, &[0, 0, 0], 4, 0, 0).unwrap();
+    assert_eq!(predict_damage(2, &veteran, &shitposter, 200, 0, &cfg).unwrap(), preview(130, 70, 0));
+}
+
+#[test]
+fn config_from_params() {
+    use anchor_lang::AnchorSerialize;
+    use defighter::state::config::BalanceConfigParams;
+
+    let attacker = Fighter::new(1, 0, &[0, 0, 0], 0, 0, 0).unwrap();
+    let defender = Fighter::new(0, 0, &[0, 0, 0], 0, 0, 0).unwrap();
+
+    let demo = BalanceConfigParams::default().try_to_vec().unwrap();
+    let cfg = CombatConfig::from_params(&demo).unwrap();
+    assert_eq!(predict_damage(2, &attacker, &defender, 200, 0, &cfg).unwrap(), preview(125, 75, 0));
+
+    // A live config with a higher base damage and a softer class edge
+    let live = BalanceConfigParams { base_damage: 200, class_win_bps: 11000, ..Default::default() };
+    let cfg = CombatConfig::from_params(&live.try_to_vec().unwrap()).unwrap();
+    assert_eq!(predict_damage(2, &attacker, &defender, 300, 0, &cfg).unwrap(), preview(220, 80, 0));
+
+    let broken = BalanceConfigParams { dim_t: 0, ..Default::default() };
+    assert!(CombatConfig::from_params(&broken.try_to_vec().unwrap()).is_err());
+    assert!(CombatConfig::from_params(&demo[..demo.len() - 1]).is_err());
+}
+
+#[test]
+fn config_from_account() {
+    use anchor_lang::AccountSerialize;
+    use defighter::state::config::{BalanceConfig, BalanceConfigParams};
+
+    let live = BalanceConfigParams { base_damage: 200, ..Default::default() };
+    let mut data = Vec::new();
+    BalanceConfig::with_params(&live).try_serialize(&mut data).unwrap();
+    let cfg = CombatConfig::from_account(&data).unwrap();
+
+    let attacker = Fighter::new(1, 0, &[0, 0, 0], 0, 0, 0).unwrap();
+    let defender = Fighter::new(0, 0, &[0, 0, 0], 0, 0, 0).unwrap();
+    assert_eq!(predict_damage(2, &attacker, &defender, 300, 0, &cfg).unwrap(), preview(250, 50, 0));
+
+    data[0] ^= 1;
+    assert!(CombatConfig::from_account(&data).is_err());
+}
+
+#[test]
+fn move_entropy_vectors() {
+    // Attacker's salt first, as `resolve_battle` passes them
+    let (a, b) = ([1u8; 32], [2u8; 32]);
+    assert_eq!(move_entropy(1, 2, &a, &b, &BATTLE).unwrap(), 8106506535942139585);
+    assert_eq!(move_entropy(2, 1, &b, &a, &BATTLE).unwrap(), 3954769011673620108);
+    // Either salt moves the roll
+    assert_ne!(move_entropy(1, 2, &a, &a, &BATTLE).unwrap(), move_entropy(1, 2, &a, &b, &BATTLE).unwrap());
+    assert_ne!(move_entropy(1, 2, &b, &b, &BATTLE).unwrap(), move_entropy(1, 2, &a, &b, &BATTLE).unwrap());
+}
+
+#[test]
+fn upgrade_cost_vectors() {
+    assert_eq!(upgrade_cost(1, 100, 10000), 100);
+    assert_eq!(upgrade_cost(10, 100, 10000), 1000);
+    // Fractional exponents follow l^p to within 0.1%: 10^1.4 = 25.119
+    assert_eq!(upgrade_cost(10, 100, 14000), 2512);
+    assert_eq!(upgrade_cost(16, 100, 14000), 4850);
+    assert_eq!(upgrade_cost(81, 100, 14000), 46976);
+    assert_eq!(upgrade_cost(10, 100, 20000), 10000);
+}
+
+#[test]
+fn commitment_hash_vector() {
+    let hash = commitment_hash(3, &[1u8; 32], &PLAYER, &BATTLE).unwrap();
+    assert_eq!(
+        hash,
+        [
+            89, 178, 234, 45, 89, 158, 185, 209, 148, 230, 23, 11, 60, 157, 245, 124,
+            81, 36, 191, 120, 173, 113, 46, 21, 198, 208, 115, 59, 204, 126, 104, 94,
+        ]
+    );
+}
+
+#[test]
+fn commitment_matches_program() {
+    use anchor_lang::prelude::Pubkey;
+    use defighter::state::player::MoveChoice;
+
+    let ours = commitment_hash(1, &[42u8; 32], &PLAYER, &BATTLE).unwrap();
+    let program = defighter::logic::commitment_hash(
+        MoveChoice::RugPullRumor,
+        &[42u8; 32],
+        &Pubkey::new_from_array(PLAYER),
+        &Pubkey::new_from_array(BATTLE),
+    );
+    assert_eq!(ours, program.to_vec());
+}
+
+#[test]
+fn rejects_malformed_input() {
+    let cfg = CombatConfig::new();
+    let fighter = Fighter::new(0, 0, &[0, 0, 0], 0, 0, 0).unwrap();
+    assert!(predict_damage(6, &fighter, &fighter, 200, 0, &cfg).is_err());
+    assert!(expected_damage(6, &fighter, &fighter, &cfg).is_err());
+    assert!(Fighter::new(3, 0, &[0, 0, 0], 0, 0, 0).is_err());
+    assert!(Fighter::new(0, 0, &[0, 0], 0, 0, 0).is_err());
+    assert!(commitment_hash(0, &[0u8; 31], &PLAYER, &BATTLE).is_err());
+    assert!(move_entropy(0, 1, &[0u8; 32], &[0u8; 32], &[0u8; 8]).is_err());
+    assert!(move_entropy(0, 1, &[0u8; 31], &[0u8; 32], &BATTLE).is_err());
+    assert!(CombatConfig::from_account(&[0u8; 16]).is_err());
+}
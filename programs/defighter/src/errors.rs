@@ -15,6 +15,64 @@ pub enum CustomError {
     #[msg("Config not found")] NotConfigured,
     #[msg("Ability exceeds max level")] MaxLevel,
     #[msg("Move does not match player class")] InvalidClassMove,
+    #[msg("Invalid boss parameters")] InvalidBossParams,
+    #[msg("Boss already defeated")] BossDefeated,
+    #[msg("Signer is not this session's key")] InvalidSession,
+    #[msg("Session expired")] SessionExpired,
+    #[msg("Session does not cover this instruction")] SessionScope,
+    #[msg("Invalid session duration or scope")] InvalidSessionParams,
+    #[msg("Upgrade cost exponent must be between 1.0 and 3.0")] UnsupportedUpgradeExponent,
+    #[msg("Config value out of range")] InvalidConfig,
+    #[msg("Signer is not the pending admin")] NotPendingAdmin,
+    #[msg("Admin set needs 1..=16 distinct members and 1 <= threshold <= members")] InvalidAdminSet,
+    #[msg("Signer is not a member of the admin set")] NotAdminSetMember,
+    #[msg("Admin set does not control the config")] AdminSetNotInControl,
+    #[msg("Member already approved this proposal")] AlreadyApproved,
+    #[msg("Proposal lacks enough approvals")] NotEnoughApprovals,
+    #[msg("Proposal already executed")] ProposalExecuted,
+    #[msg("A config change is already queued")] ConfigChangePending,
+    #[msg("No config change is queued")] NoPendingConfig,
+    #[msg("Config change timelock has not elapsed")] TimelockNotElapsed,
+    #[msg("This feature is paused")] ProgramPaused,
+    #[msg("Only the admin or guardian may pause, and only the admin may unpause")] PauseUnauthorized,
+    #[msg("Unknown pause flag")] InvalidPauseFlags,
+    #[msg("These players battled too recently")] PairOnCooldown,
+    #[msg("Not enough energy")] NotEnoughEnergy,
+    #[msg("Energy is already full")] EnergyFull,
+    #[msg("Item modifiers exceed its rarity's cap")] InvalidItemParams,
+    #[msg("Item is not in the inventory")] ItemNotOwned,
+    #[msg("Item is restricted to another class")] ItemClassMismatch,
+    #[msg("Inventory is full")] InventoryFull,
+    #[msg("Nothing is equipped in that slot")] SlotEmpty,
+    #[msg("Loot table holds at most 32 items")] InvalidLootTable,
+    #[msg("Cosmetic has not been unlocked")] CosmeticLocked,
+    #[msg("Recipe index is out of range for its kind")] InvalidRecipe,
+    #[msg("Recipe is not active")] RecipeInactive,
+    #[msg("Cosmetic is already unlocked")] AlreadyUnlocked,
+    #[msg("Not enough crafting materials")] NotEnoughMaterials,
+    #[msg("Cosmetic is being worn")] CosmeticWorn,
+    #[msg("No boost of that kind left")] NoBoost,
+    #[msg("Collection cosmetic index is out of range for its kind")] InvalidNftCollection,
+    #[msg("Token account does not hold the NFT")] NftNotHeld,
+    #[msg("Not the Metaplex metadata account for the NFT")] InvalidNftMetadata,
+    #[msg("NFT is not a verified member of an active allowlisted collection")] NftNotInCollection,
+    #[msg("NFT perk does not belong to this customization")] InvalidNftPerk,
+    #[msg("NFT is still held and its collection is active")] NftStillHeld,
+    #[msg("Player is already tokenized")] AlreadyTokenized,
+    #[msg("Character mint must be an initialized 0-decimal mint with a fixed supply of 1")] InvalidCharacterMint,
+    #[msg("Player has unresolved battles")] OpenBattles,
+    #[msg("Character recently changed wallets")] CharacterCooldown,
+    #[msg("The customization must move with its character")] CustomizationNotMoved,
+    #[msg("Tokenized characters move with their token")] TokenizedPlayer,
+    #[msg("Wallet holds the maximum number of characters")] TooManyCharacters,
+    #[msg("Both sides belong to the same wallet")] SameOwner,
+    #[msg("Gear is worn by another character")] GearInUse,
+    #[msg("Every ability must be at max level to prestige")] AbilitiesNotMaxed,
+    #[msg("The slot hash after the commit is not available; reveal from the next slot, before it ages out")] SlotHashUnavailable,
+    #[msg("Deadlines are out of range")] InvalidDeadlines,
+    #[msg("Account already has the current layout")] AlreadyMigrated,
+    #[msg("Proposal is not for this instruction")] WrongProposalAction,
+    #[msg("The wallet has already used its first character slot")] CharacterSlotTaken,
 }
 
 
@@ -1,5 +1,18 @@
 use anchor_lang::prelude::*;
-use crate::state::player::MoveChoice;
+use crate::state::admin::ProposalAction;
+use crate::state::config::BalanceConfigParams;
+use crate::state::consumable::Boost;
+use crate::state::crafting::{CosmeticKind, Material};
+use crate::state::item::{GearSlot, GearStats, Rarity};
+use crate::state::loot::LootEntry;
+use crate::state::player::{FighterClass, MoveChoice};
+
+/// Decode an event from its `sol_log_data` payload (discriminator + borsh),
+/// for off-chain readers of the program's logs.
+pub fn decode_event<T: AnchorDeserialize + Discriminator>(raw: &[u8]) -> Option<T> {
+    let body = raw.strip_prefix(T::DISCRIMINATOR)?;
+    T::deserialize(&mut &body[..]).ok()
+}
 
 #[event]
 pub struct BattleInitiated {
@@ -8,6 +21,8 @@ pub struct BattleInitiated {
     pub opponent: Pubkey,
     pub commit_deadline_slot: u64,
     pub reveal_deadline_slot: u64,
+    pub config_version: u32,
+    pub xp_scale_bps: u16,
 }
 
 #[event]
@@ -21,6 +36,7 @@ pub struct MoveRevealed {
     pub battle: Pubkey,
     pub player: Pubkey,
     pub move_choice: MoveChoice,
+    pub boost: Option<Boost>,
 }
 
 #[event]
@@ -33,6 +49,12 @@ pub struct BattleResolved {
     pub opponent_damage: u16,
 }
 
+#[event]
+pub struct BattleCancelled {
+    pub battle: Pubkey,
+    pub challenger: Pubkey,
+}
+
 #[event]
 pub struct XpAwarded {
     pub player: Pubkey,
@@ -47,4 +69,314 @@ pub struct AbilityUpgraded {
     pub cost: u64,
 }
 
+#[event]
+pub struct AbilitiesRespecced {
+    pub player: Pubkey,
+    /// Levels before the reset
+    pub levels: [u16; 3],
+    pub refund: u64,
+}
+
+#[event]
+pub struct Prestiged {
+    pub player: Pubkey,
+    pub rank: u8,
+    /// Power the character now gets from prestige, after the cap
+    pub power_bps: u32,
+}
+
+#[event]
+pub struct EnergySpent {
+    pub player: Pubkey,
+    pub amount: u16,
+    /// Energy left afterwards
+    pub energy: u16,
+}
+
+#[event]
+pub struct EnergyRefilled {
+    pub player: Pubkey,
+    pub amount: u16,
+    pub xp_cost: u64,
+    pub energy: u16,
+}
+
+#[event]
+pub struct BossCreated {
+    pub boss: Pubkey,
+    pub id: u32,
+    pub class: FighterClass,
+    pub max_hp: u32,
+    pub shared_hp: bool,
+}
+
+#[event]
+pub struct BossBattleInitiated {
+    pub battle: Pubkey,
+    pub boss: Pubkey,
+    pub player: Pubkey,
+    pub commit_deadline_slot: u64,
+    pub reveal_deadline_slot: u64,
+}
+
+#[event]
+pub struct BossBattleResolved {
+    pub battle: Pubkey,
+    pub boss: Pubkey,
+    pub player: Pubkey,
+    pub player_won: bool,
+    pub boss_move: Option<MoveChoice>,
+    pub player_damage: u16,
+    pub boss_damage: u16,
+    pub boss_hp: u32,
+}
+
+#[event]
+pub struct SessionCreated {
+    pub authority: Pubkey,
+    pub session_key: Pubkey,
+    pub expires_slot: u64,
+    pub scope: u8,
+}
+
+#[event]
+pub struct SessionRevoked {
+    pub authority: Pubkey,
+    pub session_key: Pubkey,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    /// Version `new` took effect as
+    pub version: u32,
+    pub old: BalanceConfigParams,
+    pub new: BalanceConfigParams,
+}
+
+#[event]
+pub struct PauseChanged {
+    pub authority: Pubkey,
+    pub old: u8,
+    pub new: u8,
+}
+
+#[event]
+pub struct GuardianChanged {
+    pub admin: Pubkey,
+    pub guardian: Option<Pubkey>,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub admin: Pubkey,
+    pub version: u32,
+    pub params: BalanceConfigParams,
+    pub effective_slot: u64,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub admin: Pubkey,
+    pub version: u32,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminAccepted {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminSetCreated {
+    pub admin_set: Pubkey,
+    pub id: u32,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+}
 
+#[event]
+pub struct ConfigChangeProposed {
+    pub admin_set: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+}
+
+#[event]
+pub struct ConfigChangeApproved {
+    pub proposal: Pubkey,
+    pub member: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ConfigChangeExecuted {
+    pub proposal: Pubkey,
+    pub action: ProposalAction,
+}
+
+#[event]
+pub struct ItemCreated {
+    pub item: Pubkey,
+    pub id: u32,
+    pub slot: GearSlot,
+    pub rarity: Rarity,
+    pub class: Option<FighterClass>,
+    pub stats: GearStats,
+}
+
+#[event]
+pub struct ItemGranted {
+    pub inventory: Pubkey,
+    pub owner: Pubkey,
+    pub item_id: u32,
+}
+
+#[event]
+pub struct ItemEquipped {
+    pub player: Pubkey,
+    pub item_id: u32,
+    pub slot: GearSlot,
+    /// Item id that was in the slot before
+    pub replaced: Option<u32>,
+    /// The player's capped gear totals afterwards
+    pub gear: GearStats,
+}
+
+#[event]
+pub struct ItemUnequipped {
+    pub player: Pubkey,
+    pub item_id: u32,
+    pub slot: GearSlot,
+    pub gear: GearStats,
+}
+
+#[event]
+pub struct LootDropped {
+    pub player: Pubkey,
+    pub battle: Pubkey,
+    pub rarity: Rarity,
+    pub roll: u64,
+    /// The winner's pity count afterwards
+    pub pity: u16,
+    /// False if the bag was full and the drop was lost
+    pub stored: bool,
+}
+
+#[event]
+pub struct LootTableSet {
+    pub entries: Vec<LootEntry>,
+}
+
+#[event]
+pub struct MaterialsEarned {
+    pub player: Pubkey,
+    pub battle: Pubkey,
+    pub material: Material,
+    pub amount: u16,
+    pub balance: u32,
+}
+
+#[event]
+pub struct RecipeSet {
+    pub kind: CosmeticKind,
+    pub index: u8,
+    pub materials: [u32; Material::COUNT],
+    pub xp_cost: u64,
+    pub active: bool,
+}
+
+#[event]
+pub struct CosmeticCrafted {
+    pub player: Pubkey,
+    pub kind: CosmeticKind,
+    pub index: u8,
+    pub materials: [u32; Material::COUNT],
+    pub xp_cost: u64,
+}
+
+#[event]
+pub struct CosmeticSalvaged {
+    pub player: Pubkey,
+    pub kind: CosmeticKind,
+    pub index: u8,
+    pub refund: [u32; Material::COUNT],
+}
+
+#[event]
+pub struct BoostGranted {
+    pub owner: Pubkey,
+    pub boost: Boost,
+    pub amount: u16,
+    pub balance: u16,
+    /// The battle that dropped it; `None` for an admin grant
+    pub battle: Option<Pubkey>,
+}
+
+#[event]
+pub struct BoostUsed {
+    pub battle: Pubkey,
+    pub player: Pubkey,
+    pub boost: Boost,
+    pub remaining: u16,
+}
+
+#[event]
+pub struct NftCollectionSet {
+    pub collection: Pubkey,
+    pub kind: CosmeticKind,
+    pub index: u8,
+    pub active: bool,
+}
+
+#[event]
+pub struct NftVerified {
+    pub player: Pubkey,
+    pub collection: Pubkey,
+    pub mint: Pubkey,
+    pub kind: CosmeticKind,
+    pub index: u8,
+}
+
+#[event]
+pub struct NftPerkRevoked {
+    pub player: Pubkey,
+    pub collection: Pubkey,
+    pub mint: Pubkey,
+    /// Whether the cosmetic had to be taken off
+    pub stripped: bool,
+}
+
+#[event]
+pub struct PlayerTokenized {
+    pub player: Pubkey,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct PlayerTransferred {
+    pub old_player: Pubkey,
+    pub new_player: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    /// The character token, for a tokenized claim
+    pub mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct PlayerClosed {
+    pub player: Pubkey,
+    pub authority: Pubkey,
+    pub elo: i32,
+    /// The wallet's next characters start at this rating
+    pub tombstone_elo: Option<i32>,
+}
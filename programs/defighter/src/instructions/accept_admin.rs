@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+use crate::state::config::BalanceConfig;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    pub new_admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let cfg = &mut ctx.accounts.config;
+    let new_admin = ctx.accounts.new_admin.key();
+    require!(cfg.pending_admin == Some(new_admin), CustomError::NotPendingAdmin);
+
+    let old_admin = cfg.admin;
+    cfg.admin = new_admin;
+    cfg.pending_admin = None;
+    emit!(crate::events::AdminAccepted { old_admin, new_admin });
+    Ok(())
+}
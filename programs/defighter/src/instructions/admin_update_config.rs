@@ -1,19 +1,26 @@
 use anchor_lang::prelude::*;
-use crate::state::config::BalanceConfig;
+use crate::state::config::{BalanceConfig, BalanceConfigParams, PendingConfig};
+use super::update_config::queue_change;
 
 #[derive(Accounts)]
 pub struct AdminUpdateConfig<'info> {
-    #[account(mut, seeds = [b"config"], bump, has_one = admin)]
+    #[account(seeds = [b"config"], bump, has_one = admin)]
     pub config: Account<'info, BalanceConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"pending_config"],
+        bump,
+        space = PendingConfig::SPACE
+    )]
+    pub pending_config: Account<'info, PendingConfig>,
+    #[account(mut)]
     pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
 }
 
-pub fn handler(ctx: Context<AdminUpdateConfig>, new_config: BalanceConfig) -> Result<()> {
-    // Preserve admin; update other fields
-    let admin = ctx.accounts.config.admin;
-    *ctx.accounts.config = new_config;
-    ctx.accounts.config.admin = admin;
-    Ok(())
+/// Queue a replacement of every tunable; `update_config` changes a single one.
+pub fn handler(ctx: Context<AdminUpdateConfig>, params: BalanceConfigParams) -> Result<()> {
+    queue_change(&ctx.accounts.config, &mut ctx.accounts.pending_config, params, ctx.accounts.clock.slot)
 }
-
-
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::state::config::{BalanceConfig, PendingConfig};
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct ApplyConfigChange<'info> {
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(mut, seeds = [b"pending_config"], bump)]
+    pub pending_config: Account<'info, PendingConfig>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Permissionless once the timelock has passed.
+pub fn handler(ctx: Context<ApplyConfigChange>) -> Result<()> {
+    let pending = &mut ctx.accounts.pending_config;
+    require!(pending.active, CustomError::NoPendingConfig);
+    require!(ctx.accounts.clock.slot >= pending.effective_slot, CustomError::TimelockNotElapsed);
+
+    let cfg = &mut ctx.accounts.config;
+    let old = cfg.params();
+    cfg.apply(&pending.params);
+    cfg.version = pending.version;
+    pending.active = false;
+
+    emit!(crate::events::ConfigUpdated { admin: cfg.admin, version: cfg.version, old, new: pending.params });
+    Ok(())
+}
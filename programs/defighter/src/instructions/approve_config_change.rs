@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::state::admin::{AdminSet, ConfigProposal};
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct ApproveConfigChange<'info> {
+    pub admin_set: Account<'info, AdminSet>,
+    #[account(mut, has_one = admin_set)]
+    pub proposal: Account<'info, ConfigProposal>,
+    pub member: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ApproveConfigChange>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    require!(!proposal.executed, CustomError::ProposalExecuted);
+    let member = ctx.accounts.member.key();
+    let bit = 1u16 << ctx.accounts.admin_set.member_index(&member)?;
+    require!(proposal.approvals & bit == 0, CustomError::AlreadyApproved);
+    proposal.approvals |= bit;
+
+    emit!(crate::events::ConfigChangeApproved {
+        proposal: proposal.key(),
+        member,
+        approvals: proposal.approval_count(),
+    });
+    Ok(())
+}
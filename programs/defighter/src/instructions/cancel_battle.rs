@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::state::battle::{Battle, BattleState};
+use crate::state::config::BalanceConfig;
+use crate::state::player::Player;
+use crate::errors::CustomError;
+use super::resolve_battle::refund_energy;
+
+#[derive(Accounts)]
+pub struct CancelBattle<'info> {
+    #[account(mut)]
+    pub battle: Account<'info, Battle>,
+    #[account(mut, address = battle.challenger)]
+    pub player_challenger: Account<'info, Player>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Close out a challenge the opponent let lapse without committing. Only
+/// the challenger's side is touched, so this works even after the opponent's
+/// character has been closed or moved and `resolve_battle` can't load it.
+/// Nobody earns anything; the challenger gets the battle's energy cost back.
+pub fn handler(ctx: Context<CancelBattle>) -> Result<()> {
+    let battle = &mut ctx.accounts.battle;
+    require!(matches!(battle.state, BattleState::WaitingForCommits), CustomError::NotReadyToResolve);
+    require!(battle.commit_opponent.is_none(), CustomError::NotReadyToResolve);
+    require!(ctx.accounts.clock.slot > battle.commit_deadline_slot, CustomError::NotReadyToResolve);
+
+    battle.state = BattleState::Resolved;
+    let challenger = &mut ctx.accounts.player_challenger;
+    challenger.open_battles = challenger.open_battles.saturating_sub(1);
+    let cfg = &BalanceConfig::with_params(&battle.rules);
+    refund_energy(challenger, battle.rules.battle_energy_cost, ctx.accounts.clock.slot, cfg);
+    emit!(crate::events::BattleCancelled { battle: battle.key(), challenger: challenger.key() });
+    Ok(())
+}
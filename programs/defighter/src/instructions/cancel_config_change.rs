@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+use crate::state::config::{BalanceConfig, PendingConfig};
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(seeds = [b"config"], bump, has_one = admin)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(mut, seeds = [b"pending_config"], bump)]
+    pub pending_config: Account<'info, PendingConfig>,
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelConfigChange>) -> Result<()> {
+    cancel_change(&ctx.accounts.config, &mut ctx.accounts.pending_config)
+}
+
+pub(crate) fn cancel_change(cfg: &BalanceConfig, pending: &mut PendingConfig) -> Result<()> {
+    require!(pending.active, CustomError::NoPendingConfig);
+    pending.active = false;
+    emit!(crate::events::ConfigChangeCancelled { admin: cfg.admin, version: pending.version });
+    Ok(())
+}
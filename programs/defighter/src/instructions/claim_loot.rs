@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::state::item::Inventory;
+use crate::state::loot::{LootBag, LootTable};
+use super::grant_item::add_item;
+
+#[derive(Accounts)]
+pub struct ClaimLoot<'info> {
+    #[account(mut, seeds = [b"loot_bag", owner.key().as_ref()], bump, has_one = owner)]
+    pub loot_bag: Account<'info, LootBag>,
+    #[account(seeds = [b"loot_table"], bump)]
+    pub loot_table: Account<'info, LootTable>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [b"inventory", owner.key().as_ref()],
+        bump,
+        space = Inventory::SPACE
+    )]
+    pub inventory: Account<'info, Inventory>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Turn every drop the table can fill into an item. Drops of a rarity the
+/// table has nothing for, or that don't fit the inventory, stay in the bag.
+pub fn handler(ctx: Context<ClaimLoot>) -> Result<()> {
+    let bag = &mut ctx.accounts.loot_bag;
+    let inventory = &mut ctx.accounts.inventory;
+    inventory.owner = ctx.accounts.owner.key();
+
+    let mut kept = Vec::new();
+    for drop in bag.drops.drain(..) {
+        match ctx.accounts.loot_table.pick(&drop) {
+            Some(item_id) if inventory.items.len() < Inventory::MAX_ITEMS => add_item(inventory, item_id)?,
+            _ => kept.push(drop),
+        }
+    }
+    bag.drops = kept;
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use crate::logic::nft::held_nft;
+use crate::state::config::BalanceConfig;
+use crate::state::customization::{move_customization, CharacterCustomizationV1, CHARACTER_CUSTOM_SEED};
+use crate::state::nft::CharacterMint;
+use crate::state::item::release_gear;
+use crate::state::player::Player;
+use crate::state::profile::Profile;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct ClaimPlayer<'info> {
+    #[account(mut, seeds = [b"character_mint", character_mint.mint.as_ref()], bump)]
+    pub character_mint: Account<'info, CharacterMint>,
+    #[account(
+        mut,
+        address = character_mint.player,
+        seeds = [b"player", old_authority.key().as_ref(), &[old_player.index]],
+        bump,
+        close = old_authority
+    )]
+    pub old_player: Account<'info, Player>,
+    #[account(mut, seeds = [b"profile", old_authority.key().as_ref()], bump)]
+    pub old_profile: Account<'info, Profile>,
+    #[account(
+        init_if_needed,
+        payer = new_authority,
+        seeds = [b"profile", new_authority.key().as_ref()],
+        bump,
+        space = Profile::SPACE
+    )]
+    pub new_profile: Account<'info, Profile>,
+    #[account(
+        init,
+        payer = new_authority,
+        seeds = [b"player", new_authority.key().as_ref(), &[new_profile.next_index]],
+        bump,
+        space = Player::SPACE
+    )]
+    pub new_player: Account<'info, Player>,
+    /// CHECK: the character's customization PDA, moved and closed if it exists
+    #[account(mut, seeds = [CHARACTER_CUSTOM_SEED, old_player.key().as_ref()], bump)]
+    pub old_customization: UncheckedAccount<'info>,
+    /// Required if the character has a customization
+    #[account(
+        init,
+        payer = new_authority,
+        seeds = [CHARACTER_CUSTOM_SEED, new_player.key().as_ref()],
+        bump,
+        space = CharacterCustomizationV1::SPACE
+    )]
+    pub new_customization: Option<Account<'info, CharacterCustomizationV1>>,
+    /// CHECK: the old wallet's inventory, if any; the character's gear is taken off
+    #[account(mut, seeds = [b"inventory", old_authority.key().as_ref()], bump)]
+    pub old_inventory: UncheckedAccount<'info>,
+    /// CHECK: parsed by hand; must be `new_authority`'s token account holding the character token
+    pub token_account: UncheckedAccount<'info>,
+    /// CHECK: the seller's wallet, refunded the old accounts' rent
+    #[account(mut)]
+    pub old_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub new_authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Move a tokenized character to the wallet now holding its token: XP,
+/// abilities, ELO, energy and customization come along, gear stays behind,
+/// and the character can't battle for `Player::TRANSFER_COOLDOWN_SLOTS`.
+/// Between the sale and the claim nobody can play it: the old wallet no
+/// longer holds the token, and the new one isn't its authority yet.
+pub fn handler(ctx: Context<ClaimPlayer>) -> Result<()> {
+    let new_authority = ctx.accounts.new_authority.key();
+    require_keys_neq!(ctx.accounts.old_authority.key(), new_authority, CustomError::SameOwner);
+    require!(
+        held_nft(&ctx.accounts.token_account, &new_authority) == Some(ctx.accounts.character_mint.mint),
+        CustomError::NftNotHeld
+    );
+
+    let new_key = ctx.accounts.new_player.key();
+    let index = ctx.accounts.new_profile.add_character(new_authority, ctx.accounts.config.max_characters)?;
+    ctx.accounts.old_profile.remove_character();
+    ctx.accounts.old_player.move_to(&mut ctx.accounts.new_player, new_authority, index, ctx.accounts.clock.slot)?;
+    release_gear(&ctx.accounts.old_inventory, ctx.accounts.old_player.key())?;
+    move_customization(
+        &ctx.accounts.old_customization,
+        ctx.accounts.new_customization.as_mut(),
+        new_key,
+        &ctx.accounts.old_authority,
+    )?;
+    ctx.accounts.character_mint.player = new_key;
+
+    emit!(crate::events::PlayerTransferred {
+        old_player: ctx.accounts.old_player.key(),
+        new_player: new_key,
+        old_authority: ctx.accounts.old_authority.key(),
+        new_authority,
+        mint: Some(ctx.accounts.character_mint.mint),
+    });
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::state::customization::{close_customization, CHARACTER_CUSTOM_SEED};
+use crate::state::item::release_gear;
+use crate::state::player::Player;
+use crate::state::profile::Profile;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct ClosePlayer<'info> {
+    #[account(
+        mut,
+        seeds = [b"player", authority.key().as_ref(), &[player.index]],
+        bump,
+        has_one = authority,
+        close = authority
+    )]
+    pub player: Account<'info, Player>,
+    #[account(mut, seeds = [b"profile", authority.key().as_ref()], bump)]
+    pub profile: Account<'info, Profile>,
+    /// CHECK: the character's customization PDA, closed too if it exists
+    #[account(mut, seeds = [CHARACTER_CUSTOM_SEED, player.key().as_ref()], bump)]
+    pub character_customization: UncheckedAccount<'info>,
+    /// CHECK: the wallet's inventory, if any; the character's gear is taken off
+    #[account(mut, seeds = [b"inventory", authority.key().as_ref()], bump)]
+    pub inventory: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Delete a character for good, refunding its rent and freeing its place
+/// under `max_characters`. Its index is never handed out again, and a
+/// character rated below the start leaves its rating on the profile as a
+/// tombstone, so closing can't be used to shed a bad ELO.
+pub fn handler(ctx: Context<ClosePlayer>) -> Result<()> {
+    let player = &ctx.accounts.player;
+    require!(player.character_mint.is_none(), CustomError::TokenizedPlayer);
+    require!(player.open_battles == 0, CustomError::OpenBattles);
+
+    let profile = &mut ctx.accounts.profile;
+    profile.bury(player.elo);
+    release_gear(&ctx.accounts.inventory, player.key())?;
+    close_customization(&ctx.accounts.character_customization, &ctx.accounts.authority)?;
+
+    emit!(crate::events::PlayerClosed {
+        player: player.key(),
+        authority: player.authority,
+        elo: player.elo,
+        tombstone_elo: profile.tombstone_elo,
+    });
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::battle::BattleState;
+use crate::state::boss::BossBattle;
+use crate::state::config::BalanceConfig;
+use crate::state::player::Player;
+use crate::state::session::{acting_authority, SessionToken};
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct CommitBossMove<'info> {
+    #[account(mut)]
+    pub battle: Account<'info, BossBattle>,
+    /// The player's wallet, or a session key acting for it
+    pub player: Signer<'info>,
+    #[account(address = battle.character @ CustomError::NotInBattle)]
+    pub player_account: Account<'info, Player>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    pub clock: Sysvar<'info, Clock>,
+    pub session: Option<Account<'info, SessionToken>>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
+}
+
+pub fn handler(ctx: Context<CommitBossMove>, commitment: [u8; 32]) -> Result<()> {
+    ctx.accounts.player_account.require_holder(ctx.accounts.character_token.as_deref())?;
+    ctx.accounts.config.require_unpaused(BalanceConfig::PAUSE_COMMITS)?;
+    let key = acting_authority(
+        ctx.accounts.player.key(),
+        ctx.accounts.session.as_deref(),
+        SessionToken::COMMIT,
+        ctx.accounts.clock.slot,
+    )?;
+    let battle = &mut ctx.accounts.battle;
+    require_keys_eq!(battle.player, key, CustomError::NotInBattle);
+    require!(matches!(battle.state, BattleState::WaitingForCommits), CustomError::AlreadyCommitted);
+    require!(ctx.accounts.clock.slot <= battle.commit_deadline_slot, CustomError::DeadlinePassed);
+
+    battle.commitment = Some(commitment);
+    battle.commit_slot = ctx.accounts.clock.slot;
+    battle.state = BattleState::WaitingForReveals;
+
+    emit!(crate::events::MoveCommitted { battle: battle.key(), player: battle.player });
+    Ok(())
+}
@@ -1,26 +1,60 @@
 use anchor_lang::prelude::*;
 use crate::state::battle::{Battle, BattleState};
+use crate::state::config::BalanceConfig;
+use crate::state::player::Player;
+use crate::state::session::{acting_authority, SessionToken};
 use crate::errors::CustomError;
 
 #[derive(Accounts)]
 pub struct CommitMove<'info> {
     #[account(mut)]
     pub battle: Account<'info, Battle>,
+    /// The player's wallet, or a session key acting for it
     pub player: Signer<'info>,
+    /// The acting authority's character in the battle; the opponent pays
+    /// the battle's energy cost and takes on the open battle here, on accepting
+    #[account(mut)]
+    pub player_account: Account<'info, Player>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
     pub clock: Sysvar<'info, Clock>,
+    pub session: Option<Account<'info, SessionToken>>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
 }
 
 pub fn handler(ctx: Context<CommitMove>, commitment: [u8; 32]) -> Result<()> {
+    ctx.accounts.player_account.require_holder(ctx.accounts.character_token.as_deref())?;
+    ctx.accounts.config.require_unpaused(BalanceConfig::PAUSE_COMMITS)?;
     let battle = &mut ctx.accounts.battle;
     require!(matches!(battle.state, BattleState::WaitingForCommits), CustomError::NotReadyToResolve);
     require!(ctx.accounts.clock.slot <= battle.commit_deadline_slot, CustomError::DeadlinePassed);
-    let key = ctx.accounts.player.key();
-    if key == battle.challenger {
+    let key = acting_authority(
+        ctx.accounts.player.key(),
+        ctx.accounts.session.as_deref(),
+        SessionToken::COMMIT,
+        ctx.accounts.clock.slot,
+    )?;
+    require_keys_eq!(ctx.accounts.player_account.authority, key, CustomError::NotInBattle);
+    let character = ctx.accounts.player_account.key();
+    if character == battle.challenger {
         require!(battle.commit_challenger.is_none(), CustomError::AlreadyCommitted);
         battle.commit_challenger = Some(commitment);
-    } else if key == battle.opponent {
+    } else if character == battle.opponent {
         require!(battle.commit_opponent.is_none(), CustomError::AlreadyCommitted);
         battle.commit_opponent = Some(commitment);
+        let now = ctx.accounts.clock.slot;
+        // Energy follows the battle's rules too, cost and regen alike
+        let cfg = &BalanceConfig::with_params(&battle.rules);
+        let account = &mut ctx.accounts.player_account;
+        account.open_battles = account.open_battles.saturating_add(1);
+        account.spend_energy(battle.rules.battle_energy_cost, now, cfg)?;
+        emit!(crate::events::EnergySpent {
+            player: account.key(),
+            amount: battle.rules.battle_energy_cost,
+            energy: account.energy(now, cfg),
+        });
     } else {
         return err!(CustomError::NotInBattle);
     }
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::state::crafting::{CraftReceipt, Recipe};
+use crate::state::config::BalanceConfig;
+use crate::state::customization::{CharacterCustomizationV1, CHARACTER_CUSTOM_SEED};
+use crate::state::loot::LootBag;
+use crate::state::player::Player;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct CraftCosmetic<'info> {
+    #[account(mut, seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
+    pub player: Account<'info, Player>,
+    #[account(mut, seeds = [CHARACTER_CUSTOM_SEED, player.key().as_ref()], bump, has_one = player)]
+    pub character_customization: Account<'info, CharacterCustomizationV1>,
+    #[account(mut, seeds = [b"loot_bag", authority.key().as_ref()], bump)]
+    pub loot_bag: Account<'info, LootBag>,
+    #[account(seeds = [b"recipe".as_ref(), &[recipe.kind as u8], &[recipe.index]], bump)]
+    pub recipe: Account<'info, Recipe>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"crafted", player.key().as_ref(), &[recipe.kind as u8], &[recipe.index]],
+        bump,
+        space = CraftReceipt::SPACE
+    )]
+    pub craft_receipt: Account<'info, CraftReceipt>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
+}
+
+/// Spend the recipe's materials and XP to unlock its cosmetic for good,
+/// recording the materials paid for a later salvage
+pub fn handler(ctx: Context<CraftCosmetic>) -> Result<()> {
+    ctx.accounts.player.require_holder(ctx.accounts.character_token.as_deref())?;
+    ctx.accounts.config.require_unpaused(BalanceConfig::PAUSE_CUSTOMIZATION)?;
+    let recipe = &ctx.accounts.recipe;
+    let custom = &mut ctx.accounts.character_customization;
+    let bag = &mut ctx.accounts.loot_bag;
+    let player = &mut ctx.accounts.player;
+    require!(recipe.active, CustomError::RecipeInactive);
+    require!(!custom.is_unlocked(recipe.kind, recipe.index), CustomError::AlreadyUnlocked);
+    require!(
+        bag.materials.iter().zip(recipe.materials).all(|(have, cost)| *have >= cost),
+        CustomError::NotEnoughMaterials
+    );
+    require!(player.xp >= recipe.xp_cost, CustomError::NotEnoughXP);
+
+    for (have, cost) in bag.materials.iter_mut().zip(recipe.materials) {
+        *have -= cost;
+    }
+    player.xp -= recipe.xp_cost;
+    custom.set_crafted(recipe.kind, recipe.index, true);
+    ctx.accounts.craft_receipt.materials = recipe.materials;
+
+    emit!(crate::events::CosmeticCrafted {
+        player: player.key(),
+        kind: recipe.kind,
+        index: recipe.index,
+        materials: recipe.materials,
+        xp_cost: recipe.xp_cost,
+    });
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::admin::{AdminSet, ConfigProposal, ProposalAction};
+use crate::state::config::BalanceConfig;
+use super::execute_config_change::require_admin;
+
+#[derive(Accounts)]
+#[instruction(id: u32)]
+pub struct CreateAdminSet<'info> {
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"admin_set".as_ref(), &id.to_le_bytes()],
+        bump,
+        space = AdminSet::SPACE
+    )]
+    pub admin_set: Account<'info, AdminSet>,
+    /// The admin, or anyone running an approved `proposal`; pays for
+    /// `admin_set`
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// The set holding the config now, if any; with `proposal`, it hands
+    /// the config on in place of the admin's signature
+    pub current_set: Option<Account<'info, AdminSet>>,
+    #[account(mut)]
+    pub proposal: Option<Account<'info, ConfigProposal>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Hand the config to an M-of-N set. The current admin gives up direct
+/// control; the set can hand it back to a single key via `ProposeAdmin`, or
+/// on to another set through a `CreateAdminSet` proposal.
+pub fn handler(ctx: Context<CreateAdminSet>, id: u32, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    AdminSet::validate(&members, threshold)?;
+    require_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.admin.key(),
+        ctx.accounts.current_set.as_ref(),
+        ctx.accounts.proposal.as_mut(),
+        ProposalAction::CreateAdminSet { id, members_hash: AdminSet::members_hash(&members), threshold },
+    )?;
+
+    let admin_set = &mut ctx.accounts.admin_set;
+    admin_set.id = id;
+    admin_set.members = members.clone();
+    admin_set.threshold = threshold;
+    admin_set.proposal_count = 0;
+
+    let cfg = &mut ctx.accounts.config;
+    let old_admin = cfg.admin;
+    cfg.admin = admin_set.key();
+    cfg.pending_admin = None;
+
+    emit!(crate::events::AdminSetCreated { admin_set: admin_set.key(), id, members, threshold });
+    emit!(crate::events::AdminAccepted { old_admin, new_admin: admin_set.key() });
+    Ok(())
+}
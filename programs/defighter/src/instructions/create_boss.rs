@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::state::admin::{AdminSet, ConfigProposal, ProposalAction};
+use crate::state::boss::{Boss, BossParams};
+use crate::state::config::BalanceConfig;
+use crate::errors::CustomError;
+use super::execute_config_change::require_admin;
+
+#[derive(Accounts)]
+#[instruction(id: u32)]
+pub struct CreateBoss<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"boss", &id.to_le_bytes()],
+        bump,
+        space = Boss::SPACE
+    )]
+    pub boss: Account<'info, Boss>,
+    /// The admin, or anyone running an approved `proposal`; pays for
+    /// new accounts
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// With `proposal`, stands in for the admin's signature once the
+    /// config belongs to an admin set
+    pub admin_set: Option<Account<'info, AdminSet>>,
+    #[account(mut)]
+    pub proposal: Option<Account<'info, ConfigProposal>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateBoss>, id: u32, params: BossParams) -> Result<()> {
+    require_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.admin.key(),
+        ctx.accounts.admin_set.as_ref(),
+        ctx.accounts.proposal.as_mut(),
+        ProposalAction::CreateBoss { id, params },
+    )?;
+    require!(params.max_hp > 0, CustomError::InvalidBossParams);
+    require!(params.special_bps <= 10_000, CustomError::InvalidBossParams);
+    // Regular bosses fight a single round, whose HP is a u16
+    require!(params.shared_hp || params.max_hp <= u16::MAX as u32, CustomError::InvalidBossParams);
+
+    let boss = &mut ctx.accounts.boss;
+    boss.id = id;
+    boss.class = params.class;
+    boss.level = params.level;
+    boss.max_hp = params.max_hp;
+    boss.hp = params.max_hp;
+    boss.special_bps = params.special_bps;
+    boss.shared_hp = params.shared_hp;
+    boss.xp_win = params.xp_win;
+    boss.xp_loss = params.xp_loss;
+    boss.fights = 0;
+    boss.defeated_by = None;
+
+    emit!(crate::events::BossCreated {
+        boss: boss.key(),
+        id,
+        class: params.class,
+        max_hp: params.max_hp,
+        shared_hp: params.shared_hp,
+    });
+    Ok(())
+}
@@ -1,28 +1,38 @@
 use anchor_lang::prelude::*;
-use crate::state::{Player, CharacterCustomizationV1, CharacterCustomizationDataV1, CHARACTER_CUSTOM_SEED};
+use crate::state::{BalanceConfig, Player, CharacterCustomizationV1, CharacterCustomizationDataV1, CHARACTER_CUSTOM_SEED};
+use crate::errors::CustomError;
 
 #[derive(Accounts)]
 pub struct CreateCharacterCustomization<'info> {
-    #[account(mut)]
+    #[account(seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
     pub player: Account<'info, Player>,
     #[account(
         init,
         payer = authority,
         seeds = [CHARACTER_CUSTOM_SEED, player.key().as_ref()],
         bump,
-        space = CharacterCustomizationV1::SPACE as usize,
+        space = CharacterCustomizationV1::SPACE,
     )]
     pub character_customization: Account<'info, CharacterCustomizationV1>,
     #[account(mut)]
     pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
     pub system_program: Program<'info, System>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
 }
 
 pub fn handler(ctx: Context<CreateCharacterCustomization>, data: CharacterCustomizationDataV1) -> Result<()> {
+    ctx.accounts.player.require_holder(ctx.accounts.character_token.as_deref())?;
+    ctx.accounts.config.require_unpaused(BalanceConfig::PAUSE_CUSTOMIZATION)?;
     let player = &ctx.accounts.player;
-    require!(player.authority == ctx.accounts.authority.key(), error!(crate::errors::GameError::Unauthorized));
-
     let acct = &mut ctx.accounts.character_customization;
+    // Fresh account: only free cosmetics so far, and the prestige outfit
+    let prestige: Vec<_> = player.prestige_unlock(&ctx.accounts.config).into_iter().collect();
+    require!(acct.allows(&data, &prestige), CustomError::CosmeticLocked);
+
     acct.player = player.key();
     acct.gender = data.gender;
     acct.palette_index = data.palette_index;
@@ -34,7 +44,9 @@ pub fn handler(ctx: Context<CreateCharacterCustomization>, data: CharacterCustom
     acct.face_flags = data.face_flags;
     acct.accessory_slots = data.accessory_slots;
     acct.version = CharacterCustomizationV1::VERSION;
-    acct.reserved = [0; 24];
+    acct.unlocked_accessories = 0;
+    acct.unlocked_outfits = 0;
+    acct.reserved = [0; 4];
     Ok(())
 }
 
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::state::admin::{AdminSet, ConfigProposal, ProposalAction};
+use crate::state::item::{Item, ItemParams};
+use crate::state::config::BalanceConfig;
+use crate::errors::CustomError;
+use super::execute_config_change::require_admin;
+
+#[derive(Accounts)]
+#[instruction(id: u32)]
+pub struct CreateItem<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"item", &id.to_le_bytes()],
+        bump,
+        space = Item::SPACE
+    )]
+    pub item: Account<'info, Item>,
+    /// The admin, or anyone running an approved `proposal`; pays for
+    /// new accounts
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// With `proposal`, stands in for the admin's signature once the
+    /// config belongs to an admin set
+    pub admin_set: Option<Account<'info, AdminSet>>,
+    #[account(mut)]
+    pub proposal: Option<Account<'info, ConfigProposal>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateItem>, id: u32, params: ItemParams) -> Result<()> {
+    require_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.admin.key(),
+        ctx.accounts.admin_set.as_ref(),
+        ctx.accounts.proposal.as_mut(),
+        ProposalAction::CreateItem { id, params },
+    )?;
+    require!(params.is_valid(), CustomError::InvalidItemParams);
+
+    let item = &mut ctx.accounts.item;
+    item.id = id;
+    item.slot = params.slot;
+    item.rarity = params.rarity;
+    item.class = params.class;
+    item.stats = params.stats;
+
+    emit!(crate::events::ItemCreated {
+        item: item.key(),
+        id,
+        slot: params.slot,
+        rarity: params.rarity,
+        class: params.class,
+        stats: params.stats,
+    });
+    Ok(())
+}
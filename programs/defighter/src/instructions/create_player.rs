@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
+use crate::state::config::BalanceConfig;
 use crate::state::player::{Player, FighterClass};
+use crate::state::profile::Profile;
 
 #[derive(Accounts)]
 #[instruction(class: FighterClass)]
@@ -7,27 +9,36 @@ pub struct CreatePlayer<'info> {
     /// The payer who will fund the rent for the new player account (user wallet)
     #[account(mut)]
     pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"profile", authority.key().as_ref()],
+        bump,
+        space = Profile::SPACE
+    )]
+    pub profile: Account<'info, Profile>,
     #[account(
         init,
         payer = payer,
-        seeds = [b"player", authority.key().as_ref()],
+        seeds = [b"player", authority.key().as_ref(), &[profile.next_index]],
         bump,
-        space = 8 + 32 + 1 + 8 + (2*3) + 4 + 1
+        space = Player::SPACE
     )]
     pub player: Account<'info, Player>,
     #[account(mut)]
     pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
     pub system_program: Program<'info, System>,
 }
 
+/// Create the wallet's next character, up to `max_characters` at once. It
+/// starts at the wallet's tombstone rating, if a closed character left one.
 pub fn handler(ctx: Context<CreatePlayer>, class: FighterClass) -> Result<()> {
-    let player = &mut ctx.accounts.player;
-    player.authority = ctx.accounts.authority.key();
-    player.class = class;
-    player.xp = 0;
-    player.abilities = [0, 0, 0];
-    player.elo = 1000;
-    player.version = 1;
+    let authority = ctx.accounts.authority.key();
+    let index = ctx.accounts.profile.add_character(authority, ctx.accounts.config.max_characters)?;
+    let elo = ctx.accounts.profile.starting_elo();
+    *ctx.accounts.player = Player { elo, index, ..Player::new(authority, class, [0; 3]) };
     Ok(())
 }
 
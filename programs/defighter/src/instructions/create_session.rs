@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::session::SessionToken;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct CreateSession<'info> {
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"session", authority.key().as_ref(), session_key.as_ref()],
+        bump,
+        space = SessionToken::SPACE
+    )]
+    pub session: Account<'info, SessionToken>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn handler(ctx: Context<CreateSession>, session_key: Pubkey, duration_slots: u64, scope: u8) -> Result<()> {
+    require!(
+        duration_slots > 0 && duration_slots <= SessionToken::MAX_DURATION_SLOTS,
+        CustomError::InvalidSessionParams
+    );
+    require!(scope != 0 && scope & !SessionToken::ALL == 0, CustomError::InvalidSessionParams);
+
+    let session = &mut ctx.accounts.session;
+    session.authority = ctx.accounts.authority.key();
+    session.session_key = session_key;
+    session.expires_slot = ctx.accounts.clock.slot.saturating_add(duration_slots);
+    session.scope = scope;
+
+    emit!(crate::events::SessionCreated {
+        authority: session.authority,
+        session_key,
+        expires_slot: session.expires_slot,
+        scope,
+    });
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::state::item::{Inventory, Item};
+use crate::state::player::Player;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct EquipItem<'info> {
+    #[account(mut, seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
+    pub player: Account<'info, Player>,
+    #[account(mut, seeds = [b"inventory", authority.key().as_ref()], bump)]
+    pub inventory: Account<'info, Inventory>,
+    #[account(seeds = [b"item", &item.id.to_le_bytes()], bump)]
+    pub item: Account<'info, Item>,
+    pub authority: Signer<'info>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
+}
+
+/// Wear `item` in its slot, replacing whatever was there. The wallet's
+/// gear goes on one character at a time.
+pub fn handler(ctx: Context<EquipItem>) -> Result<()> {
+    ctx.accounts.player.require_holder(ctx.accounts.character_token.as_deref())?;
+    let player = &mut ctx.accounts.player;
+    let inventory = &mut ctx.accounts.inventory;
+    let item = &ctx.accounts.item;
+    require!(inventory.owns(item.id), CustomError::ItemNotOwned);
+    require!(item.class.map_or(true, |class| class == player.class), CustomError::ItemClassMismatch);
+    inventory.require_wearer(player.key())?;
+    inventory.wearer = Some(player.key());
+
+    let slot = item.slot as usize;
+    let replaced = inventory.equipped[slot].replace(item.id);
+    inventory.equipped_stats[slot] = item.stats;
+    player.gear = inventory.gear();
+
+    emit!(crate::events::ItemEquipped {
+        player: player.key(),
+        item_id: item.id,
+        slot: item.slot,
+        replaced,
+        gear: player.gear,
+    });
+    Ok(())
+}
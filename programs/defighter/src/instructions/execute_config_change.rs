@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use crate::state::admin::{AdminSet, ConfigProposal, ProposalAction};
+use crate::state::config::{BalanceConfig, PendingConfig};
+use crate::errors::CustomError;
+use super::cancel_config_change::cancel_change;
+use super::propose_admin::set_pending_admin;
+use super::set_guardian::set_guardian;
+use super::set_paused::set_paused;
+use super::update_config::queue_change;
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        constraint = config.admin == admin_set.key() @ CustomError::AdminSetNotInControl
+    )]
+    pub config: Account<'info, BalanceConfig>,
+    pub admin_set: Account<'info, AdminSet>,
+    #[account(mut, has_one = admin_set)]
+    pub proposal: Account<'info, ConfigProposal>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"pending_config"],
+        bump,
+        space = PendingConfig::SPACE
+    )]
+    pub pending_config: Account<'info, PendingConfig>,
+    /// Anyone may execute; pays for `pending_config` if it doesn't exist yet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Permissionless once enough members approved.
+pub fn handler(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    spend_proposal(proposal, &ctx.accounts.admin_set)?;
+
+    let cfg = &mut ctx.accounts.config;
+    let pending = &mut ctx.accounts.pending_config;
+    match proposal.action {
+        ProposalAction::Update(update) => {
+            let mut params = cfg.params();
+            update.apply(&mut params);
+            queue_change(cfg, pending, params, ctx.accounts.clock.slot)?
+        }
+        ProposalAction::CancelChange => cancel_change(cfg, pending)?,
+        ProposalAction::ProposeAdmin(new_admin) => set_pending_admin(cfg, new_admin),
+        ProposalAction::SetPaused(flags) => set_paused(cfg, ctx.accounts.admin_set.key(), flags, ctx.accounts.clock.slot)?,
+        ProposalAction::SetGuardian(guardian) => set_guardian(cfg, guardian),
+        _ => return err!(CustomError::WrongProposalAction),
+    }
+    Ok(())
+}
+
+/// Stand-in for `has_one = admin` on admin instructions an admin set can
+/// run too: `admin` must be the config's admin, or `proposal` an approved
+/// proposal for exactly `action` from the set holding the config, which
+/// this spends. `admin` then only pays.
+pub(crate) fn require_admin(
+    config: &BalanceConfig,
+    admin: &Pubkey,
+    admin_set: Option<&Account<AdminSet>>,
+    proposal: Option<&mut Account<ConfigProposal>>,
+    action: ProposalAction,
+) -> Result<()> {
+    if config.admin == *admin {
+        return Ok(());
+    }
+    let (Some(admin_set), Some(proposal)) = (admin_set, proposal) else {
+        return err!(ErrorCode::ConstraintHasOne);
+    };
+    require_keys_eq!(config.admin, admin_set.key(), CustomError::AdminSetNotInControl);
+    require_keys_eq!(proposal.admin_set, admin_set.key(), ErrorCode::ConstraintHasOne);
+    require!(proposal.action == action, CustomError::WrongProposalAction);
+    spend_proposal(proposal, admin_set)
+}
+
+/// Mark `proposal` executed once it has enough approvals
+fn spend_proposal(proposal: &mut Account<ConfigProposal>, admin_set: &AdminSet) -> Result<()> {
+    require!(!proposal.executed, CustomError::ProposalExecuted);
+    require!(proposal.approval_count() >= admin_set.threshold, CustomError::NotEnoughApprovals);
+    proposal.executed = true;
+    emit!(crate::events::ConfigChangeExecuted { proposal: proposal.key(), action: proposal.action });
+    Ok(())
+}
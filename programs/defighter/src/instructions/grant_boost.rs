@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::admin::{AdminSet, ConfigProposal, ProposalAction};
+use crate::state::consumable::{Boost, Consumables};
+use crate::state::config::BalanceConfig;
+use super::execute_config_change::require_admin;
+
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct GrantBoost<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"consumables", recipient.as_ref()],
+        bump,
+        space = Consumables::SPACE
+    )]
+    pub consumables: Account<'info, Consumables>,
+    /// The admin, or anyone running an approved `proposal`; pays for
+    /// new accounts
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// With `proposal`, stands in for the admin's signature once the
+    /// config belongs to an admin set
+    pub admin_set: Option<Account<'info, AdminSet>>,
+    #[account(mut)]
+    pub proposal: Option<Account<'info, ConfigProposal>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Mint `amount` of `boost` to `recipient`
+pub fn handler(ctx: Context<GrantBoost>, recipient: Pubkey, boost: Boost, amount: u16) -> Result<()> {
+    require_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.admin.key(),
+        ctx.accounts.admin_set.as_ref(),
+        ctx.accounts.proposal.as_mut(),
+        ProposalAction::GrantBoost { recipient, boost, amount },
+    )?;
+    let consumables = &mut ctx.accounts.consumables;
+    consumables.owner = recipient;
+    let balance = consumables.add(boost, amount);
+    emit!(crate::events::BoostGranted { owner: recipient, boost, amount, balance, battle: None });
+    Ok(())
+}
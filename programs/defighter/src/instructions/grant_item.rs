@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::state::admin::{AdminSet, ConfigProposal, ProposalAction};
+use crate::state::item::{Inventory, Item};
+use crate::state::config::BalanceConfig;
+use crate::errors::CustomError;
+use super::execute_config_change::require_admin;
+
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct GrantItem<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    pub item: Account<'info, Item>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"inventory", recipient.as_ref()],
+        bump,
+        space = Inventory::SPACE
+    )]
+    pub inventory: Account<'info, Inventory>,
+    /// The admin, or anyone running an approved `proposal`; pays for
+    /// new accounts
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// With `proposal`, stands in for the admin's signature once the
+    /// config belongs to an admin set
+    pub admin_set: Option<Account<'info, AdminSet>>,
+    #[account(mut)]
+    pub proposal: Option<Account<'info, ConfigProposal>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<GrantItem>, recipient: Pubkey) -> Result<()> {
+    require_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.admin.key(),
+        ctx.accounts.admin_set.as_ref(),
+        ctx.accounts.proposal.as_mut(),
+        ProposalAction::GrantItem { recipient, item_id: ctx.accounts.item.id },
+    )?;
+    let inventory = &mut ctx.accounts.inventory;
+    inventory.owner = recipient;
+    add_item(inventory, ctx.accounts.item.id)
+}
+
+/// Put `item_id` in the bag; shared with anything else that hands out items
+pub(crate) fn add_item(inventory: &mut Account<Inventory>, item_id: u32) -> Result<()> {
+    require!(inventory.items.len() < Inventory::MAX_ITEMS, CustomError::InventoryFull);
+    inventory.items.push(item_id);
+    emit!(crate::events::ItemGranted { inventory: inventory.key(), owner: inventory.owner, item_id });
+    Ok(())
+}
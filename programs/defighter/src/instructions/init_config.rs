@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::config::BalanceConfig;
+use crate::state::config::{BalanceConfig, BalanceConfigParams};
 
 #[derive(Accounts)]
 pub struct InitConfig<'info> {
@@ -8,7 +8,7 @@ pub struct InitConfig<'info> {
         payer = admin,
         seeds = [b"config"],
         bump,
-        space = 8 + 32 + (4*4) + 2 + 1 + 1 + 8 + 2 + 2 + 8 + 2 + 4 + 4
+        space = BalanceConfig::SPACE
     )]
     pub config: Account<'info, BalanceConfig>,
     #[account(mut)]
@@ -16,40 +16,11 @@ pub struct InitConfig<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
-    ctx: Context<InitConfig>,
-    base: i32,
-    linear_a: i32,
-    dim_k: i32,
-    dim_t: i32,
-    max_level: u16,
-    tie_break_rand: bool,
-    use_dim_bonus: bool,
-    xp_base: u64,
-    xp_tie_bps: u16,
-    xp_loser_bps: u16,
-    upgrade_c0: u64,
-    upgrade_p_bps: u16,
-    rps_win_base: i32,
-    rps_tie_base: i32,
-) -> Result<()> {
+pub fn handler(ctx: Context<InitConfig>, params: BalanceConfigParams) -> Result<()> {
+    params.validate()?;
     let cfg = &mut ctx.accounts.config;
     cfg.admin = ctx.accounts.admin.key();
-    cfg.base = base;
-    cfg.linear_a = linear_a;
-    cfg.dim_k = dim_k;
-    cfg.dim_t = dim_t;
-    cfg.max_level = max_level;
-    cfg.tie_break_rand = tie_break_rand;
-    cfg.use_dim_bonus = use_dim_bonus;
-    cfg.xp_base = xp_base;
-    cfg.xp_tie_bps = xp_tie_bps;
-    cfg.xp_loser_bps = xp_loser_bps;
-    cfg.upgrade_c0 = upgrade_c0;
-    cfg.upgrade_p_bps = upgrade_p_bps;
-    cfg.rps_win_base = rps_win_base;
-    cfg.rps_tie_base = rps_tie_base;
+    cfg.apply(&params);
+    cfg.version = 1;
     Ok(())
 }
-
-
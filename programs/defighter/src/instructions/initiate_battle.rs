@@ -1,5 +1,11 @@
 use anchor_lang::prelude::*;
-use crate::state::{battle::{Battle, BattleState}, player::Player};
+use crate::state::battle::{Battle, BattleState};
+use crate::state::config::BalanceConfig;
+use crate::state::consumable::Consumables;
+use crate::state::loot::LootBag;
+use crate::state::pair::PairRecord;
+use crate::state::player::Player;
+use crate::errors::CustomError;
 
 #[derive(Accounts)]
 #[instruction(opponent: Pubkey, nonce: u64, commit_deadline_slots: u64, reveal_deadline_slots: u64)]
@@ -7,16 +13,86 @@ pub struct InitiateBattle<'info> {
     #[account(
         init,
         payer = challenger,
-        seeds = [b"battle", challenger.key().as_ref(), opponent.as_ref(), &nonce.to_le_bytes()],
+        seeds = [b"battle", challenger_account.key().as_ref(), opponent.as_ref(), &nonce.to_le_bytes()],
         bump,
-        space = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 8 + (1+32) + (1+32) + (1+1) + (1+1) + (1+32) + 2 + 2
+        space = Battle::SPACE,
+        // Checked here, ahead of the other inits: a wallet fighting itself
+        // would share one loot bag between both sides
+        constraint = opponent_account.authority != challenger.key() @ CustomError::SameOwner
     )]
     pub battle: Account<'info, Battle>,
+    /// Keyed by wallet, so alts' characters don't multiply the rematch budget
+    #[account(
+        init_if_needed,
+        payer = challenger,
+        seeds = [
+            b"pair",
+            PairRecord::sorted(challenger.key(), opponent_account.authority).0.as_ref(),
+            PairRecord::sorted(challenger.key(), opponent_account.authority).1.as_ref(),
+        ],
+        bump,
+        space = PairRecord::SPACE
+    )]
+    pub pair_record: Account<'info, PairRecord>,
+    /// Both sides' loot bags, so the permissionless resolve has somewhere
+    /// to put a drop; the challenger covers rent for any that don't exist yet
+    #[account(
+        init_if_needed,
+        payer = challenger,
+        seeds = [b"loot_bag", challenger.key().as_ref()],
+        bump,
+        space = LootBag::SPACE
+    )]
+    pub challenger_loot: Account<'info, LootBag>,
+    #[account(
+        init_if_needed,
+        payer = challenger,
+        seeds = [b"loot_bag", opponent_account.authority.as_ref()],
+        bump,
+        space = LootBag::SPACE
+    )]
+    pub opponent_loot: Account<'info, LootBag>,
+    /// Likewise for boost rewards
+    #[account(
+        init_if_needed,
+        payer = challenger,
+        seeds = [b"consumables", challenger.key().as_ref()],
+        bump,
+        space = Consumables::SPACE
+    )]
+    pub challenger_consumables: Account<'info, Consumables>,
+    #[account(
+        init_if_needed,
+        payer = challenger,
+        seeds = [b"consumables", opponent_account.authority.as_ref()],
+        bump,
+        space = Consumables::SPACE
+    )]
+    pub opponent_consumables: Account<'info, Consumables>,
     #[account(mut)]
     pub challenger: Signer<'info>,
-    /// CHECK: opponent just a Pubkey; no need to load Player here
+    /// The character the challenger fights with
+    #[account(
+        mut,
+        seeds = [b"player", challenger.key().as_ref(), &[challenger_account.index]],
+        bump,
+        constraint = challenger_account.authority == challenger.key()
+    )]
+    pub challenger_account: Account<'info, Player>,
+    /// The character challenged, `opponent`
+    #[account(
+        address = opponent,
+        seeds = [b"player", opponent_account.authority.as_ref(), &[opponent_account.index]],
+        bump
+    )]
+    pub opponent_account: Account<'info, Player>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
     pub system_program: Program<'info, System>,
     pub clock: Sysvar<'info, Clock>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
 }
 
 pub fn handler(
@@ -26,10 +102,32 @@ pub fn handler(
     commit_deadline_slots: u64,
     reveal_deadline_slots: u64,
 ) -> Result<()> {
+    ctx.accounts.challenger_account.require_holder(ctx.accounts.character_token.as_deref())?;
+    ctx.accounts.config.require_unpaused(BalanceConfig::PAUSE_BATTLES)?;
     let battle = &mut ctx.accounts.battle;
     let challenger = ctx.accounts.challenger.key();
+    let opponent_wallet = ctx.accounts.opponent_account.authority;
     let now = ctx.accounts.clock.slot;
-    battle.challenger = challenger;
+    let cfg = &ctx.accounts.config;
+    let challenger_account = &mut ctx.accounts.challenger_account;
+    let opponent_account = &ctx.accounts.opponent_account;
+    require!(
+        commit_deadline_slots <= cfg.max_commit_slots && reveal_deadline_slots <= cfg.max_reveal_slots,
+        CustomError::InvalidDeadlines
+    );
+    challenger_account.require_ready(now)?;
+    opponent_account.require_ready(now)?;
+    // An opponent who couldn't afford to accept would hand over a forfeit win
+    require!(opponent_account.energy(now, cfg) >= cfg.battle_energy_cost, CustomError::NotEnoughEnergy);
+    // The opponent's character is only tied up once they accept, at commit
+    challenger_account.open_battles = challenger_account.open_battles.saturating_add(1);
+    challenger_account.spend_energy(cfg.battle_energy_cost, now, cfg)?;
+    emit!(crate::events::EnergySpent {
+        player: challenger_account.key(),
+        amount: cfg.battle_energy_cost,
+        energy: challenger_account.energy(now, cfg),
+    });
+    battle.challenger = challenger_account.key();
     battle.opponent = opponent;
     battle.nonce = nonce;
     battle.state = BattleState::WaitingForCommits;
@@ -40,16 +138,33 @@ pub fn handler(
     battle.commit_opponent = None;
     battle.reveal_challenger = None;
     battle.reveal_opponent = None;
+    battle.boost_challenger = None;
+    battle.boost_opponent = None;
+    battle.salt_challenger = [0; 32];
+    battle.salt_opponent = [0; 32];
     battle.winner = None;
     battle.challenger_hp = 200;
     battle.opponent_hp = 200;
+    battle.config_version = ctx.accounts.config.version;
+    battle.rules = ctx.accounts.config.params();
+
+    ctx.accounts.challenger_loot.owner = challenger;
+    ctx.accounts.opponent_loot.owner = opponent_wallet;
+    ctx.accounts.challenger_consumables.owner = challenger;
+    ctx.accounts.opponent_consumables.owner = opponent_wallet;
+
+    let pair = &mut ctx.accounts.pair_record;
+    (pair.low, pair.high) = PairRecord::sorted(challenger, opponent_wallet);
+    battle.xp_scale_bps = pair.record_battle(now, &battle.rules)?;
 
     emit!(crate::events::BattleInitiated {
         battle: battle.key(),
-        challenger,
+        challenger: battle.challenger,
         opponent,
         commit_deadline_slot: battle.commit_deadline_slot,
         reveal_deadline_slot: battle.reveal_deadline_slot,
+        config_version: battle.config_version,
+        xp_scale_bps: battle.xp_scale_bps,
     });
     Ok(())
 }
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use crate::state::battle::{Battle, BattleHead, BattleState};
+use crate::state::config::BalanceConfig;
+use crate::state::player::Player;
+use crate::errors::CustomError;
+use crate::utils::grow_account;
+
+#[derive(Accounts)]
+pub struct MigrateBattle<'info> {
+    /// CHECK: too short to load as a `Battle`; the owner and discriminator
+    /// are checked by hand
+    #[account(mut, owner = crate::ID)]
+    pub battle: UncheckedAccount<'info>,
+    /// The challenger's character; for a baseline battle, which named
+    /// wallets, the wallet's index-0 character
+    #[account(mut)]
+    pub player_challenger: Account<'info, Player>,
+    #[account(mut)]
+    pub player_opponent: Account<'info, Player>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow a battle written under an older layout so it can be played out.
+/// `BattleHead` carries over; the rest is filled in as if it had just been
+/// initiated: today's rules, full XP, no boosts and no salts. Baseline
+/// battles named wallets; they are pointed at the characters
+/// `migrate_legacy_player` moved those wallets' players to, which must run
+/// first, and counted in their open battles as `initiate_battle` and
+/// `commit_move` would have.
+pub fn handler(ctx: Context<MigrateBattle>) -> Result<()> {
+    let info = ctx.accounts.battle.to_account_info();
+    let head = {
+        let data = info.try_borrow_data()?;
+        require!(data.starts_with(Battle::DISCRIMINATOR), ErrorCode::AccountDiscriminatorMismatch);
+        BattleHead::deserialize(&mut &data[Battle::DISCRIMINATOR.len()..])?
+    };
+    grow_account(
+        &info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        Battle::SPACE,
+    )?;
+
+    let challenger = character_key(head.challenger, &ctx.accounts.player_challenger)?;
+    let opponent = character_key(head.opponent, &ctx.accounts.player_opponent)?;
+    let legacy = challenger != head.challenger;
+    if legacy && !matches!(head.state, BattleState::Resolved) {
+        let pc = &mut ctx.accounts.player_challenger;
+        pc.open_battles = pc.open_battles.saturating_add(1);
+        if head.commit_opponent.is_some() {
+            let po = &mut ctx.accounts.player_opponent;
+            po.open_battles = po.open_battles.saturating_add(1);
+        }
+    }
+
+    let cfg = &ctx.accounts.config;
+    let battle = Battle {
+        challenger,
+        opponent,
+        nonce: head.nonce,
+        state: head.state,
+        created_slot: head.created_slot,
+        commit_deadline_slot: head.commit_deadline_slot,
+        reveal_deadline_slot: head.reveal_deadline_slot,
+        commit_challenger: head.commit_challenger,
+        commit_opponent: head.commit_opponent,
+        reveal_challenger: head.reveal_challenger,
+        reveal_opponent: head.reveal_opponent,
+        winner: head.winner,
+        challenger_hp: head.challenger_hp,
+        opponent_hp: head.opponent_hp,
+        config_version: cfg.version,
+        rules: cfg.params(),
+        xp_scale_bps: 10_000,
+        boost_challenger: None,
+        boost_opponent: None,
+        salt_challenger: [0; 32],
+        salt_opponent: [0; 32],
+    };
+    battle.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+/// The character behind `key`, a battle side: either `player` itself, or the
+/// wallet whose index-0 character it is
+fn character_key(key: Pubkey, player: &Account<Player>) -> Result<Pubkey> {
+    if key != player.key() {
+        require!(key == player.authority && player.index == 0, CustomError::NotInBattle);
+    }
+    Ok(player.key())
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::state::config::{BalanceConfig, BalanceConfigParams};
+use crate::utils::grow_account;
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: too short to load as a `BalanceConfig`; only `admin`, which
+    /// every layout starts with, is read
+    #[account(mut, seeds = [b"config"], bump, owner = crate::ID)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: a change queued under the old layout, closed if there is one
+    #[account(mut, seeds = [b"pending_config"], bump)]
+    pub pending_config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Rebuild a config written under an older layout. Tunables have moved
+/// between layouts, so they come from `params` as with `init_config`; the
+/// admin is kept and the rest (pause bits, guardian, pending admin) starts
+/// over. Current-layout configs are refused, so this never skips the
+/// timelock.
+pub fn handler(ctx: Context<MigrateConfig>, params: BalanceConfigParams) -> Result<()> {
+    params.validate()?;
+    let info = ctx.accounts.config.to_account_info();
+    let admin = {
+        let data = info.try_borrow_data()?;
+        require!(data.starts_with(BalanceConfig::DISCRIMINATOR), ErrorCode::AccountDiscriminatorMismatch);
+        Pubkey::deserialize(&mut &data[BalanceConfig::DISCRIMINATOR.len()..])?
+    };
+    require_keys_eq!(admin, ctx.accounts.admin.key(), ErrorCode::ConstraintHasOne);
+    grow_account(
+        &info,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        BalanceConfig::SPACE,
+    )?;
+
+    let mut cfg = BalanceConfig::with_params(&params);
+    cfg.admin = admin;
+    cfg.version = 1;
+    cfg.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    let pending = ctx.accounts.pending_config.to_account_info();
+    if pending.owner == &crate::ID {
+        let admin = ctx.accounts.admin.to_account_info();
+        **admin.try_borrow_mut_lamports()? += pending.lamports();
+        **pending.try_borrow_mut_lamports()? = 0;
+        pending.assign(&System::id());
+        pending.resize(0)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use crate::state::config::BalanceConfig;
+use crate::state::consumable::Consumables;
+use crate::state::loot::LootBag;
+use crate::state::player::{LegacyPlayer, Player};
+use crate::state::profile::Profile;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct MigrateLegacyPlayer<'info> {
+    /// CHECK: a baseline `Player`, too short and at the wrong seeds to load
+    /// as one; the seeds and owner pin it down, the discriminator is
+    /// checked by hand
+    #[account(mut, seeds = [b"player", authority.key().as_ref()], bump, owner = crate::ID)]
+    pub legacy_player: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"profile", authority.key().as_ref()],
+        bump,
+        space = Profile::SPACE
+    )]
+    pub profile: Account<'info, Profile>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"player", authority.key().as_ref(), &[0]],
+        bump,
+        space = Player::SPACE
+    )]
+    pub player: Account<'info, Player>,
+    /// Baseline battles never made these, and `resolve_battle` needs them
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"loot_bag", authority.key().as_ref()],
+        bump,
+        space = LootBag::SPACE
+    )]
+    pub loot_bag: Account<'info, LootBag>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"consumables", authority.key().as_ref()],
+        bump,
+        space = Consumables::SPACE
+    )]
+    pub consumables: Account<'info, Consumables>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Move a character created before wallets held several into the wallet's
+/// first slot, index 0, and close the old account. Its class, XP, abilities
+/// and rating carry over; everything added since starts fresh. Must run
+/// before the wallet creates a character, which would take that slot
+/// (`CharacterSlotTaken`).
+pub fn handler(ctx: Context<MigrateLegacyPlayer>) -> Result<()> {
+    let info = ctx.accounts.legacy_player.to_account_info();
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        require!(data.starts_with(Player::DISCRIMINATOR), ErrorCode::AccountDiscriminatorMismatch);
+        LegacyPlayer::deserialize(&mut &data[Player::DISCRIMINATOR.len()..])?
+    };
+    let authority = ctx.accounts.authority.key();
+    require_keys_eq!(legacy.authority, authority, ErrorCode::ConstraintHasOne);
+
+    let index = ctx.accounts.profile.add_character(authority, ctx.accounts.config.max_characters)?;
+    require!(index == 0, CustomError::CharacterSlotTaken);
+    *ctx.accounts.player = Player {
+        xp: legacy.xp,
+        elo: legacy.elo,
+        ..Player::new(authority, legacy.class, legacy.abilities)
+    };
+    ctx.accounts.loot_bag.owner = authority;
+    ctx.accounts.consumables.owner = authority;
+
+    let payer = ctx.accounts.authority.to_account_info();
+    **payer.try_borrow_mut_lamports()? += info.lamports();
+    **info.try_borrow_mut_lamports()? = 0;
+    info.assign(&System::id());
+    info.resize(0)?;
+    Ok(())
+}
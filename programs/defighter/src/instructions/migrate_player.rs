@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::player::Player;
+use crate::utils::grow_account;
+
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct MigratePlayer<'info> {
+    /// CHECK: too short to load as a `Player` until grown; the seeds and
+    /// owner pin it down
+    #[account(mut, seeds = [b"player", authority.key().as_ref(), &[index]], bump, owner = crate::ID)]
+    pub player: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow a character written before `Player`'s latest fields existed. Fields
+/// are only ever appended and start at zero, so zero-filling the tail is the
+/// whole migration.
+pub fn handler(ctx: Context<MigratePlayer>, _index: u8) -> Result<()> {
+    let info = ctx.accounts.player.to_account_info();
+    grow_account(
+        &info,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        Player::SPACE,
+    )?;
+    let mut player = Player::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    player.version = Player::VERSION;
+    player.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
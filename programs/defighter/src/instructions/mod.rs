@@ -1,23 +1,113 @@
+#![allow(ambiguous_glob_reexports)]
+
 pub mod create_player;
 pub mod upgrade_ability;
+pub mod respec_abilities;
+pub mod prestige;
+pub mod preview_upgrade_costs;
+pub mod refill_energy;
 pub mod initiate_battle;
 pub mod commit_move;
 pub mod reveal_move;
 pub mod resolve_battle;
+pub mod cancel_battle;
 pub mod admin_update_config;
+pub mod update_config;
+pub mod apply_config_change;
+pub mod cancel_config_change;
+pub mod set_paused;
+pub mod set_guardian;
+pub mod propose_admin;
+pub mod accept_admin;
+pub mod create_admin_set;
+pub mod propose_config_change;
+pub mod approve_config_change;
+pub mod execute_config_change;
 pub mod init_config;
-// pub mod create_character_customization;  // TODO: Fix errors
-// pub mod update_character_customization; // TODO: Fix errors
+pub mod create_boss;
+pub mod start_boss_battle;
+pub mod commit_boss_move;
+pub mod reveal_boss_move;
+pub mod resolve_boss_battle;
+pub mod create_session;
+pub mod revoke_session;
+pub mod create_item;
+pub mod grant_item;
+pub mod equip_item;
+pub mod unequip_item;
+pub mod set_loot_table;
+pub mod claim_loot;
+pub mod set_recipe;
+pub mod craft_cosmetic;
+pub mod salvage_cosmetic;
+pub mod grant_boost;
+pub mod set_nft_collection;
+pub mod verify_nft;
+pub mod revoke_nft_perk;
+pub mod tokenize_player;
+pub mod claim_player;
+pub mod transfer_player;
+pub mod close_player;
+pub mod create_character_customization;
+pub mod update_character_customization;
+pub mod migrate_player;
+pub mod migrate_config;
+pub mod migrate_battle;
+pub mod migrate_legacy_player;
 
 pub use create_player::*;
 pub use upgrade_ability::*;
+pub use respec_abilities::*;
+pub use prestige::*;
+pub use preview_upgrade_costs::*;
+pub use refill_energy::*;
 pub use initiate_battle::*;
 pub use commit_move::*;
 pub use reveal_move::*;
 pub use resolve_battle::*;
+pub use cancel_battle::*;
 pub use admin_update_config::*;
+pub use update_config::*;
+pub use apply_config_change::*;
+pub use cancel_config_change::*;
+pub use set_paused::*;
+pub use set_guardian::*;
+pub use propose_admin::*;
+pub use accept_admin::*;
+pub use create_admin_set::*;
+pub use propose_config_change::*;
+pub use approve_config_change::*;
+pub use execute_config_change::*;
 pub use init_config::*;
-// pub use create_character_customization::*;
-// pub use update_character_customization::*;
+pub use create_boss::*;
+pub use start_boss_battle::*;
+pub use commit_boss_move::*;
+pub use reveal_boss_move::*;
+pub use resolve_boss_battle::*;
+pub use create_session::*;
+pub use revoke_session::*;
+pub use create_item::*;
+pub use grant_item::*;
+pub use equip_item::*;
+pub use unequip_item::*;
+pub use set_loot_table::*;
+pub use claim_loot::*;
+pub use set_recipe::*;
+pub use craft_cosmetic::*;
+pub use salvage_cosmetic::*;
+pub use grant_boost::*;
+pub use set_nft_collection::*;
+pub use verify_nft::*;
+pub use revoke_nft_perk::*;
+pub use tokenize_player::*;
+pub use claim_player::*;
+pub use transfer_player::*;
+pub use close_player::*;
+pub use create_character_customization::*;
+pub use update_character_customization::*;
+pub use migrate_player::*;
+pub use migrate_config::*;
+pub use migrate_battle::*;
+pub use migrate_legacy_player::*;
 
 
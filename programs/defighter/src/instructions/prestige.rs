@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::{player::Player, config::BalanceConfig};
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct Prestige<'info> {
+    #[account(mut, seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
+    pub player: Account<'info, Player>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
+}
+
+/// With every ability at `max_level`, start them over at 0 for another
+/// prestige rank: `prestige_power_bps` more power each (up to
+/// `MAX_PRESTIGE_POWER_BPS`) and the `prestige_outfit`. XP is kept.
+pub fn handler(ctx: Context<Prestige>) -> Result<()> {
+    ctx.accounts.player.require_holder(ctx.accounts.character_token.as_deref())?;
+    let player = &mut ctx.accounts.player;
+    let cfg = &ctx.accounts.config;
+    cfg.require_unpaused(BalanceConfig::PAUSE_UPGRADES)?;
+    require!(player.open_battles == 0, CustomError::OpenBattles);
+    require!(player.abilities.iter().all(|level| *level >= cfg.max_level), CustomError::AbilitiesNotMaxed);
+    require!(player.prestige < u8::MAX, CustomError::MaxLevel);
+
+    player.prestige += 1;
+    player.abilities = [0; 3];
+    player.xp_spent = 0;
+    emit!(crate::events::Prestiged {
+        player: player.key(),
+        rank: player.prestige,
+        power_bps: player.prestige_power_bps(cfg),
+    });
+    Ok(())
+}
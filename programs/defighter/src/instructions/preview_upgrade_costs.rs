@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use crate::state::{player::Player, config::BalanceConfig};
+use crate::errors::CustomError;
+use crate::logic::math::upgrade_cost;
+
+/// Most levels one preview returns, keeping the result well under the
+/// 1 KiB return-data limit
+pub const MAX_COST_PREVIEW: u8 = 32;
+
+#[derive(Accounts)]
+pub struct PreviewUpgradeCosts<'info> {
+    pub player: Account<'info, Player>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+}
+
+/// XP costs of the next `count` levels of `ability_index`, stopping at
+/// `max_level`. Read-only; clients call it as a view.
+pub fn handler(ctx: Context<PreviewUpgradeCosts>, ability_index: u8, count: u8) -> Result<Vec<u64>> {
+    let player = &ctx.accounts.player;
+    let cfg = &ctx.accounts.config;
+    let level = *player.abilities.get(ability_index as usize).ok_or(CustomError::InvalidAbility)?;
+    let count = count.min(MAX_COST_PREVIEW) as u16;
+    let last = level.saturating_add(count).min(cfg.max_level);
+    Ok((level.saturating_add(1)..=last).map(|next| upgrade_cost(next, cfg)).collect())
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use crate::state::config::BalanceConfig;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(mut, seeds = [b"config"], bump, has_one = admin)]
+    pub config: Account<'info, BalanceConfig>,
+    pub admin: Signer<'info>,
+}
+
+/// First half of an admin transfer; proposing again replaces the candidate.
+pub fn handler(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    set_pending_admin(&mut ctx.accounts.config, new_admin);
+    Ok(())
+}
+
+pub(crate) fn set_pending_admin(cfg: &mut BalanceConfig, new_admin: Pubkey) {
+    cfg.pending_admin = Some(new_admin);
+    emit!(crate::events::AdminProposed { admin: cfg.admin, pending_admin: new_admin });
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::admin::{AdminSet, ConfigProposal, ProposalAction};
+
+#[derive(Accounts)]
+pub struct ProposeConfigChange<'info> {
+    #[account(mut)]
+    pub admin_set: Account<'info, AdminSet>,
+    #[account(
+        init,
+        payer = proposer,
+        seeds = [b"proposal", admin_set.key().as_ref(), &admin_set.proposal_count.to_le_bytes()],
+        bump,
+        space = ConfigProposal::SPACE
+    )]
+    pub proposal: Account<'info, ConfigProposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Open a proposal; the proposer's approval is counted straight away.
+pub fn handler(ctx: Context<ProposeConfigChange>, action: ProposalAction) -> Result<()> {
+    let admin_set = &mut ctx.accounts.admin_set;
+    let proposer = ctx.accounts.proposer.key();
+    let index = admin_set.member_index(&proposer)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.admin_set = admin_set.key();
+    proposal.id = admin_set.proposal_count;
+    proposal.proposer = proposer;
+    proposal.action = action;
+    proposal.approvals = 1 << index;
+    proposal.executed = false;
+    proposal.created_slot = ctx.accounts.clock.slot;
+    admin_set.proposal_count = admin_set.proposal_count.saturating_add(1);
+
+    emit!(crate::events::ConfigChangeProposed {
+        admin_set: admin_set.key(),
+        proposal: proposal.key(),
+        id: proposal.id,
+        proposer,
+        action,
+    });
+    emit!(crate::events::ConfigChangeApproved { proposal: proposal.key(), member: proposer, approvals: 1 });
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::{player::Player, config::BalanceConfig};
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct RefillEnergy<'info> {
+    #[account(mut, seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
+    pub player: Account<'info, Player>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
+}
+
+/// Trade XP for up to `amount` energy. Only what fits under the cap is bought.
+pub fn handler(ctx: Context<RefillEnergy>, amount: u16) -> Result<()> {
+    ctx.accounts.player.require_holder(ctx.accounts.character_token.as_deref())?;
+    let player = &mut ctx.accounts.player;
+    let cfg = &ctx.accounts.config;
+    let now = ctx.accounts.clock.slot;
+    let room = cfg.energy_max - player.energy(now, cfg);
+    let amount = amount.min(room);
+    require!(amount > 0, CustomError::EnergyFull);
+    let xp_cost = cfg.energy_xp_cost.saturating_mul(amount as u64);
+    require!(player.xp >= xp_cost, CustomError::NotEnoughXP);
+    player.xp -= xp_cost;
+    player.add_energy(amount, now, cfg);
+    emit!(crate::events::EnergyRefilled {
+        player: player.key(),
+        amount,
+        xp_cost,
+        energy: player.energy(now, cfg),
+    });
+    Ok(())
+}
@@ -1,17 +1,30 @@
 use anchor_lang::prelude::*;
-use crate::state::{battle::{Battle, BattleState}, player::{Player, FighterClass}};
+use crate::state::{battle::{Battle, BattleState}, player::Player};
 use crate::state::config::BalanceConfig;
+use crate::state::consumable::Consumables;
+use crate::state::crafting::Material;
+use crate::state::loot::{LootBag, LootDrop};
 use crate::errors::CustomError;
-use crate::logic::{calculate_battle_outcome, tie_break_entropy};
+use crate::logic::{loot_entropy, next_pity, resolve_round, roll_boost, roll_loot, tie_break_entropy, BattleSide, RoundInput};
 
 #[derive(Accounts)]
 pub struct ResolveBattle<'info> {
     #[account(mut)]
     pub battle: Account<'info, Battle>,
-    #[account(mut, seeds = [b"player", battle.challenger.as_ref()], bump)]
+    #[account(mut, address = battle.challenger)]
     pub player_challenger: Account<'info, Player>,
-    #[account(mut, seeds = [b"player", battle.opponent.as_ref()], bump)]
+    #[account(mut, address = battle.opponent)]
     pub player_opponent: Account<'info, Player>,
+    /// Loot and boosts go to the characters' wallets
+    #[account(mut, seeds = [b"loot_bag", player_challenger.authority.as_ref()], bump)]
+    pub challenger_loot: Account<'info, LootBag>,
+    #[account(mut, seeds = [b"loot_bag", player_opponent.authority.as_ref()], bump)]
+    pub opponent_loot: Account<'info, LootBag>,
+    #[account(mut, seeds = [b"consumables", player_challenger.authority.as_ref()], bump)]
+    pub challenger_consumables: Account<'info, Consumables>,
+    #[account(mut, seeds = [b"consumables", player_opponent.authority.as_ref()], bump)]
+    pub opponent_consumables: Account<'info, Consumables>,
+    /// Read for whether a missing commit was blocked by a pause
     #[account(seeds = [b"config"], bump)]
     pub config: Account<'info, BalanceConfig>,
     pub clock: Sysvar<'info, Clock>,
@@ -26,27 +39,28 @@ pub fn handler(ctx: Context<ResolveBattle>) -> Result<()> {
     let both_revealed = battle.reveal_challenger.is_some() && battle.reveal_opponent.is_some();
     require!(both_revealed || after_reveal_deadline, CustomError::NotReadyToResolve);
 
-    let mut winner = None;
-    let cfg = &ctx.accounts.config;
+    let winner;
+    // Rules as of initiation, not whatever the config says now
+    let cfg = &BalanceConfig::with_params(&battle.rules);
 
     if both_revealed {
         // Get moves and calculate battle outcomes
         let c_move = battle.reveal_challenger.unwrap();
         let o_move = battle.reveal_opponent.unwrap();
         
-        // Get VRF for each move
+        // Each side's roll mixes both revealed salts, so neither could predict it
         let c_vrf = tie_break_entropy(
             c_move as u8,
             o_move as u8,
-            &[0u8; 32], // TODO: Use actual commitment salt when available
-            &[0u8; 32], // TODO: Use actual commitment salt when available
+            &battle.salt_challenger,
+            &battle.salt_opponent,
             &battle.key(),
         );
         let o_vrf = tie_break_entropy(
             o_move as u8,
             c_move as u8,
-            &[0u8; 32], // TODO: Use actual commitment salt when available
-            &[0u8; 32], // TODO: Use actual commitment salt when available
+            &battle.salt_opponent,
+            &battle.salt_challenger,
             &battle.key(),
         );
         
@@ -56,89 +70,41 @@ pub fn handler(ctx: Context<ResolveBattle>) -> Result<()> {
         msg!("DEBUG: Opponent move: {:?}, attacking challenger (class: {:?}) with {} HP", 
             o_move, ctx.accounts.player_challenger.class, battle.challenger_hp);
 
-        // Calculate damage for each player's move
-        let challenger_outcome = calculate_battle_outcome(
-            c_move,
-            &ctx.accounts.player_challenger,
-            ctx.accounts.player_opponent.class,
-            battle.opponent_hp,
-            c_vrf,
+        // Calculate damage for each player's move and pick the winner
+        let resolution = resolve_round(
+            RoundInput {
+                player: &ctx.accounts.player_challenger,
+                move_choice: c_move,
+                hp: battle.challenger_hp,
+                vrf_result: c_vrf,
+                boost: battle.boost_challenger,
+            },
+            RoundInput {
+                player: &ctx.accounts.player_opponent,
+                move_choice: o_move,
+                hp: battle.opponent_hp,
+                vrf_result: o_vrf,
+                boost: battle.boost_opponent,
+            },
+            cfg,
         );
-        
-        let opponent_outcome = calculate_battle_outcome(
-            o_move,
-            &ctx.accounts.player_opponent,
-            ctx.accounts.player_challenger.class,
-            battle.challenger_hp,
-            o_vrf,
-        );
-        
+        let challenger_outcome = resolution.challenger;
+        let opponent_outcome = resolution.opponent;
+
         // DEBUG: Log the outcomes
         msg!("DEBUG: Challenger outcome - damage: {}, remaining_hp: {}", 
             challenger_outcome.damage_dealt, challenger_outcome.remaining_hp);
         msg!("DEBUG: Opponent outcome - damage: {}, remaining_hp: {}", 
             opponent_outcome.damage_dealt, opponent_outcome.remaining_hp);
-        
-        // Store starting HP for true damage calculation
-        let challenger_start_hp = battle.challenger_hp as i32;
-        let opponent_start_hp = battle.opponent_hp as i32;
-        
+
         // Apply the correctly calculated remaining HP
         battle.challenger_hp = opponent_outcome.remaining_hp;  // Challenger's HP after opponent's attack
         battle.opponent_hp = challenger_outcome.remaining_hp;  // Opponent's HP after challenger's attack
-        
-        // Calculate true final HP (allowing negative values for better tiebreaking)
-        let challenger_true_hp = challenger_start_hp - (opponent_outcome.damage_dealt as i32);
-        let opponent_true_hp = opponent_start_hp - (challenger_outcome.damage_dealt as i32);
-        
-        // Determine winner based on remaining HP (using true HP for ties when both die)
-        if battle.challenger_hp == 0 && battle.opponent_hp == 0 {
-            // Both died - whoever has less negative HP wins (took less overkill damage)
-            if challenger_true_hp > opponent_true_hp {
-                winner = Some(battle.challenger); // e.g., -20 > -50
-            } else if opponent_true_hp > challenger_true_hp {
-                winner = Some(battle.opponent);
-            } else {
-                // True tie (same negative HP) - use damage dealt as tiebreaker
-                if challenger_outcome.damage_dealt > opponent_outcome.damage_dealt {
-                    winner = Some(battle.challenger);
-                } else if opponent_outcome.damage_dealt > challenger_outcome.damage_dealt {
-                    winner = Some(battle.opponent);
-                } else {
-                    // Ultimate tie - use VRF
-                    if cfg.tie_break_rand {
-                        winner = Some(if c_vrf % 2 == 0 { battle.challenger } else { battle.opponent });
-                    } else {
-                        winner = Some(battle.challenger);
-                    }
-                }
-            }
-        } else if battle.challenger_hp == 0 {
-            winner = Some(battle.opponent);
-        } else if battle.opponent_hp == 0 {
-            winner = Some(battle.challenger);
-        } else {
-            // Neither died - higher HP wins, or higher damage dealt as tiebreaker
-            if battle.challenger_hp > battle.opponent_hp {
-                winner = Some(battle.challenger);
-            } else if battle.opponent_hp > battle.challenger_hp {
-                winner = Some(battle.opponent);
-            } else {
-                // Same HP remaining - higher damage dealt wins
-                if challenger_outcome.damage_dealt > opponent_outcome.damage_dealt {
-                    winner = Some(battle.challenger);
-                } else if opponent_outcome.damage_dealt > challenger_outcome.damage_dealt {
-                    winner = Some(battle.opponent);
-                } else {
-                    // True tie - use VRF
-                    if cfg.tie_break_rand {
-                        winner = Some(if c_vrf % 2 == 0 { battle.challenger } else { battle.opponent });
-                    } else {
-                        winner = Some(battle.challenger);
-                    }
-                }
-            }
-        }
+
+        winner = Some(match resolution.winner {
+            BattleSide::Challenger => battle.challenger,
+            BattleSide::Opponent => battle.opponent,
+        });
 
         emit!(crate::events::BattleResolved {
             battle: battle.key(),
@@ -149,7 +115,58 @@ pub fn handler(ctx: Context<ResolveBattle>) -> Result<()> {
             opponent_damage: opponent_outcome.damage_dealt,
         });
 
-        award_xp(&mut ctx.accounts.player_challenger, &mut ctx.accounts.player_opponent, winner.unwrap(), cfg);
+        award_xp(
+            &mut ctx.accounts.player_challenger,
+            &mut ctx.accounts.player_opponent,
+            winner == Some(battle.challenger),
+            cfg,
+            battle.xp_scale_bps,
+        );
+
+        // Farmed-out rematches earn no XP and no loot either
+        if battle.xp_scale_bps > 0 {
+            let (winner_bag, loser_bag) = match resolution.winner {
+                BattleSide::Challenger => (&mut ctx.accounts.challenger_loot, &mut ctx.accounts.opponent_loot),
+                BattleSide::Opponent => (&mut ctx.accounts.opponent_loot, &mut ctx.accounts.challenger_loot),
+            };
+            let roll = loot_entropy(c_vrf, o_vrf, &battle.key());
+            let material = Material::from_roll(roll);
+            earn_materials(winner_bag, battle.key(), material, cfg.material_win);
+            earn_materials(loser_bag, battle.key(), material, cfg.material_loss);
+            loser_bag.pity = next_pity(loser_bag.pity, None);
+            drop_loot(winner_bag, battle.key(), roll, cfg);
+
+            let winner_consumables = match resolution.winner {
+                BattleSide::Challenger => &mut ctx.accounts.challenger_consumables,
+                BattleSide::Opponent => &mut ctx.accounts.opponent_consumables,
+            };
+            if let Some(boost) = roll_boost(roll, cfg) {
+                let balance = winner_consumables.add(boost, 1);
+                emit!(crate::events::BoostGranted {
+                    owner: winner_consumables.owner,
+                    boost,
+                    amount: 1,
+                    balance,
+                    battle: Some(battle.key()),
+                });
+            }
+        }
+    } else if (battle.commit_challenger.is_none() || battle.commit_opponent.is_none())
+        && ctx.accounts.config.commits_paused_between(battle.created_slot, battle.commit_deadline_slot)
+    {
+        // A commit went missing while commits were paused: nobody forfeits,
+        // and whoever paid for the battle gets it back
+        winner = None;
+        let now = ctx.accounts.clock.slot;
+        let cost = battle.rules.battle_energy_cost;
+        refund_energy(&mut ctx.accounts.player_challenger, cost, now, cfg);
+        if battle.commit_opponent.is_some() {
+            refund_energy(&mut ctx.accounts.player_opponent, cost, now, cfg);
+        }
+        emit!(crate::events::BattleCancelled {
+            battle: battle.key(),
+            challenger: battle.challenger,
+        });
     } else {
         // Forfeit path
         if battle.reveal_challenger.is_some() && battle.reveal_opponent.is_none() {
@@ -172,35 +189,77 @@ pub fn handler(ctx: Context<ResolveBattle>) -> Result<()> {
             challenger_damage: 0,
             opponent_damage: 0,
         });
-        award_xp(&mut ctx.accounts.player_challenger, &mut ctx.accounts.player_opponent, winner.unwrap(), cfg);
+        // Nobody played: close it out, but there's nothing to reward
+        let anyone_committed = battle.commit_challenger.is_some() || battle.commit_opponent.is_some();
+        let xp_scale_bps = if anyone_committed { battle.xp_scale_bps } else { 0 };
+        award_xp(
+            &mut ctx.accounts.player_challenger,
+            &mut ctx.accounts.player_opponent,
+            winner == Some(battle.challenger),
+            cfg,
+            xp_scale_bps,
+        );
     }
 
     battle.winner = winner;
     battle.state = BattleState::Resolved;
+    let challenger = &mut ctx.accounts.player_challenger;
+    challenger.open_battles = challenger.open_battles.saturating_sub(1);
+    // The opponent only counted the battle once they committed
+    if battle.commit_opponent.is_some() {
+        let opponent = &mut ctx.accounts.player_opponent;
+        opponent.open_battles = opponent.open_battles.saturating_sub(1);
+    }
     Ok(())
 }
 
-fn level_for_class(player: &Player, class: FighterClass) -> u16 {
-    match class {
-        FighterClass::Shitposter => player.abilities[0],
-        FighterClass::Builder => player.abilities[1],
-        FighterClass::VC => player.abilities[2],
+/// Roll the winner's loot and keep its pity count
+fn drop_loot(bag: &mut LootBag, battle: Pubkey, roll: u64, cfg: &BalanceConfig) {
+    let rarity = roll_loot(roll, bag.pity, cfg);
+    bag.pity = next_pity(bag.pity, rarity);
+    let Some(rarity) = rarity else { return };
+    let stored = bag.drops.len() < LootBag::MAX_DROPS;
+    if stored {
+        bag.drops.push(LootDrop { rarity, roll });
+        bag.total_drops = bag.total_drops.saturating_add(1);
+    }
+    emit!(crate::events::LootDropped { player: bag.owner, battle, rarity, roll, pity: bag.pity, stored });
+}
+
+fn earn_materials(bag: &mut LootBag, battle: Pubkey, material: Material, amount: u16) {
+    if amount == 0 {
+        return;
     }
+    let balance = &mut bag.materials[material as usize];
+    *balance = balance.saturating_add(amount as u32);
+    emit!(crate::events::MaterialsEarned { player: bag.owner, battle, material, amount, balance: *balance });
+}
+
+/// Give back a battle's energy cost
+pub(crate) fn refund_energy(player: &mut Account<Player>, amount: u16, now: u64, cfg: &BalanceConfig) {
+    let amount = player.add_energy(amount, now, cfg);
+    emit!(crate::events::EnergyRefilled {
+        player: player.key(),
+        amount,
+        xp_cost: 0,
+        energy: player.energy(now, cfg),
+    });
 }
 
-fn award_xp(pc: &mut Player, po: &mut Player, winner: Pubkey, cfg: &BalanceConfig) {
-    if winner == pc.authority {
-        pc.xp = pc.xp.saturating_add(cfg.xp_base);
-        let delta = (cfg.xp_loser_bps as u64).saturating_mul(cfg.xp_base) / 10000;
+fn award_xp(pc: &mut Account<Player>, po: &mut Account<Player>, challenger_won: bool, cfg: &BalanceConfig, xp_scale_bps: u16) {
+    let xp_base = (xp_scale_bps as u64).saturating_mul(cfg.xp_base) / 10000;
+    if challenger_won {
+        pc.xp = pc.xp.saturating_add(xp_base);
+        let delta = (cfg.xp_loser_bps as u64).saturating_mul(xp_base) / 10000;
         po.xp = po.xp.saturating_add(delta);
-        emit!(crate::events::XpAwarded { player: pc.authority, delta: cfg.xp_base as i64 });
-        emit!(crate::events::XpAwarded { player: po.authority, delta: delta as i64 });
+        emit!(crate::events::XpAwarded { player: pc.key(), delta: xp_base as i64 });
+        emit!(crate::events::XpAwarded { player: po.key(), delta: delta as i64 });
     } else {
-        po.xp = po.xp.saturating_add(cfg.xp_base);
-        let delta = (cfg.xp_loser_bps as u64).saturating_mul(cfg.xp_base) / 10000;
+        po.xp = po.xp.saturating_add(xp_base);
+        let delta = (cfg.xp_loser_bps as u64).saturating_mul(xp_base) / 10000;
         pc.xp = pc.xp.saturating_add(delta);
-        emit!(crate::events::XpAwarded { player: po.authority, delta: cfg.xp_base as i64 });
-        emit!(crate::events::XpAwarded { player: pc.authority, delta: delta as i64 });
+        emit!(crate::events::XpAwarded { player: po.key(), delta: xp_base as i64 });
+        emit!(crate::events::XpAwarded { player: pc.key(), delta: delta as i64 });
     }
 }
 
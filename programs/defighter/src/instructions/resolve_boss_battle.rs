@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use crate::state::battle::BattleState;
+use crate::state::boss::{Boss, BossBattle};
+use crate::state::config::BalanceConfig;
+use crate::state::player::Player;
+use crate::errors::CustomError;
+use crate::logic::{boss_entropy, resolve_boss_round, tie_break_entropy, RoundInput};
+
+#[derive(Accounts)]
+pub struct ResolveBossBattle<'info> {
+    #[account(mut, has_one = boss)]
+    pub battle: Account<'info, BossBattle>,
+    #[account(mut)]
+    pub boss: Account<'info, Boss>,
+    #[account(mut, address = battle.character)]
+    pub player_account: Account<'info, Player>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn handler(ctx: Context<ResolveBossBattle>) -> Result<()> {
+    let battle = &mut ctx.accounts.battle;
+    let boss = &mut ctx.accounts.boss;
+    require!(!matches!(battle.state, BattleState::Resolved), CustomError::AlreadyResolved);
+    let after_reveal_deadline = ctx.accounts.clock.slot > battle.reveal_deadline_slot;
+    require!(battle.reveal.is_some() || after_reveal_deadline, CustomError::NotReadyToResolve);
+
+    let battle_key = battle.key();
+    let boss_key = boss.key();
+    let mut player_damage = 0;
+    let mut boss_damage = 0;
+    let xp: i64;
+
+    match (battle.reveal, battle.boss_move) {
+        // A raid boss can fall to someone else while this fight was open
+        (Some(_), Some(_)) if boss.is_defeated() => {
+            battle.player_won = Some(false);
+            xp = boss.xp_loss as i64;
+        }
+        (Some(player_move), Some(boss_move)) => {
+            let entropy = boss_entropy(&battle.salt, &battle_key, &battle.slot_hash);
+            let player_roll = tie_break_entropy(player_move as u8, boss_move as u8, &battle.salt, &entropy, &battle_key);
+            let boss_roll = tie_break_entropy(boss_move as u8, player_move as u8, &entropy, &battle.salt, &battle_key);
+            let round = resolve_boss_round(
+                RoundInput {
+                    player: &ctx.accounts.player_account,
+                    move_choice: player_move,
+                    hp: battle.player_hp,
+                    vrf_result: player_roll,
+                    boost: None,
+                },
+                boss,
+                boss_key,
+                boss_move,
+                boss_roll,
+                &BalanceConfig::with_params(&battle.rules),
+            );
+            player_damage = round.player.damage_dealt;
+            boss_damage = round.boss.damage_dealt;
+            battle.player_hp = round.boss.remaining_hp;
+            battle.boss_hp = round.boss_hp;
+            battle.player_won = Some(round.player_won);
+
+            if boss.shared_hp {
+                boss.hp = round.boss_hp;
+                if round.player_won {
+                    boss.defeated_by = Some(battle.player);
+                }
+            }
+            xp = if round.player_won { boss.xp_win } else { boss.xp_loss } as i64;
+        }
+        // Never revealed: a loss that costs what a revealed loss would have
+        // paid, so walking away from a bad draw never beats playing it out
+        _ => {
+            battle.player_won = Some(false);
+            xp = -(boss.xp_loss as i64);
+        }
+    }
+
+    boss.fights = boss.fights.saturating_add(1);
+    battle.state = BattleState::Resolved;
+
+    let player = &mut ctx.accounts.player_account;
+    player.open_battles = player.open_battles.saturating_sub(1);
+    if xp != 0 {
+        player.xp = player.xp.saturating_add_signed(xp);
+        emit!(crate::events::XpAwarded { player: player.key(), delta: xp });
+    }
+    emit!(crate::events::BossBattleResolved {
+        battle: battle_key,
+        boss: boss_key,
+        player: battle.player,
+        player_won: battle.player_won == Some(true),
+        boss_move: battle.boss_move,
+        player_damage,
+        boss_damage,
+        boss_hp: battle.boss_hp,
+    });
+    Ok(())
+}
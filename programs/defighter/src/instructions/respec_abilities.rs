@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::state::{player::Player, config::BalanceConfig};
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct RespecAbilities<'info> {
+    #[account(mut, seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
+    pub player: Account<'info, Player>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
+}
+
+/// Reset every ability to level 0, refunding `respec_refund_bps` of the XP
+/// actually paid for the levels, whatever the upgrade curve is now. Not
+/// mid-battle, where the levels are about to be used.
+pub fn handler(ctx: Context<RespecAbilities>) -> Result<()> {
+    ctx.accounts.player.require_holder(ctx.accounts.character_token.as_deref())?;
+    let player = &mut ctx.accounts.player;
+    let cfg = &ctx.accounts.config;
+    cfg.require_unpaused(BalanceConfig::PAUSE_UPGRADES)?;
+    require!(player.open_battles == 0, CustomError::OpenBattles);
+
+    let levels = player.abilities;
+    let refund = ((player.xp_spent as u128) * (cfg.respec_refund_bps as u128) / 10_000) as u64;
+    player.xp = player.xp.saturating_add(refund);
+    player.abilities = [0; 3];
+    player.xp_spent = 0;
+    emit!(crate::events::AbilitiesRespecced { player: player.key(), levels, refund });
+    Ok(())
+}
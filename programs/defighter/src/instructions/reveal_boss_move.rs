@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::state::battle::BattleState;
+use crate::state::boss::{Boss, BossBattle};
+use crate::state::player::{MoveChoice, Player};
+use crate::errors::CustomError;
+use crate::logic::{boss_entropy, boss_move, commitment_hash, slot_hash_after};
+use crate::state::session::{acting_authority, SessionToken};
+
+#[derive(Accounts)]
+pub struct RevealBossMove<'info> {
+    #[account(mut, has_one = boss)]
+    pub battle: Account<'info, BossBattle>,
+    pub boss: Account<'info, Boss>,
+    /// The player's wallet, or a session key acting for it
+    pub player: Signer<'info>,
+    #[account(address = battle.character @ CustomError::NotInBattle)]
+    pub player_account: Account<'info, Player>,
+    /// CHECK: the SlotHashes sysvar, parsed by hand since it's too big to deserialize
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    pub clock: Sysvar<'info, Clock>,
+    pub session: Option<Account<'info, SessionToken>>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
+}
+
+pub fn handler(ctx: Context<RevealBossMove>, move_choice: MoveChoice, salt: [u8; 32]) -> Result<()> {
+    ctx.accounts.player_account.require_holder(ctx.accounts.character_token.as_deref())?;
+    let key = acting_authority(
+        ctx.accounts.player.key(),
+        ctx.accounts.session.as_deref(),
+        SessionToken::REVEAL,
+        ctx.accounts.clock.slot,
+    )?;
+    let battle = &mut ctx.accounts.battle;
+    require_keys_eq!(battle.player, key, CustomError::NotInBattle);
+    require!(matches!(battle.state, BattleState::WaitingForReveals), CustomError::NotReadyToResolve);
+    require!(battle.reveal.is_none(), CustomError::AlreadyRevealed);
+    require!(ctx.accounts.clock.slot <= battle.reveal_deadline_slot, CustomError::RevealDeadlinePassed);
+    require!(move_choice.class() == ctx.accounts.player_account.class, CustomError::InvalidClassMove);
+
+    let battle_key = battle.key();
+    let expected = commitment_hash(move_choice, &salt, &battle.player, &battle_key);
+    require!(battle.commitment == Some(expected), CustomError::InvalidReveal);
+
+    // The boss picks from a slot hash that didn't exist when the move was
+    // committed, so the player can't steer it by choosing a salt
+    let slot_hash = slot_hash_after(&ctx.accounts.slot_hashes.try_borrow_data()?, battle.commit_slot)
+        .ok_or(CustomError::SlotHashUnavailable)?;
+    let entropy = boss_entropy(&salt, &battle_key, &slot_hash);
+    battle.reveal = Some(move_choice);
+    battle.salt = salt;
+    battle.slot_hash = slot_hash;
+    battle.boss_move = Some(boss_move(&ctx.accounts.boss, &entropy));
+
+    emit!(crate::events::MoveRevealed { battle: battle_key, player: battle.player, move_choice, boost: None });
+    Ok(())
+}
@@ -1,53 +1,75 @@
 use anchor_lang::prelude::*;
 use crate::state::{battle::{Battle, BattleState}, player::{Player, MoveChoice}};
 use crate::errors::CustomError;
+use crate::logic::boosted_commitment_hash;
+use crate::state::consumable::{Boost, Consumables};
+use crate::state::session::{acting_authority, SessionToken};
 
 #[derive(Accounts)]
 pub struct RevealMove<'info> {
     #[account(mut)]
     pub battle: Account<'info, Battle>,
+    /// The player's wallet, or a session key acting for it
     pub player: Signer<'info>,
-    #[account(
-        mut,
-        seeds = [b"player", player.key().as_ref()],
-        bump,
-        constraint = player_account.authority == player.key() @ CustomError::NotInBattle
-    )]
-    /// CHECK: the linked player account of signer
+    /// The acting player's character in the battle; checked against the
+    /// session in the handler
+    #[account(mut)]
     pub player_account: Account<'info, Player>,
     pub clock: Sysvar<'info, Clock>,
+    /// The acting player's boosts; only needed to reveal one
+    #[account(mut)]
+    pub consumables: Option<Account<'info, Consumables>>,
+    pub session: Option<Account<'info, SessionToken>>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
 }
 
-pub fn handler(ctx: Context<RevealMove>, move_choice: MoveChoice, salt: [u8; 32]) -> Result<()> {
+pub fn handler(ctx: Context<RevealMove>, move_choice: MoveChoice, salt: [u8; 32], boost: Option<Boost>) -> Result<()> {
+    ctx.accounts.player_account.require_holder(ctx.accounts.character_token.as_deref())?;
     let battle = &mut ctx.accounts.battle;
     require!(matches!(battle.state, BattleState::WaitingForReveals), CustomError::NotReadyToResolve);
     require!(ctx.accounts.clock.slot <= battle.reveal_deadline_slot, CustomError::RevealDeadlinePassed);
-    let key = ctx.accounts.player.key();
+    let key = acting_authority(
+        ctx.accounts.player.key(),
+        ctx.accounts.session.as_deref(),
+        SessionToken::REVEAL,
+        ctx.accounts.clock.slot,
+    )?;
+    require_keys_eq!(ctx.accounts.player_account.authority, key, CustomError::NotInBattle);
 
     // Enforce class-move consistency
     require!(move_choice.class() == ctx.accounts.player_account.class, CustomError::InvalidClassMove);
 
     let battle_key = battle.key();
-    let expected = anchor_lang::solana_program::keccak::hashv(&[
-        &[move_choice.to_byte()],
-        &salt,
-        key.as_ref(),
-        battle_key.as_ref(),
-    ]).0;
-
-    if key == battle.challenger {
+    let expected = boosted_commitment_hash(move_choice, boost, &salt, &key, &battle_key);
+
+    let character = ctx.accounts.player_account.key();
+    if character == battle.challenger {
         require!(battle.commit_challenger == Some(expected), CustomError::InvalidReveal);
         require!(battle.reveal_challenger.is_none(), CustomError::AlreadyRevealed);
         battle.reveal_challenger = Some(move_choice);
-    } else if key == battle.opponent {
+        battle.boost_challenger = boost;
+        battle.salt_challenger = salt;
+    } else if character == battle.opponent {
         require!(battle.commit_opponent == Some(expected), CustomError::InvalidReveal);
         require!(battle.reveal_opponent.is_none(), CustomError::AlreadyRevealed);
         battle.reveal_opponent = Some(move_choice);
+        battle.boost_opponent = boost;
+        battle.salt_opponent = salt;
     } else {
         return err!(CustomError::NotInBattle);
     }
 
-    emit!(crate::events::MoveRevealed { battle: battle.key(), player: key, move_choice });
+    // Burned only now, so a boost that is never revealed is never spent
+    if let Some(boost) = boost {
+        let consumables = ctx.accounts.consumables.as_mut().ok_or(CustomError::NoBoost)?;
+        require_keys_eq!(consumables.owner, key, CustomError::NoBoost);
+        let remaining = consumables.burn(boost)?;
+        emit!(crate::events::BoostUsed { battle: battle_key, player: key, boost, remaining });
+    }
+
+    emit!(crate::events::MoveRevealed { battle: battle_key, player: key, move_choice, boost });
     Ok(())
 }
 
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::logic::nft::held_nft;
+use crate::state::customization::{CharacterCustomizationV1, CHARACTER_CUSTOM_SEED};
+use crate::state::nft::{NftCollection, NftPerk};
+use crate::state::player::Player;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct RevokeNftPerk<'info> {
+    #[account(seeds = [b"player", authority.key().as_ref(), &[player.index]], bump)]
+    pub player: Account<'info, Player>,
+    #[account(mut, seeds = [CHARACTER_CUSTOM_SEED, player.key().as_ref()], bump, has_one = player)]
+    pub character_customization: Account<'info, CharacterCustomizationV1>,
+    #[account(
+        mut,
+        seeds = [b"nft_perk", character_customization.key().as_ref(), nft_perk.collection.as_ref()],
+        bump,
+        close = authority
+    )]
+    pub nft_perk: Account<'info, NftPerk>,
+    #[account(seeds = [b"nft_collection", nft_perk.collection.as_ref()], bump)]
+    pub nft_collection: Account<'info, NftCollection>,
+    /// CHECK: only read through `held_nft`
+    #[account(address = nft_perk.token_account)]
+    pub token_account: UncheckedAccount<'info>,
+    /// CHECK: the player's wallet, refunded the perk's rent
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+}
+
+/// Permissionless: drop a perk whose NFT has left the wallet (or whose
+/// collection was delisted), taking its cosmetic off if it's worn and
+/// not otherwise unlocked.
+pub fn handler(ctx: Context<RevokeNftPerk>) -> Result<()> {
+    let perk = &ctx.accounts.nft_perk;
+    let held = held_nft(&ctx.accounts.token_account, &ctx.accounts.authority.key()) == Some(perk.mint);
+    require!(!held || !ctx.accounts.nft_collection.active, CustomError::NftStillHeld);
+
+    let custom = &mut ctx.accounts.character_customization;
+    let stripped = custom.is_worn(perk.kind, perk.index) && !custom.is_unlocked(perk.kind, perk.index);
+    if stripped {
+        custom.strip(perk.kind, perk.index);
+    }
+
+    emit!(crate::events::NftPerkRevoked {
+        player: ctx.accounts.player.key(),
+        collection: perk.collection,
+        mint: perk.mint,
+        stripped,
+    });
+    Ok(())
+}
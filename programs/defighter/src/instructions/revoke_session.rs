@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+use crate::state::session::SessionToken;
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    #[account(mut, has_one = authority, close = authority)]
+    pub session: Account<'info, SessionToken>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<RevokeSession>) -> Result<()> {
+    emit!(crate::events::SessionRevoked {
+        authority: ctx.accounts.authority.key(),
+        session_key: ctx.accounts.session.session_key,
+    });
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::state::crafting::{salvage_refund, CraftReceipt, Recipe};
+use crate::state::config::BalanceConfig;
+use crate::state::customization::{CharacterCustomizationV1, CHARACTER_CUSTOM_SEED};
+use crate::state::loot::LootBag;
+use crate::state::player::Player;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct SalvageCosmetic<'info> {
+    #[account(seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
+    pub player: Account<'info, Player>,
+    #[account(mut, seeds = [CHARACTER_CUSTOM_SEED, player.key().as_ref()], bump, has_one = player)]
+    pub character_customization: Account<'info, CharacterCustomizationV1>,
+    #[account(mut, seeds = [b"loot_bag", authority.key().as_ref()], bump)]
+    pub loot_bag: Account<'info, LootBag>,
+    #[account(seeds = [b"recipe".as_ref(), &[recipe.kind as u8], &[recipe.index]], bump)]
+    pub recipe: Account<'info, Recipe>,
+    #[account(
+        mut,
+        seeds = [b"crafted", player.key().as_ref(), &[recipe.kind as u8], &[recipe.index]],
+        bump,
+        close = authority
+    )]
+    pub craft_receipt: Account<'info, CraftReceipt>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
+}
+
+/// Give up a crafted cosmetic for `salvage_refund_bps` of the materials it
+/// was crafted with. XP is not refunded.
+pub fn handler(ctx: Context<SalvageCosmetic>) -> Result<()> {
+    ctx.accounts.player.require_holder(ctx.accounts.character_token.as_deref())?;
+    let config = &ctx.accounts.config;
+    config.require_unpaused(BalanceConfig::PAUSE_CUSTOMIZATION)?;
+    let recipe = &ctx.accounts.recipe;
+    let custom = &mut ctx.accounts.character_customization;
+    let bag = &mut ctx.accounts.loot_bag;
+    // Free cosmetics are never crafted, so they can't be salvaged either
+    require!(custom.is_crafted(recipe.kind, recipe.index), CustomError::CosmeticLocked);
+    require!(!custom.is_worn(recipe.kind, recipe.index), CustomError::CosmeticWorn);
+
+    let refund = salvage_refund(&ctx.accounts.craft_receipt.materials, config.salvage_refund_bps);
+    for (have, amount) in bag.materials.iter_mut().zip(refund) {
+        *have = have.saturating_add(amount);
+    }
+    custom.set_crafted(recipe.kind, recipe.index, false);
+
+    emit!(crate::events::CosmeticSalvaged {
+        player: ctx.accounts.player.key(),
+        kind: recipe.kind,
+        index: recipe.index,
+        refund,
+    });
+    Ok(())
+}
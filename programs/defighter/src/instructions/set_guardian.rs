@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+use crate::state::config::BalanceConfig;
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(mut, seeds = [b"config"], bump, has_one = admin)]
+    pub config: Account<'info, BalanceConfig>,
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+    set_guardian(&mut ctx.accounts.config, guardian);
+    Ok(())
+}
+
+pub(crate) fn set_guardian(cfg: &mut BalanceConfig, guardian: Option<Pubkey>) {
+    cfg.guardian = guardian;
+    emit!(crate::events::GuardianChanged { admin: cfg.admin, guardian });
+}
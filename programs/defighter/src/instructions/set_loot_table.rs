@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::admin::{AdminSet, ConfigProposal, ProposalAction};
+use crate::state::config::BalanceConfig;
+use crate::state::item::Item;
+use crate::state::loot::{LootEntry, LootTable};
+use crate::errors::CustomError;
+use super::execute_config_change::require_admin;
+
+/// The new table's `Item` accounts follow as remaining accounts, in order.
+#[derive(Accounts)]
+pub struct SetLootTable<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"loot_table"],
+        bump,
+        space = LootTable::SPACE
+    )]
+    pub loot_table: Account<'info, LootTable>,
+    /// The admin, or anyone running an approved `proposal`; pays for
+    /// new accounts
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// With `proposal`, stands in for the admin's signature once the
+    /// config belongs to an admin set
+    pub admin_set: Option<Account<'info, AdminSet>>,
+    #[account(mut)]
+    pub proposal: Option<Account<'info, ConfigProposal>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Replace the table. Rarities come from the items themselves, so a drop
+/// always turns into an item of the rarity it rolled.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, SetLootTable<'info>>) -> Result<()> {
+    require!(ctx.remaining_accounts.len() <= LootTable::MAX_ENTRIES, CustomError::InvalidLootTable);
+    let entries = ctx
+        .remaining_accounts
+        .iter()
+        .map(|info| {
+            let item = Account::<Item>::try_from(info)?;
+            Ok(LootEntry { item_id: item.id, rarity: item.rarity })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let item_ids: Vec<u32> = entries.iter().map(|e| e.item_id).collect();
+    require_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.admin.key(),
+        ctx.accounts.admin_set.as_ref(),
+        ctx.accounts.proposal.as_mut(),
+        ProposalAction::SetLootTable(LootTable::items_hash(&item_ids)),
+    )?;
+
+    ctx.accounts.loot_table.entries = entries.clone();
+    emit!(crate::events::LootTableSet { entries });
+    Ok(())
+}
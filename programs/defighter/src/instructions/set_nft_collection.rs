@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::state::admin::{AdminSet, ConfigProposal, ProposalAction};
+use crate::state::crafting::CosmeticKind;
+use crate::state::config::BalanceConfig;
+use crate::state::nft::NftCollection;
+use crate::errors::CustomError;
+use super::execute_config_change::require_admin;
+
+#[derive(Accounts)]
+#[instruction(collection: Pubkey)]
+pub struct SetNftCollection<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"nft_collection", collection.as_ref()],
+        bump,
+        space = NftCollection::SPACE
+    )]
+    pub nft_collection: Account<'info, NftCollection>,
+    /// The admin, or anyone running an approved `proposal`; pays for
+    /// new accounts
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// With `proposal`, stands in for the admin's signature once the
+    /// config belongs to an admin set
+    pub admin_set: Option<Account<'info, AdminSet>>,
+    #[account(mut)]
+    pub proposal: Option<Account<'info, ConfigProposal>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Allowlist `collection`, or remap or delist it. Perks already verified
+/// keep the cosmetic they were granted until re-verified or revoked.
+pub fn handler(
+    ctx: Context<SetNftCollection>,
+    collection: Pubkey,
+    kind: CosmeticKind,
+    index: u8,
+    active: bool,
+) -> Result<()> {
+    require_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.admin.key(),
+        ctx.accounts.admin_set.as_ref(),
+        ctx.accounts.proposal.as_mut(),
+        ProposalAction::SetNftCollection { collection, kind, index, active },
+    )?;
+    require!(index < kind.capacity(), CustomError::InvalidNftCollection);
+
+    let entry = &mut ctx.accounts.nft_collection;
+    entry.collection = collection;
+    entry.kind = kind;
+    entry.index = index;
+    entry.active = active;
+
+    emit!(crate::events::NftCollectionSet { collection, kind, index, active });
+    Ok(())
+}
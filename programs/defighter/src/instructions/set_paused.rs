@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::config::BalanceConfig;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    /// The admin, or the guardian when only adding flags
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetPaused>, flags: u8) -> Result<()> {
+    let cfg = &mut ctx.accounts.config;
+    let authority = ctx.accounts.authority.key();
+    let is_admin = authority == cfg.admin;
+    let is_guardian = cfg.guardian == Some(authority);
+    // A guardian can only widen the pause
+    require!(
+        is_admin || (is_guardian && flags & cfg.paused == cfg.paused),
+        CustomError::PauseUnauthorized
+    );
+    set_paused(cfg, authority, flags, Clock::get()?.slot)
+}
+
+pub(crate) fn set_paused(cfg: &mut BalanceConfig, authority: Pubkey, flags: u8, now: u64) -> Result<()> {
+    require!(flags & !BalanceConfig::PAUSE_ALL == 0, CustomError::InvalidPauseFlags);
+    let old = cfg.paused;
+    cfg.paused = flags;
+    // Remember when commits stopped, so battles they lapsed can be refunded
+    let commits = BalanceConfig::PAUSE_COMMITS;
+    match (old & commits != 0, flags & commits != 0) {
+        (false, true) => {
+            cfg.commits_paused_from = now;
+            cfg.commits_paused_until = u64::MAX;
+        }
+        (true, false) => cfg.commits_paused_until = now,
+        _ => {}
+    }
+    emit!(crate::events::PauseChanged { authority, old, new: flags });
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::state::admin::{AdminSet, ConfigProposal, ProposalAction};
+use crate::state::crafting::{CosmeticKind, Recipe, RecipeParams};
+use crate::state::config::BalanceConfig;
+use crate::errors::CustomError;
+use super::execute_config_change::require_admin;
+
+#[derive(Accounts)]
+#[instruction(kind: CosmeticKind, index: u8)]
+pub struct SetRecipe<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"recipe".as_ref(), &[kind as u8], &[index]],
+        bump,
+        space = Recipe::SPACE
+    )]
+    pub recipe: Account<'info, Recipe>,
+    /// The admin, or anyone running an approved `proposal`; pays for
+    /// new accounts
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// With `proposal`, stands in for the admin's signature once the
+    /// config belongs to an admin set
+    pub admin_set: Option<Account<'info, AdminSet>>,
+    #[account(mut)]
+    pub proposal: Option<Account<'info, ConfigProposal>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Create or reprice the recipe that unlocks `index` of `kind`
+pub fn handler(ctx: Context<SetRecipe>, kind: CosmeticKind, index: u8, params: RecipeParams) -> Result<()> {
+    require_admin(
+        &ctx.accounts.config,
+        &ctx.accounts.admin.key(),
+        ctx.accounts.admin_set.as_ref(),
+        ctx.accounts.proposal.as_mut(),
+        ProposalAction::SetRecipe { kind, index, params },
+    )?;
+    require!(index < kind.capacity(), CustomError::InvalidRecipe);
+
+    let recipe = &mut ctx.accounts.recipe;
+    recipe.kind = kind;
+    recipe.index = index;
+    recipe.materials = params.materials;
+    recipe.xp_cost = params.xp_cost;
+    recipe.active = params.active;
+
+    emit!(crate::events::RecipeSet {
+        kind,
+        index,
+        materials: params.materials,
+        xp_cost: params.xp_cost,
+        active: params.active,
+    });
+    Ok(())
+}
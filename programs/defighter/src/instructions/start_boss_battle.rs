@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use crate::state::battle::BattleState;
+use crate::state::boss::{Boss, BossBattle};
+use crate::state::player::Player;
+use crate::state::config::BalanceConfig;
+use crate::errors::CustomError;
+use crate::logic::PLAYER_START_HP;
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct StartBossBattle<'info> {
+    #[account(
+        init,
+        payer = player,
+        seeds = [b"boss_battle", boss.key().as_ref(), player.key().as_ref(), &nonce.to_le_bytes()],
+        bump,
+        space = BossBattle::SPACE
+    )]
+    pub battle: Account<'info, BossBattle>,
+    pub boss: Account<'info, Boss>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"player", player.key().as_ref(), &[player_account.index]],
+        bump,
+        constraint = player_account.authority == player.key()
+    )]
+    pub player_account: Account<'info, Player>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
+}
+
+pub fn handler(
+    ctx: Context<StartBossBattle>,
+    nonce: u64,
+    commit_deadline_slots: u64,
+    reveal_deadline_slots: u64,
+) -> Result<()> {
+    ctx.accounts.player_account.require_holder(ctx.accounts.character_token.as_deref())?;
+    ctx.accounts.config.require_unpaused(BalanceConfig::PAUSE_BATTLES)?;
+    let boss = &ctx.accounts.boss;
+    require!(!boss.is_defeated(), CustomError::BossDefeated);
+
+    require!(
+        commit_deadline_slots.saturating_add(reveal_deadline_slots) <= BossBattle::MAX_WINDOW_SLOTS,
+        CustomError::InvalidDeadlines
+    );
+
+    let now = ctx.accounts.clock.slot;
+    // Everything from here to resolve runs on this snapshot; only pauses stay live
+    let rules = ctx.accounts.config.params();
+    let cfg = &BalanceConfig::with_params(&rules);
+    let player_account = &mut ctx.accounts.player_account;
+    player_account.require_ready(now)?;
+    player_account.open_battles = player_account.open_battles.saturating_add(1);
+    player_account.spend_energy(cfg.battle_energy_cost, now, cfg)?;
+    emit!(crate::events::EnergySpent {
+        player: player_account.key(),
+        amount: cfg.battle_energy_cost,
+        energy: player_account.energy(now, cfg),
+    });
+
+    let battle = &mut ctx.accounts.battle;
+    battle.boss = boss.key();
+    battle.player = ctx.accounts.player.key();
+    battle.character = ctx.accounts.player_account.key();
+    battle.nonce = nonce;
+    battle.state = BattleState::WaitingForCommits;
+    battle.created_slot = now;
+    battle.commit_deadline_slot = now.saturating_add(commit_deadline_slots);
+    battle.reveal_deadline_slot = battle.commit_deadline_slot.saturating_add(reveal_deadline_slots);
+    battle.commit_slot = 0;
+    battle.commitment = None;
+    battle.reveal = None;
+    battle.salt = [0; 32];
+    battle.slot_hash = [0; 32];
+    battle.boss_move = None;
+    battle.player_won = None;
+    battle.player_hp = PLAYER_START_HP;
+    battle.boss_hp = if boss.shared_hp { boss.hp } else { boss.max_hp };
+    battle.rules = rules;
+
+    emit!(crate::events::BossBattleInitiated {
+        battle: battle.key(),
+        boss: battle.boss,
+        player: battle.player,
+        commit_deadline_slot: battle.commit_deadline_slot,
+        reveal_deadline_slot: battle.reveal_deadline_slot,
+    });
+    Ok(())
+}
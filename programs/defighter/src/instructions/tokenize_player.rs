@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::logic::nft::{held_nft, is_one_of_one};
+use crate::state::nft::CharacterMint;
+use crate::state::player::Player;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct TokenizePlayer<'info> {
+    #[account(mut, seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
+    pub player: Account<'info, Player>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"character_mint", mint.key().as_ref()],
+        bump,
+        space = CharacterMint::SPACE
+    )]
+    pub character_mint: Account<'info, CharacterMint>,
+    /// CHECK: parsed by hand; must be a fixed-supply 1/1 mint
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: parsed by hand; must be `authority`'s token account holding the mint's token
+    pub token_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Bind the character to a 1/1 token the player minted (typically earlier in
+/// the same transaction, revoking the mint authority). From then on whoever
+/// holds the token can `claim_player`. There is no way back.
+pub fn handler(ctx: Context<TokenizePlayer>) -> Result<()> {
+    let mint = ctx.accounts.mint.key();
+    let player = &mut ctx.accounts.player;
+    require!(player.character_mint.is_none(), CustomError::AlreadyTokenized);
+    require!(is_one_of_one(&ctx.accounts.mint), CustomError::InvalidCharacterMint);
+    require!(
+        held_nft(&ctx.accounts.token_account, &ctx.accounts.authority.key()) == Some(mint),
+        CustomError::NftNotHeld
+    );
+
+    player.character_mint = Some(mint);
+    let registry = &mut ctx.accounts.character_mint;
+    registry.mint = mint;
+    registry.player = player.key();
+
+    emit!(crate::events::PlayerTokenized { player: player.key(), authority: player.authority, mint });
+    Ok(())
+}
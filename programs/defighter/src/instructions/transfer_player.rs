@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use crate::state::config::BalanceConfig;
+use crate::state::customization::{move_customization, CharacterCustomizationV1, CHARACTER_CUSTOM_SEED};
+use crate::state::item::release_gear;
+use crate::state::player::Player;
+use crate::state::profile::Profile;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct TransferPlayer<'info> {
+    #[account(
+        mut,
+        seeds = [b"player", old_authority.key().as_ref(), &[old_player.index]],
+        bump,
+        close = old_authority
+    )]
+    pub old_player: Account<'info, Player>,
+    #[account(mut, seeds = [b"profile", old_authority.key().as_ref()], bump)]
+    pub old_profile: Account<'info, Profile>,
+    #[account(
+        init_if_needed,
+        payer = new_authority,
+        seeds = [b"profile", new_authority.key().as_ref()],
+        bump,
+        space = Profile::SPACE
+    )]
+    pub new_profile: Account<'info, Profile>,
+    #[account(
+        init,
+        payer = new_authority,
+        seeds = [b"player", new_authority.key().as_ref(), &[new_profile.next_index]],
+        bump,
+        space = Player::SPACE
+    )]
+    pub new_player: Account<'info, Player>,
+    /// CHECK: the character's customization PDA, moved and closed if it exists
+    #[account(mut, seeds = [CHARACTER_CUSTOM_SEED, old_player.key().as_ref()], bump)]
+    pub old_customization: UncheckedAccount<'info>,
+    /// Required if the character has a customization
+    #[account(
+        init,
+        payer = new_authority,
+        seeds = [CHARACTER_CUSTOM_SEED, new_player.key().as_ref()],
+        bump,
+        space = CharacterCustomizationV1::SPACE
+    )]
+    pub new_customization: Option<Account<'info, CharacterCustomizationV1>>,
+    /// CHECK: the old wallet's inventory, if any; the character's gear is taken off
+    #[account(mut, seeds = [b"inventory", old_authority.key().as_ref()], bump)]
+    pub old_inventory: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub old_authority: Signer<'info>,
+    #[account(mut)]
+    pub new_authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Move a character to another wallet with both wallets' consent. Works
+/// like `claim_player`, transfer cooldown included so it can't be used to
+/// dodge it; tokenized characters only move with their token.
+pub fn handler(ctx: Context<TransferPlayer>) -> Result<()> {
+    let old_player = &ctx.accounts.old_player;
+    require!(old_player.character_mint.is_none(), CustomError::TokenizedPlayer);
+
+    let new_authority = ctx.accounts.new_authority.key();
+    require_keys_neq!(ctx.accounts.old_authority.key(), new_authority, CustomError::SameOwner);
+    let new_key = ctx.accounts.new_player.key();
+    let index = ctx.accounts.new_profile.add_character(new_authority, ctx.accounts.config.max_characters)?;
+    ctx.accounts.old_profile.remove_character();
+    old_player.move_to(&mut ctx.accounts.new_player, new_authority, index, ctx.accounts.clock.slot)?;
+    release_gear(&ctx.accounts.old_inventory, old_player.key())?;
+    move_customization(
+        &ctx.accounts.old_customization,
+        ctx.accounts.new_customization.as_mut(),
+        new_key,
+        &ctx.accounts.old_authority,
+    )?;
+
+    emit!(crate::events::PlayerTransferred {
+        old_player: old_player.key(),
+        new_player: new_key,
+        old_authority: ctx.accounts.old_authority.key(),
+        new_authority,
+        mint: None,
+    });
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::state::item::{GearSlot, GearStats, Inventory};
+use crate::state::player::Player;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct UnequipItem<'info> {
+    #[account(mut, seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
+    pub player: Account<'info, Player>,
+    #[account(mut, seeds = [b"inventory", authority.key().as_ref()], bump)]
+    pub inventory: Account<'info, Inventory>,
+    pub authority: Signer<'info>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
+}
+
+pub fn handler(ctx: Context<UnequipItem>, slot: GearSlot) -> Result<()> {
+    ctx.accounts.player.require_holder(ctx.accounts.character_token.as_deref())?;
+    let player = &mut ctx.accounts.player;
+    let inventory = &mut ctx.accounts.inventory;
+    inventory.require_wearer(player.key())?;
+    let index = slot as usize;
+    let item_id = inventory.equipped[index].take().ok_or(CustomError::SlotEmpty)?;
+    inventory.equipped_stats[index] = GearStats::default();
+    inventory.settle_wearer();
+    player.gear = inventory.gear();
+
+    emit!(crate::events::ItemUnequipped { player: player.key(), item_id, slot, gear: player.gear });
+    Ok(())
+}
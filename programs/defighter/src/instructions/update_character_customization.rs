@@ -1,9 +1,11 @@
 use anchor_lang::prelude::*;
-use crate::state::{Player, CharacterCustomizationV1, CharacterCustomizationDataV1, CHARACTER_CUSTOM_SEED};
+use crate::state::{BalanceConfig, Player, CharacterCustomizationV1, CharacterCustomizationDataV1, CHARACTER_CUSTOM_SEED};
+use crate::errors::CustomError;
+use crate::logic::nft::nft_unlocks;
 
 #[derive(Accounts)]
 pub struct UpdateCharacterCustomization<'info> {
-    #[account(mut)]
+    #[account(seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
     pub player: Account<'info, Player>,
     #[account(
         mut,
@@ -13,14 +15,28 @@ pub struct UpdateCharacterCustomization<'info> {
     )]
     pub character_customization: Account<'info, CharacterCustomizationV1>,
     pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
 }
 
-pub fn handler(ctx: Context<UpdateCharacterCustomization>, data: CharacterCustomizationDataV1) -> Result<()> {
-    let player = &ctx.accounts.player;
-    require!(player.authority == ctx.accounts.authority.key(), error!(crate::errors::GameError::Unauthorized));
-
+/// Cosmetics unlocked by NFTs need each `NftPerk` and the token account it
+/// was verified with as remaining account pairs; both are re-checked here.
+/// The prestige outfit needs nothing extra.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateCharacterCustomization<'info>>,
+    data: CharacterCustomizationDataV1,
+) -> Result<()> {
+    ctx.accounts.player.require_holder(ctx.accounts.character_token.as_deref())?;
+    ctx.accounts.config.require_unpaused(BalanceConfig::PAUSE_CUSTOMIZATION)?;
     let acct = &mut ctx.accounts.character_customization;
-    // Keep player, version intact
+    let mut unlocks = nft_unlocks(ctx.remaining_accounts, &acct.key(), &ctx.accounts.authority.key())?;
+    unlocks.extend(ctx.accounts.player.prestige_unlock(&ctx.accounts.config));
+    require!(acct.allows(&data, &unlocks), CustomError::CosmeticLocked);
+
+    // Keep player, version and unlocks intact
     acct.gender = data.gender;
     acct.palette_index = data.palette_index;
     acct.skin_tone_index = data.skin_tone_index;
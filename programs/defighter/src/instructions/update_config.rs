@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::state::config::{BalanceConfig, BalanceConfigParams, ConfigUpdate, PendingConfig};
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(seeds = [b"config"], bump, has_one = admin)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"pending_config"],
+        bump,
+        space = PendingConfig::SPACE
+    )]
+    pub pending_config: Account<'info, PendingConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Queue a single-field change on top of the live config.
+pub fn handler(ctx: Context<UpdateConfig>, update: ConfigUpdate) -> Result<()> {
+    let cfg = &ctx.accounts.config;
+    let mut params = cfg.params();
+    update.apply(&mut params);
+    queue_change(cfg, &mut ctx.accounts.pending_config, params, ctx.accounts.clock.slot)
+}
+
+/// Shared by every update path, including admin-set proposals. Only one
+/// change waits at a time; the admin cancels it to queue another.
+pub(crate) fn queue_change(
+    cfg: &BalanceConfig,
+    pending: &mut PendingConfig,
+    params: BalanceConfigParams,
+    now: u64,
+) -> Result<()> {
+    // Checked as a whole, since some invariants span fields
+    params.validate()?;
+    require!(!pending.active, CustomError::ConfigChangePending);
+
+    pending.active = true;
+    pending.version = cfg.version.saturating_add(1);
+    pending.params = params;
+    pending.queued_by = cfg.admin;
+    pending.queued_slot = now;
+    pending.effective_slot = now.saturating_add(BalanceConfig::CONFIG_DELAY_SLOTS);
+
+    emit!(crate::events::ConfigChangeQueued {
+        admin: cfg.admin,
+        version: pending.version,
+        params,
+        effective_slot: pending.effective_slot,
+    });
+    Ok(())
+}
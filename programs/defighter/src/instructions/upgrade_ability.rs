@@ -5,17 +5,22 @@ use crate::logic::math::upgrade_cost;
 
 #[derive(Accounts)]
 pub struct UpgradeAbility<'info> {
-    #[account(mut, seeds = [b"player", authority.key().as_ref()], bump, has_one = authority)]
+    #[account(mut, seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
     pub player: Account<'info, Player>,
     pub authority: Signer<'info>,
     /// CHECK: read-only config
     #[account(seeds = [b"config"], bump)]
     pub config: Account<'info, BalanceConfig>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
 }
 
 pub fn handler(ctx: Context<UpgradeAbility>, ability_index: u8) -> Result<()> {
+    ctx.accounts.player.require_holder(ctx.accounts.character_token.as_deref())?;
     let player = &mut ctx.accounts.player;
     let cfg = &ctx.accounts.config;
+    cfg.require_unpaused(BalanceConfig::PAUSE_UPGRADES)?;
     require!((ability_index as usize) < player.abilities.len(), CustomError::InvalidAbility);
     let idx = ability_index as usize;
     let next = player.abilities[idx].saturating_add(1);
@@ -23,6 +28,7 @@ pub fn handler(ctx: Context<UpgradeAbility>, ability_index: u8) -> Result<()> {
     let cost = upgrade_cost(next, cfg);
     require!(player.xp >= cost, CustomError::NotEnoughXP);
     player.xp = player.xp.saturating_sub(cost);
+    player.xp_spent = player.xp_spent.saturating_add(cost);
     player.abilities[idx] = next;
     emit!(crate::events::AbilityUpgraded { player: player.key(), ability_index, new_level: next, cost });
     Ok(())
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use crate::logic::nft::{held_nft, metadata_pda, parse_metadata, METADATA_PROGRAM_ID};
+use crate::state::config::BalanceConfig;
+use crate::state::customization::{CharacterCustomizationV1, CHARACTER_CUSTOM_SEED};
+use crate::state::nft::{NftCollection, NftPerk};
+use crate::state::player::Player;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct VerifyNft<'info> {
+    #[account(seeds = [b"player", authority.key().as_ref(), &[player.index]], bump, has_one = authority)]
+    pub player: Account<'info, Player>,
+    #[account(seeds = [CHARACTER_CUSTOM_SEED, player.key().as_ref()], bump, has_one = player)]
+    pub character_customization: Account<'info, CharacterCustomizationV1>,
+    #[account(seeds = [b"nft_collection", nft_collection.collection.as_ref()], bump)]
+    pub nft_collection: Account<'info, NftCollection>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"nft_perk", character_customization.key().as_ref(), nft_collection.collection.as_ref()],
+        bump,
+        space = NftPerk::SPACE
+    )]
+    pub nft_perk: Account<'info, NftPerk>,
+    /// CHECK: parsed by hand; must be a token account of `authority` holding the NFT
+    pub token_account: UncheckedAccount<'info>,
+    /// CHECK: parsed by hand; must be the Metaplex metadata PDA of the NFT's mint
+    pub metadata: UncheckedAccount<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, BalanceConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: parsed by hand; required for a tokenized character: its
+    /// authority's token account holding the character token
+    pub character_token: Option<UncheckedAccount<'info>>,
+}
+
+/// Record that the player holds an NFT from an allowlisted collection,
+/// unlocking its cosmetic. Re-verifying refreshes the perk, e.g. after
+/// moving the NFT to another token account.
+pub fn handler(ctx: Context<VerifyNft>) -> Result<()> {
+    ctx.accounts.player.require_holder(ctx.accounts.character_token.as_deref())?;
+    ctx.accounts.config.require_unpaused(BalanceConfig::PAUSE_CUSTOMIZATION)?;
+    let entry = &ctx.accounts.nft_collection;
+    require!(entry.active, CustomError::NftNotInCollection);
+
+    let mint = held_nft(&ctx.accounts.token_account, &ctx.accounts.authority.key())
+        .ok_or(CustomError::NftNotHeld)?;
+    let metadata = &ctx.accounts.metadata;
+    require!(
+        *metadata.owner == METADATA_PROGRAM_ID && metadata.key() == metadata_pda(&mint),
+        CustomError::InvalidNftMetadata
+    );
+    let parsed = parse_metadata(&metadata.try_borrow_data()?).ok_or(CustomError::InvalidNftMetadata)?;
+    require_keys_eq!(parsed.mint, mint, CustomError::InvalidNftMetadata);
+    require!(parsed.verified_collection == Some(entry.collection), CustomError::NftNotInCollection);
+
+    let perk = &mut ctx.accounts.nft_perk;
+    perk.customization = ctx.accounts.character_customization.key();
+    perk.collection = entry.collection;
+    perk.mint = mint;
+    perk.token_account = ctx.accounts.token_account.key();
+    perk.kind = entry.kind;
+    perk.index = entry.index;
+    perk.verified_slot = Clock::get()?.slot;
+
+    emit!(crate::events::NftVerified {
+        player: ctx.accounts.player.key(),
+        collection: entry.collection,
+        mint,
+        kind: entry.kind,
+        index: entry.index,
+    });
+    Ok(())
+}
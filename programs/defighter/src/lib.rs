@@ -1,3 +1,6 @@
+// anchor 0.31's #[program] expansion still calls the deprecated AccountInfo::realloc
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
 
 pub mod state;
@@ -23,6 +26,22 @@ pub mod defighter {
         instructions::upgrade_ability::handler(ctx, ability_index)
     }
 
+    pub fn respec_abilities(ctx: Context<RespecAbilities>) -> Result<()> {
+        instructions::respec_abilities::handler(ctx)
+    }
+
+    pub fn prestige(ctx: Context<Prestige>) -> Result<()> {
+        instructions::prestige::handler(ctx)
+    }
+
+    pub fn preview_upgrade_costs(ctx: Context<PreviewUpgradeCosts>, ability_index: u8, count: u8) -> Result<Vec<u64>> {
+        instructions::preview_upgrade_costs::handler(ctx, ability_index, count)
+    }
+
+    pub fn refill_energy(ctx: Context<RefillEnergy>, amount: u16) -> Result<()> {
+        instructions::refill_energy::handler(ctx, amount)
+    }
+
     pub fn initiate_battle(
         ctx: Context<InitiateBattle>,
         opponent: Pubkey,
@@ -41,68 +60,226 @@ pub mod defighter {
         ctx: Context<RevealMove>,
         move_choice: state::player::MoveChoice,
         salt: [u8; 32],
+        boost: Option<state::consumable::Boost>,
     ) -> Result<()> {
-        instructions::reveal_move::handler(ctx, move_choice, salt)
+        instructions::reveal_move::handler(ctx, move_choice, salt, boost)
     }
 
     pub fn resolve_battle(ctx: Context<ResolveBattle>) -> Result<()> {
         instructions::resolve_battle::handler(ctx)
     }
 
-    pub fn admin_update_config(ctx: Context<AdminUpdateConfig>, new_config: state::config::BalanceConfig) -> Result<()> {
-        instructions::admin_update_config::handler(ctx, new_config)
-    }
-
-    pub fn init_config(
-        ctx: Context<InitConfig>,
-        base: i32,
-        linear_a: i32,
-        dim_k: i32,
-        dim_t: i32,
-        max_level: u16,
-        tie_break_rand: bool,
-        use_dim_bonus: bool,
-        xp_base: u64,
-        xp_tie_bps: u16,
-        xp_loser_bps: u16,
-        upgrade_c0: u64,
-        upgrade_p_bps: u16,
-        rps_win_base: i32,
-        rps_tie_base: i32,
+    pub fn cancel_battle(ctx: Context<CancelBattle>) -> Result<()> {
+        instructions::cancel_battle::handler(ctx)
+    }
+
+    pub fn admin_update_config(ctx: Context<AdminUpdateConfig>, params: state::config::BalanceConfigParams) -> Result<()> {
+        instructions::admin_update_config::handler(ctx, params)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, update: state::config::ConfigUpdate) -> Result<()> {
+        instructions::update_config::handler(ctx, update)
+    }
+
+    pub fn apply_config_change(ctx: Context<ApplyConfigChange>) -> Result<()> {
+        instructions::apply_config_change::handler(ctx)
+    }
+
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        instructions::cancel_config_change::handler(ctx)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, flags: u8) -> Result<()> {
+        instructions::set_paused::handler(ctx, flags)
+    }
+
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+        instructions::set_guardian::handler(ctx, guardian)
+    }
+
+    pub fn init_config(ctx: Context<InitConfig>, params: state::config::BalanceConfigParams) -> Result<()> {
+        instructions::init_config::handler(ctx, params)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin::handler(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin::handler(ctx)
+    }
+
+    pub fn create_admin_set(ctx: Context<CreateAdminSet>, id: u32, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::create_admin_set::handler(ctx, id, members, threshold)
+    }
+
+    pub fn propose_config_change(ctx: Context<ProposeConfigChange>, action: state::admin::ProposalAction) -> Result<()> {
+        instructions::propose_config_change::handler(ctx, action)
+    }
+
+    pub fn approve_config_change(ctx: Context<ApproveConfigChange>) -> Result<()> {
+        instructions::approve_config_change::handler(ctx)
+    }
+
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        instructions::execute_config_change::handler(ctx)
+    }
+
+    pub fn create_boss(ctx: Context<CreateBoss>, id: u32, params: state::boss::BossParams) -> Result<()> {
+        instructions::create_boss::handler(ctx, id, params)
+    }
+
+    pub fn start_boss_battle(
+        ctx: Context<StartBossBattle>,
+        nonce: u64,
+        commit_deadline_slots: u64,
+        reveal_deadline_slots: u64,
+    ) -> Result<()> {
+        instructions::start_boss_battle::handler(ctx, nonce, commit_deadline_slots, reveal_deadline_slots)
+    }
+
+    pub fn commit_boss_move(ctx: Context<CommitBossMove>, commitment: [u8; 32]) -> Result<()> {
+        instructions::commit_boss_move::handler(ctx, commitment)
+    }
+
+    pub fn reveal_boss_move(
+        ctx: Context<RevealBossMove>,
+        move_choice: state::player::MoveChoice,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::reveal_boss_move::handler(ctx, move_choice, salt)
+    }
+
+    pub fn resolve_boss_battle(ctx: Context<ResolveBossBattle>) -> Result<()> {
+        instructions::resolve_boss_battle::handler(ctx)
+    }
+
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        session_key: Pubkey,
+        duration_slots: u64,
+        scope: u8,
     ) -> Result<()> {
-        instructions::init_config::handler(
-            ctx,
-            base,
-            linear_a,
-            dim_k,
-            dim_t,
-            max_level,
-            tie_break_rand,
-            use_dim_bonus,
-            xp_base,
-            xp_tie_bps,
-            xp_loser_bps,
-            upgrade_c0,
-            upgrade_p_bps,
-            rps_win_base,
-            rps_tie_base,
-        )
-    }
-
-    // TODO: Fix character customization functions
-    // pub fn create_character_customization(
-    //     ctx: Context<CreateCharacterCustomization>,
-    //     data: state::customization::CharacterCustomizationDataV1,
-    // ) -> Result<()> {
-    //     instructions::create_character_customization::handler(ctx, data)
-    // }
-
-    // pub fn update_character_customization(
-    //     ctx: Context<UpdateCharacterCustomization>,
-    //     data: state::customization::CharacterCustomizationDataV1,
-    // ) -> Result<()> {
-    //     instructions::update_character_customization::handler(ctx, data)
-    // }
+        instructions::create_session::handler(ctx, session_key, duration_slots, scope)
+    }
+
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        instructions::revoke_session::handler(ctx)
+    }
+
+    pub fn create_item(ctx: Context<CreateItem>, id: u32, params: state::item::ItemParams) -> Result<()> {
+        instructions::create_item::handler(ctx, id, params)
+    }
+
+    pub fn grant_item(ctx: Context<GrantItem>, recipient: Pubkey) -> Result<()> {
+        instructions::grant_item::handler(ctx, recipient)
+    }
+
+    pub fn equip_item(ctx: Context<EquipItem>) -> Result<()> {
+        instructions::equip_item::handler(ctx)
+    }
+
+    pub fn unequip_item(ctx: Context<UnequipItem>, slot: state::item::GearSlot) -> Result<()> {
+        instructions::unequip_item::handler(ctx, slot)
+    }
+
+    pub fn set_loot_table<'info>(ctx: Context<'_, '_, 'info, 'info, SetLootTable<'info>>) -> Result<()> {
+        instructions::set_loot_table::handler(ctx)
+    }
+
+    pub fn claim_loot(ctx: Context<ClaimLoot>) -> Result<()> {
+        instructions::claim_loot::handler(ctx)
+    }
+
+    pub fn set_recipe(
+        ctx: Context<SetRecipe>,
+        kind: state::crafting::CosmeticKind,
+        index: u8,
+        params: state::crafting::RecipeParams,
+    ) -> Result<()> {
+        instructions::set_recipe::handler(ctx, kind, index, params)
+    }
+
+    pub fn craft_cosmetic(ctx: Context<CraftCosmetic>) -> Result<()> {
+        instructions::craft_cosmetic::handler(ctx)
+    }
+
+    pub fn salvage_cosmetic(ctx: Context<SalvageCosmetic>) -> Result<()> {
+        instructions::salvage_cosmetic::handler(ctx)
+    }
+
+    pub fn grant_boost(
+        ctx: Context<GrantBoost>,
+        recipient: Pubkey,
+        boost: state::consumable::Boost,
+        amount: u16,
+    ) -> Result<()> {
+        instructions::grant_boost::handler(ctx, recipient, boost, amount)
+    }
+
+    pub fn set_nft_collection(
+        ctx: Context<SetNftCollection>,
+        collection: Pubkey,
+        kind: state::crafting::CosmeticKind,
+        index: u8,
+        active: bool,
+    ) -> Result<()> {
+        instructions::set_nft_collection::handler(ctx, collection, kind, index, active)
+    }
+
+    pub fn verify_nft(ctx: Context<VerifyNft>) -> Result<()> {
+        instructions::verify_nft::handler(ctx)
+    }
+
+    pub fn revoke_nft_perk(ctx: Context<RevokeNftPerk>) -> Result<()> {
+        instructions::revoke_nft_perk::handler(ctx)
+    }
+
+    pub fn tokenize_player(ctx: Context<TokenizePlayer>) -> Result<()> {
+        instructions::tokenize_player::handler(ctx)
+    }
+
+    pub fn claim_player(ctx: Context<ClaimPlayer>) -> Result<()> {
+        instructions::claim_player::handler(ctx)
+    }
+
+    pub fn transfer_player(ctx: Context<TransferPlayer>) -> Result<()> {
+        instructions::transfer_player::handler(ctx)
+    }
+
+    pub fn close_player(ctx: Context<ClosePlayer>) -> Result<()> {
+        instructions::close_player::handler(ctx)
+    }
+
+    pub fn create_character_customization(
+        ctx: Context<CreateCharacterCustomization>,
+        data: state::customization::CharacterCustomizationDataV1,
+    ) -> Result<()> {
+        instructions::create_character_customization::handler(ctx, data)
+    }
+
+    pub fn update_character_customization<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateCharacterCustomization<'info>>,
+        data: state::customization::CharacterCustomizationDataV1,
+    ) -> Result<()> {
+        instructions::update_character_customization::handler(ctx, data)
+    }
+
+    pub fn migrate_player(ctx: Context<MigratePlayer>, index: u8) -> Result<()> {
+        instructions::migrate_player::handler(ctx, index)
+    }
+
+    pub fn migrate_config(ctx: Context<MigrateConfig>, params: state::config::BalanceConfigParams) -> Result<()> {
+        instructions::migrate_config::handler(ctx, params)
+    }
+
+    pub fn migrate_battle(ctx: Context<MigrateBattle>) -> Result<()> {
+        instructions::migrate_battle::handler(ctx)
+    }
+
+    pub fn migrate_legacy_player(ctx: Context<MigrateLegacyPlayer>) -> Result<()> {
+        instructions::migrate_legacy_player::handler(ctx)
+    }
 }
 
 
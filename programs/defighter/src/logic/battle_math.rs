@@ -1,5 +1,9 @@
-use anchor_lang::prelude::*;
-use crate::state::player::{Player, FighterClass, MoveChoice};
+use crate::state::config::{BalanceConfig, ResolutionMode};
+use crate::state::consumable::Boost;
+use crate::state::item::GearStats;
+use crate::state::player::{AbilityEffect, Player, FighterClass, MoveChoice};
+use super::math::ability_bonus;
+use super::scoring::resolve_score_round;
 
 /// Battle outcome for a single move
 #[derive(Debug, Clone)]
@@ -18,57 +22,44 @@ pub enum MoveResult {
     SpecialCritical,
 }
 
+/// Boosts in play for one attack: the attacker's own and the defender's
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AttackBoosts {
+    pub attacker: Option<Boost>,
+    pub defender: Option<Boost>,
+}
+
 /// Calculate damage for a move
 pub fn calculate_damage(
     move_choice: MoveChoice,
     attacker: &Player,
-    defender_class: FighterClass,
+    defender: &Player,
     vrf_result: u64,
+    cfg: &BalanceConfig,
 ) -> u16 {
-    // Base damage is always 100
-    let base_damage = 100.0;
-    
-    // Class advantage multiplier
-    let class_advantage = get_class_advantage(move_choice.class(), defender_class);
-    
-    // Player power progression
-    let player_power = calculate_player_power(attacker);
-    
-    // Move outcome multiplier
-    let (move_multiplier, _move_result) = get_move_outcome(move_choice, vrf_result);
-    
-    // Final damage calculation
-    let final_damage = base_damage * class_advantage * player_power * move_multiplier;
-    
-    final_damage as u16
+    calculate_battle_outcome(move_choice, attacker, defender, u16::MAX, vrf_result, AttackBoosts::default(), cfg).damage_dealt
 }
 
 /// Calculate battle outcome with move result
 pub fn calculate_battle_outcome(
     move_choice: MoveChoice,
     attacker: &Player,
-    defender_class: FighterClass,
+    defender: &Player,
     defender_hp: u16,
     vrf_result: u64,
+    boosts: AttackBoosts,
+    cfg: &BalanceConfig,
 ) -> BattleOutcome {
-    let base_damage = 100.0;
-    let class_advantage = get_class_advantage(move_choice.class(), defender_class);
-    let player_power = calculate_player_power(attacker);
-    let (move_multiplier, move_result) = get_move_outcome(move_choice, vrf_result);
-    
-    let final_damage = (base_damage * class_advantage * player_power * move_multiplier) as u16;
+    let base_damage = cfg.base_damage as f64;
+    let class_advantage = get_class_advantage(move_choice.class(), defender.class, cfg);
+    let player_power = calculate_player_power(attacker, cfg);
+    let (move_multiplier, move_result) = get_move_outcome(move_choice, attacker, vrf_result, boosts.attacker, cfg);
+    let defense = defense_multiplier(defender, cfg) * shield_multiplier(boosts.defender, cfg);
+    let gear = gear_multiplier(attacker, defender);
+
+    let final_damage = (base_damage * class_advantage * player_power * move_multiplier * defense * gear) as u16;
     let remaining_hp = defender_hp.saturating_sub(final_damage);
-    
-    // ULTRA DEBUG: Test the exact scenario
-    if defender_hp == 200 && final_damage == 80 {
-        msg!("CRITICAL DEBUG: 200 HP - 80 damage = {} (should be 120)", remaining_hp);
-        msg!("CRITICAL DEBUG: saturating_sub test: {}", 200u16.saturating_sub(80u16));
-    }
-    if defender_hp == 200 && final_damage == 125 {
-        msg!("CRITICAL DEBUG: 200 HP - 125 damage = {} (should be 75)", remaining_hp);
-        msg!("CRITICAL DEBUG: saturating_sub test: {}", 200u16.saturating_sub(125u16));
-    }
-    
+
     BattleOutcome {
         damage_dealt: final_damage,
         move_result,
@@ -77,157 +68,274 @@ pub fn calculate_battle_outcome(
 }
 
 /// Get class advantage multiplier (rock-paper-scissors)
-fn get_class_advantage(attacker_class: FighterClass, defender_class: FighterClass) -> f64 {
+fn get_class_advantage(attacker_class: FighterClass, defender_class: FighterClass, cfg: &BalanceConfig) -> f64 {
     use FighterClass::*;
-    
-    match (attacker_class, defender_class) {
-        // Winning matchups (25% bonus)
-        (Shitposter, VC) => 1.25,      // Memes destroy reputations
-        (VC, Builder) => 1.25,         // Funding controls roadmaps  
-        (Builder, Shitposter) => 1.25, // Shipping beats hype
-        
-        // Losing matchups (20% penalty)
-        (VC, Shitposter) => 0.80,      
-        (Builder, VC) => 0.80,         
-        (Shitposter, Builder) => 0.80, 
-        
+
+    let bps = match (attacker_class, defender_class) {
+        // Winning matchups
+        (Shitposter, VC) => cfg.class_win_bps,      // Memes destroy reputations
+        (VC, Builder) => cfg.class_win_bps,         // Funding controls roadmaps
+        (Builder, Shitposter) => cfg.class_win_bps, // Shipping beats hype
+
+        // Losing matchups
+        (VC, Shitposter) => cfg.class_loss_bps,
+        (Builder, VC) => cfg.class_loss_bps,
+        (Shitposter, Builder) => cfg.class_loss_bps,
+
         // Same class (neutral)
+        _ => return 1.0,
+    };
+    bps as f64 / 10_000.0
+}
+
+/// Calculate player power progression multiplier. Ability levels act
+/// through their per-class effects instead (see `effect_bonus`).
+fn calculate_player_power(player: &Player, cfg: &BalanceConfig) -> f64 {
+    // XP tier bonus
+    let xp_tier = (player.xp / cfg.xp_tier_size.max(1)) as f64;
+    let xp_bonus = xp_tier * (cfg.xp_tier_bonus_bps as f64 / 10_000.0);
+    let prestige_bonus = player.prestige_power_bps(cfg) as f64 / 10_000.0;
+
+    1.0 + xp_bonus + prestige_bonus
+}
+
+/// `player`'s bonus points towards `effect`; zero if its class doesn't level it.
+pub fn effect_bonus(player: &Player, effect: AbilityEffect, cfg: &BalanceConfig) -> u32 {
+    player
+        .class
+        .ability_effects()
+        .iter()
+        .position(|e| *e == effect)
+        .map_or(0, |i| ability_bonus(player.abilities[i], cfg) as u32)
+}
+
+/// Multiplier on damage `defender` takes, from its Defense ability
+fn defense_multiplier(defender: &Player, cfg: &BalanceConfig) -> f64 {
+    let blocked_bps = effect_bonus(defender, AbilityEffect::Defense, cfg)
+        .saturating_mul(cfg.defense_bps as u32)
+        .min(BalanceConfig::MAX_DEFENSE_BPS);
+    1.0 - blocked_bps as f64 / 10_000.0
+}
+
+/// Multiplier from the attacker's gear attack and the defender's gear defense.
+/// `Player.gear` is already capped; the clamp guards hand-built players.
+fn gear_multiplier(attacker: &Player, defender: &Player) -> f64 {
+    let cap = GearStats::MAX_TOTAL_BPS;
+    let attack = attacker.gear.attack_bps.min(cap) as f64 / 10_000.0;
+    let defense = defender.gear.defense_bps.min(cap) as f64 / 10_000.0;
+    (1.0 + attack) * (1.0 - defense)
+}
+
+/// Multiplier on damage taken by a defender playing `boost`
+fn shield_multiplier(boost: Option<Boost>, cfg: &BalanceConfig) -> f64 {
+    match boost {
+        Some(Boost::LiquidityShield) => 1.0 - cfg.shield_bps.min(BalanceConfig::MAX_DEFENSE_BPS as u16) as f64 / 10_000.0,
         _ => 1.0,
     }
 }
 
-/// Calculate player power progression multiplier
-fn calculate_player_power(player: &Player) -> f64 {
-    // Total ability levels bonus
-    let total_levels: u16 = player.abilities.iter().sum();
-    let ability_bonus = (total_levels as f64) * 0.05;
-    
-    // XP tier bonus  
-    let xp_tier = (player.xp / 1000) as f64;
-    let xp_bonus = xp_tier * 0.02;
-    
-    1.0 + ability_bonus + xp_bonus
+/// Miss chance (percent), hit-window end (percent), hit and crit multipliers
+fn special_table(move_choice: MoveChoice, cfg: &BalanceConfig) -> (u32, u32, f64, f64) {
+    let special = cfg.special_moves[move_choice.class() as usize];
+    (
+        special.miss_pct as u32,
+        special.hit_end_pct as u32,
+        special.hit_mult_bps as f64 / 10_000.0,
+        special.crit_mult_bps as f64 / 10_000.0,
+    )
 }
 
-/// Determine move outcome based on VRF and move type
-fn get_move_outcome(move_choice: MoveChoice, vrf_result: u64) -> (f64, MoveResult) {
+/// Determine move outcome based on VRF, move type, the attacker's abilities
+/// and its boost
+fn get_move_outcome(
+    move_choice: MoveChoice,
+    attacker: &Player,
+    vrf_result: u64,
+    boost: Option<Boost>,
+    cfg: &BalanceConfig,
+) -> (f64, MoveResult) {
     if !move_choice.is_special_move() {
-        // Basic moves always hit for 1.0x damage
-        return (1.0, MoveResult::BasicHit);
+        // Basic moves always hit, harder with BasicDamage levels
+        let bonus_bps = effect_bonus(attacker, AbilityEffect::BasicDamage, cfg) as f64 * cfg.basic_damage_bps as f64;
+        return (1.0 + bonus_bps / 10_000.0, MoveResult::BasicHit);
     }
     
     // Convert VRF to percentage (0-100)
-    let roll = (vrf_result % 100) as u8;
-    
-    match move_choice {
-        MoveChoice::RugPullRumor => {
-            // 30% miss, 50% hit (1.5x), 20% crit (2.2x)
-            if roll < 30 {
-                (0.0, MoveResult::SpecialMiss)
-            } else if roll < 80 {
-                (1.5, MoveResult::SpecialHit)  
-            } else {
-                (2.2, MoveResult::SpecialCritical)
-            }
-        },
-        MoveChoice::TestnetDeploy => {
-            // 25% miss, 55% hit (1.6x), 20% crit (2.3x)
-            if roll < 25 {
-                (0.0, MoveResult::SpecialMiss)
-            } else if roll < 80 {
-                (1.6, MoveResult::SpecialHit)
-            } else {
-                (2.3, MoveResult::SpecialCritical)
-            }
-        },
-        MoveChoice::ExitLiquidity => {
-            // 35% miss, 45% hit (1.4x), 20% crit (2.0x)
-            if roll < 35 {
-                (0.0, MoveResult::SpecialMiss)
-            } else if roll < 80 {
-                (1.4, MoveResult::SpecialHit)
-            } else {
-                (2.0, MoveResult::SpecialCritical)
-            }
-        },
-        // Basic moves (should not reach here due to early return)
-        _ => (1.0, MoveResult::BasicHit),
+    let roll = (vrf_result % 100) as u32;
+    let (miss, hit_end, hit, crit) = special_table(move_choice, cfg);
+
+    // Accuracy turns the top of the miss window into hits
+    let accuracy = effect_bonus(attacker, AbilityEffect::SpecialAccuracy, cfg)
+        .saturating_mul(cfg.accuracy_bps as u32) / 100;
+    let miss = miss.saturating_sub(accuracy);
+    // Alpha Leak grows the crit window down into the hit window, never into misses
+    let hit_end = match boost {
+        Some(Boost::AlphaLeak) => hit_end.saturating_sub(cfg.alpha_leak_bps as u32 / 100).max(miss),
+        _ => hit_end,
+    };
+    let crit = crit + effect_bonus(attacker, AbilityEffect::CritPower, cfg) as f64 * cfg.crit_bps as f64 / 10_000.0;
+
+    if roll < miss {
+        (0.0, MoveResult::SpecialMiss)
+    } else if roll < hit_end {
+        (hit, MoveResult::SpecialHit)
+    } else {
+        (crit, MoveResult::SpecialCritical)
     }
 }
 
-/// Calculate expected damage for strategic AI (optional future use)
+/// Side of a battle, used to report round winners without needing pubkeys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BattleSide {
+    Challenger,
+    Opponent,
+}
+
+/// One side's input to a round: who attacks, with what, at what HP and roll
+#[derive(Clone, Copy)]
+pub struct RoundInput<'a> {
+    pub player: &'a Player,
+    pub move_choice: MoveChoice,
+    pub hp: u16,
+    pub vrf_result: u64,
+    /// Boost revealed with the move; only `ResolutionMode::Damage` uses it
+    pub boost: Option<Boost>,
+}
+
+/// Both attacks of a revealed round and who won it
+#[derive(Debug, Clone)]
+pub struct RoundResolution {
+    /// Challenger's attack on the opponent
+    pub challenger: BattleOutcome,
+    /// Opponent's attack on the challenger
+    pub opponent: BattleOutcome,
+    pub winner: BattleSide,
+}
+
+/// Resolve a simultaneous exchange of moves and pick the winner under the
+/// config's `resolution_mode`
+pub fn resolve_round(challenger: RoundInput, opponent: RoundInput, cfg: &BalanceConfig) -> RoundResolution {
+    match cfg.resolution_mode {
+        ResolutionMode::Damage => resolve_damage_round(challenger, opponent, cfg),
+        ResolutionMode::Score => resolve_score_round(challenger, opponent, cfg),
+    }
+}
+
+fn resolve_damage_round(challenger: RoundInput, opponent: RoundInput, cfg: &BalanceConfig) -> RoundResolution {
+    let challenger_outcome = calculate_battle_outcome(
+        challenger.move_choice,
+        challenger.player,
+        opponent.player,
+        opponent.hp,
+        challenger.vrf_result,
+        AttackBoosts { attacker: challenger.boost, defender: opponent.boost },
+        cfg,
+    );
+    let opponent_outcome = calculate_battle_outcome(
+        opponent.move_choice,
+        opponent.player,
+        challenger.player,
+        challenger.hp,
+        opponent.vrf_result,
+        AttackBoosts { attacker: opponent.boost, defender: challenger.boost },
+        cfg,
+    );
+
+    let challenger_hp = opponent_outcome.remaining_hp;
+    let opponent_hp = challenger_outcome.remaining_hp;
+
+    // True final HP (allowing negative values for better tiebreaking)
+    let challenger_true_hp = challenger.hp as i32 - opponent_outcome.damage_dealt as i32;
+    let opponent_true_hp = opponent.hp as i32 - challenger_outcome.damage_dealt as i32;
+
+    // Both died: whoever took less overkill wins. Otherwise higher remaining HP wins.
+    let by_hp = if challenger_hp == 0 && opponent_hp == 0 {
+        challenger_true_hp.cmp(&opponent_true_hp)
+    } else {
+        challenger_hp.cmp(&opponent_hp)
+    };
+    let winner = match by_hp
+        .then(challenger_outcome.damage_dealt.cmp(&opponent_outcome.damage_dealt))
+    {
+        std::cmp::Ordering::Greater => BattleSide::Challenger,
+        std::cmp::Ordering::Less => BattleSide::Opponent,
+        // True tie - use VRF
+        std::cmp::Ordering::Equal => break_tie(challenger.vrf_result, cfg),
+    };
+
+    RoundResolution { challenger: challenger_outcome, opponent: opponent_outcome, winner }
+}
+
+/// Exact ties go to the challenger unless `tie_break_rand` lets the roll decide
+pub(crate) fn break_tie(challenger_vrf: u64, cfg: &BalanceConfig) -> BattleSide {
+    if cfg.tie_break_rand && challenger_vrf % 2 == 1 {
+        BattleSide::Opponent
+    } else {
+        BattleSide::Challenger
+    }
+}
+
+/// Probability-weighted damage over every roll, for strategy helpers
 pub fn calculate_expected_damage(
     move_choice: MoveChoice,
     attacker: &Player,
-    defender_class: FighterClass,
+    defender: &Player,
+    cfg: &BalanceConfig,
 ) -> f64 {
-    let base_damage = 100.0;
-    let class_advantage = get_class_advantage(move_choice.class(), defender_class);
-    let player_power = calculate_player_power(attacker);
-    
-    let expected_multiplier = if !move_choice.is_special_move() {
-        1.0 // Basic moves
-    } else {
-        match move_choice {
-            MoveChoice::RugPullRumor => 0.30 * 0.0 + 0.50 * 1.5 + 0.20 * 2.2, // = 1.19
-            MoveChoice::TestnetDeploy => 0.25 * 0.0 + 0.55 * 1.6 + 0.20 * 2.3, // = 1.34
-            MoveChoice::ExitLiquidity => 0.35 * 0.0 + 0.45 * 1.4 + 0.20 * 2.0, // = 1.03
-            _ => 1.0,
-        }
-    };
-    
-    base_damage * class_advantage * player_power * expected_multiplier
+    let base_damage = cfg.base_damage as f64;
+    let class_advantage = get_class_advantage(move_choice.class(), defender.class, cfg);
+    let player_power = calculate_player_power(attacker, cfg);
+    let defense = defense_multiplier(defender, cfg) * gear_multiplier(attacker, defender);
+
+    let rolls = if move_choice.is_special_move() { 100 } else { 1 };
+    let expected_multiplier = (0..rolls)
+        .map(|roll| get_move_outcome(move_choice, attacker, roll, None, cfg).0)
+        .sum::<f64>()
+        / rolls as f64;
+
+    base_damage * class_advantage * player_power * expected_multiplier * defense
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::config::{BalanceConfigParams, SpecialMoveParams};
+    use crate::state::player::test_player;
     
     #[test]
     fn test_class_advantages() {
+        let cfg = demo_config();
         // Shitposter > VC
-        assert_eq!(get_class_advantage(FighterClass::Shitposter, FighterClass::VC), 1.25);
-        assert_eq!(get_class_advantage(FighterClass::VC, FighterClass::Shitposter), 0.80);
+        assert_eq!(get_class_advantage(FighterClass::Shitposter, FighterClass::VC, &cfg), 1.25);
+        assert_eq!(get_class_advantage(FighterClass::VC, FighterClass::Shitposter, &cfg), 0.80);
         
         // VC > Builder  
-        assert_eq!(get_class_advantage(FighterClass::VC, FighterClass::Builder), 1.25);
-        assert_eq!(get_class_advantage(FighterClass::Builder, FighterClass::VC), 0.80);
+        assert_eq!(get_class_advantage(FighterClass::VC, FighterClass::Builder, &cfg), 1.25);
+        assert_eq!(get_class_advantage(FighterClass::Builder, FighterClass::VC, &cfg), 0.80);
         
         // Builder > Shitposter
-        assert_eq!(get_class_advantage(FighterClass::Builder, FighterClass::Shitposter), 1.25);
-        assert_eq!(get_class_advantage(FighterClass::Shitposter, FighterClass::Builder), 0.80);
+        assert_eq!(get_class_advantage(FighterClass::Builder, FighterClass::Shitposter, &cfg), 1.25);
+        assert_eq!(get_class_advantage(FighterClass::Shitposter, FighterClass::Builder, &cfg), 0.80);
     }
     
     #[test] 
     fn test_battle_math_demo_scenario() {
-        use crate::state::player::{Player, FighterClass, MoveChoice};
+        use crate::state::player::{FighterClass, MoveChoice};
         
         // Create basic players (no abilities, no XP)
-        let challenger = Player {
-            authority: anchor_lang::prelude::Pubkey::default(),
-            class: FighterClass::Shitposter,
-            xp: 0,
-            abilities: [0, 0, 0],
-            elo: 1000,
-            version: 1,
-        };
+        let challenger = test_player(FighterClass::Shitposter, [0, 0, 0]);
         
-        let opponent = Player {
-            authority: anchor_lang::prelude::Pubkey::default(), 
-            class: FighterClass::Builder,
-            xp: 0,
-            abilities: [0, 0, 0],
-            elo: 1000,
-            version: 1,
-        };
+        let opponent = test_player(FighterClass::Builder, [0, 0, 0]);
         
         // Test MemeBomb (Shitposter → Builder)
         let meme_bomb_outcome = calculate_battle_outcome(
             MoveChoice::MemeBomb,
             &challenger,
-            FighterClass::Builder,
+            &opponent,
             200,
-            0 // VRF doesn't matter for basic moves
+            0, // VRF doesn't matter for basic moves
+            AttackBoosts::default(),
+            &demo_config(),
         );
         
         // Should be: 100 * 0.80 * 1.0 * 1.0 = 80 damage
@@ -238,9 +346,11 @@ mod tests {
         let ship_it_outcome = calculate_battle_outcome(
             MoveChoice::ShipIt,
             &opponent,
-            FighterClass::Shitposter,
+            &challenger,
             200,
-            0 // VRF doesn't matter for basic moves
+            0, // VRF doesn't matter for basic moves
+            AttackBoosts::default(),
+            &demo_config(),
         );
         
         // Should be: 100 * 1.25 * 1.0 * 1.0 = 125 damage
@@ -250,16 +360,146 @@ mod tests {
     
     #[test] 
     fn test_player_power() {
-        let player = Player {
-            authority: Pubkey::default(),
-            class: FighterClass::Shitposter,
-            xp: 2500, // Tier 2
-            abilities: [4, 3, 2], // 9 total levels
-            elo: 1000,
-            version: 1,
-        };
+        let player = Player { xp: 2500, ..test_player(FighterClass::Shitposter, [4, 3, 2]) };
         
-        // Expected: 1.0 + (9 * 0.05) + (2 * 0.02) = 1.49
-        assert_eq!(calculate_player_power(&player), 1.49);
+        // Abilities no longer count here: 1.0 + (2 * 0.02) = 1.04
+        assert_eq!(calculate_player_power(&player, &demo_config()), 1.04);
+
+        // 3 ranks at 4% would be +12%; prestige tops out at +10%
+        let cfg = BalanceConfig { prestige_power_bps: 400, ..demo_config() };
+        let veteran = Player { xp: 0, prestige: 3, ..player };
+        assert_eq!(calculate_player_power(&veteran, &cfg), 1.1);
+    }
+
+    fn demo_config() -> BalanceConfig {
+        BalanceConfig::with_params(&BalanceConfigParams::default())
+    }
+
+    #[test]
+    fn damage_tables_come_from_the_config() {
+        let fresh = test_player(FighterClass::Shitposter, [0, 0, 0]);
+        let player = Player { xp: 2_500, ..fresh.clone() };
+        let vc = Player { class: FighterClass::VC, ..fresh.clone() };
+        let cfg = BalanceConfig {
+            base_damage: 200,
+            class_win_bps: 15_000,
+            xp_tier_size: 500,
+            xp_tier_bonus_bps: 1_000,
+            ..demo_config()
+        };
+        assert_eq!(get_class_advantage(FighterClass::Shitposter, FighterClass::VC, &cfg), 1.5);
+        // 5 tiers at 10% each
+        assert_eq!(calculate_player_power(&player, &cfg), 1.5);
+        assert_eq!(calculate_damage(MoveChoice::MemeBomb, &player, &vc, 0, &cfg), 450);
+
+        let mut cfg = demo_config();
+        cfg.special_moves[FighterClass::Shitposter as usize] =
+            SpecialMoveParams { miss_pct: 0, hit_end_pct: 50, hit_mult_bps: 10_000, crit_mult_bps: 30_000 };
+        assert_eq!(calculate_damage(MoveChoice::RugPullRumor, &fresh, &fresh, 0, &cfg), 100);
+        assert_eq!(calculate_damage(MoveChoice::RugPullRumor, &fresh, &fresh, 50, &cfg), 300);
+    }
+
+    #[test]
+    fn test_ability_effects() {
+        let cfg = demo_config();
+        let builder = test_player(FighterClass::Builder, [0, 0, 0]);
+        let shitposter = test_player(FighterClass::Shitposter, [0, 0, 0]);
+
+        // BasicDamage: 2 levels = 4 points * 250 bps = +10%
+        let hitter = test_player(FighterClass::Shitposter, [2, 0, 0]);
+        assert_eq!(calculate_damage(MoveChoice::MemeBomb, &hitter, &builder, 0, &cfg), 88);
+        // The same levels elsewhere leave the basic move alone
+        let other = test_player(FighterClass::Shitposter, [0, 1, 1]);
+        assert_eq!(calculate_damage(MoveChoice::MemeBomb, &other, &builder, 0, &cfg), 80);
+
+        // SpecialAccuracy: 5 levels = 10 points * 50 bps = 5% off the 30% miss window
+        let accurate = test_player(FighterClass::Shitposter, [0, 5, 0]);
+        assert_eq!(calculate_damage(MoveChoice::RugPullRumor, &accurate, &shitposter, 24, &cfg), 0);
+        assert_eq!(calculate_damage(MoveChoice::RugPullRumor, &accurate, &shitposter, 25, &cfg), 150);
+        assert_eq!(calculate_damage(MoveChoice::RugPullRumor, &shitposter, &shitposter, 25, &cfg), 0);
+
+        // CritPower: 3 levels = 6 points * 500 bps = +0.3x on a 2.2x crit
+        let critter = test_player(FighterClass::Shitposter, [0, 0, 3]);
+        assert_eq!(calculate_damage(MoveChoice::RugPullRumor, &critter, &shitposter, 99, &cfg), 250);
+
+        // Defense: 5 levels = 10 points * 100 bps = -10%, capped at half
+        let tank = test_player(FighterClass::Builder, [0, 0, 5]);
+        assert_eq!(calculate_damage(MoveChoice::ShipIt, &builder, &tank, 0, &cfg), 90);
+        let wall = test_player(FighterClass::Builder, [0, 0, 100]);
+        assert_eq!(calculate_damage(MoveChoice::ShipIt, &builder, &wall, 0, &cfg), 50);
+        // Shitposters don't level Defense at all
+        assert_eq!(calculate_damage(MoveChoice::ShipIt, &builder, &critter, 0, &cfg), 125);
+
+        // Expected damage follows the shifted windows: 0.55 * 1.5 + 0.2 * 2.2
+        let expected = calculate_expected_damage(MoveChoice::RugPullRumor, &accurate, &shitposter, &cfg);
+        assert!((expected - 126.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn gear_scales_damage_up_to_the_cap() {
+        let cfg = demo_config();
+        let plain = test_player(FighterClass::Builder, [0, 0, 0]);
+        let armed = Player { gear: GearStats { attack_bps: 300, defense_bps: 0 }, ..plain.clone() };
+        let armored = Player { gear: GearStats { attack_bps: 0, defense_bps: 400 }, ..plain.clone() };
+        assert_eq!(calculate_damage(MoveChoice::ShipIt, &plain, &plain, 0, &cfg), 100);
+        assert_eq!(calculate_damage(MoveChoice::ShipIt, &armed, &plain, 0, &cfg), 103);
+        assert_eq!(calculate_damage(MoveChoice::ShipIt, &plain, &armored, 0, &cfg), 96);
+
+        // Hand-built stats past the cap count as the cap
+        let overgeared = Player { gear: GearStats { attack_bps: 5_000, defense_bps: 0 }, ..plain.clone() };
+        assert_eq!(calculate_damage(MoveChoice::ShipIt, &overgeared, &plain, 0, &cfg), 105);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn boosts_shield_and_widen_crits() {
+        let cfg = BalanceConfig { shield_bps: 2_000, alpha_leak_bps: 1_500, ..demo_config() };
+        let builder = test_player(FighterClass::Builder, [0, 0, 0]);
+        let shitposter = Player { class: FighterClass::Shitposter, ..builder.clone() };
+        let hit = |move_choice, attacker: &Player, defender: &Player, roll, boosts| {
+            calculate_battle_outcome(move_choice, attacker, defender, 200, roll, boosts, &cfg).damage_dealt
+        };
+        let shielded = AttackBoosts { attacker: None, defender: Some(Boost::LiquidityShield) };
+        let leaking = AttackBoosts { attacker: Some(Boost::AlphaLeak), defender: None };
+
+        assert_eq!(hit(MoveChoice::ShipIt, &builder, &builder, 0, AttackBoosts::default()), 100);
+        assert_eq!(hit(MoveChoice::ShipIt, &builder, &builder, 0, shielded), 80);
+        // Only the defender's shield counts
+        let own_shield = AttackBoosts { attacker: Some(Boost::LiquidityShield), defender: None };
+        assert_eq!(hit(MoveChoice::ShipIt, &builder, &builder, 0, own_shield), 100);
+
+        // Roll 70 is a plain hit (1.5x) until the leak moves the crit line from 80 to 65
+        assert_eq!(hit(MoveChoice::RugPullRumor, &shitposter, &shitposter, 70, AttackBoosts::default()), 150);
+        assert_eq!(hit(MoveChoice::RugPullRumor, &shitposter, &shitposter, 70, leaking), 220);
+        assert_eq!(hit(MoveChoice::RugPullRumor, &shitposter, &shitposter, 29, leaking), 0);
+        // Basic moves never crit, leak or not
+        assert_eq!(hit(MoveChoice::ShipIt, &builder, &builder, 99, leaking), 100);
+    }
+
+    #[test]
+    fn test_resolve_round() {
+        let mut challenger = test_player(FighterClass::Shitposter, [0, 0, 0]);
+        let mut opponent = Player { class: FighterClass::Builder, ..challenger.clone() };
+
+        // MemeBomb (80) vs ShipIt (125): opponent keeps more HP
+        let round = resolve_round(
+            RoundInput { player: &challenger, move_choice: MoveChoice::MemeBomb, hp: 200, vrf_result: 0, boost: None },
+            RoundInput { player: &opponent, move_choice: MoveChoice::ShipIt, hp: 200, vrf_result: 0, boost: None },
+            &demo_config(),
+        );
+        assert_eq!(round.challenger.remaining_hp, 120);
+        assert_eq!(round.opponent.remaining_hp, 75);
+        assert_eq!(round.winner, BattleSide::Opponent);
+
+        // Mirror match with identical rolls is a true tie
+        challenger.class = FighterClass::VC;
+        opponent.class = FighterClass::VC;
+        let mirror = |tie_break_rand, vrf_result| resolve_round(
+            RoundInput { player: &challenger, move_choice: MoveChoice::SeriesACannon, hp: 200, vrf_result, boost: None },
+            RoundInput { player: &opponent, move_choice: MoveChoice::SeriesACannon, hp: 200, vrf_result, boost: None },
+            &BalanceConfig { tie_break_rand, ..demo_config() },
+        ).winner;
+        assert_eq!(mirror(false, 1), BattleSide::Challenger);
+        assert_eq!(mirror(true, 1), BattleSide::Opponent);
+        assert_eq!(mirror(true, 2), BattleSide::Challenger);
+    }
+}
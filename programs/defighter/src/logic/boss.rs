@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use crate::state::boss::Boss;
+use crate::state::config::BalanceConfig;
+use crate::state::player::{MoveChoice, Player};
+use super::battle_math::{resolve_round, BattleOutcome, BattleSide, RoundInput};
+
+/// HP a player brings into a boss fight, same as a PvP battle
+pub const PLAYER_START_HP: u16 = 200;
+
+/// The boss as a `Player`, so the regular damage math applies to it.
+pub fn boss_fighter(boss: &Boss, key: Pubkey) -> Player {
+    Player::new(key, boss.class, [boss.level; 3])
+}
+
+/// Entropy for the boss's move: keccak(salt || battle || slot_hash), where
+/// `slot_hash` is the hash of the first slot after the commit. The player
+/// can't know it when committing, so no salt can be ground for a good draw,
+/// and the salt keeps it from being read off the chain by anyone else.
+pub fn boss_entropy(salt: &[u8; 32], battle: &Pubkey, slot_hash: &[u8; 32]) -> [u8; 32] {
+    anchor_lang::solana_program::keccak::hashv(&[salt, battle.as_ref(), slot_hash]).0
+}
+
+/// Pick the boss's basic or special move from `special_bps` and the entropy.
+pub fn boss_move(boss: &Boss, entropy: &[u8; 32]) -> MoveChoice {
+    let [basic, special] = boss.class.moves();
+    let roll = u64::from_le_bytes(entropy[0..8].try_into().unwrap()) % 10_000;
+    if roll < boss.special_bps as u64 { special } else { basic }
+}
+
+/// Result of one exchange between a player and a boss
+#[derive(Debug, Clone)]
+pub struct BossRound {
+    /// Player's attack on the boss
+    pub player: BattleOutcome,
+    /// Boss's attack on the player
+    pub boss: BattleOutcome,
+    pub player_won: bool,
+    /// Boss HP after the exchange; the new pool for raid bosses
+    pub boss_hp: u32,
+}
+
+/// Resolve a player-vs-boss exchange. Regular bosses fight a full round at
+/// `max_hp` like a PvP battle; raid bosses are only beaten by the hit that
+/// empties their shared pool.
+pub fn resolve_boss_round(
+    player: RoundInput,
+    boss: &Boss,
+    boss_key: Pubkey,
+    boss_move: MoveChoice,
+    boss_roll: u64,
+    cfg: &BalanceConfig,
+) -> BossRound {
+    let fighter = boss_fighter(boss, boss_key);
+    let start_hp = if boss.shared_hp { boss.hp } else { boss.max_hp };
+    let round = resolve_round(
+        player,
+        RoundInput {
+            player: &fighter,
+            move_choice: boss_move,
+            hp: start_hp.min(u16::MAX as u32) as u16,
+            vrf_result: boss_roll,
+            boost: None,
+        },
+        cfg,
+    );
+    let damage = round.challenger.damage_dealt as u32;
+    let boss_hp = start_hp.saturating_sub(damage);
+    let player_won = if boss.shared_hp { boss_hp == 0 } else { round.winner == BattleSide::Challenger };
+    BossRound { player: round.challenger, boss: round.opponent, player_won, boss_hp }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::config::BalanceConfigParams;
+    use crate::state::player::{test_player, FighterClass};
+
+    fn boss(shared_hp: bool, hp: u32) -> Boss {
+        Boss {
+            id: 1,
+            class: FighterClass::VC,
+            level: 0,
+            max_hp: 150,
+            hp,
+            special_bps: 0,
+            shared_hp,
+            xp_win: 50,
+            xp_loss: 5,
+            fights: 0,
+            defeated_by: None,
+        }
+    }
+
+    fn demo_config() -> BalanceConfig {
+        BalanceConfig::with_params(&BalanceConfigParams::default())
+    }
+
+    fn player() -> Player {
+        Player { authority: Pubkey::new_unique(), ..test_player(FighterClass::Shitposter, [0; 3]) }
+    }
+
+    #[test]
+    fn boss_move_follows_special_bps() {
+        let mut b = boss(false, 150);
+        let entropy = boss_entropy(&[7; 32], &Pubkey::new_unique(), &[10; 32]);
+        assert_eq!(boss_move(&b, &entropy), MoveChoice::SeriesACannon);
+        b.special_bps = 10_000;
+        assert_eq!(boss_move(&b, &entropy), MoveChoice::ExitLiquidity);
+    }
+
+    #[test]
+    fn raid_boss_needs_the_killing_blow() {
+        let p = player();
+        let input = RoundInput { player: &p, move_choice: MoveChoice::MemeBomb, hp: PLAYER_START_HP, vrf_result: 0, boost: None };
+
+        // MemeBomb vs VC deals 125; a 1000 HP pool survives it
+        let round = resolve_boss_round(input, &boss(true, 1000), Pubkey::default(), MoveChoice::SeriesACannon, 0, &demo_config());
+        assert!(!round.player_won);
+        assert_eq!(round.boss_hp, 875);
+
+        let round = resolve_boss_round(input, &boss(true, 100), Pubkey::default(), MoveChoice::SeriesACannon, 0, &demo_config());
+        assert!(round.player_won);
+        assert_eq!(round.boss_hp, 0);
+
+        // Regular bosses fight a normal round: 150 - 125 = 25 left vs 200 - 80
+        let round = resolve_boss_round(input, &boss(false, 150), Pubkey::default(), MoveChoice::SeriesACannon, 0, &demo_config());
+        assert!(round.player_won);
+        assert_eq!(round.boss_hp, 25);
+        assert_eq!(round.boss.remaining_hp, 120);
+    }
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::consumable::Boost;
+use crate::state::player::MoveChoice;
+
+/// Commitment checked by `reveal_move`: keccak(move_byte || salt || player || battle).
+pub fn commitment_hash(
+    move_choice: MoveChoice,
+    salt: &[u8; 32],
+    player: &Pubkey,
+    battle: &Pubkey,
+) -> [u8; 32] {
+    anchor_lang::solana_program::keccak::hashv(&[
+        &[move_choice.to_byte()],
+        salt,
+        player.as_ref(),
+        battle.as_ref(),
+    ]).0
+}
+
+/// Commitment for a move played with a boost: the boost byte is appended,
+/// so a boost-free commitment is exactly `commitment_hash`.
+pub fn boosted_commitment_hash(
+    move_choice: MoveChoice,
+    boost: Option<Boost>,
+    salt: &[u8; 32],
+    player: &Pubkey,
+    battle: &Pubkey,
+) -> [u8; 32] {
+    let Some(boost) = boost else {
+        return commitment_hash(move_choice, salt, player, battle);
+    };
+    anchor_lang::solana_program::keccak::hashv(&[
+        &[move_choice.to_byte()],
+        salt,
+        player.as_ref(),
+        battle.as_ref(),
+        &[boost.to_byte()],
+    ]).0
+}
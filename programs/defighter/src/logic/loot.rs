@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use crate::state::config::BalanceConfig;
+use crate::state::consumable::Boost;
+use crate::state::item::Rarity;
+
+const RARITIES: [Rarity; 5] = [Rarity::Common, Rarity::Uncommon, Rarity::Rare, Rarity::Epic, Rarity::Legendary];
+
+/// Loot roll for a resolved battle, derived from both sides' round entropy
+pub fn loot_entropy(challenger_vrf: u64, opponent_vrf: u64, battle: &Pubkey) -> u64 {
+    let hash = anchor_lang::solana_program::keccak::hashv(&[
+        b"loot",
+        &challenger_vrf.to_le_bytes(),
+        &opponent_vrf.to_le_bytes(),
+        battle.as_ref(),
+    ]);
+    u64::from_le_bytes(hash.0[0..8].try_into().unwrap())
+}
+
+/// What a win drops, given the winner's pity count. The low bits decide
+/// whether anything drops, the high bits which rarity. Once `pity` reaches
+/// `loot_pity_threshold` the drop is forced and drawn from Rare and above.
+pub fn roll_loot(roll: u64, pity: u16, cfg: &BalanceConfig) -> Option<Rarity> {
+    let guaranteed = cfg.loot_pity_threshold > 0 && pity >= cfg.loot_pity_threshold;
+    if !guaranteed && (roll as u32) % 10_000 >= cfg.loot_drop_bps as u32 {
+        return None;
+    }
+    let floor = if guaranteed { Rarity::Rare } else { Rarity::Common };
+    let weights: Vec<(Rarity, u64)> = RARITIES
+        .iter()
+        .zip(cfg.loot_rarity_weights)
+        .filter(|(r, _)| **r >= floor)
+        .map(|(r, w)| (*r, w as u64))
+        .collect();
+    let total: u64 = weights.iter().map(|(_, w)| w).sum();
+    if total == 0 {
+        // Only reachable for pity with no Rare+ weights configured
+        return guaranteed.then_some(floor);
+    }
+    let mut pick = (roll >> 32) % total;
+    for (rarity, weight) in weights {
+        if pick < weight {
+            return Some(rarity);
+        }
+        pick -= weight;
+    }
+    unreachable!("pick is below the total weight")
+}
+
+/// Boost a win drops alongside its loot, if any. Rolled from a rehash of the
+/// loot roll so it doesn't track the loot drop.
+pub fn roll_boost(roll: u64, cfg: &BalanceConfig) -> Option<Boost> {
+    let hash = anchor_lang::solana_program::keccak::hashv(&[b"boost", &roll.to_le_bytes()]);
+    let boost_roll = u64::from_le_bytes(hash.0[0..8].try_into().unwrap());
+    ((boost_roll as u32) % 10_000 < cfg.boost_drop_bps as u32).then(|| Boost::from_roll(boost_roll >> 32))
+}
+
+/// Pity after a battle: reset by a Rare-or-better drop, otherwise one more
+pub fn next_pity(pity: u16, dropped: Option<Rarity>) -> u16 {
+    match dropped {
+        Some(rarity) if rarity >= Rarity::Rare => 0,
+        _ => pity.saturating_add(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::config::BalanceConfigParams;
+
+    fn cfg(drop_bps: u16, weights: [u16; 5], pity: u16) -> BalanceConfig {
+        BalanceConfig::with_params(&BalanceConfigParams {
+            loot_drop_bps: drop_bps,
+            loot_rarity_weights: weights,
+            loot_pity_threshold: pity,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn drops_follow_rate_and_weights() {
+        let cfg = cfg(3_000, [1, 1, 0, 0, 1], 0);
+        assert_eq!(roll_loot(3_000, 0, &cfg), None);
+        assert_eq!(roll_loot(2_999, 0, &cfg), Some(Rarity::Common));
+        assert_eq!(roll_loot((1 << 32) | 2_999, 0, &cfg), Some(Rarity::Uncommon));
+        // Zero-weight rarities are skipped
+        assert_eq!(roll_loot((2 << 32) | 2_999, 0, &cfg), Some(Rarity::Legendary));
+
+        // Over many rolls the rate lands near 30%
+        let drops = (0..10_000u64).filter(|i| roll_loot(loot_entropy(*i, 0, &Pubkey::default()), 0, &cfg).is_some()).count();
+        assert!((2_800..3_200).contains(&drops), "{drops}");
+    }
+
+    #[test]
+    fn pity_forces_a_rare_or_better() {
+        let cfg = cfg(0, [1, 1, 1, 1, 0], 3);
+        assert_eq!(roll_loot(0, 2, &cfg), None);
+        assert_eq!(roll_loot(0, 3, &cfg), Some(Rarity::Rare));
+        assert_eq!(roll_loot(1 << 32, 3, &cfg), Some(Rarity::Epic));
+        assert_eq!(roll_loot(0, 3, &self::cfg(0, [1, 0, 0, 0, 0], 3)), Some(Rarity::Rare));
+
+        assert_eq!(next_pity(2, None), 3);
+        assert_eq!(next_pity(2, Some(Rarity::Uncommon)), 3);
+        assert_eq!(next_pity(9, Some(Rarity::Rare)), 0);
+    }
+}
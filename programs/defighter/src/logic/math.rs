@@ -11,6 +11,12 @@ pub fn ability_power(level: u16, cfg: &BalanceConfig) -> i32 {
     }
 }
 
+/// Points an ability level is worth over an unlevelled ability, so level 0
+/// never changes combat regardless of the curve's `base`.
+pub fn ability_bonus(level: u16, cfg: &BalanceConfig) -> i32 {
+    ability_power(level, cfg).saturating_sub(ability_power(0, cfg)).max(0)
+}
+
 pub fn powi_u64(base: u64, exp: u32) -> u64 {
     // fast exponentiation for small integer exponents
     let mut result = 1u64;
@@ -24,23 +30,70 @@ pub fn powi_u64(base: u64, exp: u32) -> u64 {
     result
 }
 
+/// `upgrade_c0 * level_next^p` with `p = upgrade_p_bps / 10000`, rounded to
+/// the nearest XP. Exponents outside `BalanceConfig::UPGRADE_P_BPS_RANGE` are
+/// rejected by config validation, so they are clamped here rather than trusted.
 pub fn upgrade_cost(level_next: u16, cfg: &BalanceConfig) -> u64 {
-    // MVP: support p = 1.0 or 1.4 approximated by piecewise: level^(14/10) ~ level^1 * level^(4/10)
-    // Use simple linear if p_bps == 10000
-    if cfg.upgrade_p_bps == 10000 {
-        return cfg.upgrade_c0.saturating_mul(level_next as u64);
-    }
-    // Approx fractional exponent via sqrt chaining for 0.4 ~ sqrt(sqrt(level)) ~ rough
-    // Keep simple and bounded for determinism
-    let l = level_next as u64;
-    let approx_pow = if cfg.upgrade_p_bps == 14000 {
-        let sqrt_l = integer_sqrt(l.max(1));
-        let fourth_root = integer_sqrt(sqrt_l.max(1));
-        l.saturating_mul(fourth_root) // ~ l^(1 + 0.5*0.5) = l^1.25; underestimates 1.4 but monotonic
-    } else {
-        l
-    };
-    cfg.upgrade_c0.saturating_mul(approx_pow)
+    let p_bps = cfg.upgrade_p_bps.clamp(
+        *BalanceConfig::UPGRADE_P_BPS_RANGE.start(),
+        *BalanceConfig::UPGRADE_P_BPS_RANGE.end(),
+    );
+    let pow = pow_fixed(level_next as u64, p_bps);
+    let cost = (cfg.upgrade_c0 as u128).saturating_mul(pow).saturating_add(FIXED_ONE / 2) >> FIXED_FRAC_BITS;
+    cost.min(u64::MAX as u128) as u64
+}
+
+/// Fractional bits of the fixed-point values `pow_fixed` works in.
+pub const FIXED_FRAC_BITS: u32 = 48;
+const FIXED_ONE: u128 = 1 << FIXED_FRAC_BITS;
+/// ln(2) in Q48
+const LN_2: u128 = 195_103_586_505_167;
+
+/// `base^(p_bps / 10000)` in Q48. Whole exponents multiply exactly; fractional
+/// ones go through `exp2(p * log2(base))`, well within 0.1% for bases up to
+/// `u16::MAX` and exponents up to 3.
+pub fn pow_fixed(base: u64, p_bps: u16) -> u128 {
+    if base == 0 {
+        return 0;
+    }
+    if p_bps % 10_000 == 0 {
+        return (powi_u64(base, (p_bps / 10_000) as u32) as u128).saturating_mul(FIXED_ONE);
+    }
+    let exponent = log2_fixed(base) * p_bps as u128 / 10_000;
+    exp2_fixed(exponent)
+}
+
+/// log2 of a positive integer in Q48, one fractional bit per squaring.
+fn log2_fixed(n: u64) -> u128 {
+    let int = 63 - n.leading_zeros();
+    // Normalise into [1, 2)
+    let mut y = ((n as u128) << FIXED_FRAC_BITS) >> int;
+    let mut result = (int as u128) << FIXED_FRAC_BITS;
+    for bit in (0..FIXED_FRAC_BITS).rev() {
+        y = (y * y) >> FIXED_FRAC_BITS;
+        if y >= 2 * FIXED_ONE {
+            y >>= 1;
+            result |= 1 << bit;
+        }
+    }
+    result
+}
+
+/// 2^x for Q48 `x`: the integer part shifts, the fraction is a Taylor series
+/// of e^(f * ln 2), which converges fast since f * ln 2 < 0.7.
+fn exp2_fixed(x: u128) -> u128 {
+    let int = (x >> FIXED_FRAC_BITS) as u32;
+    let z = ((x & (FIXED_ONE - 1)) * LN_2) >> FIXED_FRAC_BITS;
+    let mut term = FIXED_ONE;
+    let mut sum = FIXED_ONE;
+    for i in 1..=20u128 {
+        term = ((term * z) >> FIXED_FRAC_BITS) / i;
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
+    sum.checked_shl(int).filter(|v| v >> int == sum).unwrap_or(u128::MAX)
 }
 
 pub fn integer_sqrt(n: u64) -> u64 {
@@ -56,3 +109,38 @@ pub fn integer_sqrt(n: u64) -> u64 {
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pow_fixed_is_accurate() {
+        for p_bps in (10_000..=30_000).step_by(250) {
+            for base in (1..=2_000u64).chain([4_095, 10_000, 65_535]) {
+                let got = pow_fixed(base, p_bps as u16) as f64 / FIXED_ONE as f64;
+                let want = (base as f64).powf(p_bps as f64 / 10_000.0);
+                let err = (got - want).abs() / want;
+                assert!(err < 1e-3, "{base}^{p_bps}bps: {got} vs {want}");
+            }
+        }
+    }
+
+    #[test]
+    fn upgrade_cost_curve() {
+        let cfg = |upgrade_c0, upgrade_p_bps| BalanceConfig { upgrade_c0, upgrade_p_bps, ..Default::default() };
+        // Whole exponents are exact
+        assert_eq!(upgrade_cost(10, &cfg(100, 10_000)), 1_000);
+        assert_eq!(upgrade_cost(10, &cfg(7, 20_000)), 700);
+        assert_eq!(upgrade_cost(100, &cfg(1, 30_000)), 1_000_000);
+        // 10^1.4 = 25.1189, 81^1.4 = 469.763
+        assert_eq!(upgrade_cost(10, &cfg(100, 14_000)), 2_512);
+        assert_eq!(upgrade_cost(81, &cfg(100, 14_000)), 46_976);
+        assert_eq!(upgrade_cost(0, &cfg(100, 14_000)), 0);
+        // Strictly increasing along the default curve
+        let demo = cfg(10, 14_000);
+        assert!((1..200).all(|l| upgrade_cost(l + 1, &demo) > upgrade_cost(l, &demo)));
+        // Saturates instead of overflowing
+        assert_eq!(upgrade_cost(u16::MAX, &cfg(u64::MAX, 30_000)), u64::MAX);
+    }
+}
@@ -3,11 +3,19 @@ pub mod scoring;
 pub mod math;
 pub mod vrf;
 pub mod battle_math;
+pub mod commitment;
+pub mod boss;
+pub mod loot;
+pub mod nft;
 
 pub use rps::*;
 pub use scoring::*;
 pub use math::*;
 pub use vrf::*;
 pub use battle_math::*;
+pub use commitment::*;
+pub use boss::*;
+pub use loot::*;
+pub use nft::*;
 
 
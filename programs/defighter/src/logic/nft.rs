@@ -0,0 +1,248 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+use crate::errors::CustomError;
+use crate::state::crafting::CosmeticKind;
+use crate::state::nft::NftPerk;
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// `Key::MetadataV1`, the first byte of a Metaplex metadata account
+const METADATA_V1: u8 = 4;
+/// Base SPL token account length; Token-2022 extensions come after it
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// The fields we need from an SPL token account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenHolding {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+/// Read an initialized SPL Token or Token-2022 account. Frozen accounts
+/// still count: the holder owns the token, they just can't move it.
+pub fn parse_token_account(data: &[u8]) -> Option<TokenHolding> {
+    if data.len() < TOKEN_ACCOUNT_LEN || data[108] == 0 {
+        return None;
+    }
+    Some(TokenHolding {
+        mint: Pubkey::try_from(&data[0..32]).ok()?,
+        owner: Pubkey::try_from(&data[32..64]).ok()?,
+        amount: u64::from_le_bytes(data[64..72].try_into().ok()?),
+    })
+}
+
+/// The mint of the NFT `token` holds for `owner`, if any
+pub fn held_nft(token: &AccountInfo, owner: &Pubkey) -> Option<Pubkey> {
+    if *token.owner != TOKEN_PROGRAM_ID && *token.owner != TOKEN_2022_PROGRAM_ID {
+        return None;
+    }
+    let holding = parse_token_account(&token.try_borrow_data().ok()?)?;
+    (holding.owner == *owner && holding.amount > 0).then_some(holding.mint)
+}
+
+/// SPL mint length
+const MINT_LEN: usize = 82;
+
+/// Whether `mint` is an initialized SPL Token mint that can only ever have
+/// one whole token, held freely: no decimals, supply 1, mint and freeze
+/// authorities revoked. Token-2022 mints are refused outright, since a
+/// permanent delegate, transfer hook or non-transferable extension would
+/// let someone other than the holder decide where the character goes.
+pub fn is_one_of_one(mint: &AccountInfo) -> bool {
+    if *mint.owner != TOKEN_PROGRAM_ID {
+        return false;
+    }
+    mint.try_borrow_data().map(|data| is_one_of_one_mint(&data)).unwrap_or(false)
+}
+
+fn is_one_of_one_mint(data: &[u8]) -> bool {
+    data.len() == MINT_LEN
+        && data[0..4] == [0; 4] // mint_authority: None
+        && data[36..44] == 1u64.to_le_bytes() // supply
+        && data[44] == 0 // decimals
+        && data[45] == 1 // is_initialized
+        && data[46..50] == [0; 4] // freeze_authority: None
+}
+
+/// The Metaplex metadata PDA for `mint`
+pub fn metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"metadata", METADATA_PROGRAM_ID.as_ref(), mint.as_ref()], &METADATA_PROGRAM_ID).0
+}
+
+/// The fields we need from a Metaplex metadata account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NftMetadata {
+    pub mint: Pubkey,
+    /// The collection, only if the collection authority verified membership
+    pub verified_collection: Option<Pubkey>,
+}
+
+/// Borsh cursor over metadata; every read fails on truncated data
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn pubkey(&mut self) -> Option<Pubkey> {
+        Pubkey::try_from(self.take(32)?).ok()
+    }
+
+    fn skip_string(&mut self) -> Option<()> {
+        let len = u32::from_le_bytes(self.take(4)?.try_into().ok()?);
+        self.take(len as usize).map(|_| ())
+    }
+}
+
+/// Read a `MetadataV1` account up to its `collection` field. Accounts written
+/// before collections existed end early and read as having none.
+pub fn parse_metadata(data: &[u8]) -> Option<NftMetadata> {
+    let mut r = Reader(data);
+    if r.u8()? != METADATA_V1 {
+        return None;
+    }
+    r.pubkey()?; // update_authority
+    let mint = r.pubkey()?;
+    r.skip_string()?; // name
+    r.skip_string()?; // symbol
+    r.skip_string()?; // uri
+    r.take(2)?; // seller_fee_basis_points
+    if r.u8()? == 1 {
+        // creators: address, verified, share
+        let count = u32::from_le_bytes(r.take(4)?.try_into().ok()?);
+        r.take(count as usize * 34)?;
+    }
+    r.take(2)?; // primary_sale_happened, is_mutable
+
+    let verified_collection = (|| {
+        for _ in 0..2 {
+            // edition_nonce, token_standard: Option<u8>
+            if r.u8()? == 1 {
+                r.u8()?;
+            }
+        }
+        if r.u8()? != 1 {
+            return None;
+        }
+        let verified = r.u8()? == 1;
+        let key = r.pubkey()?;
+        verified.then_some(key)
+    })();
+    Some(NftMetadata { mint, verified_collection })
+}
+
+/// Cosmetics unlocked by `(NftPerk, token account)` remaining-account pairs,
+/// re-verifying that each perk's NFT is still in `owner`'s token account
+pub fn nft_unlocks<'info>(
+    pairs: &'info [AccountInfo<'info>],
+    customization: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Vec<(CosmeticKind, u8)>> {
+    require!(pairs.len() % 2 == 0, CustomError::InvalidNftPerk);
+    pairs
+        .chunks(2)
+        .map(|pair| {
+            let perk = Account::<NftPerk>::try_from(&pair[0])?;
+            require_keys_eq!(perk.customization, *customization, CustomError::InvalidNftPerk);
+            require_keys_eq!(pair[1].key(), perk.token_account, CustomError::NftNotHeld);
+            require!(held_nft(&pair[1], owner) == Some(perk.mint), CustomError::NftNotHeld);
+            Ok((perk.kind, perk.index))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn borsh_string(out: &mut Vec<u8>, s: &str) {
+        out.extend((s.len() as u32).to_le_bytes());
+        out.extend(s.as_bytes());
+    }
+
+    fn metadata(mint: Pubkey, creators: u32, collection: Option<(bool, Pubkey)>) -> Vec<u8> {
+        let mut data = vec![METADATA_V1];
+        data.extend(Pubkey::new_unique().to_bytes());
+        data.extend(mint.to_bytes());
+        borsh_string(&mut data, "Mad Lad #1");
+        borsh_string(&mut data, "MAD");
+        borsh_string(&mut data, "https://example.com/1.json");
+        data.extend(500u16.to_le_bytes());
+        data.push(1);
+        data.extend(creators.to_le_bytes());
+        data.extend(vec![7; creators as usize * 34]);
+        data.extend([1, 1]);
+        data.extend([1, 255]); // edition_nonce
+        data.extend([1, 4]); // token_standard
+        match collection {
+            Some((verified, key)) => {
+                data.extend([1, verified as u8]);
+                data.extend(key.to_bytes());
+            }
+            None => data.push(0),
+        }
+        // On-chain accounts are zero-padded past the fields
+        data.extend([0; 64]);
+        data
+    }
+
+    #[test]
+    fn reads_verified_collections_only() {
+        let (mint, collection) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let parsed = parse_metadata(&metadata(mint, 2, Some((true, collection)))).unwrap();
+        assert_eq!(parsed, NftMetadata { mint, verified_collection: Some(collection) });
+        let unverified = parse_metadata(&metadata(mint, 0, Some((false, collection)))).unwrap();
+        assert_eq!(unverified.verified_collection, None);
+        assert_eq!(parse_metadata(&metadata(mint, 1, None)).unwrap().verified_collection, None);
+
+        let mut wrong_key = metadata(mint, 0, None);
+        wrong_key[0] = 6;
+        assert_eq!(parse_metadata(&wrong_key), None);
+        assert_eq!(parse_metadata(&metadata(mint, 0, None)[..80]), None);
+    }
+
+    #[test]
+    fn reads_token_accounts() {
+        let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0; TOKEN_ACCOUNT_LEN];
+        data[0..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data[64..72].copy_from_slice(&1u64.to_le_bytes());
+        assert_eq!(parse_token_account(&data), None);
+        data[108] = 2; // frozen
+        assert_eq!(parse_token_account(&data), Some(TokenHolding { mint, owner, amount: 1 }));
+        assert_eq!(parse_token_account(&data[..100]), None);
+    }
+
+    #[test]
+    fn one_of_one_mints_have_a_fixed_supply() {
+        let mut data = vec![0; MINT_LEN];
+        data[36..44].copy_from_slice(&1u64.to_le_bytes());
+        data[45] = 1;
+        assert!(is_one_of_one_mint(&data));
+        let mut mintable = data.clone();
+        mintable[0] = 1;
+        let mut fungible = data.clone();
+        fungible[44] = 6;
+        let mut uninitialized = data.clone();
+        uninitialized[45] = 0;
+        let mut freezable = data.clone();
+        freezable[46] = 1;
+        for bad in [mintable, fungible, uninitialized, freezable, data[..81].to_vec()] {
+            assert!(!is_one_of_one_mint(&bad));
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use crate::logic::rps::{rps_compare, RpsOutcome};
-use crate::state::player::{FighterClass};
+use crate::logic::battle_math::{break_tie, BattleOutcome, BattleSide, MoveResult, RoundInput, RoundResolution};
+use crate::state::player::{FighterClass, MoveChoice, Player};
 use crate::state::config::BalanceConfig;
+use crate::state::consumable::Boost;
+use crate::state::item::GearStats;
 use crate::logic::math::ability_power;
 
 pub struct Scores { pub challenger: i32, pub opponent: i32 }
@@ -26,4 +29,205 @@ pub fn compute_scores(
     )
 }
 
+/// Ability level a move scores with: basic moves use index 0 (BasicDamage for
+/// every class), specials index 1 (the class's special-move ability).
+pub fn score_level(player: &Player, move_choice: MoveChoice) -> u16 {
+    if move_choice.is_special_move() {
+        player.abilities[1]
+    } else {
+        player.abilities[0]
+    }
+}
+
+/// `score` after the bonuses Damage mode puts on damage: the attacker's gear
+/// attack, prestige power and, on a special, Alpha Leak (`alpha_leak_bps`
+/// more score) raise it; the defender's gear defense and Liquidity Shield
+/// cut it.
+fn modified_score(score: i32, attacker: &RoundInput, defender: &RoundInput, cfg: &BalanceConfig) -> i32 {
+    let cap = GearStats::MAX_TOTAL_BPS as i64;
+    let mut attack_bps = 10_000
+        + (attacker.player.gear.attack_bps as i64).min(cap)
+        + attacker.player.prestige_power_bps(cfg) as i64;
+    if attacker.move_choice.is_special_move() && attacker.boost == Some(Boost::AlphaLeak) {
+        attack_bps += cfg.alpha_leak_bps as i64;
+    }
+    let mut kept_bps = 10_000 - (defender.player.gear.defense_bps as i64).min(cap);
+    if defender.boost == Some(Boost::LiquidityShield) {
+        kept_bps = kept_bps * (10_000 - (cfg.shield_bps as i64).min(BalanceConfig::MAX_DEFENSE_BPS as i64)) / 10_000;
+    }
+    (score as i64 * attack_bps / 10_000 * kept_bps / 10_000).clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+/// `ResolutionMode::Score`: the higher score wins the exchange and deals the
+/// margin as damage, so HP still tracks who is ahead across rounds.
+pub fn resolve_score_round(challenger: RoundInput, opponent: RoundInput, cfg: &BalanceConfig) -> RoundResolution {
+    let (raw, _) = compute_scores(
+        challenger.player.class,
+        opponent.player.class,
+        score_level(challenger.player, challenger.move_choice),
+        score_level(opponent.player, opponent.move_choice),
+        cfg,
+    );
+    let scores = Scores {
+        challenger: modified_score(raw.challenger, &challenger, &opponent, cfg),
+        opponent: modified_score(raw.opponent, &opponent, &challenger, cfg),
+    };
+    let winner = match scores.challenger.cmp(&scores.opponent) {
+        std::cmp::Ordering::Greater => BattleSide::Challenger,
+        std::cmp::Ordering::Less => BattleSide::Opponent,
+        std::cmp::Ordering::Equal => break_tie(challenger.vrf_result, cfg),
+    };
+    let margin = scores.challenger.abs_diff(scores.opponent).min(u16::MAX as u32) as u16;
+    let (challenger_damage, opponent_damage) = match winner {
+        BattleSide::Challenger => (margin, 0),
+        BattleSide::Opponent => (0, margin),
+    };
+
+    RoundResolution {
+        challenger: score_outcome(challenger.move_choice, challenger_damage, opponent.hp),
+        opponent: score_outcome(opponent.move_choice, opponent_damage, challenger.hp),
+        winner,
+    }
+}
+
+fn score_outcome(move_choice: MoveChoice, damage_dealt: u16, defender_hp: u16) -> BattleOutcome {
+    BattleOutcome {
+        damage_dealt,
+        remaining_hp: defender_hp.saturating_sub(damage_dealt),
+        move_result: if move_choice.is_special_move() { MoveResult::SpecialHit } else { MoveResult::BasicHit },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::battle_math::resolve_round;
+    use crate::state::config::{BalanceConfigParams, ResolutionMode};
+    use crate::state::player::test_player;
+
+    fn fighter(class: FighterClass, abilities: [u16; 3]) -> Player {
+        test_player(class, abilities)
+    }
+
+    fn score_config() -> BalanceConfig {
+        BalanceConfig {
+            base: 10,
+            linear_a: 2,
+            dim_k: 20,
+            dim_t: 10,
+            rps_win_base: 20,
+            rps_tie_base: 10,
+            tie_break_rand: true,
+            resolution_mode: ResolutionMode::Score,
+            ..BalanceConfig::with_params(&BalanceConfigParams::default())
+        }
+    }
 
+    fn round(
+        c: &Player,
+        c_move: MoveChoice,
+        o: &Player,
+        o_move: MoveChoice,
+        cfg: &BalanceConfig,
+    ) -> RoundResolution {
+        resolve_round(
+            RoundInput { player: c, move_choice: c_move, hp: 200, vrf_result: 1, boost: None },
+            RoundInput { player: o, move_choice: o_move, hp: 200, vrf_result: 0, boost: None },
+            cfg,
+        )
+    }
+
+    #[test]
+    fn scores_follow_config() {
+        let cfg = score_config();
+        // Advantage: 20 + (10 + 2*3) vs 0 + (10 + 2*0)
+        let (scores, rps) = compute_scores(FighterClass::Shitposter, FighterClass::VC, 3, 0, &cfg);
+        assert!(rps == RpsOutcome::ChallengerWin);
+        assert_eq!((scores.challenger, scores.opponent), (36, 10));
+
+        // Diminishing curve: 10 + 20*10/(10+10)
+        let dim = BalanceConfig { use_dim_bonus: true, ..cfg };
+        let (scores, _) = compute_scores(FighterClass::VC, FighterClass::VC, 10, 0, &dim);
+        assert_eq!((scores.challenger, scores.opponent), (30, 20));
+    }
+
+    #[test]
+    fn score_mode_winner_and_margin() {
+        let cfg = score_config();
+        let builder = fighter(FighterClass::Builder, [0, 0, 0]);
+        let shitposter = fighter(FighterClass::Shitposter, [12, 0, 0]);
+
+        // Builder's RPS edge (30 vs 10) beats nine levels...
+        let r = round(&builder, MoveChoice::ShipIt, &fighter(FighterClass::Shitposter, [9, 0, 0]), MoveChoice::MemeBomb, &cfg);
+        assert_eq!(r.winner, BattleSide::Challenger);
+        assert_eq!((r.challenger.damage_dealt, r.opponent.damage_dealt), (2, 0));
+        assert_eq!(r.challenger.remaining_hp, 198);
+
+        // ...but not twelve, and only the level behind the chosen move counts
+        let r = round(&builder, MoveChoice::ShipIt, &shitposter, MoveChoice::MemeBomb, &cfg);
+        assert_eq!(r.winner, BattleSide::Opponent);
+        assert_eq!(r.opponent.damage_dealt, 4);
+        let r = round(&builder, MoveChoice::ShipIt, &shitposter, MoveChoice::RugPullRumor, &cfg);
+        assert_eq!(r.winner, BattleSide::Challenger);
+        assert!(matches!(r.opponent.move_result, MoveResult::SpecialHit));
+    }
+
+    #[test]
+    fn score_mode_ties() {
+        let vc = fighter(FighterClass::VC, [2, 2, 2]);
+        let cfg = score_config();
+        // Challenger's roll is odd, so a random tie-break hands it to the opponent
+        let r = round(&vc, MoveChoice::SeriesACannon, &vc, MoveChoice::ExitLiquidity, &cfg);
+        assert_eq!(r.winner, BattleSide::Opponent);
+        assert_eq!((r.challenger.damage_dealt, r.opponent.damage_dealt), (0, 0));
+
+        let fixed = BalanceConfig { tie_break_rand: false, ..cfg };
+        assert_eq!(round(&vc, MoveChoice::SeriesACannon, &vc, MoveChoice::ExitLiquidity, &fixed).winner, BattleSide::Challenger);
+    }
+
+    #[test]
+    fn score_mode_applies_boosts_gear_and_prestige() {
+        let cfg = score_config();
+        let builder = fighter(FighterClass::Builder, [0, 0, 0]);
+        let basic = fighter(FighterClass::Shitposter, [9, 0, 0]);
+        let special = fighter(FighterClass::Shitposter, [0, 9, 0]);
+        let exchange = |o: &Player, o_move, c_boost, o_boost| resolve_score_round(
+            RoundInput { player: &builder, move_choice: MoveChoice::ShipIt, hp: 200, vrf_result: 0, boost: c_boost },
+            RoundInput { player: o, move_choice: o_move, hp: 200, vrf_result: 0, boost: o_boost },
+            &cfg,
+        );
+
+        // 30 vs 28 unboosted; a 20% shield cuts the builder to 24
+        assert_eq!(exchange(&basic, MoveChoice::MemeBomb, None, None).winner, BattleSide::Challenger);
+        let r = exchange(&basic, MoveChoice::MemeBomb, None, Some(Boost::LiquidityShield));
+        assert_eq!((r.winner, r.opponent.damage_dealt), (BattleSide::Opponent, 4));
+        // The builder's own shield does nothing for its score
+        assert_eq!(exchange(&basic, MoveChoice::MemeBomb, Some(Boost::LiquidityShield), None).winner, BattleSide::Challenger);
+
+        // Alpha Leak adds 15% to a special (28 -> 32), never to a basic move
+        let r = exchange(&special, MoveChoice::RugPullRumor, None, Some(Boost::AlphaLeak));
+        assert_eq!((r.winner, r.opponent.damage_dealt), (BattleSide::Opponent, 2));
+        assert_eq!(exchange(&basic, MoveChoice::MemeBomb, None, Some(Boost::AlphaLeak)).winner, BattleSide::Challenger);
+
+        // +5% gear and +10% prestige: 28 -> 32
+        let veteran = Player { gear: GearStats { attack_bps: 500, defense_bps: 0 }, prestige: 10, ..basic.clone() };
+        let r = exchange(&veteran, MoveChoice::MemeBomb, None, None);
+        assert_eq!((r.winner, r.opponent.damage_dealt), (BattleSide::Opponent, 2));
+    }
+
+    #[test]
+    fn modes_diverge() {
+        let score = score_config();
+        let damage = BalanceConfig { resolution_mode: ResolutionMode::Damage, basic_damage_bps: 250, ..score };
+        let builder = fighter(FighterClass::Builder, [0, 0, 0]);
+        let shitposter = fighter(FighterClass::Shitposter, [10, 0, 0]);
+
+        // 30 all on score, and the odd roll breaks the tie for the opponent...
+        let r = round(&builder, MoveChoice::ShipIt, &shitposter, MoveChoice::MemeBomb, &score);
+        assert_eq!(r.winner, BattleSide::Opponent);
+        // ...while on HP the class edge's 125 outdoes a +50% MemeBomb's 120
+        let r = round(&builder, MoveChoice::ShipIt, &shitposter, MoveChoice::MemeBomb, &damage);
+        assert_eq!(r.winner, BattleSide::Challenger);
+        assert_eq!((r.challenger.damage_dealt, r.opponent.damage_dealt), (125, 120));
+    }
+}
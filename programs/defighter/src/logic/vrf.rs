@@ -23,3 +23,49 @@ pub fn tie_break_entropy(
 }
 
 
+
+/// The hash of the first slot after `slot`, from the SlotHashes sysvar's
+/// data (a `u64` count, then `(slot, hash)` pairs, newest first). `None`
+/// until that slot has landed, or once the sysvar no longer reaches back to
+/// `slot`: then the first later entry would shift as old ones age out, and
+/// waiting would pick a new hash.
+pub fn slot_hash_after(data: &[u8], slot: u64) -> Option<[u8; 32]> {
+    const ENTRY: usize = 8 + 32;
+    let count = u64::from_le_bytes(data.get(0..8)?.try_into().ok()?) as usize;
+    let mut after = None;
+    for entry in data[8..].chunks_exact(ENTRY).take(count) {
+        let entry_slot = u64::from_le_bytes(entry[0..8].try_into().unwrap());
+        if entry_slot <= slot {
+            return after;
+        }
+        after = Some(entry[8..].try_into().unwrap());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot_hashes(slots: &[u64]) -> Vec<u8> {
+        let mut data = (slots.len() as u64).to_le_bytes().to_vec();
+        for slot in slots {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[*slot as u8; 32]);
+        }
+        data
+    }
+
+    #[test]
+    fn picks_the_first_landed_slot_after_the_commit() {
+        // Slot 11 was skipped, so 12 is the first after 10
+        let data = slot_hashes(&[14, 13, 12, 10, 9]);
+        assert_eq!(slot_hash_after(&data, 10), Some([12; 32]));
+        assert_eq!(slot_hash_after(&data, 13), Some([14; 32]));
+        // Nothing has landed after 14 yet
+        assert_eq!(slot_hash_after(&data, 14), None);
+        // Entries back to 8 have aged out, so the first after 8 is unknown
+        assert_eq!(slot_hash_after(&data, 8), None);
+        assert_eq!(slot_hash_after(&[], 8), None);
+    }
+}
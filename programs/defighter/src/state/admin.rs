@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use crate::errors::CustomError;
+use super::boss::BossParams;
+use super::config::ConfigUpdate;
+use super::consumable::Boost;
+use super::crafting::{CosmeticKind, RecipeParams};
+use super::item::ItemParams;
+
+/// An M-of-N set of keys that jointly holds `BalanceConfig.admin`. Once the
+/// config's admin is this PDA, changes go through `ConfigProposal`s.
+#[account]
+pub struct AdminSet {
+    pub id: u32,
+    pub members: Vec<Pubkey>,
+    /// Approvals a proposal needs before it can execute
+    pub threshold: u8,
+    /// Next proposal id; also seeds the proposal PDA
+    pub proposal_count: u64,
+}
+
+impl AdminSet {
+    /// Approvals are a bitmask over member indices
+    pub const MAX_MEMBERS: usize = 16;
+    pub const SPACE: usize = 8 + 4 + (4 + 32 * Self::MAX_MEMBERS) + 1 + 8;
+
+    pub fn member_index(&self, key: &Pubkey) -> Result<usize> {
+        self.members.iter().position(|m| m == key).ok_or_else(|| error!(CustomError::NotAdminSetMember))
+    }
+
+    pub fn validate(members: &[Pubkey], threshold: u8) -> Result<()> {
+        require!(!members.is_empty() && members.len() <= Self::MAX_MEMBERS, CustomError::InvalidAdminSet);
+        require!(threshold >= 1 && threshold as usize <= members.len(), CustomError::InvalidAdminSet);
+        let duplicate = members.iter().enumerate().any(|(i, m)| members[..i].contains(m));
+        require!(!duplicate, CustomError::InvalidAdminSet);
+        Ok(())
+    }
+
+    /// What a `ProposalAction::CreateAdminSet` commits to for `members`
+    pub fn members_hash(members: &[Pubkey]) -> [u8; 32] {
+        let keys: Vec<&[u8]> = members.iter().map(|m| m.as_ref()).collect();
+        anchor_lang::solana_program::keccak::hashv(&keys).0
+    }
+}
+
+/// What an executed proposal does to the config
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProposalAction {
+    /// Same as a single-key `update_config`: queued behind the timelock
+    Update(ConfigUpdate),
+    /// Same as `cancel_config_change`
+    CancelChange,
+    /// Hand the config to a single key, which must then `accept_admin`
+    ProposeAdmin(Pubkey),
+    /// Same as an admin `set_paused`, so it may also unpause
+    SetPaused(u8),
+    SetGuardian(Option<Pubkey>),
+    // The rest need accounts of their own, so `execute_config_change`
+    // refuses them; the instruction of the same name runs them instead,
+    // with the proposal standing in for the admin's signature.
+    /// Hand the config to another set; `members_hash` is
+    /// `AdminSet::members_hash` of its members
+    CreateAdminSet { id: u32, members_hash: [u8; 32], threshold: u8 },
+    CreateItem { id: u32, params: ItemParams },
+    GrantItem { recipient: Pubkey, item_id: u32 },
+    /// `LootTable::items_hash` of the new table's item ids, in order
+    SetLootTable([u8; 32]),
+    SetRecipe { kind: CosmeticKind, index: u8, params: RecipeParams },
+    CreateBoss { id: u32, params: BossParams },
+    SetNftCollection { collection: Pubkey, kind: CosmeticKind, index: u8, active: bool },
+    GrantBoost { recipient: Pubkey, boost: Boost, amount: u16 },
+}
+
+impl ProposalAction {
+    /// Tag plus the largest variant, `CreateAdminSet`
+    pub const SIZE: usize = 1 + 4 + 32 + 1;
+}
+
+#[account]
+pub struct ConfigProposal {
+    pub admin_set: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    /// Bit `i` set once `admin_set.members[i]` approved
+    pub approvals: u16,
+    pub executed: bool,
+    pub created_slot: u64,
+}
+
+impl ConfigProposal {
+    pub const SPACE: usize = 8 + 32 + 8 + 32 + ProposalAction::SIZE + 2 + 1 + 8;
+
+    pub fn approval_count(&self) -> u8 {
+        self.approvals.count_ones() as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::config::SpecialMoveParams;
+    use crate::state::crafting::Material;
+    use crate::state::player::FighterClass;
+
+    #[test]
+    fn every_action_fits() {
+        let key = Pubkey::new_unique();
+        let actions = [
+            ProposalAction::Update(ConfigUpdate::SpecialMove(FighterClass::VC, SpecialMoveParams::default())),
+            ProposalAction::ProposeAdmin(key),
+            ProposalAction::SetGuardian(Some(key)),
+            ProposalAction::CreateAdminSet { id: 0, members_hash: [0; 32], threshold: 1 },
+            ProposalAction::GrantItem { recipient: key, item_id: 0 },
+            ProposalAction::SetRecipe {
+                kind: CosmeticKind::Outfit,
+                index: 0,
+                params: RecipeParams { materials: [0; Material::COUNT], xp_cost: 0, active: true },
+            },
+            ProposalAction::SetNftCollection { collection: key, kind: CosmeticKind::Outfit, index: 0, active: true },
+            ProposalAction::GrantBoost { recipient: key, boost: Boost::AlphaLeak, amount: 1 },
+        ];
+        for action in actions {
+            assert!(action.try_to_vec().unwrap().len() <= ProposalAction::SIZE, "{action:?}");
+        }
+    }
+}
@@ -1,9 +1,13 @@
 use anchor_lang::prelude::*;
+use super::consumable::Boost;
 use super::player::MoveChoice;
+use super::config::BalanceConfigParams;
 
 #[account]
 pub struct Battle {
+    /// The challenger's `Player`, not its wallet
     pub challenger: Pubkey,
+    /// The opponent's `Player`
     pub opponent: Pubkey,
     pub nonce: u64,
     pub state: BattleState,
@@ -14,9 +18,51 @@ pub struct Battle {
     pub commit_opponent: Option<[u8; 32]>,
     pub reveal_challenger: Option<MoveChoice>,
     pub reveal_opponent: Option<MoveChoice>,
+    /// `challenger` or `opponent` once resolved
     pub winner: Option<Pubkey>,
     pub challenger_hp: u16,
     pub opponent_hp: u16,
+    /// `BalanceConfig.version` at initiation
+    pub config_version: u32,
+    /// Rules the battle resolves under, copied at initiation so a config
+    /// change mid-battle can't alter the outcome
+    pub rules: BalanceConfigParams,
+    /// XP multiplier for this battle, lowered by recent rematches
+    pub xp_scale_bps: u16,
+    /// Boosts burned at reveal, applied when the battle resolves
+    pub boost_challenger: Option<Boost>,
+    pub boost_opponent: Option<Boost>,
+    /// Salts revealed with each move. Neither side knew the other's when
+    /// committing, so the round and loot entropy built from them can't be
+    /// ground in advance.
+    pub salt_challenger: [u8; 32],
+    pub salt_opponent: [u8; 32],
+}
+
+/// The fields every `Battle` layout starts with, in order; what
+/// `migrate_battle` carries over. Before indexed characters, `challenger`
+/// and `opponent` were wallets.
+#[derive(AnchorDeserialize)]
+pub struct BattleHead {
+    pub challenger: Pubkey,
+    pub opponent: Pubkey,
+    pub nonce: u64,
+    pub state: BattleState,
+    pub created_slot: u64,
+    pub commit_deadline_slot: u64,
+    pub reveal_deadline_slot: u64,
+    pub commit_challenger: Option<[u8; 32]>,
+    pub commit_opponent: Option<[u8; 32]>,
+    pub reveal_challenger: Option<MoveChoice>,
+    pub reveal_opponent: Option<MoveChoice>,
+    pub winner: Option<Pubkey>,
+    pub challenger_hp: u16,
+    pub opponent_hp: u16,
+}
+
+impl Battle {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 8 + (1 + 32) + (1 + 32) + (1 + 1) + (1 + 1) + (1 + 32) + 2 + 2 + 4
+        + BalanceConfigParams::SIZE + 2 + (1 + 1) + (1 + 1) + 32 + 32;
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use super::battle::BattleState;
+use super::config::BalanceConfigParams;
+use super::player::{FighterClass, MoveChoice};
+
+/// A program-controlled opponent for PvE fights.
+#[account]
+pub struct Boss {
+    pub id: u32,
+    pub class: FighterClass,
+    /// Applied to all three of the class's abilities
+    pub level: u16,
+    pub max_hp: u32,
+    /// Remaining pool when `shared_hp` is set; otherwise always `max_hp`
+    pub hp: u32,
+    /// Chance in basis points that the boss picks its special move
+    pub special_bps: u16,
+    /// Raid mode: every fight chips away at the same HP pool
+    pub shared_hp: bool,
+    pub xp_win: u64,
+    pub xp_loss: u64,
+    pub fights: u32,
+    /// Player who landed the killing blow on a raid boss
+    pub defeated_by: Option<Pubkey>,
+}
+
+impl Boss {
+    pub const SPACE: usize = 8 + 4 + 1 + 2 + 4 + 4 + 2 + 1 + 8 + 8 + 4 + (1 + 32);
+
+    pub fn is_defeated(&self) -> bool {
+        self.shared_hp && self.hp == 0
+    }
+}
+
+/// Admin-supplied boss stats.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BossParams {
+    pub class: FighterClass,
+    pub level: u16,
+    pub max_hp: u32,
+    pub special_bps: u16,
+    pub shared_hp: bool,
+    pub xp_win: u64,
+    pub xp_loss: u64,
+}
+
+/// A single player's fight against a boss.
+#[account]
+pub struct BossBattle {
+    pub boss: Pubkey,
+    /// The wallet playing; commits and reveals are checked against it
+    pub player: Pubkey,
+    /// The `Player` it fights with
+    pub character: Pubkey,
+    pub nonce: u64,
+    pub state: BattleState,
+    pub created_slot: u64,
+    pub commit_deadline_slot: u64,
+    pub reveal_deadline_slot: u64,
+    pub commit_slot: u64,
+    pub commitment: Option<[u8; 32]>,
+    pub reveal: Option<MoveChoice>,
+    /// Revealed salt; seeds the boss's move so it is fixed only after the player's
+    pub salt: [u8; 32],
+    /// Hash of the first slot after `commit_slot`, read at reveal
+    pub slot_hash: [u8; 32],
+    pub boss_move: Option<MoveChoice>,
+    pub player_won: Option<bool>,
+    pub player_hp: u16,
+    pub boss_hp: u32,
+    /// Rules the fight runs under from its energy cost to resolve, copied
+    /// at the start so a config change mid-fight can't alter it
+    pub rules: BalanceConfigParams,
+}
+
+impl BossBattle {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 1 + 8 + 8 + 8 + 8 + (1 + 32) + (1 + 1) + 32 + 32 + (1 + 1) + (1 + 1) + 2 + 4
+        + BalanceConfigParams::SIZE;
+    /// Commit plus reveal window. SlotHashes keeps 512 slots, so the hash
+    /// after the commit is still there at the reveal deadline.
+    pub const MAX_WINDOW_SLOTS: u64 = 500;
+}
@@ -1,6 +1,10 @@
 use anchor_lang::prelude::*;
+use crate::errors::CustomError;
+use crate::state::crafting::CosmeticKind;
+use crate::state::player::FighterClass;
 
 #[account]
+#[derive(Default)]
 pub struct BalanceConfig {
     pub admin: Pubkey,
     pub base: i32,
@@ -17,6 +21,675 @@ pub struct BalanceConfig {
     pub upgrade_p_bps: u16,
     pub rps_win_base: i32,
     pub rps_tie_base: i32,
+    /// Basic-move damage bonus per ability bonus point, in bps
+    pub basic_damage_bps: u16,
+    /// Special-move miss window shrink per ability bonus point, in bps of the roll
+    pub accuracy_bps: u16,
+    /// Critical multiplier bonus per ability bonus point, in bps
+    pub crit_bps: u16,
+    /// Damage reduction per ability bonus point, in bps (capped at `MAX_DEFENSE_BPS`)
+    pub defense_bps: u16,
+    pub resolution_mode: ResolutionMode,
+    /// Slots before the same two players can battle again
+    pub pair_cooldown_slots: u64,
+    /// Window over which repeat battles between a pair are counted
+    pub pair_window_slots: u64,
+    /// XP cut per earlier battle against the same opponent in the window
+    pub pair_decay_bps: u16,
+    /// Energy a player regenerates up to
+    pub energy_max: u16,
+    /// Slots to regenerate one energy
+    pub energy_regen_slots: u64,
+    /// Energy each side spends to enter a PvP or boss battle
+    pub battle_energy_cost: u16,
+    /// XP `refill_energy` burns per energy point
+    pub energy_xp_cost: u64,
+    /// Chance a revealed PvP win drops loot
+    pub loot_drop_bps: u16,
+    /// Relative odds of each `Rarity` once something drops
+    pub loot_rarity_weights: [u16; 5],
+    /// Battles without a Rare-or-better drop after which the next win
+    /// guarantees one; 0 disables pity
+    pub loot_pity_threshold: u16,
+    /// Crafting materials the winner of a revealed PvP battle earns
+    pub material_win: u16,
+    /// Crafting materials the loser earns
+    pub material_loss: u16,
+    /// Share of a recipe's materials `salvage_cosmetic` gives back
+    pub salvage_refund_bps: u16,
+    /// Damage a Liquidity Shield blocks, in bps
+    pub shield_bps: u16,
+    /// Crit window an Alpha Leak adds to a special move, in bps of the roll
+    pub alpha_leak_bps: u16,
+    /// Chance a revealed PvP win also drops a boost
+    pub boost_drop_bps: u16,
+    /// Characters one wallet may hold at once
+    pub max_characters: u8,
+    /// Share of the upgrade XP `respec_abilities` gives back
+    pub respec_refund_bps: u16,
+    /// Power each prestige rank adds, in bps (capped at `MAX_PRESTIGE_POWER_BPS`)
+    pub prestige_power_bps: u16,
+    /// Outfit style prestiged characters unlock; `FREE_OUTFIT` for none
+    pub prestige_outfit: u8,
+    /// Longest commit window a PvP challenger may ask for
+    pub max_commit_slots: u64,
+    /// Longest reveal window a PvP challenger may ask for
+    pub max_reveal_slots: u64,
+    /// Damage a 1.0x hit deals before any multiplier
+    pub base_damage: u16,
+    /// Damage multiplier when the attacker's class beats the defender's, in bps
+    pub class_win_bps: u16,
+    /// Damage multiplier when the defender's class beats the attacker's, in bps
+    pub class_loss_bps: u16,
+    /// XP per power tier
+    pub xp_tier_size: u64,
+    /// Power each XP tier adds, in bps
+    pub xp_tier_bonus_bps: u16,
+    /// Each class's special move, indexed by `FighterClass`
+    pub special_moves: [SpecialMoveParams; 3],
+    /// Set by `propose_admin`; becomes `admin` once it signs `accept_admin`
+    pub pending_admin: Option<Pubkey>,
+    /// Bumped each time a queued change takes effect; battles record it
+    pub version: u32,
+    /// `PAUSE_*` bits; not timelocked so an exploit can be stopped at once
+    pub paused: u8,
+    /// May set `paused` bits but not clear them
+    pub guardian: Option<Pubkey>,
+    /// Slots the last `PAUSE_COMMITS` window opened and closed; `u64::MAX`
+    /// while it is still open, both 0 if commits were never paused
+    pub commits_paused_from: u64,
+    pub commits_paused_until: u64,
+}
+
+/// Roll windows and multipliers of one special move. A roll of 0..100
+/// below `miss_pct` misses, below `hit_end_pct` hits, and crits otherwise.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SpecialMoveParams {
+    pub miss_pct: u8,
+    pub hit_end_pct: u8,
+    pub hit_mult_bps: u16,
+    pub crit_mult_bps: u16,
+}
+
+impl SpecialMoveParams {
+    pub const SIZE: usize = 1 + 1 + 2 + 2;
+}
+
+/// How a revealed exchange picks its winner
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ResolutionMode {
+    /// Both moves deal HP damage (`logic::battle_math`); more HP left wins
+    #[default]
+    Damage,
+    /// RPS base plus `ability_power` (`logic::scoring`); the higher score wins
+    /// and deals the margin as damage
+    Score,
+}
+
+impl BalanceConfig {
+    /// Discriminator, `admin`, the tunables, then `pending_admin`, `version`,
+    /// `paused`, `guardian` and the last commit pause
+    pub const SPACE: usize = 8 + 32 + BalanceConfigParams::SIZE + (1 + 32) + 4 + 1 + (1 + 32) + 8 + 8;
+
+    /// Defaults `init_config` seeds the ability coefficients with. With the
+    /// demo linear curve (2 points per level) that is +5% basic damage, -1%
+    /// miss chance, +0.1x crit and -2% damage taken per level.
+    pub const DEFAULT_BASIC_DAMAGE_BPS: u16 = 250;
+    pub const DEFAULT_ACCURACY_BPS: u16 = 50;
+    pub const DEFAULT_CRIT_BPS: u16 = 500;
+    pub const DEFAULT_DEFENSE_BPS: u16 = 100;
+
+    /// Defense never blocks more than half of a hit
+    pub const MAX_DEFENSE_BPS: u32 = 5_000;
+
+    /// Prestige never adds more than +10% power, whatever the rank
+    pub const MAX_PRESTIGE_POWER_BPS: u32 = 1_000;
+
+    /// Highest `base_damage` an admin may set
+    pub const MAX_BASE_DAMAGE: u16 = 1_000;
+
+    /// Largest hit or crit multiplier a special move may have (5x)
+    pub const MAX_SPECIAL_MULT_BPS: u16 = 50_000;
+
+    /// Highest `max_commit_slots` / `max_reveal_slots` an admin may set (~1 day)
+    pub const MAX_DEADLINE_SLOTS: u64 = 216_000;
+
+    /// Upgrade-cost exponents `pow_fixed` is accurate for: 1.0 to 3.0
+    pub const UPGRADE_P_BPS_RANGE: std::ops::RangeInclusive<u16> = 10_000..=30_000;
+
+    /// Highest `max_level` an admin may set
+    pub const MAX_LEVEL_CAP: u16 = 1_000;
+
+    /// Slots between queueing a change and it taking effect (~1h at 400ms)
+    pub const CONFIG_DELAY_SLOTS: u64 = 9_000;
+
+    /// Kill switches in `paused`. Each stops new work of its kind; reveals and
+    /// resolves stay open so battles already under way can finish.
+    pub const PAUSE_BATTLES: u8 = 1 << 0;
+    pub const PAUSE_COMMITS: u8 = 1 << 1;
+    pub const PAUSE_UPGRADES: u8 = 1 << 2;
+    pub const PAUSE_CUSTOMIZATION: u8 = 1 << 3;
+    pub const PAUSE_ALL: u8 =
+        Self::PAUSE_BATTLES | Self::PAUSE_COMMITS | Self::PAUSE_UPGRADES | Self::PAUSE_CUSTOMIZATION;
+
+    pub fn require_unpaused(&self, feature: u8) -> Result<()> {
+        require!(self.paused & feature == 0, CustomError::ProgramPaused);
+        Ok(())
+    }
+
+    /// Whether the last commit pause overlapped slots `from..=to`. Only the
+    /// latest pause is kept, so one that ended before a newer one began is
+    /// forgotten.
+    pub fn commits_paused_between(&self, from: u64, to: u64) -> bool {
+        self.commits_paused_from <= to && self.commits_paused_until > from
+    }
+
+    /// A detached config carrying `p`, for math that runs off a snapshot
+    pub fn with_params(p: &BalanceConfigParams) -> Self {
+        let mut cfg = Self::default();
+        cfg.apply(p);
+        cfg
+    }
+
+    /// Everything but `admin`
+    pub fn params(&self) -> BalanceConfigParams {
+        BalanceConfigParams {
+            base: self.base,
+            linear_a: self.linear_a,
+            dim_k: self.dim_k,
+            dim_t: self.dim_t,
+            max_level: self.max_level,
+            tie_break_rand: self.tie_break_rand,
+            use_dim_bonus: self.use_dim_bonus,
+            xp_base: self.xp_base,
+            xp_tie_bps: self.xp_tie_bps,
+            xp_loser_bps: self.xp_loser_bps,
+            upgrade_c0: self.upgrade_c0,
+            upgrade_p_bps: self.upgrade_p_bps,
+            rps_win_base: self.rps_win_base,
+            rps_tie_base: self.rps_tie_base,
+            basic_damage_bps: self.basic_damage_bps,
+            accuracy_bps: self.accuracy_bps,
+            crit_bps: self.crit_bps,
+            defense_bps: self.defense_bps,
+            resolution_mode: self.resolution_mode,
+            pair_cooldown_slots: self.pair_cooldown_slots,
+            pair_window_slots: self.pair_window_slots,
+            pair_decay_bps: self.pair_decay_bps,
+            energy_max: self.energy_max,
+            energy_regen_slots: self.energy_regen_slots,
+            battle_energy_cost: self.battle_energy_cost,
+            energy_xp_cost: self.energy_xp_cost,
+            loot_drop_bps: self.loot_drop_bps,
+            loot_rarity_weights: self.loot_rarity_weights,
+            loot_pity_threshold: self.loot_pity_threshold,
+            material_win: self.material_win,
+            material_loss: self.material_loss,
+            salvage_refund_bps: self.salvage_refund_bps,
+            shield_bps: self.shield_bps,
+            alpha_leak_bps: self.alpha_leak_bps,
+            boost_drop_bps: self.boost_drop_bps,
+            max_characters: self.max_characters,
+            respec_refund_bps: self.respec_refund_bps,
+            prestige_power_bps: self.prestige_power_bps,
+            prestige_outfit: self.prestige_outfit,
+            max_commit_slots: self.max_commit_slots,
+            max_reveal_slots: self.max_reveal_slots,
+            base_damage: self.base_damage,
+            class_win_bps: self.class_win_bps,
+            class_loss_bps: self.class_loss_bps,
+            xp_tier_size: self.xp_tier_size,
+            xp_tier_bonus_bps: self.xp_tier_bonus_bps,
+            special_moves: self.special_moves,
+        }
+    }
+
+    /// Overwrite every tunable, leaving `admin` alone
+    pub fn apply(&mut self, p: &BalanceConfigParams) {
+        self.base = p.base;
+        self.linear_a = p.linear_a;
+        self.dim_k = p.dim_k;
+        self.dim_t = p.dim_t;
+        self.max_level = p.max_level;
+        self.tie_break_rand = p.tie_break_rand;
+        self.use_dim_bonus = p.use_dim_bonus;
+        self.xp_base = p.xp_base;
+        self.xp_tie_bps = p.xp_tie_bps;
+        self.xp_loser_bps = p.xp_loser_bps;
+        self.upgrade_c0 = p.upgrade_c0;
+        self.upgrade_p_bps = p.upgrade_p_bps;
+        self.rps_win_base = p.rps_win_base;
+        self.rps_tie_base = p.rps_tie_base;
+        self.basic_damage_bps = p.basic_damage_bps;
+        self.accuracy_bps = p.accuracy_bps;
+        self.crit_bps = p.crit_bps;
+        self.defense_bps = p.defense_bps;
+        self.resolution_mode = p.resolution_mode;
+        self.pair_cooldown_slots = p.pair_cooldown_slots;
+        self.pair_window_slots = p.pair_window_slots;
+        self.pair_decay_bps = p.pair_decay_bps;
+        self.energy_max = p.energy_max;
+        self.energy_regen_slots = p.energy_regen_slots;
+        self.battle_energy_cost = p.battle_energy_cost;
+        self.energy_xp_cost = p.energy_xp_cost;
+        self.loot_drop_bps = p.loot_drop_bps;
+        self.loot_rarity_weights = p.loot_rarity_weights;
+        self.loot_pity_threshold = p.loot_pity_threshold;
+        self.material_win = p.material_win;
+        self.material_loss = p.material_loss;
+        self.salvage_refund_bps = p.salvage_refund_bps;
+        self.shield_bps = p.shield_bps;
+        self.alpha_leak_bps = p.alpha_leak_bps;
+        self.boost_drop_bps = p.boost_drop_bps;
+        self.max_characters = p.max_characters;
+        self.respec_refund_bps = p.respec_refund_bps;
+        self.prestige_power_bps = p.prestige_power_bps;
+        self.prestige_outfit = p.prestige_outfit;
+        self.max_commit_slots = p.max_commit_slots;
+        self.max_reveal_slots = p.max_reveal_slots;
+        self.base_damage = p.base_damage;
+        self.class_win_bps = p.class_win_bps;
+        self.class_loss_bps = p.class_loss_bps;
+        self.xp_tier_size = p.xp_tier_size;
+        self.xp_tier_bonus_bps = p.xp_tier_bonus_bps;
+        self.special_moves = p.special_moves;
+    }
+}
+
+/// The admin-tunable part of `BalanceConfig`, as taken by `init_config` and
+/// `admin_update_config`. `Default` is the demo balance.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BalanceConfigParams {
+    pub base: i32,
+    pub linear_a: i32,
+    pub dim_k: i32,
+    pub dim_t: i32,
+    pub max_level: u16,
+    pub tie_break_rand: bool,
+    pub use_dim_bonus: bool,
+    pub xp_base: u64,
+    pub xp_tie_bps: u16,
+    pub xp_loser_bps: u16,
+    pub upgrade_c0: u64,
+    pub upgrade_p_bps: u16,
+    pub rps_win_base: i32,
+    pub rps_tie_base: i32,
+    pub basic_damage_bps: u16,
+    pub accuracy_bps: u16,
+    pub crit_bps: u16,
+    pub defense_bps: u16,
+    pub resolution_mode: ResolutionMode,
+    pub pair_cooldown_slots: u64,
+    pub pair_window_slots: u64,
+    pub pair_decay_bps: u16,
+    pub energy_max: u16,
+    pub energy_regen_slots: u64,
+    pub battle_energy_cost: u16,
+    pub energy_xp_cost: u64,
+    pub loot_drop_bps: u16,
+    pub loot_rarity_weights: [u16; 5],
+    pub loot_pity_threshold: u16,
+    pub material_win: u16,
+    pub material_loss: u16,
+    pub salvage_refund_bps: u16,
+    pub shield_bps: u16,
+    pub alpha_leak_bps: u16,
+    pub boost_drop_bps: u16,
+    pub max_characters: u8,
+    pub respec_refund_bps: u16,
+    pub prestige_power_bps: u16,
+    pub prestige_outfit: u8,
+    pub max_commit_slots: u64,
+    pub max_reveal_slots: u64,
+    pub base_damage: u16,
+    pub class_win_bps: u16,
+    pub class_loss_bps: u16,
+    pub xp_tier_size: u64,
+    pub xp_tier_bonus_bps: u16,
+    pub special_moves: [SpecialMoveParams; 3],
+}
+
+impl Default for BalanceConfigParams {
+    fn default() -> Self {
+        Self {
+            base: 10,
+            linear_a: 2,
+            dim_k: 20,
+            dim_t: 10,
+            max_level: 100,
+            tie_break_rand: true,
+            use_dim_bonus: false,
+            xp_base: 10,
+            xp_tie_bps: 7500,
+            xp_loser_bps: 2000,
+            upgrade_c0: 10,
+            upgrade_p_bps: 14000,
+            rps_win_base: 20,
+            rps_tie_base: 10,
+            basic_damage_bps: BalanceConfig::DEFAULT_BASIC_DAMAGE_BPS,
+            accuracy_bps: BalanceConfig::DEFAULT_ACCURACY_BPS,
+            crit_bps: BalanceConfig::DEFAULT_CRIT_BPS,
+            defense_bps: BalanceConfig::DEFAULT_DEFENSE_BPS,
+            resolution_mode: ResolutionMode::Damage,
+            // A minute between rematches; the fifth in a day earns nothing
+            pair_cooldown_slots: 150,
+            pair_window_slots: 216_000,
+            pair_decay_bps: 2_500,
+            // Ten battles banked, one back per hour
+            energy_max: 10,
+            energy_regen_slots: 9_000,
+            battle_energy_cost: 1,
+            energy_xp_cost: 5,
+            // A third of wins drop something; a Rare is due every ten battles at worst
+            loot_drop_bps: 3_000,
+            loot_rarity_weights: [6_000, 2_500, 1_000, 400, 100],
+            loot_pity_threshold: 10,
+            // Salvaging gets half a recipe back, so crafting and salvaging never pays
+            material_win: 3,
+            material_loss: 1,
+            salvage_refund_bps: 5_000,
+            // A shield takes a fifth off one hit; a leak turns 15% of hits into crits
+            shield_bps: 2_000,
+            alpha_leak_bps: 1_500,
+            boost_drop_bps: 2_000,
+            // One of each class
+            max_characters: 3,
+            // A respec costs half the XP sunk into upgrades
+            respec_refund_bps: 5_000,
+            // +1% power per rank; no prestige outfit until the admin picks one
+            prestige_power_bps: 100,
+            prestige_outfit: 0,
+            // Ten minutes to answer a challenge and ten more to reveal
+            max_commit_slots: 1_500,
+            max_reveal_slots: 1_500,
+            // A winning class hits 25% harder, a losing one 20% softer
+            base_damage: 100,
+            class_win_bps: 12_500,
+            class_loss_bps: 8_000,
+            // +2% power per 1000 XP
+            xp_tier_size: 1_000,
+            xp_tier_bonus_bps: 200,
+            special_moves: [
+                // RugPullRumor: 30% miss, 50% hit (1.5x), 20% crit (2.2x)
+                SpecialMoveParams { miss_pct: 30, hit_end_pct: 80, hit_mult_bps: 15_000, crit_mult_bps: 22_000 },
+                // TestnetDeploy: 25% miss, 55% hit (1.6x), 20% crit (2.3x)
+                SpecialMoveParams { miss_pct: 25, hit_end_pct: 80, hit_mult_bps: 16_000, crit_mult_bps: 23_000 },
+                // ExitLiquidity: 35% miss, 45% hit (1.4x), 20% crit (2.0x)
+                SpecialMoveParams { miss_pct: 35, hit_end_pct: 80, hit_mult_bps: 14_000, crit_mult_bps: 20_000 },
+            ],
+        }
+    }
+}
+
+impl BalanceConfigParams {
+    pub const SIZE: usize = (4 * 4) + 2 + 1 + 1 + 8 + 2 + 2 + 8 + 2 + 4 + 4 + (2 * 4) + 1 + 8 + 8 + 2 + 2 + 8 + 2 + 8 + 2 + (2 * 5) + 2 + 2 + 2 + 2 + 2 + 2 + 2 + 1 + 2 + 2 + 1 + 8 + 8 + 2 + 2 + 2 + 8 + 2 + (SpecialMoveParams::SIZE * 3);
+
+    /// Invariants the battle and upgrade math rely on
+    pub fn validate(&self) -> Result<()> {
+        require!((1..=BalanceConfig::MAX_LEVEL_CAP).contains(&self.max_level), CustomError::InvalidConfig);
+        // Ability power curves must not go negative or divide by zero
+        require!(self.base >= 0 && self.linear_a >= 0 && self.dim_k >= 0, CustomError::InvalidConfig);
+        require!(self.dim_t > 0, CustomError::InvalidConfig);
+        // A loser never earns more than a tie
+        require!(self.xp_tie_bps <= 10_000, CustomError::InvalidConfig);
+        require!(self.xp_loser_bps <= self.xp_tie_bps, CustomError::InvalidConfig);
+        require!(self.upgrade_c0 > 0, CustomError::InvalidConfig);
+        require!(
+            BalanceConfig::UPGRADE_P_BPS_RANGE.contains(&self.upgrade_p_bps),
+            CustomError::UnsupportedUpgradeExponent
+        );
+        require!(0 <= self.rps_tie_base && self.rps_tie_base <= self.rps_win_base, CustomError::InvalidConfig);
+        for bps in [self.basic_damage_bps, self.accuracy_bps, self.crit_bps, self.defense_bps] {
+            require!(bps <= 10_000, CustomError::InvalidConfig);
+        }
+        require!(self.pair_decay_bps <= 10_000, CustomError::InvalidConfig);
+        require!(self.pair_cooldown_slots <= self.pair_window_slots, CustomError::InvalidConfig);
+        // A battle must be affordable from a full bar
+        require!(self.energy_regen_slots > 0, CustomError::InvalidConfig);
+        require!(self.battle_energy_cost <= self.energy_max, CustomError::InvalidConfig);
+        require!(self.loot_drop_bps <= 10_000, CustomError::InvalidConfig);
+        // Something must be rollable once a drop happens
+        let total_weight: u32 = self.loot_rarity_weights.iter().map(|w| *w as u32).sum();
+        require!(total_weight > 0 || (self.loot_drop_bps == 0 && self.loot_pity_threshold == 0), CustomError::InvalidConfig);
+        require!(self.salvage_refund_bps <= 10_000, CustomError::InvalidConfig);
+        // A shield never makes a fighter untouchable
+        require!(self.shield_bps <= BalanceConfig::MAX_DEFENSE_BPS as u16, CustomError::InvalidConfig);
+        require!(self.alpha_leak_bps <= 10_000 && self.boost_drop_bps <= 10_000, CustomError::InvalidConfig);
+        // Every wallet gets at least one character
+        require!(self.max_characters > 0, CustomError::InvalidConfig);
+        require!(self.respec_refund_bps <= 10_000, CustomError::InvalidConfig);
+        require!(self.prestige_power_bps as u32 <= BalanceConfig::MAX_PRESTIGE_POWER_BPS, CustomError::InvalidConfig);
+        require!(self.prestige_outfit < CosmeticKind::Outfit.capacity(), CustomError::InvalidConfig);
+        // Deadlines bound how long a battle can hold a character hostage
+        for slots in [self.max_commit_slots, self.max_reveal_slots] {
+            require!((1..=BalanceConfig::MAX_DEADLINE_SLOTS).contains(&slots), CustomError::InvalidConfig);
+        }
+        require!((1..=BalanceConfig::MAX_BASE_DAMAGE).contains(&self.base_damage), CustomError::InvalidConfig);
+        // Class advantage helps the winner and hurts the loser, by at most 2x
+        require!((10_000..=20_000).contains(&self.class_win_bps), CustomError::InvalidConfig);
+        require!((5_000..=10_000).contains(&self.class_loss_bps), CustomError::InvalidConfig);
+        require!(self.xp_tier_size > 0 && self.xp_tier_bonus_bps <= 1_000, CustomError::InvalidConfig);
+        // Windows stay in order within the roll, and a crit is never weaker than a hit
+        for special in self.special_moves {
+            require!(special.miss_pct <= special.hit_end_pct && special.hit_end_pct <= 100, CustomError::InvalidConfig);
+            require!(
+                10_000 <= special.hit_mult_bps
+                    && special.hit_mult_bps <= special.crit_mult_bps
+                    && special.crit_mult_bps <= BalanceConfig::MAX_SPECIAL_MULT_BPS,
+                CustomError::InvalidConfig
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A timelocked config change, seeds `[b"pending_config"]`. Created on the
+/// first queued change and reused afterwards; `active` says whether one is
+/// waiting.
+#[account]
+pub struct PendingConfig {
+    pub active: bool,
+    /// `BalanceConfig.version` once applied
+    pub version: u32,
+    pub params: BalanceConfigParams,
+    /// Admin (single key or admin set) that queued it
+    pub queued_by: Pubkey,
+    pub queued_slot: u64,
+    /// First slot `apply_config_change` accepts
+    pub effective_slot: u64,
+}
+
+impl PendingConfig {
+    pub const SPACE: usize = 8 + 1 + 4 + BalanceConfigParams::SIZE + 32 + 8 + 8;
+}
+
+/// One tunable and its new value, for `update_config`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigUpdate {
+    Base(i32),
+    LinearA(i32),
+    DimK(i32),
+    DimT(i32),
+    MaxLevel(u16),
+    TieBreakRand(bool),
+    UseDimBonus(bool),
+    XpBase(u64),
+    XpTieBps(u16),
+    XpLoserBps(u16),
+    UpgradeC0(u64),
+    UpgradePBps(u16),
+    RpsWinBase(i32),
+    RpsTieBase(i32),
+    BasicDamageBps(u16),
+    AccuracyBps(u16),
+    CritBps(u16),
+    DefenseBps(u16),
+    ResolutionMode(ResolutionMode),
+    PairCooldownSlots(u64),
+    PairWindowSlots(u64),
+    PairDecayBps(u16),
+    EnergyMax(u16),
+    EnergyRegenSlots(u64),
+    BattleEnergyCost(u16),
+    EnergyXpCost(u64),
+    LootDropBps(u16),
+    LootRarityWeights([u16; 5]),
+    LootPityThreshold(u16),
+    MaterialWin(u16),
+    MaterialLoss(u16),
+    SalvageRefundBps(u16),
+    ShieldBps(u16),
+    AlphaLeakBps(u16),
+    BoostDropBps(u16),
+    MaxCharacters(u8),
+    RespecRefundBps(u16),
+    PrestigePowerBps(u16),
+    PrestigeOutfit(u8),
+    MaxCommitSlots(u64),
+    MaxRevealSlots(u64),
+    BaseDamage(u16),
+    ClassWinBps(u16),
+    ClassLossBps(u16),
+    XpTierSize(u64),
+    XpTierBonusBps(u16),
+    SpecialMove(FighterClass, SpecialMoveParams),
+}
+
+impl ConfigUpdate {
+    pub fn apply(self, p: &mut BalanceConfigParams) {
+        match self {
+            ConfigUpdate::Base(v) => p.base = v,
+            ConfigUpdate::LinearA(v) => p.linear_a = v,
+            ConfigUpdate::DimK(v) => p.dim_k = v,
+            ConfigUpdate::DimT(v) => p.dim_t = v,
+            ConfigUpdate::MaxLevel(v) => p.max_level = v,
+            ConfigUpdate::TieBreakRand(v) => p.tie_break_rand = v,
+            ConfigUpdate::UseDimBonus(v) => p.use_dim_bonus = v,
+            ConfigUpdate::XpBase(v) => p.xp_base = v,
+            ConfigUpdate::XpTieBps(v) => p.xp_tie_bps = v,
+            ConfigUpdate::XpLoserBps(v) => p.xp_loser_bps = v,
+            ConfigUpdate::UpgradeC0(v) => p.upgrade_c0 = v,
+            ConfigUpdate::UpgradePBps(v) => p.upgrade_p_bps = v,
+            ConfigUpdate::RpsWinBase(v) => p.rps_win_base = v,
+            ConfigUpdate::RpsTieBase(v) => p.rps_tie_base = v,
+            ConfigUpdate::BasicDamageBps(v) => p.basic_damage_bps = v,
+            ConfigUpdate::AccuracyBps(v) => p.accuracy_bps = v,
+            ConfigUpdate::CritBps(v) => p.crit_bps = v,
+            ConfigUpdate::DefenseBps(v) => p.defense_bps = v,
+            ConfigUpdate::ResolutionMode(v) => p.resolution_mode = v,
+            ConfigUpdate::PairCooldownSlots(v) => p.pair_cooldown_slots = v,
+            ConfigUpdate::PairWindowSlots(v) => p.pair_window_slots = v,
+            ConfigUpdate::PairDecayBps(v) => p.pair_decay_bps = v,
+            ConfigUpdate::EnergyMax(v) => p.energy_max = v,
+            ConfigUpdate::EnergyRegenSlots(v) => p.energy_regen_slots = v,
+            ConfigUpdate::BattleEnergyCost(v) => p.battle_energy_cost = v,
+            ConfigUpdate::EnergyXpCost(v) => p.energy_xp_cost = v,
+            ConfigUpdate::LootDropBps(v) => p.loot_drop_bps = v,
+            ConfigUpdate::LootRarityWeights(v) => p.loot_rarity_weights = v,
+            ConfigUpdate::LootPityThreshold(v) => p.loot_pity_threshold = v,
+            ConfigUpdate::MaterialWin(v) => p.material_win = v,
+            ConfigUpdate::MaterialLoss(v) => p.material_loss = v,
+            ConfigUpdate::SalvageRefundBps(v) => p.salvage_refund_bps = v,
+            ConfigUpdate::ShieldBps(v) => p.shield_bps = v,
+            ConfigUpdate::AlphaLeakBps(v) => p.alpha_leak_bps = v,
+            ConfigUpdate::BoostDropBps(v) => p.boost_drop_bps = v,
+            ConfigUpdate::MaxCharacters(v) => p.max_characters = v,
+            ConfigUpdate::RespecRefundBps(v) => p.respec_refund_bps = v,
+            ConfigUpdate::PrestigePowerBps(v) => p.prestige_power_bps = v,
+            ConfigUpdate::PrestigeOutfit(v) => p.prestige_outfit = v,
+            ConfigUpdate::MaxCommitSlots(v) => p.max_commit_slots = v,
+            ConfigUpdate::MaxRevealSlots(v) => p.max_reveal_slots = v,
+            ConfigUpdate::BaseDamage(v) => p.base_damage = v,
+            ConfigUpdate::ClassWinBps(v) => p.class_win_bps = v,
+            ConfigUpdate::ClassLossBps(v) => p.class_loss_bps = v,
+            ConfigUpdate::XpTierSize(v) => p.xp_tier_size = v,
+            ConfigUpdate::XpTierBonusBps(v) => p.xp_tier_bonus_bps = v,
+            ConfigUpdate::SpecialMove(class, v) => p.special_moves[class as usize] = v,
+        }
+    }
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demo_params_are_valid() {
+        let demo = BalanceConfigParams::default();
+        demo.validate().unwrap();
+
+        let mut cfg = BalanceConfig::default();
+        cfg.apply(&demo);
+        assert_eq!(cfg.params(), demo);
+    }
+
+    #[test]
+    fn space_matches_layout() {
+        let cfg = BalanceConfig {
+            pending_admin: Some(Pubkey::new_unique()),
+            guardian: Some(Pubkey::new_unique()),
+            ..BalanceConfig::with_params(&BalanceConfigParams::default())
+        };
+        let mut data = Vec::new();
+        cfg.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), BalanceConfig::SPACE);
+    }
+
+    #[test]
+    fn validate_rejects_broken_invariants() {
+        let bad = [
+            ConfigUpdate::MaxLevel(0),
+            ConfigUpdate::MaxLevel(BalanceConfig::MAX_LEVEL_CAP + 1),
+            ConfigUpdate::Base(-1),
+            ConfigUpdate::DimT(0),
+            ConfigUpdate::XpTieBps(10_001),
+            ConfigUpdate::XpLoserBps(8_000),
+            ConfigUpdate::UpgradeC0(0),
+            ConfigUpdate::UpgradePBps(9_000),
+            ConfigUpdate::RpsTieBase(21),
+            ConfigUpdate::RpsTieBase(-1),
+            ConfigUpdate::CritBps(10_001),
+            ConfigUpdate::PairDecayBps(10_001),
+            ConfigUpdate::PairWindowSlots(100),
+            ConfigUpdate::EnergyRegenSlots(0),
+            ConfigUpdate::BattleEnergyCost(11),
+            ConfigUpdate::LootDropBps(10_001),
+            ConfigUpdate::LootRarityWeights([0; 5]),
+            ConfigUpdate::SalvageRefundBps(10_001),
+            ConfigUpdate::ShieldBps(5_001),
+            ConfigUpdate::BoostDropBps(10_001),
+            ConfigUpdate::MaxCharacters(0),
+            ConfigUpdate::RespecRefundBps(10_001),
+            ConfigUpdate::PrestigePowerBps(1_001),
+            ConfigUpdate::PrestigeOutfit(32),
+            ConfigUpdate::MaxCommitSlots(0),
+            ConfigUpdate::MaxRevealSlots(BalanceConfig::MAX_DEADLINE_SLOTS + 1),
+            ConfigUpdate::BaseDamage(0),
+            ConfigUpdate::ClassWinBps(9_999),
+            ConfigUpdate::ClassLossBps(10_001),
+            ConfigUpdate::XpTierSize(0),
+            ConfigUpdate::XpTierBonusBps(1_001),
+            ConfigUpdate::SpecialMove(
+                FighterClass::VC,
+                SpecialMoveParams { miss_pct: 50, hit_end_pct: 40, hit_mult_bps: 15_000, crit_mult_bps: 20_000 },
+            ),
+            ConfigUpdate::SpecialMove(
+                FighterClass::Builder,
+                SpecialMoveParams { miss_pct: 30, hit_end_pct: 80, hit_mult_bps: 15_000, crit_mult_bps: 12_000 },
+            ),
+        ];
+        for update in bad {
+            let mut p = BalanceConfigParams::default();
+            update.apply(&mut p);
+            assert!(p.validate().is_err(), "{update:?} should be rejected");
+        }
+
+        let mut p = BalanceConfigParams::default();
+        ConfigUpdate::MaxLevel(BalanceConfig::MAX_LEVEL_CAP).apply(&mut p);
+        ConfigUpdate::ResolutionMode(ResolutionMode::Score).apply(&mut p);
+        p.validate().unwrap();
+        assert_eq!((p.max_level, p.resolution_mode), (BalanceConfig::MAX_LEVEL_CAP, ResolutionMode::Score));
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+/// One-shot battle boosts. Committed inside the move hash, burned on reveal
+/// and applied to that round only.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Boost {
+    /// Cuts damage taken by `shield_bps`
+    LiquidityShield = 0,
+    /// Widens a special move's crit window by `alpha_leak_bps` of the roll
+    AlphaLeak = 1,
+}
+
+impl Boost {
+    pub const COUNT: usize = 2;
+    const ALL: [Boost; Self::COUNT] = [Boost::LiquidityShield, Boost::AlphaLeak];
+
+    pub fn to_byte(self) -> u8 {
+        self as u8
+    }
+
+    /// The kind a battle reward pays out, from its roll
+    pub fn from_roll(roll: u64) -> Self {
+        Self::ALL[(roll % Self::COUNT as u64) as usize]
+    }
+}
+
+/// A player's unspent boosts, seeds `[b"consumables", owner]`. Created with
+/// the first battle a player is in, or by `grant_boost`.
+#[account]
+pub struct Consumables {
+    pub owner: Pubkey,
+    /// Balance of each `Boost`
+    pub balances: [u16; Boost::COUNT],
+}
+
+impl Consumables {
+    pub const SPACE: usize = 8 + 32 + 2 * Boost::COUNT;
+
+    pub fn add(&mut self, boost: Boost, amount: u16) -> u16 {
+        let balance = &mut self.balances[boost as usize];
+        *balance = balance.saturating_add(amount);
+        *balance
+    }
+
+    /// Burn one `boost`, returning what's left
+    pub fn burn(&mut self, boost: Boost) -> Result<u16> {
+        let balance = &mut self.balances[boost as usize];
+        *balance = balance.checked_sub(1).ok_or(crate::errors::CustomError::NoBoost)?;
+        Ok(*balance)
+    }
+}
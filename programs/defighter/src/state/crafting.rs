@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+
+/// Crafting material kinds; indexes `LootBag.materials` and `Recipe.materials`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Material {
+    Thread = 0,
+    Scrap = 1,
+    Chip = 2,
+}
+
+impl Material {
+    pub const COUNT: usize = 3;
+    const ALL: [Material; Self::COUNT] = [Material::Thread, Material::Scrap, Material::Chip];
+
+    /// The kind a battle pays out in, from its loot roll
+    pub fn from_roll(roll: u64) -> Self {
+        Self::ALL[((roll >> 48) % Self::COUNT as u64) as usize]
+    }
+}
+
+/// A customization field gated behind unlocks
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CosmeticKind {
+    /// `accessory_slots` entries
+    Accessory = 0,
+    /// `outfit_style_index`
+    Outfit = 1,
+}
+
+impl CosmeticKind {
+    /// Indices that can be unlocked; one bit each in `CharacterCustomizationV1`
+    pub fn capacity(self) -> u8 {
+        match self {
+            CosmeticKind::Accessory => 128,
+            CosmeticKind::Outfit => 32,
+        }
+    }
+}
+
+/// What it costs to unlock one cosmetic, seeds `[b"recipe", kind, index]`.
+/// Admin-managed; `set_recipe` may reprice it at any time.
+#[account]
+pub struct Recipe {
+    pub kind: CosmeticKind,
+    pub index: u8,
+    pub materials: [u32; Material::COUNT],
+    /// XP burned on top of the materials
+    pub xp_cost: u64,
+    /// Inactive recipes can't be crafted but can still be salvaged
+    pub active: bool,
+}
+
+impl Recipe {
+    pub const SPACE: usize = 8 + 1 + 1 + 4 * Material::COUNT + 8 + 1;
+}
+
+/// Admin-supplied recipe costs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecipeParams {
+    pub materials: [u32; Material::COUNT],
+    pub xp_cost: u64,
+    pub active: bool,
+}
+
+/// What one crafted cosmetic cost, seeds `[b"crafted", player, kind, index]`.
+/// Salvage refunds from this rather than the recipe, which may have been
+/// repriced since, and closes it.
+#[account]
+pub struct CraftReceipt {
+    pub materials: [u32; Material::COUNT],
+}
+
+impl CraftReceipt {
+    pub const SPACE: usize = 8 + 4 * Material::COUNT;
+}
+
+/// Materials a salvage returns: `refund_bps` of each of `cost`, rounded down
+pub fn salvage_refund(cost: &[u32; Material::COUNT], refund_bps: u16) -> [u32; Material::COUNT] {
+    cost.map(|amount| (amount as u64 * refund_bps as u64 / 10_000) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn salvage_rounds_refunds_down() {
+        assert_eq!(salvage_refund(&[10, 3, 0], 5_000), [5, 1, 0]);
+        assert_eq!(salvage_refund(&[u32::MAX, 1, 7], 10_000), [u32::MAX, 1, 7]);
+        assert_eq!(salvage_refund(&[10, 3, 0], 0), [0; 3]);
+    }
+}
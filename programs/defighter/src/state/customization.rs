@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use super::crafting::CosmeticKind;
+use crate::errors::CustomError;
 
 pub const CHARACTER_CUSTOM_SEED: &[u8] = b"character_custom";
 
@@ -15,12 +17,78 @@ pub struct CharacterCustomizationV1 {
     pub face_flags: u16,           // bitfield: mustache(0), lipstick(1), glasses(2), eyebrows_alt(3), mouth_alt(4)
     pub accessory_slots: [u8; 2],  // 0=headwear, 1=weapon (255=none)
     pub version: u8,               // =1
-    pub reserved: [u8; 24],        // future expansion
+    pub unlocked_accessories: u128, // bit per crafted accessory index
+    pub unlocked_outfits: u32,     // bit per crafted outfit style
+    pub reserved: [u8; 4],         // future expansion
 }
 
 impl CharacterCustomizationV1 {
     pub const VERSION: u8 = 1;
     pub const SPACE: usize = 8 /*disc*/ + 68; // discriminator + struct size
+
+    /// `accessory_slots` value for an empty slot
+    pub const NO_ACCESSORY: u8 = 255;
+    /// Outfit style everyone has without crafting
+    pub const FREE_OUTFIT: u8 = 0;
+
+    /// Free cosmetics need no unlock
+    pub fn is_free(kind: CosmeticKind, index: u8) -> bool {
+        match kind {
+            CosmeticKind::Accessory => index == Self::NO_ACCESSORY,
+            CosmeticKind::Outfit => index == Self::FREE_OUTFIT,
+        }
+    }
+
+    /// Whether `index` has been crafted (and not salvaged since)
+    pub fn is_crafted(&self, kind: CosmeticKind, index: u8) -> bool {
+        index < kind.capacity()
+            && match kind {
+                CosmeticKind::Accessory => self.unlocked_accessories & (1 << index) != 0,
+                CosmeticKind::Outfit => self.unlocked_outfits & (1 << index) != 0,
+            }
+    }
+
+    pub fn is_unlocked(&self, kind: CosmeticKind, index: u8) -> bool {
+        Self::is_free(kind, index) || self.is_crafted(kind, index)
+    }
+
+    /// Record a craft or salvage; `index` must be below `kind.capacity()`
+    pub fn set_crafted(&mut self, kind: CosmeticKind, index: u8, crafted: bool) {
+        match kind {
+            CosmeticKind::Accessory if crafted => self.unlocked_accessories |= 1 << index,
+            CosmeticKind::Accessory => self.unlocked_accessories &= !(1 << index),
+            CosmeticKind::Outfit if crafted => self.unlocked_outfits |= 1 << index,
+            CosmeticKind::Outfit => self.unlocked_outfits &= !(1 << index),
+        }
+    }
+
+    /// Whether the current look uses `index`
+    pub fn is_worn(&self, kind: CosmeticKind, index: u8) -> bool {
+        match kind {
+            CosmeticKind::Accessory => self.accessory_slots.contains(&index),
+            CosmeticKind::Outfit => self.outfit_style_index == index,
+        }
+    }
+
+    /// Take `index` off, falling back to the free default
+    pub fn strip(&mut self, kind: CosmeticKind, index: u8) {
+        match kind {
+            CosmeticKind::Accessory => {
+                for slot in self.accessory_slots.iter_mut().filter(|slot| **slot == index) {
+                    *slot = Self::NO_ACCESSORY;
+                }
+            }
+            CosmeticKind::Outfit if self.outfit_style_index == index => self.outfit_style_index = Self::FREE_OUTFIT,
+            CosmeticKind::Outfit => {}
+        }
+    }
+
+    /// Whether every gated field of `data` is free, crafted or in `nft_unlocks`
+    pub fn allows(&self, data: &CharacterCustomizationDataV1, nft_unlocks: &[(CosmeticKind, u8)]) -> bool {
+        let unlocked = |kind, index| self.is_unlocked(kind, index) || nft_unlocks.contains(&(kind, index));
+        unlocked(CosmeticKind::Outfit, data.outfit_style_index)
+            && data.accessory_slots.iter().all(|i| unlocked(CosmeticKind::Accessory, *i))
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
@@ -36,4 +104,97 @@ pub struct CharacterCustomizationDataV1 {
     pub accessory_slots: [u8; 2],
 }
 
+/// Copy `old` into `new` for `player` and close it, refunding `rent_to`.
+/// `new` must be given exactly when `old` exists, so unlocks are never
+/// left behind for a re-created player to inherit.
+pub fn move_customization(
+    old: &AccountInfo,
+    new: Option<&mut Account<CharacterCustomizationV1>>,
+    player: Pubkey,
+    rent_to: &AccountInfo,
+) -> Result<()> {
+    match new {
+        Some(new) if !old.data_is_empty() => {
+            require_keys_eq!(*old.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+            let moved = CharacterCustomizationV1::try_deserialize(&mut &old.try_borrow_data()?[..])?;
+            new.set_inner(CharacterCustomizationV1 { player, ..moved });
+            close_customization(old, rent_to)
+        }
+        None if old.data_is_empty() => Ok(()),
+        _ => err!(CustomError::CustomizationNotMoved),
+    }
+}
+
+/// Close the customization at `old`, if it exists, the way `close = ` would
+pub fn close_customization(old: &AccountInfo, rent_to: &AccountInfo) -> Result<()> {
+    if old.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*old.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    **rent_to.try_borrow_mut_lamports()? += old.lamports();
+    **old.try_borrow_mut_lamports()? = 0;
+    old.assign(&System::id());
+    old.resize(0)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blank() -> CharacterCustomizationV1 {
+        CharacterCustomizationV1 {
+            player: Pubkey::default(),
+            gender: 0,
+            palette_index: 0,
+            skin_tone_index: 0,
+            hair_style_index: 0,
+            hair_color_index: 0,
+            outfit_style_index: 0,
+            outfit_color_index: 0,
+            face_flags: 0,
+            accessory_slots: [CharacterCustomizationV1::NO_ACCESSORY; 2],
+            version: CharacterCustomizationV1::VERSION,
+            unlocked_accessories: 0,
+            unlocked_outfits: 0,
+            reserved: [0; 4],
+        }
+    }
+
+    #[test]
+    fn gated_fields_need_unlocks() {
+        let mut acct = blank();
+        let look = CharacterCustomizationDataV1 { outfit_style_index: 3, accessory_slots: [127, 255], ..Default::default() };
+        assert!(acct.allows(&CharacterCustomizationDataV1 { accessory_slots: [255; 2], ..Default::default() }, &[]));
+        assert!(!acct.allows(&look, &[]));
+        assert!(acct.allows(&look, &[(CosmeticKind::Outfit, 3), (CosmeticKind::Accessory, 127)]));
 
+        acct.set_crafted(CosmeticKind::Outfit, 3, true);
+        acct.set_crafted(CosmeticKind::Accessory, 127, true);
+        assert!(acct.allows(&look, &[]));
+        assert!(!acct.is_unlocked(CosmeticKind::Accessory, 200));
+
+        acct.set_crafted(CosmeticKind::Accessory, 127, false);
+        assert!(!acct.allows(&look, &[]));
+        assert!(acct.is_crafted(CosmeticKind::Outfit, 3));
+    }
+
+    #[test]
+    fn strip_falls_back_to_free_defaults() {
+        let mut acct = blank();
+        acct.outfit_style_index = 4;
+        acct.accessory_slots = [9, 9];
+        acct.strip(CosmeticKind::Outfit, 5);
+        acct.strip(CosmeticKind::Accessory, 9);
+        assert_eq!((acct.outfit_style_index, acct.accessory_slots), (4, [255; 2]));
+        acct.strip(CosmeticKind::Outfit, 4);
+        assert_eq!(acct.outfit_style_index, CharacterCustomizationV1::FREE_OUTFIT);
+    }
+
+    #[test]
+    fn space_matches_layout() {
+        let mut data = Vec::new();
+        blank().try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), CharacterCustomizationV1::SPACE);
+    }
+}
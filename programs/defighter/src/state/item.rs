@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+use super::player::FighterClass;
+use crate::errors::CustomError;
+
+/// Where an item is worn; indexes `Inventory.equipped`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GearSlot {
+    Head = 0,
+    Body = 1,
+    Weapon = 2,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Rarity {
+    Common = 0,
+    Uncommon = 1,
+    Rare = 2,
+    Epic = 3,
+    Legendary = 4,
+}
+
+impl Rarity {
+    /// Largest single modifier an item of this rarity may carry, in bps
+    pub fn max_modifier_bps(self) -> u16 {
+        match self {
+            Rarity::Common => 100,
+            Rarity::Uncommon => 150,
+            Rarity::Rare => 200,
+            Rarity::Epic => 250,
+            Rarity::Legendary => 300,
+        }
+    }
+}
+
+/// Battle modifiers from gear, in bps. Summed over equipped items and capped
+/// at `GearStats::MAX_TOTAL_BPS` each.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct GearStats {
+    /// Extra damage dealt
+    pub attack_bps: u16,
+    /// Less damage taken
+    pub defense_bps: u16,
+}
+
+impl GearStats {
+    pub const SIZE: usize = 2 + 2;
+
+    /// Hard cap on a fighter's total gear bonus per stat, whatever the items say
+    pub const MAX_TOTAL_BPS: u16 = 500;
+
+    pub fn sum<'a>(stats: impl IntoIterator<Item = &'a GearStats>) -> Self {
+        let (attack, defense) = stats
+            .into_iter()
+            .fold((0u32, 0u32), |(a, d), s| (a + s.attack_bps as u32, d + s.defense_bps as u32));
+        Self {
+            attack_bps: attack.min(Self::MAX_TOTAL_BPS as u32) as u16,
+            defense_bps: defense.min(Self::MAX_TOTAL_BPS as u32) as u16,
+        }
+    }
+}
+
+/// An admin-defined gear item, seeds `[b"item", id]`. Immutable once created,
+/// so the stats cached on equipped players never go stale.
+#[account]
+pub struct Item {
+    pub id: u32,
+    pub slot: GearSlot,
+    pub rarity: Rarity,
+    /// Only this class may equip it; `None` for any class
+    pub class: Option<FighterClass>,
+    pub stats: GearStats,
+}
+
+impl Item {
+    pub const SPACE: usize = 8 + 4 + 1 + 1 + (1 + 1) + GearStats::SIZE;
+}
+
+/// Admin-supplied item definition.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ItemParams {
+    pub slot: GearSlot,
+    pub rarity: Rarity,
+    pub class: Option<FighterClass>,
+    pub stats: GearStats,
+}
+
+impl ItemParams {
+    pub fn is_valid(&self) -> bool {
+        let cap = self.rarity.max_modifier_bps();
+        self.stats.attack_bps <= cap && self.stats.defense_bps <= cap
+    }
+}
+
+/// Items a wallet owns, seeds `[b"inventory", owner]`, shared by all of
+/// its characters. Duplicates are separate entries.
+#[account]
+pub struct Inventory {
+    pub owner: Pubkey,
+    pub items: Vec<u32>,
+    /// Item id worn in each `GearSlot`
+    pub equipped: [Option<u32>; 3],
+    /// Stats of each equipped item, so totals can be recomputed without
+    /// loading every `Item`
+    pub equipped_stats: [GearStats; 3],
+    /// The `Player` wearing `equipped`; one character at a time, cleared
+    /// once every slot is empty
+    pub wearer: Option<Pubkey>,
+}
+
+impl Inventory {
+    pub const MAX_ITEMS: usize = 32;
+    pub const SPACE: usize = 8 + 32 + (4 + 4 * Self::MAX_ITEMS) + 3 * (1 + 4) + 3 * GearStats::SIZE + (1 + 32);
+
+    /// Fails if another character is wearing the gear
+    pub fn require_wearer(&self, player: Pubkey) -> Result<()> {
+        require!(self.wearer.map_or(true, |wearer| wearer == player), CustomError::GearInUse);
+        Ok(())
+    }
+
+    /// Hand the gear back once nothing is worn
+    pub fn settle_wearer(&mut self) {
+        if self.equipped.iter().all(Option::is_none) {
+            self.wearer = None;
+        }
+    }
+
+    /// Take everything off `player`, if it is the wearer; returns whether it was
+    pub fn release(&mut self, player: Pubkey) -> bool {
+        if self.wearer != Some(player) {
+            return false;
+        }
+        self.equipped = [None; 3];
+        self.equipped_stats = [GearStats::default(); 3];
+        self.wearer = None;
+        true
+    }
+
+    pub fn owns(&self, item_id: u32) -> bool {
+        self.items.contains(&item_id)
+    }
+
+    /// Capped totals of everything worn
+    pub fn gear(&self) -> GearStats {
+        GearStats::sum(&self.equipped_stats)
+    }
+}
+
+/// Unequip `player` in the wallet inventory at `inventory`, if one exists,
+/// for a character leaving the wallet
+pub fn release_gear(inventory: &AccountInfo, player: Pubkey) -> Result<()> {
+    if inventory.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*inventory.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let mut data = inventory.try_borrow_mut_data()?;
+    let mut gear = Inventory::try_deserialize(&mut &data[..])?;
+    if gear.release(player) {
+        gear.try_serialize(&mut &mut data[..])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_character_wears_the_gear_at_a_time() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut inventory = Inventory {
+            owner: Pubkey::default(),
+            items: vec![1, 2],
+            equipped: [None, None, Some(1)],
+            equipped_stats: [GearStats::default(), GearStats::default(), GearStats { attack_bps: 100, defense_bps: 0 }],
+            wearer: Some(first),
+        };
+        inventory.require_wearer(first).unwrap();
+        assert!(inventory.require_wearer(second).is_err());
+        inventory.settle_wearer();
+        assert_eq!(inventory.wearer, Some(first));
+
+        assert!(!inventory.release(second));
+        assert!(inventory.release(first));
+        assert_eq!((inventory.equipped, inventory.gear(), inventory.wearer), ([None; 3], GearStats::default(), None));
+        inventory.require_wearer(second).unwrap();
+    }
+
+    #[test]
+    fn gear_totals_are_capped() {
+        let legendary = GearStats { attack_bps: 300, defense_bps: 300 };
+        let common = GearStats { attack_bps: 100, defense_bps: 0 };
+        assert_eq!(GearStats::sum(&[legendary, common]), GearStats { attack_bps: 400, defense_bps: 300 });
+        assert_eq!(GearStats::sum(&[legendary; 3]), GearStats { attack_bps: 500, defense_bps: 500 });
+    }
+
+    #[test]
+    fn rarity_caps_item_modifiers() {
+        let params = |rarity, attack_bps| ItemParams {
+            slot: GearSlot::Weapon,
+            rarity,
+            class: None,
+            stats: GearStats { attack_bps, defense_bps: 0 },
+        };
+        assert!(params(Rarity::Common, 100).is_valid());
+        assert!(!params(Rarity::Common, 101).is_valid());
+        assert!(params(Rarity::Legendary, 300).is_valid());
+        assert!(!params(Rarity::Legendary, 301).is_valid());
+    }
+}
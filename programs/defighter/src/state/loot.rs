@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use super::crafting::Material;
+use super::item::Rarity;
+
+/// An unclaimed drop. `roll` picks the concrete item from the `LootTable`
+/// entries of `rarity` when it is claimed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LootDrop {
+    pub rarity: Rarity,
+    pub roll: u64,
+}
+
+/// A player's battle loot, seeds `[b"loot_bag", owner]`. Created with the
+/// first battle a player is in; `claim_loot` moves drops to the `Inventory`
+/// and crafting spends the materials.
+#[account]
+pub struct LootBag {
+    pub owner: Pubkey,
+    pub drops: Vec<LootDrop>,
+    /// Battles since the last Rare-or-better drop
+    pub pity: u16,
+    pub total_drops: u32,
+    /// Crafting material balances, by `Material`
+    pub materials: [u32; Material::COUNT],
+}
+
+impl LootBag {
+    /// Further drops are lost until the bag is claimed
+    pub const MAX_DROPS: usize = 16;
+    pub const SPACE: usize = 8 + 32 + (4 + Self::MAX_DROPS * (1 + 8)) + 2 + 4 + 4 * Material::COUNT;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LootEntry {
+    pub item_id: u32,
+    pub rarity: Rarity,
+}
+
+/// Items drops can turn into, seeds `[b"loot_table"]`. Each entry's rarity
+/// is copied from its `Item` by `set_loot_table`.
+#[account]
+pub struct LootTable {
+    pub entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    pub const MAX_ENTRIES: usize = 32;
+    pub const SPACE: usize = 8 + 4 + Self::MAX_ENTRIES * (4 + 1);
+
+    /// What a `ProposalAction::SetLootTable` commits to for `item_ids`
+    pub fn items_hash(item_ids: &[u32]) -> [u8; 32] {
+        let ids: Vec<[u8; 4]> = item_ids.iter().map(|id| id.to_le_bytes()).collect();
+        let ids: Vec<&[u8]> = ids.iter().map(|id| id.as_ref()).collect();
+        anchor_lang::solana_program::keccak::hashv(&ids).0
+    }
+
+    /// The item `drop` turns into, if the table has any of its rarity
+    pub fn pick(&self, drop: &LootDrop) -> Option<u32> {
+        let pool: Vec<u32> = self.entries.iter().filter(|e| e.rarity == drop.rarity).map(|e| e.item_id).collect();
+        (!pool.is_empty()).then(|| pool[(drop.roll % pool.len() as u64) as usize])
+    }
+}
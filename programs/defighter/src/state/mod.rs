@@ -2,10 +2,30 @@ pub mod player;
 pub mod battle;
 pub mod config;
 pub mod customization;
+pub mod boss;
+pub mod session;
+pub mod admin;
+pub mod pair;
+pub mod item;
+pub mod loot;
+pub mod crafting;
+pub mod consumable;
+pub mod nft;
+pub mod profile;
 
 pub use player::*;
 pub use battle::*;
 pub use config::*;
 pub use customization::*;
+pub use boss::*;
+pub use session::*;
+pub use admin::*;
+pub use pair::*;
+pub use item::*;
+pub use loot::*;
+pub use crafting::*;
+pub use consumable::*;
+pub use nft::*;
+pub use profile::*;
 
 
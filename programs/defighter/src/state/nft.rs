@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use super::crafting::CosmeticKind;
+
+/// A partner collection whose holders unlock one cosmetic, seeds
+/// `[b"nft_collection", collection]`. Admin-managed via `set_nft_collection`.
+#[account]
+pub struct NftCollection {
+    /// The Metaplex collection mint members are verified against
+    pub collection: Pubkey,
+    pub kind: CosmeticKind,
+    pub index: u8,
+    /// Inactive collections can't be verified against; existing perks can be revoked
+    pub active: bool,
+}
+
+impl NftCollection {
+    pub const SPACE: usize = 8 + 32 + 1 + 1 + 1;
+}
+
+/// An NFT unlock recorded against a customization, seeds
+/// `[b"nft_perk", character_customization, collection]`. Only good while
+/// `token_account` still holds `mint` for the player's wallet, which is
+/// re-checked whenever the perk is used and by `revoke_nft_perk`.
+#[account]
+pub struct NftPerk {
+    pub customization: Pubkey,
+    pub collection: Pubkey,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    /// Copied from the collection at verification
+    pub kind: CosmeticKind,
+    pub index: u8,
+    pub verified_slot: u64,
+}
+
+impl NftPerk {
+    pub const SPACE: usize = 8 + 32 * 4 + 1 + 1 + 8;
+}
+
+/// Ties a tokenized character's 1/1 mint to its current `Player`, seeds
+/// `[b"character_mint", mint]`. Created with `init`, so a mint can only
+/// ever stand for one character.
+#[account]
+pub struct CharacterMint {
+    pub mint: Pubkey,
+    /// Moves along with the character on `claim_player`
+    pub player: Pubkey,
+}
+
+impl CharacterMint {
+    pub const SPACE: usize = 8 + 32 + 32;
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use crate::errors::CustomError;
+use super::config::BalanceConfigParams;
+
+/// Recent battles between two players, seeds `[b"pair", low, high]` with the
+/// two authorities in byte order so either side finds the same record.
+#[account]
+pub struct PairRecord {
+    pub low: Pubkey,
+    pub high: Pubkey,
+    /// Start of the current `pair_window_slots` window
+    pub window_start_slot: u64,
+    /// Battles initiated in the current window
+    pub battles_in_window: u16,
+    pub last_battle_slot: u64,
+    pub total_battles: u64,
+}
+
+impl PairRecord {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 2 + 8 + 8;
+
+    /// The two keys in seed order
+    pub fn sorted(a: Pubkey, b: Pubkey) -> (Pubkey, Pubkey) {
+        if a <= b { (a, b) } else { (b, a) }
+    }
+
+    /// Count a new battle at `now`, enforcing the cooldown, and return the
+    /// XP scale in bps it will resolve with.
+    pub fn record_battle(&mut self, now: u64, rules: &BalanceConfigParams) -> Result<u16> {
+        if self.total_battles > 0 {
+            require!(
+                now >= self.last_battle_slot.saturating_add(rules.pair_cooldown_slots),
+                CustomError::PairOnCooldown
+            );
+        }
+        if self.total_battles == 0 || now >= self.window_start_slot.saturating_add(rules.pair_window_slots) {
+            self.window_start_slot = now;
+            self.battles_in_window = 0;
+        }
+        let repeats = self.battles_in_window as u32;
+        self.battles_in_window = self.battles_in_window.saturating_add(1);
+        self.last_battle_slot = now;
+        self.total_battles = self.total_battles.saturating_add(1);
+        Ok(decayed_xp_bps(repeats, rules.pair_decay_bps))
+    }
+}
+
+/// Each earlier battle in the window takes `decay_bps` off, down to zero.
+pub fn decayed_xp_bps(repeats: u32, decay_bps: u16) -> u16 {
+    10_000u32.saturating_sub(repeats.saturating_mul(decay_bps as u32)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> PairRecord {
+        PairRecord {
+            low: Pubkey::default(),
+            high: Pubkey::default(),
+            window_start_slot: 0,
+            battles_in_window: 0,
+            last_battle_slot: 0,
+            total_battles: 0,
+        }
+    }
+
+    #[test]
+    fn repeats_decay_then_reset_with_the_window() {
+        let rules = BalanceConfigParams {
+            pair_cooldown_slots: 10,
+            pair_window_slots: 100,
+            pair_decay_bps: 4_000,
+            ..Default::default()
+        };
+        let mut pair = record();
+        assert_eq!(pair.record_battle(5, &rules).unwrap(), 10_000);
+        assert!(pair.record_battle(14, &rules).is_err());
+        assert_eq!(pair.record_battle(15, &rules).unwrap(), 6_000);
+        assert_eq!(pair.record_battle(30, &rules).unwrap(), 2_000);
+        assert_eq!(pair.record_battle(40, &rules).unwrap(), 0);
+        // The window opened at slot 5
+        assert_eq!(pair.record_battle(105, &rules).unwrap(), 10_000);
+        assert_eq!((pair.battles_in_window, pair.total_battles), (1, 5));
+    }
+
+    #[test]
+    fn pair_order_does_not_matter() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(PairRecord::sorted(a, b), PairRecord::sorted(b, a));
+    }
+}
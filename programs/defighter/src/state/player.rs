@@ -1,5 +1,13 @@
 use anchor_lang::prelude::*;
+use crate::errors::CustomError;
+use crate::logic::nft::held_nft;
+use crate::state::config::BalanceConfig;
+use crate::state::crafting::CosmeticKind;
+use crate::state::customization::CharacterCustomizationV1;
+use crate::state::item::GearStats;
 
+/// New fields go at the end and must default to zero: `migrate_player`
+/// grows accounts from older layouts by zero-filling the tail.
 #[account]
 pub struct Player {
     pub authority: Pubkey,
@@ -8,6 +16,149 @@ pub struct Player {
     pub abilities: [u16; 3],
     pub elo: i32,
     pub version: u8,
+    /// Energy below `energy_max` as of `energy_updated_slot`; stored as a
+    /// deficit so a new player starts full whatever the config says
+    pub energy_used: u16,
+    /// Regen is counted from here; only whole points are ever settled
+    pub energy_updated_slot: u64,
+    /// Capped totals of equipped gear, kept in sync by `equip_item`/`unequip_item`
+    pub gear: GearStats,
+    /// PvP and boss battles started but not yet resolved
+    pub open_battles: u16,
+    /// Set by `tokenize_player`; whoever holds this 1/1 can claim the character
+    pub character_mint: Option<Pubkey>,
+    /// No new battles before this slot; set when the character changes wallets
+    pub cooldown_until_slot: u64,
+    /// Position among the wallet's characters; the last `Player` seed
+    pub index: u8,
+    /// Times the character has maxed every ability and started over
+    pub prestige: u8,
+    /// XP paid for the current ability levels; what a respec refunds from
+    pub xp_spent: u64,
+}
+
+impl Player {
+    /// Layout `create_player` and `migrate_player` write; bump when adding a field
+    pub const VERSION: u8 = 2;
+    pub const SPACE: usize = 8 + 32 + 1 + 8 + (2 * 3) + 4 + 1 + 2 + 8 + GearStats::SIZE + 2 + (1 + 32) + 8 + 1 + 1 + 8;
+    /// Rating a wallet's first character starts at
+    pub const START_ELO: i32 = 1000;
+    /// ~1 day at 400ms slots; stops a bought rating being farmed straight away
+    pub const TRANSFER_COOLDOWN_SLOTS: u64 = 216_000;
+
+    /// A character as `create_player` leaves it: no XP, gear or history
+    pub fn new(authority: Pubkey, class: FighterClass, abilities: [u16; 3]) -> Self {
+        Player {
+            authority,
+            class,
+            xp: 0,
+            abilities,
+            elo: Self::START_ELO,
+            version: Self::VERSION,
+            energy_used: 0,
+            energy_updated_slot: 0,
+            gear: GearStats::default(),
+            open_battles: 0,
+            character_mint: None,
+            cooldown_until_slot: 0,
+            index: 0,
+            prestige: 0,
+            xp_spent: 0,
+        }
+    }
+
+    /// Fails while the post-transfer cooldown runs
+    pub fn require_ready(&self, now: u64) -> Result<()> {
+        require!(now >= self.cooldown_until_slot, CustomError::CharacterCooldown);
+        Ok(())
+    }
+
+    /// A tokenized character answers only to a wallet still holding its
+    /// token, so a seller loses control as soon as the token leaves.
+    /// `token` is the authority's token account for `character_mint`.
+    pub fn require_holder(&self, token: Option<&AccountInfo>) -> Result<()> {
+        let Some(mint) = self.character_mint else { return Ok(()) };
+        let held = token.and_then(|token| held_nft(token, &self.authority));
+        require!(held == Some(mint), CustomError::NftNotHeld);
+        Ok(())
+    }
+
+    /// Copy the character to a new wallet's account at `index`, starting the
+    /// transfer cooldown. Gear lives in the old wallet's inventory, so none
+    /// comes along.
+    pub fn move_to(&self, to: &mut Player, authority: Pubkey, index: u8, now: u64) -> Result<()> {
+        require!(self.open_battles == 0, CustomError::OpenBattles);
+        *to = Player {
+            authority,
+            index,
+            gear: GearStats::default(),
+            cooldown_until_slot: now.saturating_add(Self::TRANSFER_COOLDOWN_SLOTS),
+            ..self.clone()
+        };
+        Ok(())
+    }
+
+    /// Power bonus from prestige ranks, capped at `MAX_PRESTIGE_POWER_BPS`
+    pub fn prestige_power_bps(&self, cfg: &BalanceConfig) -> u32 {
+        (self.prestige as u32)
+            .saturating_mul(cfg.prestige_power_bps as u32)
+            .min(BalanceConfig::MAX_PRESTIGE_POWER_BPS)
+    }
+
+    /// Cosmetic a prestiged character may wear without crafting it
+    pub fn prestige_unlock(&self, cfg: &BalanceConfig) -> Option<(CosmeticKind, u8)> {
+        (self.prestige > 0 && cfg.prestige_outfit != CharacterCustomizationV1::FREE_OUTFIT)
+            .then_some((CosmeticKind::Outfit, cfg.prestige_outfit))
+    }
+
+    /// Energy available at `now`, regen included
+    pub fn energy(&self, now: u64, cfg: &BalanceConfig) -> u16 {
+        let mut p = self.clone();
+        p.settle_energy(now, cfg);
+        cfg.energy_max.saturating_sub(p.energy_used)
+    }
+
+    /// Fold regen since `energy_updated_slot` into `energy_used`. Leftover
+    /// slots toward the next point carry over.
+    pub fn settle_energy(&mut self, now: u64, cfg: &BalanceConfig) {
+        self.energy_used = self.energy_used.min(cfg.energy_max);
+        let regen_slots = cfg.energy_regen_slots.max(1);
+        let points = now.saturating_sub(self.energy_updated_slot) / regen_slots;
+        if points >= self.energy_used as u64 {
+            self.energy_used = 0;
+            self.energy_updated_slot = now;
+        } else {
+            self.energy_used -= points as u16;
+            self.energy_updated_slot += points * regen_slots;
+        }
+    }
+
+    pub fn spend_energy(&mut self, amount: u16, now: u64, cfg: &BalanceConfig) -> Result<()> {
+        self.settle_energy(now, cfg);
+        require!(cfg.energy_max - self.energy_used >= amount, CustomError::NotEnoughEnergy);
+        self.energy_used += amount;
+        Ok(())
+    }
+
+    /// Restore up to `amount`, never past `energy_max`; returns what was added
+    pub fn add_energy(&mut self, amount: u16, now: u64, cfg: &BalanceConfig) -> u16 {
+        self.settle_energy(now, cfg);
+        let added = amount.min(self.energy_used);
+        self.energy_used -= added;
+        added
+    }
+}
+
+/// A `Player` as the baseline wrote it, at `[b"player", authority]` before
+/// wallets held several characters; what `migrate_legacy_player` carries over
+#[derive(AnchorDeserialize)]
+pub struct LegacyPlayer {
+    pub authority: Pubkey,
+    pub class: FighterClass,
+    pub xp: u64,
+    pub abilities: [u16; 3],
+    pub elo: i32,
+    pub version: u8,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
@@ -30,9 +181,66 @@ pub enum MoveChoice {
     ExitLiquidity = 5, // Special
 }
 
+/// What levelling an ability improves in combat
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AbilityEffect {
+    /// Damage of the class's basic move
+    BasicDamage,
+    /// Shrinks the special move's miss window
+    SpecialAccuracy,
+    /// Raises the special move's critical multiplier
+    CritPower,
+    /// Reduces damage taken from every move
+    Defense,
+}
+
+impl FighterClass {
+    pub const ALL: [FighterClass; 3] = [FighterClass::Shitposter, FighterClass::Builder, FighterClass::VC];
+
+    /// What each `Player.abilities` index improves for this class
+    pub fn ability_effects(self) -> [AbilityEffect; 3] {
+        use AbilityEffect::*;
+        match self {
+            FighterClass::Shitposter => [BasicDamage, SpecialAccuracy, CritPower],
+            FighterClass::Builder => [BasicDamage, SpecialAccuracy, Defense],
+            FighterClass::VC => [BasicDamage, CritPower, Defense],
+        }
+    }
+
+    /// The class's `[basic, special]` moves
+    pub fn moves(self) -> [MoveChoice; 2] {
+        match self {
+            FighterClass::Shitposter => [MoveChoice::MemeBomb, MoveChoice::RugPullRumor],
+            FighterClass::Builder => [MoveChoice::ShipIt, MoveChoice::TestnetDeploy],
+            FighterClass::VC => [MoveChoice::SeriesACannon, MoveChoice::ExitLiquidity],
+        }
+    }
+
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(FighterClass::Shitposter),
+            1 => Some(FighterClass::Builder),
+            2 => Some(FighterClass::VC),
+            _ => None,
+        }
+    }
+}
+
 impl MoveChoice {
     pub fn to_byte(self) -> u8 { self as u8 }
 
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(MoveChoice::MemeBomb),
+            1 => Some(MoveChoice::RugPullRumor),
+            2 => Some(MoveChoice::ShipIt),
+            3 => Some(MoveChoice::TestnetDeploy),
+            4 => Some(MoveChoice::SeriesACannon),
+            5 => Some(MoveChoice::ExitLiquidity),
+            _ => None,
+        }
+    }
+
     pub fn class(self) -> FighterClass {
         match self {
             MoveChoice::MemeBomb | MoveChoice::RugPullRumor => FighterClass::Shitposter,
@@ -51,3 +259,72 @@ impl MoveChoice {
 }
 
 
+
+/// A fresh character with no authority, for the combat math tests
+#[cfg(test)]
+pub fn test_player(class: FighterClass, abilities: [u16; 3]) -> Player {
+    Player::new(Pubkey::default(), class, abilities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::config::BalanceConfigParams;
+
+    fn player() -> Player {
+        test_player(FighterClass::Builder, [0; 3])
+    }
+
+    #[test]
+    fn moving_keeps_progress_but_not_gear() {
+        let mut from = Player { xp: 500, abilities: [3, 1, 0], elo: 1200, character_mint: Some(Pubkey::new_unique()), ..player() };
+        from.gear.attack_bps = 300;
+        let (mut to, wallet) = (player(), Pubkey::new_unique());
+        from.move_to(&mut to, wallet, 2, 10).unwrap();
+        assert_eq!((to.authority, to.index, to.xp, to.abilities, to.elo), (wallet, 2, 500, [3, 1, 0], 1200));
+        assert_eq!((to.gear, to.character_mint), (GearStats::default(), from.character_mint));
+        assert!(to.require_ready(10 + Player::TRANSFER_COOLDOWN_SLOTS - 1).is_err());
+        to.require_ready(10 + Player::TRANSFER_COOLDOWN_SLOTS).unwrap();
+
+        from.open_battles = 1;
+        assert!(from.move_to(&mut to, wallet, 0, 10).is_err());
+    }
+
+    #[test]
+    fn prestige_perks_come_from_config() {
+        let cfg = BalanceConfig::with_params(&BalanceConfigParams { prestige_outfit: 7, ..Default::default() });
+        let mut p = player();
+        assert_eq!((p.prestige_power_bps(&cfg), p.prestige_unlock(&cfg)), (0, None));
+        p.prestige = 2;
+        assert_eq!((p.prestige_power_bps(&cfg), p.prestige_unlock(&cfg)), (200, Some((CosmeticKind::Outfit, 7))));
+        p.prestige = u8::MAX;
+        assert_eq!(p.prestige_power_bps(&cfg), BalanceConfig::MAX_PRESTIGE_POWER_BPS);
+        let plain = BalanceConfig::with_params(&BalanceConfigParams::default());
+        assert_eq!(p.prestige_unlock(&plain), None);
+    }
+
+    #[test]
+    fn energy_regenerates_lazily_up_to_the_cap() {
+        let cfg = BalanceConfig::with_params(&BalanceConfigParams {
+            energy_max: 3,
+            energy_regen_slots: 100,
+            ..Default::default()
+        });
+        let mut p = player();
+        assert_eq!(p.energy(0, &cfg), 3);
+        for _ in 0..3 {
+            p.spend_energy(1, 50, &cfg).unwrap();
+        }
+        assert!(p.spend_energy(1, 50, &cfg).is_err());
+
+        // Regen runs from the first spend after a full bar, partial progress kept
+        assert_eq!((p.energy(149, &cfg), p.energy(150, &cfg)), (0, 1));
+        p.spend_energy(1, 160, &cfg).unwrap();
+        assert_eq!((p.energy_used, p.energy_updated_slot), (3, 150));
+        assert_eq!(p.energy(250, &cfg), 1);
+        assert_eq!(p.energy(10_000, &cfg), 3);
+
+        assert_eq!(p.add_energy(5, 250, &cfg), 2);
+        assert_eq!(p.energy(250, &cfg), 3);
+    }
+}
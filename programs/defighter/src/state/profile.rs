@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use crate::errors::CustomError;
+use crate::state::player::Player;
+
+/// A wallet's character roster, seeds `[b"profile", authority]`. Characters
+/// are `Player`s at `[b"player", authority, &[index]]`; indexes are handed
+/// out in order and never reused, so a departed character's seeds stay dead.
+#[account]
+pub struct Profile {
+    pub authority: Pubkey,
+    /// Characters the wallet holds now, capped by `max_characters`
+    pub characters: u8,
+    /// Index the next character gets
+    pub next_index: u8,
+    /// Lowest rating among closed characters rated below `Player::START_ELO`;
+    /// new characters start here, so closing one doesn't reset a bad rating
+    pub tombstone_elo: Option<i32>,
+}
+
+impl Profile {
+    pub const SPACE: usize = 8 + 32 + 1 + 1 + (1 + 4);
+
+    /// Take the next character index, if the wallet has room under `max`
+    pub fn add_character(&mut self, authority: Pubkey, max: u8) -> Result<u8> {
+        require!(self.characters < max, CustomError::TooManyCharacters);
+        let index = self.next_index;
+        self.next_index = index.checked_add(1).ok_or(CustomError::TooManyCharacters)?;
+        self.authority = authority;
+        self.characters += 1;
+        Ok(index)
+    }
+
+    /// A character left the wallet, freeing its place under the cap
+    pub fn remove_character(&mut self) {
+        self.characters = self.characters.saturating_sub(1);
+    }
+
+    /// A character rated `elo` was closed for good. Below the starting
+    /// rating it leaves a tombstone; returns whether it did.
+    pub fn bury(&mut self, elo: i32) -> bool {
+        self.remove_character();
+        if elo >= Player::START_ELO {
+            return false;
+        }
+        self.tombstone_elo = Some(self.tombstone_elo.map_or(elo, |t| t.min(elo)));
+        true
+    }
+
+    /// Rating the wallet's next character starts at
+    pub fn starting_elo(&self) -> i32 {
+        self.tombstone_elo.unwrap_or(Player::START_ELO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_are_never_reused() {
+        let owner = Pubkey::new_unique();
+        let mut profile = Profile { authority: Pubkey::default(), characters: 0, next_index: 0, tombstone_elo: None };
+        assert_eq!(profile.add_character(owner, 2).unwrap(), 0);
+        assert_eq!(profile.add_character(owner, 2).unwrap(), 1);
+        assert!(profile.add_character(owner, 2).is_err());
+
+        profile.remove_character();
+        assert_eq!(profile.add_character(owner, 2).unwrap(), 2);
+        assert_eq!((profile.authority, profile.characters), (owner, 2));
+
+        let mut full = Profile { authority: owner, characters: 0, next_index: u8::MAX, tombstone_elo: None };
+        assert!(full.add_character(owner, 3).is_err());
+    }
+
+    #[test]
+    fn only_a_low_rating_leaves_a_tombstone() {
+        let owner = Pubkey::new_unique();
+        let mut profile = Profile { authority: owner, characters: 3, next_index: 3, tombstone_elo: None };
+        assert!(!profile.bury(Player::START_ELO + 200));
+        assert_eq!((profile.characters, profile.starting_elo()), (2, Player::START_ELO));
+        assert!(profile.bury(900));
+        assert!(profile.bury(950));
+        assert_eq!((profile.characters, profile.starting_elo()), (0, 900));
+    }
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::errors::CustomError;
+
+/// Lets an ephemeral key act for `authority` in battle instructions until
+/// `expires_slot`, so a battle doesn't need a wallet prompt per move.
+#[account]
+pub struct SessionToken {
+    pub authority: Pubkey,
+    pub session_key: Pubkey,
+    pub expires_slot: u64,
+    /// `SessionToken::COMMIT | REVEAL | RESOLVE` bits the key may use
+    pub scope: u8,
+}
+
+impl SessionToken {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 1;
+
+    pub const COMMIT: u8 = 1 << 0;
+    pub const REVEAL: u8 = 1 << 1;
+    /// Resolves are permissionless; the bit tells clients the key may crank them
+    pub const RESOLVE: u8 = 1 << 2;
+    pub const ALL: u8 = Self::COMMIT | Self::REVEAL | Self::RESOLVE;
+
+    /// About a day at 400ms slots
+    pub const MAX_DURATION_SLOTS: u64 = 216_000;
+}
+
+/// The wallet `signer` acts for: itself, or the authority of a live session
+/// whose key it is and whose scope covers `scope`.
+pub fn acting_authority(
+    signer: Pubkey,
+    session: Option<&SessionToken>,
+    scope: u8,
+    slot: u64,
+) -> Result<Pubkey> {
+    let Some(session) = session else {
+        return Ok(signer);
+    };
+    require_keys_eq!(session.session_key, signer, CustomError::InvalidSession);
+    require!(slot <= session.expires_slot, CustomError::SessionExpired);
+    require!(session.scope & scope == scope, CustomError::SessionScope);
+    Ok(session.authority)
+}
@@ -1,3 +1,23 @@
-// Utility functions can be added here as needed.
-
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::errors::CustomError;
 
+/// Grow `account`, written under an older and shorter layout, to `len`
+/// bytes. The new tail is zeroed; `payer` tops up the rent exemption.
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    len: usize,
+) -> Result<()> {
+    require!(account.data_len() < len, CustomError::AlreadyMigrated);
+    let shortfall = Rent::get()?.minimum_balance(len).saturating_sub(account.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(system_program.clone(), system_program::Transfer { from: payer.clone(), to: account.clone() }),
+            shortfall,
+        )?;
+    }
+    account.resize(len)?;
+    Ok(())
+}
@@ -10,7 +10,7 @@ import {
 } from "@solana/web3.js";
 import { useState } from "react";
 import { getProgram } from "@/lib/program";
-import { playerPda, battlePda, configPda } from "@/lib/pdas";
+import { playerPda, profilePda, battlePda, configPda, pairPda, lootBagPda, consumablesPda } from "@/lib/pdas";
 import { randomSalt32, commitmentHash } from "@/lib/commitment";
 import { loadOrCreateBotKeypair } from "@/lib/bot-storage";
 
@@ -317,22 +317,59 @@ export default function BattlePage() {
           "Init Config",
           (async () => {
             const ix = await program.methods
-              .initConfig(
-                10,
-                2,
-                20,
-                10,
-                100,
-                true,
-                false,
-                new BN(10),
-                7500,
-                2000,
-                new BN(10),
-                14000,
-                20,
-                10
-              )
+              .initConfig({
+                base: 10,
+                linearA: 2,
+                dimK: 20,
+                dimT: 10,
+                maxLevel: 100,
+                tieBreakRand: true,
+                useDimBonus: false,
+                xpBase: new BN(10),
+                xpTieBps: 7500,
+                xpLoserBps: 2000,
+                upgradeC0: new BN(10),
+                upgradePBps: 14000,
+                rpsWinBase: 20,
+                rpsTieBase: 10,
+                basicDamageBps: 250,
+                accuracyBps: 50,
+                critBps: 500,
+                defenseBps: 100,
+                resolutionMode: { damage: {} },
+                pairCooldownSlots: new BN(150),
+                pairWindowSlots: new BN(216000),
+                pairDecayBps: 2500,
+                energyMax: 10,
+                energyRegenSlots: new BN(9000),
+                battleEnergyCost: 1,
+                energyXpCost: new BN(5),
+                lootDropBps: 3000,
+                lootRarityWeights: [6000, 2500, 1000, 400, 100],
+                lootPityThreshold: 10,
+                materialWin: 3,
+                materialLoss: 1,
+                salvageRefundBps: 5000,
+                shieldBps: 2000,
+                alphaLeakBps: 1500,
+                boostDropBps: 2000,
+                maxCharacters: 3,
+                respecRefundBps: 5000,
+                prestigePowerBps: 100,
+                prestigeOutfit: 0,
+                maxCommitSlots: new BN(1500),
+                maxRevealSlots: new BN(1500),
+                baseDamage: 100,
+                classWinBps: 12500,
+                classLossBps: 8000,
+                xpTierSize: new BN(1000),
+                xpTierBonusBps: 200,
+                specialMoves: [
+                  { missPct: 30, hitEndPct: 80, hitMultBps: 15000, critMultBps: 22000 },
+                  { missPct: 25, hitEndPct: 80, hitMultBps: 16000, critMultBps: 23000 },
+                  { missPct: 35, hitEndPct: 80, hitMultBps: 14000, critMultBps: 20000 },
+                ],
+              } as any)
               .accounts({ config: cfg, admin: me, systemProgram: SystemProgram.programId } as any)
               .instruction();
             const tx = new Transaction().add(
@@ -374,9 +411,11 @@ export default function BattlePage() {
         const ix = await program.methods
           .createPlayer({ builder: {} })
           .accounts({
+            profile: profilePda(bot.publicKey)[0],
             player: pdaB,
             authority: bot.publicKey,
             payer: me,
+            config: cfg,
             systemProgram: SystemProgram.programId,
           } as any)
           .instruction();
@@ -441,7 +480,7 @@ export default function BattlePage() {
 
       // --- Battle lifecycle ---
       const nonce = new BN(Date.now() + Math.floor(Math.random() * 1_000_000));
-      const [battle] = battlePda(me, bot.publicKey, nonce);
+      const [battle] = battlePda(pdaA, pdaB, nonce);
 
       // Build single-signer set for batch signing: Create Player A (if needed) and Initiate
       {
@@ -451,7 +490,14 @@ export default function BattlePage() {
         if (needCreateA) {
           const createAIx = await program.methods
             .createPlayer({ shitposter: {} })
-            .accounts({ player: pdaA, authority: me, payer: me, systemProgram: SystemProgram.programId } as any)
+            .accounts({
+              profile: profilePda(me)[0],
+              player: pdaA,
+              authority: me,
+              payer: me,
+              config: cfg,
+              systemProgram: SystemProgram.programId,
+            } as any)
             .instruction();
           const txA = new Transaction().add(
             ComputeBudgetProgram.setComputeUnitLimit({ units: 200_000 }),
@@ -463,10 +509,18 @@ export default function BattlePage() {
         }
 
         const initiateIx = await program.methods
-          .initiateBattle(bot.publicKey, nonce, new BN(1500), new BN(1500))
+          .initiateBattle(pdaB, nonce, new BN(1500), new BN(1500))
           .accounts({
             battle,
+            pairRecord: pairPda(me, bot.publicKey)[0],
+            challengerLoot: lootBagPda(me)[0],
+            opponentLoot: lootBagPda(bot.publicKey)[0],
+            challengerConsumables: consumablesPda(me)[0],
+            opponentConsumables: consumablesPda(bot.publicKey)[0],
             challenger: me,
+            challengerAccount: pdaA,
+            opponentAccount: pdaB,
+            config: cfg,
             systemProgram: SystemProgram.programId,
             clock: new PublicKey("SysvarC1ock11111111111111111111111111111111"),
           } as any)
@@ -497,6 +551,8 @@ export default function BattlePage() {
           .accounts({
             battle,
             player: me,
+            playerAccount: pdaA,
+            config: cfg,
             clock: new PublicKey("SysvarC1ock11111111111111111111111111111111"),
           } as any)
           .instruction();
@@ -527,6 +583,8 @@ export default function BattlePage() {
           .accounts({
             battle,
             player: bot.publicKey,
+            playerAccount: pdaB,
+            config: cfg,
             clock: new PublicKey("SysvarC1ock11111111111111111111111111111111"),
           } as any)
           .instruction();
@@ -584,7 +642,7 @@ export default function BattlePage() {
       // Reveal A (single-signer)
       {
         const ix = await program.methods
-          .revealMove({ memeBomb: {} } as any, [...saltA] as any)
+          .revealMove({ memeBomb: {} } as any, [...saltA] as any, null)
           .accounts({
             battle,
             player: me,
@@ -615,7 +673,7 @@ export default function BattlePage() {
       // Reveal B (multi-signer)
       {
         const ix = await program.methods
-          .revealMove({ shipIt: {} } as any, [...saltB] as any)
+          .revealMove({ shipIt: {} } as any, [...saltB] as any, null)
           .accounts({
             battle,
             player: bot.publicKey,
@@ -679,7 +737,11 @@ export default function BattlePage() {
               battle,
               playerChallenger: pdaA,
               playerOpponent: pdaB,
-              config: cfg,
+              challengerLoot: lootBagPda(me)[0],
+              opponentLoot: lootBagPda(bot.publicKey)[0],
+              challengerConsumables: consumablesPda(me)[0],
+              opponentConsumables: consumablesPda(bot.publicKey)[0],
+              config: configPda()[0],
               clock: new PublicKey("SysvarC1ock11111111111111111111111111111111"),
             } as any)
             .instruction();
@@ -725,8 +787,8 @@ export default function BattlePage() {
         // Determine winner display name
         const getPlayerName = (pubkey: string | null | undefined) => {
           if (!pubkey) return "<none>";
-          if (pubkey === me.toBase58()) return "You (Player A)";
-          if (pubkey === bot.publicKey.toBase58()) return "Bot (Player B)";
+          if (pubkey === pdaA.toBase58()) return "You (Player A)";
+          if (pubkey === pdaB.toBase58()) return "Bot (Player B)";
           return pubkey; // fallback to pubkey if unknown
         };
 
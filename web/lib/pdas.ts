@@ -2,13 +2,19 @@ import { PublicKey } from "@solana/web3.js";
 import { BN } from "@coral-xyz/anchor";
 import { getProgramId } from "./program";
 
-export function playerPda(authority: PublicKey): [PublicKey, number] {
+// A wallet's characters are numbered from 0 in creation order
+export function playerPda(authority: PublicKey, index = 0): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
-    [Buffer.from("player"), authority.toBuffer()],
+    [Buffer.from("player"), authority.toBuffer(), Buffer.from([index])],
     getProgramId()
   );
 }
 
+export function profilePda(authority: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("profile"), authority.toBuffer()], getProgramId());
+}
+
+// Seeded by the two characters' player PDAs, not their wallets
 export function battlePda(challenger: PublicKey, opponent: PublicKey, nonce: BN): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
     [Buffer.from("battle"), challenger.toBuffer(), opponent.toBuffer(), toLeBytes8(nonce)],
@@ -16,6 +22,23 @@ export function battlePda(challenger: PublicKey, opponent: PublicKey, nonce: BN)
   );
 }
 
+export function pairPda(a: PublicKey, b: PublicKey): [PublicKey, number] {
+  // Keys in byte order, so both players derive the same record
+  const [low, high] = Buffer.compare(a.toBuffer(), b.toBuffer()) <= 0 ? [a, b] : [b, a];
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("pair"), low.toBuffer(), high.toBuffer()],
+    getProgramId()
+  );
+}
+
+export function lootBagPda(owner: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("loot_bag"), owner.toBuffer()], getProgramId());
+}
+
+export function consumablesPda(owner: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("consumables"), owner.toBuffer()], getProgramId());
+}
+
 export function configPda(): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([Buffer.from("config")], getProgramId());
 }